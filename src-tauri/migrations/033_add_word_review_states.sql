@@ -0,0 +1,30 @@
+-- 添加间隔重复（Spaced Repetition）支持
+-- 根据练习结果维护每个单词的记忆状态，并据此重新安排后续复习
+
+-- 1. 学习计划使用的复习算法（sm2 / fsrs）
+ALTER TABLE study_plans ADD COLUMN review_algorithm TEXT NOT NULL DEFAULT 'sm2' CHECK (review_algorithm IN ('sm2', 'fsrs'));
+
+-- 2. 创建单词记忆状态表
+CREATE TABLE IF NOT EXISTS word_review_states (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    plan_id INTEGER NOT NULL,               -- 关联的学习计划ID
+    word_id INTEGER NOT NULL,               -- 单词ID
+    ease_factor REAL NOT NULL DEFAULT 2.5,  -- SM-2 难度因子（最小 1.3）
+    interval_days INTEGER NOT NULL DEFAULT 0, -- 当前复习间隔（天）
+    repetitions INTEGER NOT NULL DEFAULT 0, -- 连续答对次数
+    stability REAL NOT NULL DEFAULT 0.0,    -- FSRS 记忆稳定性（天）
+    difficulty REAL NOT NULL DEFAULT 0.0,   -- FSRS 难度（1-10）
+    lapses INTEGER NOT NULL DEFAULT 0,      -- 遗忘次数
+    last_grade INTEGER,                     -- 最近一次评分 0-5
+    last_reviewed_at TEXT,                  -- 最近一次复习日期 YYYY-MM-DD
+    due_date TEXT,                          -- 下次到期日期 YYYY-MM-DD
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now')),
+    FOREIGN KEY (plan_id) REFERENCES study_plans (id) ON DELETE CASCADE,
+    FOREIGN KEY (word_id) REFERENCES words (id) ON DELETE CASCADE,
+    UNIQUE(plan_id, word_id)
+);
+
+-- 3. 创建索引
+CREATE INDEX IF NOT EXISTS idx_word_review_states_plan_id ON word_review_states(plan_id);
+CREATE INDEX IF NOT EXISTS idx_word_review_states_due_date ON word_review_states(plan_id, due_date);
@@ -0,0 +1,5 @@
+-- 为 AI 提供商添加接口类型
+-- openai: OpenAI 兼容接口 / anthropic: Anthropic Messages API / ollama: Ollama 原生接口 / mock: 脚本化测试提供商
+-- 已有提供商（OpenRouter、月之暗面）均为 OpenAI 兼容接口
+
+ALTER TABLE ai_providers ADD COLUMN provider_type TEXT NOT NULL DEFAULT 'openai' CHECK (provider_type IN ('openai', 'anthropic', 'ollama', 'mock'));
@@ -0,0 +1,37 @@
+-- 添加可恢复的批量分析任务
+-- 批量分析的输入文本、提取的单词列表以及每个单词的状态和结果持久化到数据库，
+-- 应用关闭或崩溃后可以从未完成的单词继续分析
+
+-- 1. 创建批量分析任务表
+CREATE TABLE IF NOT EXISTS analysis_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    input_text TEXT,                        -- 输入文本（直接分析单词列表时为空）
+    extraction_mode TEXT NOT NULL DEFAULT 'focus', -- 单词提取模式
+    model_id INTEGER,                       -- 使用的 AI 模型ID
+    status TEXT NOT NULL DEFAULT 'extracting' CHECK (status IN ('extracting', 'analyzing', 'completed', 'partial', 'cancelled', 'failed')),
+    total_words INTEGER NOT NULL DEFAULT 0, -- 单词总数
+    completed_words INTEGER NOT NULL DEFAULT 0, -- 已完成单词数
+    failed_words INTEGER NOT NULL DEFAULT 0, -- 失败单词数
+    error_message TEXT,                     -- 任务失败原因
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now')),
+    FOREIGN KEY (model_id) REFERENCES ai_models (id) ON DELETE SET NULL
+);
+
+-- 2. 创建任务单词表
+CREATE TABLE IF NOT EXISTS analysis_job_words (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id INTEGER NOT NULL,                -- 关联的任务ID
+    position INTEGER NOT NULL,              -- 单词在提取结果中的顺序
+    word TEXT NOT NULL,                     -- 单词
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'completed', 'failed')),
+    result_json TEXT,                       -- 分析结果（PhonicsWord JSON）
+    error_message TEXT,                     -- 失败原因
+    updated_at TEXT DEFAULT (datetime('now')),
+    FOREIGN KEY (job_id) REFERENCES analysis_jobs (id) ON DELETE CASCADE,
+    UNIQUE(job_id, position)
+);
+
+-- 3. 创建索引
+CREATE INDEX IF NOT EXISTS idx_analysis_jobs_updated_at ON analysis_jobs(updated_at);
+CREATE INDEX IF NOT EXISTS idx_analysis_job_words_job_status ON analysis_job_words(job_id, status);
@@ -0,0 +1,25 @@
+-- 添加自然拼读分析缓存
+-- 按 (规范化单词, 模型ID, 提示词版本) 缓存批量分析结果，
+-- 重复导入包含相同单词的材料时无需再次请求 AI 模型
+
+CREATE TABLE IF NOT EXISTS phonics_analysis_cache (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    normalized_word TEXT NOT NULL,          -- 规范化单词（去除首尾空白、小写）
+    model_id INTEGER NOT NULL,              -- 分析使用的 AI 模型ID
+    prompt_version TEXT NOT NULL,           -- 批量分析提示词版本
+    word TEXT NOT NULL,                     -- AI 返回的单词原文
+    frequency INTEGER NOT NULL DEFAULT 0,
+    chinese_translation TEXT NOT NULL DEFAULT '',
+    pos_abbreviation TEXT NOT NULL DEFAULT '',
+    pos_english TEXT NOT NULL DEFAULT '',
+    pos_chinese TEXT NOT NULL DEFAULT '',
+    ipa TEXT NOT NULL DEFAULT '',
+    syllables TEXT NOT NULL DEFAULT '',
+    phonics_rule TEXT NOT NULL DEFAULT '',
+    analysis_explanation TEXT NOT NULL DEFAULT '',
+    hit_count INTEGER NOT NULL DEFAULT 0,   -- 命中次数
+    created_at TEXT DEFAULT (datetime('now')),
+    last_used_at TEXT DEFAULT (datetime('now')),
+    FOREIGN KEY (model_id) REFERENCES ai_models (id) ON DELETE CASCADE,
+    UNIQUE(normalized_word, model_id, prompt_version)
+);
@@ -0,0 +1,29 @@
+-- 添加 AI 调用用量与费用统计
+-- 记录每次 LLM 调用的 token 用量、耗时、模型和用途，并支持按模型配置价格估算费用
+
+-- 1. 模型价格（每百万 token 的美元价格，未配置时不估算费用）
+ALTER TABLE ai_models ADD COLUMN input_price_per_million REAL;
+ALTER TABLE ai_models ADD COLUMN output_price_per_million REAL;
+
+-- 2. 创建 AI 调用用量日志表
+CREATE TABLE IF NOT EXISTS ai_usage_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    model_id INTEGER,                       -- AI 模型ID（模型删除后置空）
+    provider_id INTEGER,                    -- AI 提供商ID（提供商删除后置空）
+    model_name TEXT NOT NULL,               -- 调用时的模型名称
+    provider_name TEXT NOT NULL,            -- 调用时的提供商名称
+    purpose TEXT NOT NULL CHECK (purpose IN ('extraction', 'batch_phonics', 'phonics', 'plan_generation', 'test')),
+    prompt_tokens INTEGER,                  -- 输入 token 数
+    completion_tokens INTEGER,              -- 输出 token 数
+    tokens_estimated BOOLEAN NOT NULL DEFAULT 0, -- token 数是否为估算值（流式响应不返回用量）
+    latency_ms INTEGER NOT NULL,            -- 调用耗时（毫秒）
+    success BOOLEAN NOT NULL DEFAULT 1,     -- 调用是否成功
+    estimated_cost REAL,                    -- 按调用时价格估算的费用（美元）
+    created_at TEXT DEFAULT (datetime('now')),
+    FOREIGN KEY (model_id) REFERENCES ai_models (id) ON DELETE SET NULL,
+    FOREIGN KEY (provider_id) REFERENCES ai_providers (id) ON DELETE SET NULL
+);
+
+-- 3. 创建索引
+CREATE INDEX IF NOT EXISTS idx_ai_usage_log_created_at ON ai_usage_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_ai_usage_log_purpose ON ai_usage_log(purpose);
@@ -0,0 +1,19 @@
+-- 添加提示词版本历史
+-- 提示词模板可在应用数据目录的 prompts 目录中自定义（未自定义时使用内置模板），
+-- 每个用过的模板版本（内容哈希）连同内容保存一份，便于追溯分析结果和学习计划由哪个版本生成
+
+-- 1. 创建提示词版本表
+CREATE TABLE IF NOT EXISTS prompt_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    prompt_name TEXT NOT NULL,              -- 提示词名称（word_extraction / phonics / batch_phonics / study_plan）
+    version TEXT NOT NULL,                  -- 版本号（内容 SHA-256 前 12 位）
+    content TEXT NOT NULL,                  -- 模板内容
+    source TEXT NOT NULL DEFAULT 'default' CHECK (source IN ('default', 'custom')),
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(prompt_name, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_prompt_versions_name_created ON prompt_versions(prompt_name, created_at);
+
+-- 2. 记录批量分析任务使用的提示词版本
+ALTER TABLE analysis_jobs ADD COLUMN prompt_version TEXT;
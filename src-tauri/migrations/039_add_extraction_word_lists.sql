@@ -0,0 +1,13 @@
+-- 添加本地单词提取的用户词表
+-- stop: 重点模式下额外排除的停用词（补充内置停用词）
+-- known: 用户已掌握的单词（按原形保存，任何提取模式都会排除）
+
+CREATE TABLE IF NOT EXISTS extraction_word_lists (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    list_type TEXT NOT NULL CHECK (list_type IN ('stop', 'known')),
+    word TEXT NOT NULL,                     -- 小写单词
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(list_type, word)
+);
+
+CREATE INDEX IF NOT EXISTS idx_extraction_word_lists_type ON extraction_word_lists(list_type);
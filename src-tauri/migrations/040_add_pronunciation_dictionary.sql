@@ -0,0 +1,14 @@
+-- 添加离线发音词典（CMUdict 格式导入）
+-- 按 ARPABET 保存读音，查询时再转换为美式或英式 IPA
+-- 同一单词的多个读音用 variant 区分（CMUdict 中 READ(1) 的 variant 为 1）
+
+CREATE TABLE IF NOT EXISTS pronunciation_dictionary (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    word TEXT NOT NULL,                     -- 小写单词
+    variant INTEGER NOT NULL DEFAULT 0,     -- 读音序号，默认读音为 0
+    arpabet TEXT NOT NULL,                  -- ARPABET 音素，以空格分隔
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(word, variant)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pronunciation_dictionary_word ON pronunciation_dictionary(word);
@@ -0,0 +1,14 @@
+-- 添加数据库自动备份设置
+-- 备份文件（数据库 + TTS 发音缓存）保存在应用数据目录的 backups 目录中，
+-- 自动备份按间隔执行，只保留最近 retention_count 个自动备份，手动备份不会被清理
+
+CREATE TABLE IF NOT EXISTS backup_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),  -- 单行配置
+    enabled BOOLEAN NOT NULL DEFAULT 1,     -- 是否启用自动备份
+    interval_hours INTEGER NOT NULL DEFAULT 24 CHECK (interval_hours > 0),
+    retention_count INTEGER NOT NULL DEFAULT 7 CHECK (retention_count > 0),
+    last_backup_at TEXT,                    -- 最近一次备份时间（RFC 3339）
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO backup_settings (id) VALUES (1);
@@ -0,0 +1,29 @@
+-- 添加破坏性操作快照（用于撤销）
+-- 重置数据、删除单词本/学习计划、批量移除计划单词之前，把受影响的行（包括会被级联删除的行）
+-- 以 JSON 保存一份，撤销时按主键写回；只保留最近的若干个快照
+
+-- 1. 快照主表
+CREATE TABLE IF NOT EXISTS operation_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    operation TEXT NOT NULL,                -- reset_user_data / reset_selected_tables / delete_word_book / delete_study_plan / remove_plan_words
+    target_id INTEGER,                      -- 操作对象 ID（单词本或学习计划）
+    description TEXT NOT NULL,
+    row_count INTEGER NOT NULL DEFAULT 0,   -- 快照中的总行数
+    created_at TEXT DEFAULT (datetime('now')),
+    undone_at TEXT                          -- 撤销时间，未撤销为 NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_operation_snapshots_undone ON operation_snapshots(undone_at, id);
+
+-- 2. 每张表的快照数据
+CREATE TABLE IF NOT EXISTS operation_snapshot_tables (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    snapshot_id INTEGER NOT NULL,
+    table_name TEXT NOT NULL,
+    columns TEXT NOT NULL,                  -- 快照时的列名（JSON 数组）
+    row_count INTEGER NOT NULL,
+    rows_json TEXT NOT NULL,                -- 行数据（JSON 对象数组）
+    FOREIGN KEY (snapshot_id) REFERENCES operation_snapshots(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_operation_snapshot_tables_snapshot ON operation_snapshot_tables(snapshot_id);
@@ -0,0 +1,40 @@
+-- 添加单词全文索引（FTS5）
+-- 覆盖所有单词本的 word / meaning / description / phonics_rule / analysis_explanation，
+-- 使用 trigram 分词以支持中文释义和单词内部的子串匹配；索引内容来自 words 表，由触发器保持同步
+
+-- 1. 全文索引表（外部内容表，不重复保存文本）
+CREATE VIRTUAL TABLE IF NOT EXISTS words_fts USING fts5(
+    word,
+    meaning,
+    description,
+    phonics_rule,
+    analysis_explanation,
+    content = 'words',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+
+-- 2. 同步触发器
+CREATE TRIGGER IF NOT EXISTS words_fts_after_insert AFTER INSERT ON words
+BEGIN
+    INSERT INTO words_fts (rowid, word, meaning, description, phonics_rule, analysis_explanation)
+    VALUES (new.id, new.word, new.meaning, new.description, new.phonics_rule, new.analysis_explanation);
+END;
+
+CREATE TRIGGER IF NOT EXISTS words_fts_after_delete AFTER DELETE ON words
+BEGIN
+    INSERT INTO words_fts (words_fts, rowid, word, meaning, description, phonics_rule, analysis_explanation)
+    VALUES ('delete', old.id, old.word, old.meaning, old.description, old.phonics_rule, old.analysis_explanation);
+END;
+
+CREATE TRIGGER IF NOT EXISTS words_fts_after_update
+AFTER UPDATE OF word, meaning, description, phonics_rule, analysis_explanation ON words
+BEGIN
+    INSERT INTO words_fts (words_fts, rowid, word, meaning, description, phonics_rule, analysis_explanation)
+    VALUES ('delete', old.id, old.word, old.meaning, old.description, old.phonics_rule, old.analysis_explanation);
+    INSERT INTO words_fts (rowid, word, meaning, description, phonics_rule, analysis_explanation)
+    VALUES (new.id, new.word, new.meaning, new.description, new.phonics_rule, new.analysis_explanation);
+END;
+
+-- 3. 为已有单词建立索引
+INSERT INTO words_fts (words_fts) VALUES ('rebuild');
@@ -0,0 +1,5 @@
+-- 添加学习计划暂停支持
+-- 暂停期间冻结逾期统计（暂停日之后的日程不算逾期），恢复时未完成的日程按暂停天数整体后移
+
+-- 1. 暂停时间（UTC，未暂停为 NULL）
+ALTER TABLE study_plans ADD COLUMN paused_at TEXT;
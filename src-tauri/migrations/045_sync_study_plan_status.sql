@@ -0,0 +1,16 @@
+-- 学习计划状态统一由 unified_status 驱动
+-- 管理状态 status 由统一状态推导（Draft -> draft，Deleted -> deleted，其余为 normal），
+-- 修复历史数据中两者不一致的记录（例如编辑后仍为 normal 的草稿）
+
+UPDATE study_plans
+SET status = CASE unified_status
+        WHEN 'Draft' THEN 'draft'
+        WHEN 'Deleted' THEN 'deleted'
+        ELSE 'normal'
+    END
+WHERE unified_status IS NOT NULL
+  AND status IS NOT CASE unified_status
+        WHEN 'Draft' THEN 'draft'
+        WHEN 'Deleted' THEN 'deleted'
+        ELSE 'normal'
+    END;
@@ -0,0 +1,17 @@
+-- 添加学习计划模板
+-- 保存常用的计划设置（强度、周期、复习频率）和日程形状（每天新词占比），
+-- 生成学习计划时可以从模板开始，复制计划时按源计划的日程形状重新安排新单词本
+
+CREATE TABLE IF NOT EXISTS study_plan_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL DEFAULT '',
+    intensity_level TEXT NOT NULL,          -- easy / normal / intensive
+    study_period_days INTEGER NOT NULL,     -- 1 / 3 / 7 / 14 / 28
+    review_frequency INTEGER NOT NULL,
+    day_weights TEXT NOT NULL DEFAULT '[]', -- 每天新词数的相对比例（JSON 数组），为空表示平均分配
+    source_plan_id INTEGER,                 -- 从学习计划保存时的源计划（计划删除后保留模板）
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now')),
+    FOREIGN KEY (source_plan_id) REFERENCES study_plans(id) ON DELETE SET NULL
+);
@@ -0,0 +1,11 @@
+-- 区分撤销时可以覆盖的快照行
+-- 软删除和外键置空的行在操作后仍然存在，撤销时按主键覆盖；
+-- 其余行已被物理删除，撤销时重新插入，相同主键已被新数据占用时拒绝撤销而不是覆盖新数据
+
+ALTER TABLE operation_snapshot_tables ADD COLUMN keeps_rows BOOLEAN NOT NULL DEFAULT 0;
+
+-- 已有的软删除快照保持覆盖写回
+UPDATE operation_snapshot_tables SET keeps_rows = 1
+WHERE snapshot_id IN (
+    SELECT id FROM operation_snapshots WHERE operation IN ('delete_word_book', 'delete_study_plan')
+);
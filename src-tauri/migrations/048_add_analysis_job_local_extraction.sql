@@ -0,0 +1,4 @@
+-- 记录批量分析任务使用的单词提取方式
+-- 默认由 AI 模型提取，local_extraction 为 1 时在本地提取，恢复任务时沿用同一方式
+
+ALTER TABLE analysis_jobs ADD COLUMN local_extraction BOOLEAN NOT NULL DEFAULT 0;
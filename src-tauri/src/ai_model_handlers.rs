@@ -1,7 +1,10 @@
 use crate::error::{AppError, AppResult};
+use crate::llm_provider::LlmProviderType;
 use crate::logger::Logger;
+use crate::repositories::ai_usage_repository::AIUsageRepository;
 use crate::types::*;
 use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
 /// 获取所有AI提供商（仅活跃的，不包含敏感信息）
@@ -13,7 +16,7 @@ pub async fn get_ai_providers(app: AppHandle) -> AppResult<Vec<AIProviderSafe>>
     logger.api_request("get_ai_providers", None);
 
     let query = r#"
-        SELECT id, name, display_name, base_url, api_key, description, is_active, created_at, updated_at
+        SELECT id, name, display_name, base_url, api_key, provider_type, description, is_active, created_at, updated_at
         FROM ai_providers
         WHERE is_active = 1
         ORDER BY display_name
@@ -47,6 +50,7 @@ pub async fn get_ai_providers(app: AppHandle) -> AppResult<Vec<AIProviderSafe>>
                 display_name: row.get("display_name"),
                 base_url: row.get("base_url"),
                 api_key: row.get("api_key"),
+                provider_type: row.get("provider_type"),
                 description: row.get("description"),
                 is_active: row.get("is_active"),
                 created_at: row.get("created_at"),
@@ -74,7 +78,7 @@ pub async fn get_all_ai_providers(app: AppHandle) -> AppResult<Vec<AIProviderSaf
     logger.api_request("get_all_ai_providers", None);
 
     let query = r#"
-        SELECT id, name, display_name, base_url, api_key, description, is_active, created_at, updated_at
+        SELECT id, name, display_name, base_url, api_key, provider_type, description, is_active, created_at, updated_at
         FROM ai_providers
         ORDER BY is_active DESC, display_name
     "#;
@@ -109,6 +113,7 @@ pub async fn get_all_ai_providers(app: AppHandle) -> AppResult<Vec<AIProviderSaf
                 display_name: row.get("display_name"),
                 base_url: row.get("base_url"),
                 api_key: row.get("api_key"),
+                provider_type: row.get("provider_type"),
                 description: row.get("description"),
                 is_active: row.get("is_active"),
                 created_at: row.get("created_at"),
@@ -149,10 +154,10 @@ pub async fn get_ai_models(
     let mut sql = r#"
         SELECT
             m.id, m.name, m.display_name, m.model_id, m.description,
-            m.max_tokens, m.temperature, m.is_active, m.is_default,
+            m.max_tokens, m.temperature, m.input_price_per_million, m.output_price_per_million, m.is_active, m.is_default,
             m.created_at, m.updated_at,
             p.id as provider_id, p.name as provider_name, p.display_name as provider_display_name,
-            p.base_url, p.api_key, p.description as provider_description,
+            p.base_url, p.api_key, p.provider_type, p.description as provider_description,
             p.is_active as provider_is_active, p.created_at as provider_created_at,
             p.updated_at as provider_updated_at
         FROM ai_models m
@@ -204,6 +209,8 @@ pub async fn get_ai_models(
                 description: row.get("description"),
                 max_tokens: row.get("max_tokens"),
                 temperature: row.get("temperature"),
+                input_price_per_million: row.get("input_price_per_million"),
+                output_price_per_million: row.get("output_price_per_million"),
                 is_active: row.get("is_active"),
                 is_default: row.get("is_default"),
                 created_at: row.get("created_at"),
@@ -214,6 +221,7 @@ pub async fn get_ai_models(
                     display_name: row.get("provider_display_name"),
                     base_url: row.get("base_url"),
                     api_key: row.get("api_key"),
+                    provider_type: row.get("provider_type"),
                     description: row.get("provider_description"),
                     is_active: row.get("provider_is_active"),
                     created_at: row.get("provider_created_at"),
@@ -252,10 +260,10 @@ pub async fn get_all_ai_models(
     let mut sql = r#"
         SELECT
             m.id, m.name, m.display_name, m.model_id, m.description,
-            m.max_tokens, m.temperature, m.is_active, m.is_default,
+            m.max_tokens, m.temperature, m.input_price_per_million, m.output_price_per_million, m.is_active, m.is_default,
             m.created_at, m.updated_at,
             p.id as provider_id, p.name as provider_name, p.display_name as provider_display_name,
-            p.base_url, p.api_key, p.description as provider_description,
+            p.base_url, p.api_key, p.provider_type, p.description as provider_description,
             p.is_active as provider_is_active, p.created_at as provider_created_at,
             p.updated_at as provider_updated_at
         FROM ai_models m
@@ -309,6 +317,8 @@ pub async fn get_all_ai_models(
             description: row.get("description"),
             max_tokens: row.get("max_tokens"),
             temperature: row.get("temperature"),
+            input_price_per_million: row.get("input_price_per_million"),
+            output_price_per_million: row.get("output_price_per_million"),
             is_active: row.get("is_active"),
             is_default: row.get("is_default"),
             created_at: row.get("created_at"),
@@ -319,6 +329,7 @@ pub async fn get_all_ai_models(
                 display_name: row.get("provider_display_name"),
                 base_url: row.get("base_url"),
                 api_key: row.get("api_key"),
+                provider_type: row.get("provider_type"),
                 description: row.get("provider_description"),
                 is_active: row.get("provider_is_active"),
                 created_at: row.get("provider_created_at"),
@@ -349,10 +360,10 @@ pub async fn get_default_ai_model(app: AppHandle) -> AppResult<Option<AIModelCon
     let query = r#"
         SELECT
             m.id, m.name, m.display_name, m.model_id, m.description,
-            m.max_tokens, m.temperature, m.is_active, m.is_default,
+            m.max_tokens, m.temperature, m.input_price_per_million, m.output_price_per_million, m.is_active, m.is_default,
             m.created_at, m.updated_at,
             p.id as provider_id, p.name as provider_name, p.display_name as provider_display_name,
-            p.base_url, p.api_key, p.description as provider_description,
+            p.base_url, p.api_key, p.provider_type, p.description as provider_description,
             p.is_active as provider_is_active, p.created_at as provider_created_at,
             p.updated_at as provider_updated_at
         FROM ai_models m
@@ -388,6 +399,8 @@ pub async fn get_default_ai_model(app: AppHandle) -> AppResult<Option<AIModelCon
             description: row.get("description"),
             max_tokens: row.get("max_tokens"),
             temperature: row.get("temperature"),
+            input_price_per_million: row.get("input_price_per_million"),
+            output_price_per_million: row.get("output_price_per_million"),
             is_active: row.get("is_active"),
             is_default: row.get("is_default"),
             created_at: row.get("created_at"),
@@ -398,6 +411,7 @@ pub async fn get_default_ai_model(app: AppHandle) -> AppResult<Option<AIModelCon
                 display_name: row.get("provider_display_name"),
                 base_url: row.get("base_url"),
                 api_key: row.get("api_key"),
+                provider_type: row.get("provider_type"),
                 description: row.get("provider_description"),
                 is_active: row.get("provider_is_active"),
                 created_at: row.get("provider_created_at"),
@@ -502,6 +516,7 @@ pub async fn create_ai_provider(
     display_name: String,
     base_url: String,
     api_key: String,
+    provider_type: Option<String>,
     description: Option<String>,
 ) -> AppResult<Id> {
     let pool = app.state::<SqlitePool>();
@@ -522,9 +537,19 @@ pub async fn create_ai_provider(
         return Err(AppError::ValidationError(error_msg.to_string()));
     }
 
+    let provider_type = match provider_type.as_deref().map(LlmProviderType::parse) {
+        None => LlmProviderType::OpenAiCompatible,
+        Some(Some(provider_type)) => provider_type,
+        Some(None) => {
+            let error_msg = format!("Invalid provider type: {}", provider_type.unwrap_or_default());
+            logger.api_response("create_ai_provider", false, Some(&error_msg));
+            return Err(AppError::ValidationError(error_msg));
+        }
+    };
+
     let query = r#"
-        INSERT INTO ai_providers (name, display_name, base_url, api_key, description, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+        INSERT INTO ai_providers (name, display_name, base_url, api_key, provider_type, description, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
     "#;
 
     let result = match sqlx::query(query)
@@ -532,6 +557,7 @@ pub async fn create_ai_provider(
         .bind(&display_name)
         .bind(&base_url)
         .bind(&api_key)
+        .bind(provider_type.as_str())
         .bind(&description)
         .execute(pool.inner())
         .await
@@ -567,14 +593,18 @@ pub async fn create_ai_provider(
 pub async fn update_ai_provider(
     app: AppHandle,
     provider_id: Id,
-    display_name: Option<String>,
-    base_url: Option<String>,
-    api_key: Option<String>,
-    description: Option<String>,
-    is_active: Option<bool>,
+    request: UpdateAIProviderRequest,
 ) -> AppResult<()> {
     let pool = app.state::<SqlitePool>();
     let logger = app.state::<Logger>();
+    let UpdateAIProviderRequest {
+        display_name,
+        base_url,
+        api_key,
+        provider_type,
+        description,
+        is_active,
+    } = request;
 
     logger.api_request(
         "update_ai_provider",
@@ -597,6 +627,21 @@ pub async fn update_ai_provider(
         query_parts.push("api_key = ?");
         has_updates = true;
     }
+    let provider_type = match provider_type {
+        Some(value) => match LlmProviderType::parse(&value) {
+            Some(provider_type) => {
+                query_parts.push("provider_type = ?");
+                has_updates = true;
+                Some(provider_type)
+            }
+            None => {
+                let error_msg = format!("Invalid provider type: {}", value);
+                logger.api_response("update_ai_provider", false, Some(&error_msg));
+                return Err(AppError::ValidationError(error_msg));
+            }
+        },
+        None => None,
+    };
     if description.is_some() {
         query_parts.push("description = ?");
         has_updates = true;
@@ -629,6 +674,9 @@ pub async fn update_ai_provider(
     if let Some(api_key_val) = &api_key {
         query = query.bind(api_key_val);
     }
+    if let Some(provider_type_val) = provider_type {
+        query = query.bind(provider_type_val.as_str());
+    }
     if let Some(description_val) = &description {
         query = query.bind(description_val);
     }
@@ -1027,10 +1075,10 @@ pub async fn analyze_phonics_with_model(
 
         // 通过数字 ID 查询指定模型
         let query = r#"
-            SELECT m.id, m.model_id, m.display_name, m.description, m.max_tokens, m.temperature, m.is_active, m.is_default,
+            SELECT m.id, m.model_id, m.display_name, m.description, m.max_tokens, m.temperature, m.input_price_per_million, m.output_price_per_million, m.is_active, m.is_default,
                    m.created_at, m.updated_at,
                    p.id as provider_id, p.name as provider_name, p.display_name as provider_display_name,
-                   p.base_url, p.api_key, p.description as provider_description, p.is_active as provider_is_active,
+                   p.base_url, p.api_key, p.provider_type, p.description as provider_description, p.is_active as provider_is_active,
                    p.created_at as provider_created_at, p.updated_at as provider_updated_at
             FROM ai_models m
             JOIN ai_providers p ON m.provider_id = p.id
@@ -1063,6 +1111,8 @@ pub async fn analyze_phonics_with_model(
             description: row.get("description"),
             max_tokens: row.get("max_tokens"),
             temperature: row.get("temperature"),
+            input_price_per_million: row.get("input_price_per_million"),
+            output_price_per_million: row.get("output_price_per_million"),
             is_active: row.get("is_active"),
             is_default: row.get("is_default"),
             created_at: row.get("created_at"),
@@ -1073,6 +1123,7 @@ pub async fn analyze_phonics_with_model(
                 display_name: row.get("provider_display_name"),
                 base_url: row.get("base_url"),
                 api_key: row.get("api_key"),
+                provider_type: row.get("provider_type"),
                 description: row.get("provider_description"),
                 is_active: row.get("provider_is_active"),
                 created_at: row.get("provider_created_at"),
@@ -1081,7 +1132,10 @@ pub async fn analyze_phonics_with_model(
         };
 
         let service = match AIService::from_model_config(&model_config) {
-            Ok(service) => service,
+            Ok(service) => service.with_usage_log(AIUsageRepository::new(
+                Arc::new(pool.inner().clone()),
+                Arc::new(logger.inner().clone()),
+            )),
             Err(e) => {
                 let error_msg = format!("Failed to create AI service from model config: {}", e);
                 logger.api_response("analyze_phonics_with_model", false, Some(&error_msg));
@@ -1092,10 +1146,10 @@ pub async fn analyze_phonics_with_model(
     } else {
         // 使用默认模型
         let query = r#"
-            SELECT m.id, m.model_id, m.display_name, m.description, m.max_tokens, m.temperature, m.is_active, m.is_default,
+            SELECT m.id, m.model_id, m.display_name, m.description, m.max_tokens, m.temperature, m.input_price_per_million, m.output_price_per_million, m.is_active, m.is_default,
                    m.created_at, m.updated_at,
                    p.id as provider_id, p.name as provider_name, p.display_name as provider_display_name,
-                   p.base_url, p.api_key, p.description as provider_description, p.is_active as provider_is_active,
+                   p.base_url, p.api_key, p.provider_type, p.description as provider_description, p.is_active as provider_is_active,
                    p.created_at as provider_created_at, p.updated_at as provider_updated_at
             FROM ai_models m
             JOIN ai_providers p ON m.provider_id = p.id
@@ -1125,6 +1179,8 @@ pub async fn analyze_phonics_with_model(
             description: row.get("description"),
             max_tokens: row.get("max_tokens"),
             temperature: row.get("temperature"),
+            input_price_per_million: row.get("input_price_per_million"),
+            output_price_per_million: row.get("output_price_per_million"),
             is_active: row.get("is_active"),
             is_default: row.get("is_default"),
             created_at: row.get("created_at"),
@@ -1135,6 +1191,7 @@ pub async fn analyze_phonics_with_model(
                 display_name: row.get("provider_display_name"),
                 base_url: row.get("base_url"),
                 api_key: row.get("api_key"),
+                provider_type: row.get("provider_type"),
                 description: row.get("provider_description"),
                 is_active: row.get("provider_is_active"),
                 created_at: row.get("provider_created_at"),
@@ -1143,7 +1200,10 @@ pub async fn analyze_phonics_with_model(
         };
 
         let service = match AIService::from_model_config(&model_config) {
-            Ok(service) => service,
+            Ok(service) => service.with_usage_log(AIUsageRepository::new(
+                Arc::new(pool.inner().clone()),
+                Arc::new(logger.inner().clone()),
+            )),
             Err(e) => {
                 let error_msg = format!("Failed to create AI service from default model: {}", e);
                 logger.api_response("analyze_phonics_with_model", false, Some(&error_msg));
@@ -1245,10 +1305,10 @@ pub async fn test_ai_model(
 
     // 查询模型配置
     let query = r#"
-        SELECT m.id, m.model_id, m.display_name, m.description, m.max_tokens, m.temperature, m.is_active, m.is_default,
+        SELECT m.id, m.model_id, m.display_name, m.description, m.max_tokens, m.temperature, m.input_price_per_million, m.output_price_per_million, m.is_active, m.is_default,
                m.created_at, m.updated_at,
                p.id as provider_id, p.name as provider_name, p.display_name as provider_display_name,
-               p.base_url, p.api_key, p.description as provider_description, p.is_active as provider_is_active,
+               p.base_url, p.api_key, p.provider_type, p.description as provider_description, p.is_active as provider_is_active,
                p.created_at as provider_created_at, p.updated_at as provider_updated_at
         FROM ai_models m
         JOIN ai_providers p ON m.provider_id = p.id
@@ -1281,6 +1341,8 @@ pub async fn test_ai_model(
         description: row.get("description"),
         max_tokens: row.get("max_tokens"),
         temperature: row.get("temperature"),
+        input_price_per_million: row.get("input_price_per_million"),
+        output_price_per_million: row.get("output_price_per_million"),
         is_active: row.get("is_active"),
         is_default: row.get("is_default"),
         created_at: row.get("created_at"),
@@ -1291,6 +1353,7 @@ pub async fn test_ai_model(
             display_name: row.get("provider_display_name"),
             base_url: row.get("base_url"),
             api_key: row.get("api_key"),
+            provider_type: row.get("provider_type"),
             description: row.get("provider_description"),
             is_active: row.get("provider_is_active"),
             created_at: row.get("provider_created_at"),
@@ -1308,7 +1371,10 @@ pub async fn test_ai_model(
 
     // 创建AI服务
     let ai_service = match AIService::from_model_config(&model_config) {
-        Ok(service) => service,
+        Ok(service) => service.with_usage_log(AIUsageRepository::new(
+            Arc::new(pool.inner().clone()),
+            Arc::new(logger.inner().clone()),
+        )),
         Err(e) => {
             let error_msg = format!("Failed to create AI service from model config: {}", e);
             logger.api_response("test_ai_model", false, Some(&error_msg));
@@ -1357,3 +1423,89 @@ pub async fn test_ai_model(
         }
     }
 }
+
+/// 配置AI模型价格（每百万 token 美元价格）
+#[tauri::command]
+pub async fn update_ai_model_pricing(
+    app: AppHandle,
+    model_id: Id,
+    input_price_per_million: Option<f64>,
+    output_price_per_million: Option<f64>,
+) -> AppResult<()> {
+    use crate::services::ai_model::AIModelService;
+
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "update_ai_model_pricing",
+        Some(&format!(
+            "model_id: {}, input: {:?}, output: {:?}",
+            model_id, input_price_per_million, output_price_per_million
+        )),
+    );
+
+    let service = AIModelService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+
+    match service
+        .update_model_pricing(model_id, input_price_per_million, output_price_per_million)
+        .await
+    {
+        Ok(()) => {
+            logger.api_response("update_ai_model_pricing", true, Some("Pricing updated"));
+            Ok(())
+        }
+        Err(e) => {
+            logger.api_response("update_ai_model_pricing", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 获取AI用量报表（按日/按月，并按提供商和功能拆分）
+#[tauri::command]
+pub async fn get_ai_usage_report(
+    app: AppHandle,
+    granularity: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> AppResult<AIUsageReport> {
+    use crate::services::ai_usage::AIUsageService;
+
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "get_ai_usage_report",
+        Some(&format!(
+            "granularity: {:?}, start_date: {:?}, end_date: {:?}",
+            granularity, start_date, end_date
+        )),
+    );
+
+    let service = AIUsageService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+
+    match service
+        .get_usage_report(granularity.as_deref(), start_date.as_deref(), end_date.as_deref())
+        .await
+    {
+        Ok(report) => {
+            logger.api_response(
+                "get_ai_usage_report",
+                true,
+                Some(&format!("{} calls", report.total.calls)),
+            );
+            Ok(report)
+        }
+        Err(e) => {
+            logger.api_response("get_ai_usage_report", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
@@ -1,18 +1,38 @@
-use crate::logger::Logger;
-use crate::types::AIModelConfig;
-use async_openai::{
-    config::OpenAIConfig,
-    types::{
-        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
-        CreateChatCompletionRequestArgs, Role,
-    },
-    Client,
+use crate::llm_provider::{
+    create_provider, LlmMessage, LlmProvider, LlmProviderType, LlmRequest, LlmResponse,
+    LlmResult, LlmStreamEvent, ResponseSchema,
 };
+use crate::logger::Logger;
+use crate::prompt_registry::get_prompt_registry;
+use crate::repositories::ai_usage_repository::AIUsageRepository;
+use crate::stream_parser::{CsvLineStream, JsonArrayStream};
+use crate::types::{AIModelConfig, AIUsagePurpose, AIUsageRecord, PromptName, PromptTemplate};
+use crate::word_extractor::STOP_WORD_GROUPS;
 use futures_util::StreamExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// 流式请求检查取消状态的间隔
+const STREAM_CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 流式请求被取消时返回的错误信息
+pub const STREAM_CANCELLED_MESSAGE: &str = "请求已取消";
+
+/// 自然拼读分析结构化输出的工具名
+const PHONICS_RESULT_TOOL: &str = "submit_phonics_analysis";
+
+/// 学习计划结构化输出的工具名
+const STUDY_PLAN_RESULT_TOOL: &str = "submit_study_plan";
+
+/// 批量自然拼读分析提示词版本
+///
+/// 取当前生效模板内容的哈希，修改提示词后旧的分析缓存自动失效
+pub fn batch_phonics_prompt_version() -> String {
+    get_prompt_registry().load(PromptName::BatchPhonics).version
+}
 
 /// 分析进度状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +158,7 @@ pub struct AIProvider {
     pub name: String,
     pub base_url: String,
     pub api_key: String,
+    pub provider_type: LlmProviderType,
     pub default_model: String,
 }
 
@@ -184,22 +205,36 @@ impl CsvPhonicsRecord {
 /// 通用 AI 服务
 pub struct AIService {
     provider: AIProvider,
-    client: Client<OpenAIConfig>,
+    llm: Arc<dyn LlmProvider>,
+    /// 数据库模型配置（用于用量记录和费用估算）
+    model_config: Option<AIModelConfig>,
+    /// 用量记录仓储，未设置时只写日志
+    usage_log: Option<AIUsageRepository>,
 }
 
 impl AIService {
     /// 使用指定提供商创建 AI 服务
     pub fn new(provider: AIProvider) -> Self {
-        let config = OpenAIConfig::new()
-            .with_api_key(&provider.api_key)
-            .with_api_base(&provider.base_url);
+        let llm = create_provider(provider.provider_type, &provider.base_url, &provider.api_key);
+        Self::with_llm_provider(provider, llm)
+    }
 
+    /// 使用自定义 LLM 提供商创建 AI 服务（用于测试注入 Mock）
+    pub fn with_llm_provider(provider: AIProvider, llm: Arc<dyn LlmProvider>) -> Self {
         Self {
             provider,
-            client: Client::with_config(config),
+            llm,
+            model_config: None,
+            usage_log: None,
         }
     }
 
+    /// 启用用量记录，每次 LLM 调用写入 ai_usage_log
+    pub fn with_usage_log(mut self, usage_log: AIUsageRepository) -> Self {
+        self.usage_log = Some(usage_log);
+        self
+    }
+
     /// 从数据库模型配置创建 AI 服务
     pub fn from_model_config(
         model_config: &AIModelConfig,
@@ -208,10 +243,274 @@ impl AIService {
             name: model_config.provider.name.clone(),
             base_url: model_config.provider.base_url.clone(),
             api_key: model_config.provider.api_key.clone(),
+            provider_type: LlmProviderType::from_db(&model_config.provider.provider_type),
             default_model: model_config.model_id.clone(),
         };
 
-        Ok(Self::new(provider))
+        let mut service = Self::new(provider);
+        service.model_config = Some(model_config.clone());
+        Ok(service)
+    }
+
+    /// 发送非流式请求并记录用量（失败的调用同样记录）
+    async fn complete_with_usage(
+        &self,
+        purpose: AIUsagePurpose,
+        request: LlmRequest,
+        logger: &Logger,
+    ) -> LlmResult<LlmResponse> {
+        let started_at = Instant::now();
+        let result = self.llm.complete(request).await;
+        match &result {
+            Ok(response) => {
+                self.record_usage(
+                    purpose,
+                    response.prompt_tokens,
+                    response.completion_tokens,
+                    false,
+                    started_at,
+                    true,
+                    logger,
+                )
+                .await
+            }
+            Err(_) => {
+                self.record_usage(purpose, None, None, false, started_at, false, logger)
+                    .await
+            }
+        }
+        result
+    }
+
+    /// 发送流式请求并记录用量，每收到一段增量文本回调 `on_delta`，返回完整内容
+    ///
+    /// `is_cancelled` 返回 true 时丢弃流（中断底层 HTTP 连接）并返回错误；
+    /// 记录提供商在流中返回的 token 用量，未返回时按字符数估算
+    async fn stream_with_usage(
+        &self,
+        purpose: AIUsagePurpose,
+        request: LlmRequest,
+        logger: &Logger,
+        is_cancelled: &(dyn Fn() -> bool + Sync),
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> LlmResult<String> {
+        let estimated_prompt_tokens = request
+            .messages
+            .iter()
+            .map(|message| Self::estimate_tokens(&message.content))
+            .sum();
+        let started_at = Instant::now();
+
+        let mut stream = match self.llm.complete_stream(request).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.record_usage(
+                    purpose,
+                    Some(estimated_prompt_tokens),
+                    None,
+                    true,
+                    started_at,
+                    false,
+                    logger,
+                )
+                .await;
+                return Err(e);
+            }
+        };
+
+        let mut content = String::new();
+        let mut prompt_tokens = None;
+        let mut completion_tokens = None;
+        let outcome: LlmResult<()> = loop {
+            if is_cancelled() {
+                break Err(STREAM_CANCELLED_MESSAGE.into());
+            }
+            tokio::select! {
+                item = stream.next() => match item {
+                    Some(Ok(LlmStreamEvent::Delta(delta))) => {
+                        content.push_str(&delta);
+                        on_delta(&delta);
+                    }
+                    Some(Ok(LlmStreamEvent::Usage { prompt_tokens: prompt, completion_tokens: completion })) => {
+                        prompt_tokens = prompt.or(prompt_tokens);
+                        completion_tokens = completion.or(completion_tokens);
+                    }
+                    Some(Err(e)) => break Err(e),
+                    None => break Ok(()),
+                },
+                _ = tokio::time::sleep(STREAM_CANCEL_POLL_INTERVAL) => {}
+            }
+        };
+        // 丢弃流即中断 HTTP 请求，取消后不再继续接收
+        drop(stream);
+
+        // 提供商未返回（或取消时尚未返回）的用量按字符数估算
+        let tokens_estimated = prompt_tokens.is_none() || completion_tokens.is_none();
+        self.record_usage(
+            purpose,
+            Some(prompt_tokens.unwrap_or(estimated_prompt_tokens)),
+            Some(completion_tokens.unwrap_or_else(|| Self::estimate_tokens(&content))),
+            tokens_estimated,
+            started_at,
+            outcome.is_ok(),
+            logger,
+        )
+        .await;
+
+        if outcome.is_err() && is_cancelled() {
+            logger.info(
+                "AI_SERVICE",
+                &format!("🚫 {} stream aborted after {} chars", purpose.as_str(), content.len()),
+            );
+        }
+
+        outcome.map(|_| content)
+    }
+
+    /// 记录 LLM 调用的 token 用量、耗时和估算费用
+    #[allow(clippy::too_many_arguments)]
+    async fn record_usage(
+        &self,
+        purpose: AIUsagePurpose,
+        prompt_tokens: Option<u32>,
+        completion_tokens: Option<u32>,
+        tokens_estimated: bool,
+        started_at: Instant,
+        success: bool,
+        logger: &Logger,
+    ) {
+        logger.info(
+            "AI_SERVICE",
+            &format!(
+                "📊 {} token usage via {} - prompt: {:?}, completion: {:?}{}",
+                purpose.as_str(),
+                self.llm.provider_type().as_str(),
+                prompt_tokens,
+                completion_tokens,
+                if tokens_estimated { " (estimated)" } else { "" }
+            ),
+        );
+
+        let Some(usage_log) = &self.usage_log else {
+            return;
+        };
+
+        let config = self.model_config.as_ref();
+        let estimated_cost = config.and_then(|c| {
+            if c.input_price_per_million.is_none() && c.output_price_per_million.is_none() {
+                return None;
+            }
+            let input = prompt_tokens.unwrap_or(0) as f64 * c.input_price_per_million.unwrap_or(0.0);
+            let output =
+                completion_tokens.unwrap_or(0) as f64 * c.output_price_per_million.unwrap_or(0.0);
+            Some((input + output) / 1_000_000.0)
+        });
+
+        let record = AIUsageRecord {
+            model_id: config.map(|c| c.id),
+            provider_id: config.map(|c| c.provider.id),
+            model_name: self.provider.get_default_model().to_string(),
+            provider_name: self.provider.name.clone(),
+            purpose,
+            prompt_tokens,
+            completion_tokens,
+            tokens_estimated,
+            latency_ms: started_at.elapsed().as_millis() as u64,
+            success,
+            estimated_cost,
+        };
+
+        if let Err(e) = usage_log.record(&record).await {
+            logger.error(
+                "AI_SERVICE",
+                "Failed to record AI usage",
+                Some(&e.to_string()),
+            );
+        }
+    }
+
+    /// 估算文本 token 数（约 4 个字符一个 token，用于未返回用量的流式请求）
+    fn estimate_tokens(text: &str) -> u32 {
+        text.chars().count().div_ceil(4) as u32
+    }
+
+    /// 从注册表读取当前生效的提示词模板
+    fn load_prompt(name: PromptName, logger: &Logger) -> PromptTemplate {
+        let template = get_prompt_registry().load(name);
+        logger.info(
+            "AI_SERVICE",
+            &format!(
+                "📄 Using {} prompt version {} ({})",
+                name.as_str(),
+                template.version,
+                template.source.as_str()
+            ),
+        );
+        if let Some(error) = &template.custom_error {
+            logger.info("AI_SERVICE", &format!("⚠️  {}，已使用内置模板", error));
+        }
+        template
+    }
+
+    /// 提供商支持时使用结构化输出，否则返回 None 使用启发式解析（记录选择的路径）
+    fn select_response_schema(
+        &self,
+        schema: ResponseSchema,
+        logger: &Logger,
+    ) -> Option<ResponseSchema> {
+        let provider_type = self.llm.provider_type().as_str();
+        if self.llm.supports_structured_output() {
+            logger.info(
+                "AI_SERVICE",
+                &format!(
+                    "🧩 Output path: JSON schema constrained ({}) via {}",
+                    schema.name, provider_type
+                ),
+            );
+            Some(schema)
+        } else {
+            logger.info(
+                "AI_SERVICE",
+                &format!(
+                    "🧩 Output path: heuristic parsing ({} does not support structured output for {})",
+                    provider_type, schema.name
+                ),
+            );
+            None
+        }
+    }
+
+    /// 结构化输出时在提示词末尾说明以 Schema 为准（提示词中的示例字段名可能不同）
+    fn with_schema_instruction(prompt: String, schema: Option<&ResponseSchema>) -> String {
+        match schema {
+            Some(schema) => format!(
+                "{}\n\n请调用 `{}` 工具提交结果，字段名和类型以工具参数的 JSON Schema 为准。",
+                prompt, schema.name
+            ),
+            None => prompt,
+        }
+    }
+
+    /// 重点模式的过滤规则（停用词与本地提取器共用同一份列表）
+    fn focus_filtering_instructions() -> String {
+        let groups: Vec<String> = STOP_WORD_GROUPS
+            .iter()
+            .map(|(category, words)| format!("- {}：{}", category, words.join(", ")))
+            .collect();
+
+        format!(
+            r#"
+
+**⚠️ 重点模式过滤规则（必须严格遵守）**
+在"第二步：单词筛选"阶段，**必须完全排除**以下类型的简单词汇，这些词汇不适合作为学习重点，**绝对不能出现在输出结果中**：
+
+**必须排除的词汇类型：**
+{}
+- 单字母词：a, I
+
+**严格禁止**：上述任何词汇都不得出现在最终的 CSV 输出中。如果文本中包含这些词汇，请直接跳过，不要统计频率，不要输出。"#,
+            groups.join("\n")
+        )
     }
 
     // 移除了传统词汇分析方法，只保留自然拼读分析
@@ -222,6 +521,21 @@ impl AIService {
         text: &str,
         extraction_mode: &str,
         logger: &Logger,
+    ) -> Result<crate::types::word_analysis::WordExtractionResult, Box<dyn std::error::Error>> {
+        self.extract_words_streaming(text, extraction_mode, logger, &|| false, &|_| {})
+            .await
+    }
+
+    /// 流式提取单词列表，每解析出一个单词立即回调 `on_word`
+    ///
+    /// `is_cancelled` 返回 true 时中断 HTTP 流并返回错误
+    pub async fn extract_words_streaming(
+        &self,
+        text: &str,
+        extraction_mode: &str,
+        logger: &Logger,
+        is_cancelled: &(dyn Fn() -> bool + Sync),
+        on_word: &(dyn Fn(&crate::types::word_analysis::ExtractedWord) + Sync),
     ) -> Result<crate::types::word_analysis::WordExtractionResult, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
 
@@ -234,36 +548,20 @@ impl AIService {
         );
 
         // 读取单词提取提示词
-        let extraction_prompt = include_str!("prompts/word_extraction_agent.md");
+        let extraction_prompt = Self::load_prompt(PromptName::WordExtraction, logger);
 
-        // 根据提取模式生成额外的过滤规则
+        // 根据提取模式生成额外的过滤规则（all 模式下不添加）
         let filtering_instructions = if extraction_mode == "focus" {
-            r#"
-
-**⚠️ 重点模式过滤规则（必须严格遵守）**
-在"第二步：单词筛选"阶段，**必须完全排除**以下类型的简单词汇，这些词汇不适合作为学习重点，**绝对不能出现在输出结果中**：
-
-**必须排除的词汇类型：**
-- 冠词：a, an, the
-- 基础代词：I, you, he, she, it, we, they, me, him, her, us, them
-- 基础be动词：am, is, are, was, were, be, been, being
-- 基础助动词：do, does, did, have, has, had, will, would, can, could, should, shall, may, might, must
-- 基础介词：in, on, at, to, for, of, with, by, from, up, out, off, over, under
-- 基础连词：and, or, but, so, if, when, then, than, as
-- 基础副词：not, no, yes, very, too, also, only, just, now, here, there
-- 单字母词：a, I
-- 过于简单的数字词：one, two, three, four, five, six, seven, eight, nine, ten
-
-**严格禁止**：上述任何词汇都不得出现在最终的 CSV 输出中。如果文本中包含这些词汇，请直接跳过，不要统计频率，不要输出。"#
+            Self::focus_filtering_instructions()
         } else {
-            // all 模式下不添加过滤规则
-            ""
+            String::new()
         };
 
         // 构建完整的提示词
         let system_message = extraction_prompt
+            .content
             .replace("{original_text}", text)
-            .replace("{filtering_instructions}", filtering_instructions);
+            .replace("{filtering_instructions}", &filtering_instructions);
 
         logger.info(
             "AI_SERVICE",
@@ -285,34 +583,49 @@ impl AIService {
         }
 
         // 构建请求（使用小 max_tokens，因为只需要单词列表）
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(self.provider.get_default_model())
-            .messages([ChatCompletionRequestMessage::System(
-                ChatCompletionRequestSystemMessage {
-                    content: system_message,
-                    role: Role::System,
-                    name: None,
-                },
-            )])
-            .max_tokens(2000u16) // 限制输出长度
-            .temperature(0.1) // 低温度保证稳定性
-            .stream(false) // 非流式，快速获取结果
-            .build()?;
+        let request = LlmRequest {
+            model: self.provider.get_default_model().to_string(),
+            messages: vec![LlmMessage::system(system_message)],
+            max_tokens: 2000, // 限制输出长度
+            temperature: 0.1, // 低温度保证稳定性
+            response_schema: None,
+        };
 
         logger.info("AI_SERVICE", "📤 Sending word extraction request...");
 
-        // 发送请求
-        let response = self.client.chat().create(request).await.map_err(|e| {
-            logger.info("AI_SERVICE", &format!("❌ Word extraction failed: {}", e));
-            format!("Word extraction failed: {}", e)
-        })?;
+        // 流式发送请求，每收到完整的 CSV 行立即解析
+        let mut parser = CsvLineStream::new();
+        let mut words = Vec::new();
+        let content = self
+            .stream_with_usage(
+                AIUsagePurpose::Extraction,
+                request,
+                logger,
+                is_cancelled,
+                &mut |delta| {
+                    for row in parser.push(delta) {
+                        if let Some(word) = Self::parse_extracted_word(&row) {
+                            on_word(&word);
+                            words.push(word);
+                        }
+                    }
+                },
+            )
+            .await
+            .map_err(|e| {
+                logger.info("AI_SERVICE", &format!("❌ Word extraction failed: {}", e));
+                format!("Word extraction failed: {}", e)
+            })?;
+        for row in parser.finish() {
+            if let Some(word) = Self::parse_extracted_word(&row) {
+                on_word(&word);
+                words.push(word);
+            }
+        }
 
-        // 提取响应内容
-        let content = response
-            .choices
-            .first()
-            .and_then(|choice| choice.message.content.as_ref())
-            .ok_or("No content in word extraction response")?;
+        if content.is_empty() {
+            return Err("No content in word extraction response".into());
+        }
 
         logger.info(
             "AI_SERVICE",
@@ -322,8 +635,11 @@ impl AIService {
             ),
         );
 
-        // 解析 CSV 响应
-        let words = self.parse_csv_response(content, &logger)?;
+        if words.is_empty() {
+            logger.info("AI_SERVICE", "❌ CSV parsing failed: no valid words found");
+            logger.info("AI_SERVICE", &format!("📄 Response content: {}", content));
+            return Err("No valid words found in CSV response".into());
+        }
 
         let total_count = words.len();
         let unique_count = words.len();
@@ -352,6 +668,22 @@ impl AIService {
         batch_index: usize,
         total_batches: usize,
         logger: &Logger,
+    ) -> Result<Vec<PhonicsWord>, Box<dyn std::error::Error>> {
+        self.analyze_words_batch_streaming(words, batch_index, total_batches, logger, &|| false, &|_| {})
+            .await
+    }
+
+    /// 流式批量分析单词，每解析出一个单词的结果立即回调 `on_word`
+    ///
+    /// `is_cancelled` 返回 true 时中断 HTTP 流并返回错误
+    pub async fn analyze_words_batch_streaming(
+        &self,
+        words: Vec<String>,
+        batch_index: usize,
+        total_batches: usize,
+        logger: &Logger,
+        is_cancelled: &(dyn Fn() -> bool + Sync),
+        on_word: &(dyn Fn(&PhonicsWord) + Sync),
     ) -> Result<Vec<PhonicsWord>, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
 
@@ -365,13 +697,12 @@ impl AIService {
             ),
         );
 
-        // 读取批量自然拼读分析提示词（CSV格式）
-        let phonics_prompt_template = include_str!("prompts/batch_phonics_agent.md");
-
         // 构建批量分析提示词
         let words_list = words.join(", ");
 
-        let batch_prompt = phonics_prompt_template.replace("{word_list}", &words_list);
+        let batch_prompt = Self::load_prompt(PromptName::BatchPhonics, logger)
+            .content
+            .replace("{word_list}", &words_list);
 
         logger.info(
             "AI_SERVICE",
@@ -379,34 +710,49 @@ impl AIService {
         );
 
         // 构建请求
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(self.provider.get_default_model())
-            .messages([ChatCompletionRequestMessage::System(
-                ChatCompletionRequestSystemMessage {
-                    content: batch_prompt,
-                    role: Role::System,
-                    name: None,
-                },
-            )])
-            .max_tokens(8000u16) // 每批 5 个单词
-            .temperature(0.1)
-            .stream(false) // 使用非流式输出，直接获取完整 CSV
-            .build()?;
+        let request = LlmRequest {
+            model: self.provider.get_default_model().to_string(),
+            messages: vec![LlmMessage::system(batch_prompt)],
+            max_tokens: 8000, // 每批 5 个单词
+            temperature: 0.1,
+            response_schema: None,
+        };
 
         logger.info("AI_SERVICE", "📤 Sending batch analysis request...");
 
-        // 发送请求并获取完整响应
-        let response = self.client.chat().create(request).await.map_err(|e| {
-            logger.info("AI_SERVICE", &format!("❌ Batch analysis failed: {}", e));
-            format!("Batch analysis failed: {}", e)
-        })?;
+        // 流式发送请求，每收到完整的 CSV 行立即解析
+        let mut parser = CsvLineStream::new();
+        let mut result_words = Vec::new();
+        let content = self
+            .stream_with_usage(
+                AIUsagePurpose::BatchPhonics,
+                request,
+                logger,
+                is_cancelled,
+                &mut |delta| {
+                    for row in parser.push(delta) {
+                        if let Some(word) = Self::parse_batch_csv_row(parser.header(), &row, logger) {
+                            on_word(&word);
+                            result_words.push(word);
+                        }
+                    }
+                },
+            )
+            .await
+            .map_err(|e| {
+                logger.info("AI_SERVICE", &format!("❌ Batch analysis failed: {}", e));
+                format!("Batch analysis failed: {}", e)
+            })?;
+        for row in parser.finish() {
+            if let Some(word) = Self::parse_batch_csv_row(parser.header(), &row, logger) {
+                on_word(&word);
+                result_words.push(word);
+            }
+        }
 
-        // 提取响应内容
-        let content = response
-            .choices
-            .first()
-            .and_then(|choice| choice.message.content.as_ref())
-            .ok_or("No content in batch analysis response")?;
+        if content.is_empty() {
+            return Err("No content in batch analysis response".into());
+        }
 
         logger.info(
             "AI_SERVICE",
@@ -417,8 +763,11 @@ impl AIService {
             ),
         );
 
-        // 解析 CSV 响应
-        let result_words = self.parse_batch_csv_response(content, logger)?;
+        if result_words.is_empty() {
+            logger.info("AI_SERVICE", "❌ CSV parsing failed: no valid words found");
+            logger.info("AI_SERVICE", &format!("📄 Response content: {}", content));
+            return Err("No valid words found in CSV response".into());
+        }
 
         logger.info(
             "AI_SERVICE",
@@ -461,13 +810,13 @@ impl AIService {
         // 步骤1: 读取提示词模板
         progress_manager.update_step("读取提示词模板...", start_time);
         let step1_start = std::time::Instant::now();
-        let prompt_template = include_str!("prompts/phonics_agent.md");
+        let prompt_template = Self::load_prompt(PromptName::Phonics, logger);
         let step1_duration = step1_start.elapsed();
         logger.info(
             "AI_SERVICE",
             &format!(
                 "📄 Step 1 - Loaded prompt template: {} chars in {:?}",
-                prompt_template.len(),
+                prompt_template.content.len(),
                 step1_duration
             ),
         );
@@ -496,6 +845,7 @@ impl AIService {
         };
 
         let system_message = prompt_template
+            .content
             .replace("{original_text}", text)
             .replace("{additional_text_preprocessing_steps}", additional_steps);
 
@@ -557,19 +907,24 @@ impl AIService {
         // 步骤4: 构建 AI 请求（不使用流式输出，一次性获取完整结果）
         progress_manager.update_step("构建AI请求...", start_time);
         let step4_start = std::time::Instant::now();
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(actual_model_name)
-            .messages([ChatCompletionRequestMessage::System(
-                ChatCompletionRequestSystemMessage {
-                    content: system_message,
-                    role: Role::System,
-                    name: None,
-                },
-            )])
-            .max_tokens(final_max_tokens.min(65535) as u16)
-            .temperature(final_temperature)
-            .stream(false) // 不使用流式输出，一次性获取完整结果
-            .build()?;
+        let response_schema = self.select_response_schema(
+            ResponseSchema::of::<JsonPhonicsResponse>(
+                PHONICS_RESULT_TOOL,
+                "Submit the phonics analysis of every extracted word",
+            ),
+            logger,
+        );
+        let structured = response_schema.is_some();
+        let request = LlmRequest {
+            model: actual_model_name.to_string(),
+            messages: vec![LlmMessage::system(Self::with_schema_instruction(
+                system_message,
+                response_schema.as_ref(),
+            ))],
+            max_tokens: final_max_tokens,
+            temperature: final_temperature,
+            response_schema,
+        };
         let step4_duration = step4_start.elapsed();
         logger.info(
             "AI_SERVICE",
@@ -594,7 +949,11 @@ impl AIService {
             ),
         );
 
-        let response = self.client.chat().create(request).await.map_err(|e| {
+        // 不使用流式输出，一次性获取完整结果
+        let response = self
+            .complete_with_usage(AIUsagePurpose::Phonics, request, logger)
+            .await
+            .map_err(|e| {
             logger.info("AI_SERVICE", &format!("❌ Request failed: {}", e));
             format!("Request failed: {}", e)
         })?;
@@ -606,11 +965,10 @@ impl AIService {
 
         // 步骤6: 提取响应内容
         let step6_start = std::time::Instant::now();
-        let content = response
-            .choices
-            .first()
-            .and_then(|choice| choice.message.content.as_ref())
-            .ok_or("No content in word extraction response")?;
+        let content = &response.content;
+        if content.is_empty() {
+            return Err("No content in word extraction response".into());
+        }
 
         let step6_duration = step6_start.elapsed();
         logger.info(
@@ -624,7 +982,7 @@ impl AIService {
 
         // 步骤7: 解析 JSON 响应
         let step7_start = std::time::Instant::now();
-        match self.parse_phonics_json(&content) {
+        match self.parse_phonics_response(content, structured, logger) {
             Ok(result) => {
                 let step7_duration = step7_start.elapsed();
                 let total_duration = start_time.elapsed();
@@ -683,6 +1041,20 @@ impl AIService {
         params: crate::types::study::StudyPlanAIParams,
         model_config: &AIModelConfig,
         logger: &Logger,
+    ) -> Result<crate::types::study::StudyPlanAIResult, Box<dyn std::error::Error>> {
+        self.generate_study_plan_schedule_streaming(params, model_config, logger, &|_, _| {})
+            .await
+    }
+
+    /// 流式生成学习计划日程规划，每解析出一天的计划立即回调 `on_daily_plan`（第几天, 计划）
+    ///
+    /// 取消后中断 HTTP 流并返回错误
+    pub async fn generate_study_plan_schedule_streaming(
+        &self,
+        params: crate::types::study::StudyPlanAIParams,
+        model_config: &AIModelConfig,
+        logger: &Logger,
+        on_daily_plan: &(dyn Fn(usize, &crate::types::study::DailyStudyPlan) + Sync),
     ) -> Result<crate::types::study::StudyPlanAIResult, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
 
@@ -701,13 +1073,13 @@ impl AIService {
         // 步骤1: 读取提示词模板
         progress_manager.update_step("读取学习计划提示词模板...", start_time);
         let step1_start = std::time::Instant::now();
-        let prompt_template = include_str!("prompts/study_plan_agent.md");
+        let prompt_template = Self::load_prompt(PromptName::StudyPlan, logger);
         let step1_duration = step1_start.elapsed();
         logger.info(
             "AI_SERVICE",
             &format!(
                 "📄 Step 1 - Loaded study plan prompt template: {} chars in {:?}",
-                prompt_template.len(),
+                prompt_template.content.len(),
                 step1_duration
             ),
         );
@@ -728,6 +1100,7 @@ impl AIService {
 
         // 替换提示词中的占位符
         let full_prompt = prompt_template
+            .content
             .replace("{intensity_level}", &params.intensity_level)
             .replace("{total_words}", &params.total_words.to_string())
             .replace("{period_days}", &params.period_days.to_string())
@@ -798,19 +1171,24 @@ impl AIService {
         progress_manager.update_step("创建AI聊天请求...", start_time);
         let step4_start = std::time::Instant::now();
 
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(model_name)
-            .messages([ChatCompletionRequestMessage::System(
-                ChatCompletionRequestSystemMessage {
-                    content: full_prompt,
-                    role: Role::System,
-                    name: None,
-                },
-            )])
-            .max_tokens(max_tokens.min(65535) as u16)
-            .temperature(temperature)
-            .stream(true)
-            .build()?;
+        let response_schema = self.select_response_schema(
+            ResponseSchema::of::<crate::types::study::StudyPlanAIResult>(
+                STUDY_PLAN_RESULT_TOOL,
+                "Submit the complete study plan schedule",
+            ),
+            logger,
+        );
+        let structured = response_schema.is_some();
+        let request = LlmRequest {
+            model: model_name.to_string(),
+            messages: vec![LlmMessage::system(Self::with_schema_instruction(
+                full_prompt,
+                response_schema.as_ref(),
+            ))],
+            max_tokens,
+            temperature,
+            response_schema,
+        };
 
         let step4_duration = step4_start.elapsed();
         logger.info(
@@ -818,172 +1196,88 @@ impl AIService {
             &format!("📤 Step 4 - Created chat request in {:?}", step4_duration),
         );
 
-        // 步骤5: 发送请求并建立流连接
+        // 步骤5: 发送流式请求，边接收边解析每日计划
         progress_manager.update_step("发送AI请求...", start_time);
-        let step5_start = std::time::Instant::now();
-
-        let stream = self.client.chat().create_stream(request).await;
-
-        let step5_duration = step5_start.elapsed();
-        logger.info(
-            "AI_SERVICE",
-            &format!("🌐 Step 5 - Sent request in {:?}", step5_duration),
-        );
-
-        // 步骤6: 处理流式响应
         let step6_start = std::time::Instant::now();
-        let mut stream = match stream {
-            Ok(s) => {
-                logger.info(
-                    "AI_SERVICE",
-                    "✅ Successfully established stream connection for study plan",
-                );
-                s
-            }
-            Err(e) => {
-                let total_duration = start_time.elapsed();
-                let error_msg = format!("Failed to establish stream connection: {}", e);
-                logger.info(
-                    "AI_SERVICE",
-                    &format!(
-                        "❌ Stream connection failed after {:?}: {}",
-                        total_duration, error_msg
-                    ),
-                );
-                progress_manager.error_analysis(&error_msg);
-                return Err(error_msg.into());
-            }
-        };
-
-        // 步骤7: 收集流式响应内容
-        progress_manager.update_step("接收AI学习计划规划结果...", start_time);
         logger.info(
             "AI_SERVICE",
-            "📥 Step 7 - Starting to receive study plan stream response",
+            "📥 Step 5 - Streaming study plan response",
         );
 
-        let mut full_content = String::new();
+        // 结构化输出按 Schema 使用驼峰字段名
+        let (metadata_key, daily_plans_key) = if structured {
+            ("planMetadata", "dailyPlans")
+        } else {
+            ("plan_metadata", "daily_plans")
+        };
+        let mut daily_plan_parser = JsonArrayStream::new(daily_plans_key);
         let mut chunk_count = 0;
+        let mut received_chars = 0;
+        let mut parsed_days = 0;
         let mut last_log_time = std::time::Instant::now();
-
-        while let Some(result) = stream.next().await {
-            // 检查是否已取消
-            if progress_manager.is_cancelled() {
-                logger.info("AI_SERVICE", "🚫 Study plan generation cancelled by user");
-                // 返回一个空的结果而不是错误，与自然拼读分析保持一致
-                return Ok(crate::types::study::StudyPlanAIResult {
-                    plan_metadata: crate::types::study::StudyPlanMetadata {
-                        total_words: 0,
-                        study_period_days: 0,
-                        intensity_level: "".to_string(),
-                        review_frequency: 0,
-                        plan_type: "".to_string(),
-                        start_date: "".to_string(),
-                        end_date: "".to_string(),
-                    },
-                    daily_plans: vec![],
-                });
-            }
-
-            match result {
-                Ok(response) => {
+        let stream_result = self
+            .stream_with_usage(
+                AIUsagePurpose::PlanGeneration,
+                request,
+                logger,
+                &|| progress_manager.is_cancelled(),
+                &mut |delta| {
                     chunk_count += 1;
-
-                    if let Some(choice) = response.choices.first() {
-                        if let Some(delta) = &choice.delta.content {
-                            full_content.push_str(delta);
-
-                            // 更新进度管理器
-                            progress_manager.update_chunk(
-                                chunk_count,
-                                full_content.len(),
-                                start_time,
-                            );
-
-                            // 每5秒记录一次进度
-                            if last_log_time.elapsed().as_secs() >= 5 {
-                                logger.info(
-                                    "AI_SERVICE",
-                                    &format!(
-                                        "📥 Received {} chunks, total: {} chars",
-                                        chunk_count,
-                                        full_content.len()
-                                    ),
+                    received_chars += delta.len();
+                    progress_manager.update_chunk(chunk_count, received_chars, start_time);
+
+                    // 每日计划完整到达后立即回调，最终结果仍以完整解析为准
+                    for element in daily_plan_parser.push(delta) {
+                        match self.parse_daily_plan_element(&element, structured) {
+                            Ok(daily_plan) => {
+                                on_daily_plan(parsed_days, &daily_plan);
+                                parsed_days += 1;
+                                progress_manager.update_step(
+                                    &format!("已接收 {} 天学习计划...", parsed_days),
+                                    start_time,
                                 );
-                                last_log_time = std::time::Instant::now();
                             }
+                            Err(e) => logger.info(
+                                "AI_SERVICE",
+                                &format!("⚠️  Skipped incomplete daily plan in stream: {}", e),
+                            ),
                         }
                     }
-                }
-                Err(e) => {
-                    let total_duration = start_time.elapsed();
-                    let error_msg = format!("Stream error: {}", e);
-                    progress_manager.error_analysis(&error_msg);
 
-                    // 详细的网络错误诊断
-                    logger.info(
-                        "AI_SERVICE",
-                        &format!("❌ Stream error after {:?}: {}", total_duration, e),
-                    );
-                    logger.info("AI_SERVICE", &format!("🔍 Network Error Details:"));
-                    logger.info(
-                        "AI_SERVICE",
-                        &format!("   📡 Target URL: {}", self.provider.base_url),
-                    );
-                    logger.info(
-                        "AI_SERVICE",
-                        &format!(
-                            "   🔑 API Key Length: {} chars",
-                            self.provider.api_key.len()
-                        ),
-                    );
-                    logger.info(
-                        "AI_SERVICE",
-                        &format!("   ⏱️  Request Duration: {:?}", total_duration),
-                    );
-                    logger.info("AI_SERVICE", &format!("   🎯 Model: {}", model_name));
-
-                    // 检查错误类型
-                    let error_str = e.to_string();
-                    if error_str.contains("dns") || error_str.contains("resolve") {
+                    // 每5秒记录一次进度
+                    if last_log_time.elapsed().as_secs() >= 5 {
                         logger.info(
                             "AI_SERVICE",
-                            "💡 Possible DNS resolution issue. Check internet connection.",
-                        );
-                    } else if error_str.contains("timeout") {
-                        logger.info(
-                            "AI_SERVICE",
-                            "💡 Request timeout. The API might be slow or overloaded.",
-                        );
-                    } else if error_str.contains("connection") {
-                        logger.info(
-                            "AI_SERVICE",
-                            "💡 Connection issue. Check firewall or proxy settings.",
-                        );
-                    } else if error_str.contains("401") || error_str.contains("unauthorized") {
-                        logger.info(
-                            "AI_SERVICE",
-                            "💡 Authentication issue. Check API key validity.",
-                        );
-                    } else if error_str.contains("429") {
-                        logger.info(
-                            "AI_SERVICE",
-                            "💡 Rate limit exceeded. Wait before retrying.",
-                        );
-                    } else if error_str.contains("500")
-                        || error_str.contains("502")
-                        || error_str.contains("503")
-                    {
-                        logger.info(
-                            "AI_SERVICE",
-                            "💡 Server error. The API service might be temporarily unavailable.",
+                            &format!(
+                                "📥 Received {} chunks, total: {} chars, {} daily plans",
+                                chunk_count, received_chars, parsed_days
+                            ),
                         );
+                        last_log_time = std::time::Instant::now();
                     }
+                },
+            )
+            .await;
 
-                    return Err(error_msg.into());
+        let full_content = match stream_result {
+            Ok(content) => content,
+            Err(e) => {
+                let total_duration = start_time.elapsed();
+                if progress_manager.is_cancelled() {
+                    logger.info("AI_SERVICE", "🚫 Study plan generation cancelled by user");
+                    return Err(STREAM_CANCELLED_MESSAGE.into());
                 }
+
+                let error_msg = format!("Stream error: {}", e);
+                progress_manager.error_analysis(&error_msg);
+                logger.info(
+                    "AI_SERVICE",
+                    &format!("❌ Stream error after {:?}: {}", total_duration, e),
+                );
+                self.log_network_error_hints(&e.to_string(), total_duration, model_name, logger);
+                return Err(error_msg.into());
             }
-        }
+        };
 
         let step6_duration = step6_start.elapsed();
         logger.info(
@@ -1013,7 +1307,7 @@ impl AIService {
             );
 
             // 检查是否包含JSON结构
-            if !full_content.contains("plan_metadata") || !full_content.contains("daily_plans") {
+            if !full_content.contains(metadata_key) || !full_content.contains(daily_plans_key) {
                 logger.info(
                     "AI_SERVICE",
                     "⚠️  Response does not contain complete study plan JSON structure",
@@ -1029,8 +1323,10 @@ impl AIService {
 
             // 步骤9: 解析 JSON 响应
             let step9_start = std::time::Instant::now();
-            match self.parse_study_plan_json(&full_content) {
-                Ok(result) => {
+            match self.parse_study_plan_response(&full_content, structured, logger) {
+                Ok(mut result) => {
+                    // 记录生成计划使用的提示词版本（随 ai_plan_data 保存）
+                    result.prompt_version = Some(prompt_template.version.clone());
                     let step9_duration = step9_start.elapsed();
                     let total_duration = start_time.elapsed();
                     logger.info(
@@ -1092,7 +1388,113 @@ impl AIService {
         }
     }
 
-    /// 解析学习计划规划的 JSON 响应
+    /// 根据网络错误信息输出诊断提示
+    fn log_network_error_hints(
+        &self,
+        error_str: &str,
+        total_duration: Duration,
+        model_name: &str,
+        logger: &Logger,
+    ) {
+        // 详细的网络错误诊断
+        logger.info("AI_SERVICE", "🔍 Network Error Details:");
+        logger.info(
+            "AI_SERVICE",
+            &format!("   📡 Target URL: {}", self.provider.base_url),
+        );
+        logger.info(
+            "AI_SERVICE",
+            &format!(
+                "   🔑 API Key Length: {} chars",
+                self.provider.api_key.len()
+            ),
+        );
+        logger.info(
+            "AI_SERVICE",
+            &format!("   ⏱️  Request Duration: {:?}", total_duration),
+        );
+        logger.info("AI_SERVICE", &format!("   🎯 Model: {}", model_name));
+
+        // 检查错误类型
+        if error_str.contains("dns") || error_str.contains("resolve") {
+            logger.info(
+                "AI_SERVICE",
+                "💡 Possible DNS resolution issue. Check internet connection.",
+            );
+        } else if error_str.contains("timeout") {
+            logger.info(
+                "AI_SERVICE",
+                "💡 Request timeout. The API might be slow or overloaded.",
+            );
+        } else if error_str.contains("connection") {
+            logger.info(
+                "AI_SERVICE",
+                "💡 Connection issue. Check firewall or proxy settings.",
+            );
+        } else if error_str.contains("401") || error_str.contains("unauthorized") {
+            logger.info(
+                "AI_SERVICE",
+                "💡 Authentication issue. Check API key validity.",
+            );
+        } else if error_str.contains("429") {
+            logger.info(
+                "AI_SERVICE",
+                "💡 Rate limit exceeded. Wait before retrying.",
+            );
+        } else if error_str.contains("500")
+            || error_str.contains("502")
+            || error_str.contains("503")
+        {
+            logger.info(
+                "AI_SERVICE",
+                "💡 Server error. The API service might be temporarily unavailable.",
+            );
+        }
+    }
+
+    /// 解析流式响应中的单个每日计划
+    fn parse_daily_plan_element(
+        &self,
+        element: &str,
+        structured: bool,
+    ) -> Result<crate::types::study::DailyStudyPlan, Box<dyn std::error::Error>> {
+        if structured {
+            if let Ok(plan) = serde_json::from_str(element) {
+                return Ok(plan);
+            }
+        }
+        let plan: serde_json::Value = serde_json::from_str(&self.clean_json_syntax(element))?;
+        let converted = self.convert_daily_plan_fields(plan)?;
+        Ok(serde_json::from_value(converted)?)
+    }
+
+    /// 解析学习计划规划响应：结构化输出直接反序列化，不符合 Schema 时退回启发式解析
+    fn parse_study_plan_response(
+        &self,
+        content: &str,
+        structured: bool,
+        logger: &Logger,
+    ) -> Result<crate::types::study::StudyPlanAIResult, Box<dyn std::error::Error>> {
+        if structured {
+            match serde_json::from_str::<crate::types::study::StudyPlanAIResult>(content) {
+                Ok(result) if !result.daily_plans.is_empty() => return Ok(result),
+                Ok(_) => logger.info(
+                    "AI_SERVICE",
+                    "⚠️  Structured study plan has no daily plans, falling back to heuristic parsing",
+                ),
+                Err(e) => logger.info(
+                    "AI_SERVICE",
+                    &format!(
+                        "⚠️  Structured study plan does not match schema ({}), falling back to heuristic parsing",
+                        e
+                    ),
+                ),
+            }
+        }
+        self.parse_study_plan_json(content)
+    }
+
+    /// 解析学习计划规划的 JSON 响应（启发式清理自由文本输出）
     fn parse_study_plan_json(
         &self,
         json_content: &str,
@@ -1313,6 +1715,42 @@ impl AIService {
         Ok(PhonicsAnalysisResult { words })
     }
 
+    /// 解析自然拼读分析响应：结构化输出直接反序列化，不符合 Schema 时退回启发式解析
+    fn parse_phonics_response(
+        &self,
+        content: &str,
+        structured: bool,
+        logger: &Logger,
+    ) -> Result<PhonicsAnalysisResult, Box<dyn std::error::Error>> {
+        if structured {
+            match serde_json::from_str::<JsonPhonicsResponse>(content) {
+                Ok(response) => {
+                    let words: Vec<PhonicsWord> = response
+                        .words
+                        .into_iter()
+                        .filter(|w| !w.word.is_empty())
+                        .map(|w| w.into())
+                        .collect();
+                    if !words.is_empty() {
+                        return Ok(PhonicsAnalysisResult { words });
+                    }
+                    logger.info(
+                        "AI_SERVICE",
+                        "⚠️  Structured phonics result has no words, falling back to heuristic parsing",
+                    );
+                }
+                Err(e) => logger.info(
+                    "AI_SERVICE",
+                    &format!(
+                        "⚠️  Structured phonics result does not match schema ({}), falling back to heuristic parsing",
+                        e
+                    ),
+                ),
+            }
+        }
+        self.parse_phonics_json(content)
+    }
+
     /// 简单的对话完成（用于模型测试）
     pub async fn chat_completion(
         &self,
@@ -1346,186 +1784,92 @@ impl AIService {
         );
 
         // 构建请求
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(model_name)
-            .messages([
-                ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
-                    content: "You are a helpful assistant. Please respond briefly and concisely."
-                        .to_string(),
-                    role: Role::System,
-                    name: None,
-                }),
-                ChatCompletionRequestMessage::User(
-                    async_openai::types::ChatCompletionRequestUserMessage {
-                        role: async_openai::types::Role::User,
-                        content: async_openai::types::ChatCompletionRequestUserMessageContent::Text(
-                            prompt.to_string(),
-                        ),
-                        name: None,
-                    },
-                ),
-            ])
-            .max_tokens(final_max_tokens as u16)
-            .temperature(final_temperature)
-            .stream(false) // 不使用流式输出，直接获取完整响应
-            .build()?;
+        let request = LlmRequest {
+            model: model_name.to_string(),
+            messages: vec![
+                LlmMessage::system("You are a helpful assistant. Please respond briefly and concisely."),
+                LlmMessage::user(prompt),
+            ],
+            max_tokens: final_max_tokens,
+            temperature: final_temperature,
+            response_schema: None,
+        };
 
         logger.info("AI_SERVICE", "📤 Sending chat completion request...");
 
-        // 发送请求
-        let response = self.client.chat().create(request).await.map_err(|e| {
+        // 发送请求（不使用流式输出，直接获取完整响应）
+        let response = self
+            .complete_with_usage(AIUsagePurpose::Test, request, logger)
+            .await
+            .map_err(|e| {
             logger.info("AI_SERVICE", &format!("❌ Chat completion failed: {}", e));
             format!("Chat completion failed: {}", e)
         })?;
 
         // 提取响应内容
-        if let Some(choice) = response.choices.first() {
-            if let Some(content) = &choice.message.content {
-                logger.info(
-                    "AI_SERVICE",
-                    &format!(
-                        "✅ Chat completion successful - Response length: {} chars",
-                        content.len()
-                    ),
-                );
-                return Ok(content.clone());
-            }
+        if response.content.is_empty() {
+            return Err("No response content received".into());
         }
 
-        Err("No response content received".into())
+        logger.info(
+            "AI_SERVICE",
+            &format!(
+                "✅ Chat completion successful - Response length: {} chars",
+                response.content.len()
+            ),
+        );
+        Ok(response.content)
     }
 
-    /// 解析 CSV 格式的单词提取响应
-    fn parse_csv_response(
-        &self,
-        content: &str,
-        logger: &Logger,
-    ) -> Result<Vec<crate::types::word_analysis::ExtractedWord>, Box<dyn std::error::Error>> {
-        // 清理可能的 markdown 代码块格式
-        let cleaned_content = self.clean_csv_markdown(content);
-
-        let mut words = Vec::new();
-        let lines: Vec<&str> = cleaned_content.lines().collect();
-
-        // 跳过标题行（第一行）
-        for line in lines.iter().skip(1) {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-
-            // 解析 CSV 行：格式为 "word,frequency,pos,translation" 或兼容旧格式
-            let parts: Vec<&str> = trimmed.split(',').collect();
-            if parts.len() >= 2 {
-                let word = parts[0].trim().to_string();
-                let frequency = parts[1].trim().parse::<i32>().unwrap_or(1);
-                
-                // 词性是可选的（兼容旧格式），如果存在则使用，否则为 None
-                let part_of_speech = if parts.len() >= 3 {
-                    let pos = parts[2].trim().to_string();
-                    if pos.is_empty() {
-                        None
-                    } else {
-                        Some(pos)
-                    }
-                } else {
-                    None
-                };
-                
-                // 中文翻译是可选的（兼容旧格式），如果存在则使用，否则为 None
-                let meaning = if parts.len() >= 4 {
-                    let trans = parts[3].trim().to_string();
-                    if trans.is_empty() {
-                        None
-                    } else {
-                        Some(trans)
-                    }
-                } else {
-                    None
-                };
-
-                if !word.is_empty() && word.len() >= 2 && word.len() <= 20 {
-                    words.push(crate::types::word_analysis::ExtractedWord {
-                        word,
-                        frequency,
-                        part_of_speech,
-                        meaning,
-                    });
-                }
-            }
-        }
-
-        if words.is_empty() {
-            logger.info(
-                "AI_SERVICE",
-                &format!("❌ CSV parsing failed: no valid words found"),
-            );
-            logger.info("AI_SERVICE", &format!("📄 Response content: {}", content));
-            return Err("No valid words found in CSV response".into());
+    /// 解析单词提取的 CSV 行："word,frequency,pos,translation"（兼容只有前两列的旧格式）
+    fn parse_extracted_word(line: &str) -> Option<crate::types::word_analysis::ExtractedWord> {
+        let parts: Vec<&str> = line.split(',').collect();
+        if parts.len() < 2 {
+            return None;
         }
 
-        Ok(words)
-    }
+        let word = parts[0].trim().to_string();
+        let frequency = parts[1].trim().parse::<i32>().unwrap_or(1);
 
-    /// 清理CSV响应中的markdown代码块格式
-    fn clean_csv_markdown(&self, content: &str) -> String {
-        let cleaned = content.trim();
+        // 词性和中文翻译是可选的（兼容旧格式）
+        let optional_column = |index: usize| {
+            parts
+                .get(index)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
 
-        // 移除 markdown 代码块标记
-        if cleaned.starts_with("```csv") {
-            cleaned[6..].trim_end_matches('`').to_string()
-        } else if cleaned.starts_with("```") {
-            // 移除任何代码块标记
-            let lines: Vec<&str> = cleaned.lines().collect();
-            if lines.len() > 1 {
-                lines[1..lines.len() - 1].join("\n")
-            } else {
-                cleaned.to_string()
-            }
-        } else {
-            cleaned.to_string()
+        if word.len() < 2 || word.len() > 20 {
+            return None;
         }
-    }
 
-    /// 解析批量分析的 CSV 响应
-    fn parse_batch_csv_response(
-        &self,
-        content: &str,
-        logger: &Logger,
-    ) -> Result<Vec<PhonicsWord>, Box<dyn std::error::Error>> {
-        // 清理可能的 markdown 代码块格式
-        let cleaned_content = self.clean_csv_markdown(content);
+        Some(crate::types::word_analysis::ExtractedWord {
+            word,
+            frequency,
+            part_of_speech: optional_column(2),
+            meaning: optional_column(3),
+        })
+    }
 
-        let mut words = Vec::new();
+    /// 解析批量分析的 CSV 数据行（按标题行的列名对应字段）
+    fn parse_batch_csv_row(header: Option<&str>, row: &str, logger: &Logger) -> Option<PhonicsWord> {
+        let header = header?;
+        let record = format!("{}\n{}", header, row);
         let mut rdr = csv::ReaderBuilder::new()
             .has_headers(true)
-            .from_reader(cleaned_content.as_bytes());
+            .from_reader(record.as_bytes());
 
-        for result in rdr.deserialize::<CsvPhonicsRecord>() {
-            match result {
-                Ok(record) => {
-                    words.push(record.into_phonics_word());
-                }
-                Err(e) => {
-                    logger.info(
-                        "AI_SERVICE",
-                        &format!("❌ CSV parsing error for row: {}", e),
-                    );
-                    // 继续处理其他行，不中断整个批次
-                }
+        match rdr.deserialize::<CsvPhonicsRecord>().next()? {
+            Ok(record) => Some(record.into_phonics_word()),
+            Err(e) => {
+                // 跳过该行，不中断整个批次
+                logger.info(
+                    "AI_SERVICE",
+                    &format!("❌ CSV parsing error for row: {}", e),
+                );
+                None
             }
         }
-
-        if words.is_empty() {
-            logger.info(
-                "AI_SERVICE",
-                &format!("❌ CSV parsing failed: no valid words found"),
-            );
-            logger.info("AI_SERVICE", &format!("📄 Response content: {}", content));
-            return Err("No valid words found in CSV response".into());
-        }
-
-        Ok(words)
     }
 
     /// 从响应中提取JSON部分
@@ -1545,13 +1889,13 @@ impl AIService {
 }
 
 /// JSON响应格式
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonPhonicsResponse {
     pub words: Vec<JsonPhonicsWord>,
 }
 
 /// JSON格式的单词数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonPhonicsWord {
     pub word: String,
     pub frequency: i32,
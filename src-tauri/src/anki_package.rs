@@ -0,0 +1,389 @@
+//! Anki 牌组打包
+//!
+//! 生成 Anki 可导入的 .apkg 文件（collection.anki2 格式，新旧版本 Anki 都能导入）：
+//! - 自然拼读笔记类型：单词、释义、音标、音节、拼读规则和发音
+//! - 笔记 guid 由牌组和单词生成，重复导入同一单词本时更新已有笔记
+//! - 可选打包发音音频（`[sound:...]`）
+
+use crate::error::{AppError, AppResult};
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use sqlx::{Connection, Executor, SqliteConnection};
+use std::io::Write;
+use std::path::Path;
+
+/// 笔记类型 ID（固定值，重复导入时复用同一笔记类型）
+const MODEL_ID: i64 = 1_718_000_000_001;
+/// 牌组 ID 基数，加上单词本 ID 得到牌组 ID
+const DECK_ID_BASE: i64 = 1_718_100_000_000;
+
+/// 笔记字段（顺序即 notes.flds 中的顺序）
+pub const NOTE_FIELDS: [&str; 6] = [
+    "Word",
+    "Meaning",
+    "IPA",
+    "Syllables",
+    "Phonics Rule",
+    "Audio",
+];
+
+const FRONT_TEMPLATE: &str = r#"<div class="word">{{Word}}</div>
+<div class="ipa">{{IPA}}</div>
+{{Audio}}"#;
+
+const BACK_TEMPLATE: &str = r#"{{FrontSide}}
+<hr id="answer">
+<div class="meaning">{{Meaning}}</div>
+<div class="syllables">{{Syllables}}</div>
+<div class="rule">{{Phonics Rule}}</div>"#;
+
+const CARD_CSS: &str = r#".card { font-family: Arial, sans-serif; font-size: 22px; text-align: center; color: #1f2937; background: #fff; }
+.word { font-size: 40px; font-weight: bold; }
+.ipa { color: #6b7280; }
+.syllables, .rule { margin-top: 8px; font-size: 18px; color: #3b82f6; }"#;
+
+const COLLECTION_SCHEMA: &str = r#"
+CREATE TABLE col (
+    id integer primary key, crt integer not null, mod integer not null, scm integer not null,
+    ver integer not null, dty integer not null, usn integer not null, ls integer not null,
+    conf text not null, models text not null, decks text not null, dconf text not null, tags text not null
+);
+CREATE TABLE notes (
+    id integer primary key, guid text not null, mid integer not null, mod integer not null,
+    usn integer not null, tags text not null, flds text not null, sfld text not null,
+    csum integer not null, flags integer not null, data text not null
+);
+CREATE TABLE cards (
+    id integer primary key, nid integer not null, did integer not null, ord integer not null,
+    mod integer not null, usn integer not null, type integer not null, queue integer not null,
+    due integer not null, ivl integer not null, factor integer not null, reps integer not null,
+    lapses integer not null, left integer not null, odue integer not null, odid integer not null,
+    flags integer not null, data text not null
+);
+CREATE TABLE revlog (
+    id integer primary key, cid integer not null, usn integer not null, ease integer not null,
+    ivl integer not null, lastIvl integer not null, factor integer not null, time integer not null,
+    type integer not null
+);
+CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null);
+CREATE INDEX ix_notes_usn on notes (usn);
+CREATE INDEX ix_cards_usn on cards (usn);
+CREATE INDEX ix_revlog_usn on revlog (usn);
+CREATE INDEX ix_cards_nid on cards (nid);
+CREATE INDEX ix_cards_sched on cards (did, queue, due);
+CREATE INDEX ix_revlog_cid on revlog (cid);
+CREATE INDEX ix_notes_csum on notes (csum);
+"#;
+
+/// 一条单词笔记（纯文本，打包时转义为 HTML）
+#[derive(Debug, Clone)]
+pub struct AnkiNote {
+    pub word: String,
+    pub meaning: String,
+    pub ipa: Option<String>,
+    pub syllables: Option<String>,
+    pub phonics_rule: Option<String>,
+    pub audio_file: Option<String>, // 牌组媒体中的文件名
+    pub tags: Vec<String>,
+}
+
+/// 牌组中的媒体文件
+#[derive(Debug, Clone)]
+pub struct AnkiMedia {
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+/// 待打包的牌组
+#[derive(Debug, Clone)]
+pub struct AnkiDeck {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub notes: Vec<AnkiNote>,
+    pub media: Vec<AnkiMedia>,
+}
+
+/// 单词本对应的牌组 ID
+pub fn deck_id_for_book(book_id: i64) -> i64 {
+    DECK_ID_BASE + book_id
+}
+
+/// 笔记 guid（同一牌组中的同一单词保持不变）
+pub fn note_guid(deck_id: i64, word: &str) -> String {
+    let digest = Sha1::digest(format!(
+        "redlark:{}:{}",
+        deck_id,
+        word.trim().to_lowercase()
+    ));
+    hex(&digest)[..16].to_string()
+}
+
+/// Anki 用于查重的排序字段校验和：SHA1 前 8 位十六进制
+pub fn field_checksum(text: &str) -> i64 {
+    let digest = Sha1::digest(text.as_bytes());
+    i64::from(u32::from_be_bytes([
+        digest[0], digest[1], digest[2], digest[3],
+    ]))
+}
+
+/// Anki 标签不能包含空白
+pub fn tag_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+fn note_fields(note: &AnkiNote) -> String {
+    let optional = |value: &Option<String>| value.as_deref().map(escape_html).unwrap_or_default();
+    [
+        escape_html(&note.word),
+        escape_html(&note.meaning),
+        optional(&note.ipa),
+        optional(&note.syllables),
+        optional(&note.phonics_rule),
+        note.audio_file
+            .as_ref()
+            .map(|f| format!("[sound:{}]", f))
+            .unwrap_or_default(),
+    ]
+    .join("\u{1f}")
+}
+
+fn collection_config(deck: &AnkiDeck, now: i64) -> [String; 4] {
+    let conf = json!({
+        "activeDecks": [1],
+        "addToCur": true,
+        "collapseTime": 1200,
+        "curDeck": deck.id,
+        "curModel": MODEL_ID.to_string(),
+        "dueCounts": true,
+        "estTimes": true,
+        "newBury": true,
+        "newSpread": 0,
+        "nextPos": deck.notes.len() + 1,
+        "sortBackwards": false,
+        "sortType": "noteFld",
+        "timeLim": 0
+    });
+
+    let fields: Vec<_> = NOTE_FIELDS
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| {
+            json!({
+                "name": name, "ord": ord, "font": "Arial", "size": 20,
+                "media": [], "rtl": false, "sticky": false
+            })
+        })
+        .collect();
+    let models = json!({
+        MODEL_ID.to_string(): {
+            "id": MODEL_ID,
+            "name": "Redlark Phonics",
+            "type": 0,
+            "mod": now,
+            "usn": -1,
+            "sortf": 0,
+            "did": deck.id,
+            "tmpls": [{
+                "name": "Card 1", "ord": 0, "qfmt": FRONT_TEMPLATE, "afmt": BACK_TEMPLATE,
+                "bqfmt": "", "bafmt": "", "did": null
+            }],
+            "flds": fields,
+            "css": CARD_CSS,
+            "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+            "latexPost": "\\end{document}",
+            "req": [[0, "any", [0]]],
+            "tags": [],
+            "vers": []
+        }
+    });
+
+    let deck_json = |id: i64, name: &str, desc: &str| {
+        json!({
+            "id": id, "name": name, "desc": desc, "mod": now, "usn": -1, "conf": 1,
+            "dyn": 0, "collapsed": false, "extendNew": 10, "extendRev": 50,
+            "newToday": [0, 0], "revToday": [0, 0], "lrnToday": [0, 0], "timeToday": [0, 0]
+        })
+    };
+    let decks = json!({
+        "1": deck_json(1, "Default", ""),
+        deck.id.to_string(): deck_json(deck.id, &deck.name, &deck.description),
+    });
+
+    let dconf = json!({
+        "1": {
+            "id": 1, "name": "Default", "mod": 0, "usn": 0, "maxTaken": 60, "autoplay": true,
+            "timer": 0, "replayq": true, "dyn": false,
+            "new": {
+                "bury": true, "delays": [1, 10], "initialFactor": 2500, "ints": [1, 4, 7],
+                "order": 1, "perDay": 20, "separate": true
+            },
+            "lapse": {
+                "delays": [10], "leechAction": 0, "leechFails": 8, "minInt": 1, "mult": 0
+            },
+            "rev": {
+                "bury": true, "ease4": 1.3, "fuzz": 0.05, "ivlFct": 1, "maxIvl": 36500,
+                "minSpace": 1, "perDay": 100
+            }
+        }
+    });
+
+    [
+        conf.to_string(),
+        models.to_string(),
+        decks.to_string(),
+        dconf.to_string(),
+    ]
+}
+
+async fn write_collection(path: &Path, deck: &AnkiDeck) -> AppResult<()> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Delete);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let now = now_ms / 1000;
+
+    conn.execute(COLLECTION_SCHEMA).await?;
+
+    let [conf, models, decks, dconf] = collection_config(deck, now);
+    sqlx::query("INSERT INTO col VALUES (1, ?, ?, ?, 11, 0, 0, 0, ?, ?, ?, ?, '{}')")
+        .bind(now)
+        .bind(now_ms)
+        .bind(now_ms)
+        .bind(conf)
+        .bind(models)
+        .bind(decks)
+        .bind(dconf)
+        .execute(&mut conn)
+        .await?;
+
+    let mut tx = conn.begin().await?;
+    for (index, note) in deck.notes.iter().enumerate() {
+        let note_id = now_ms + index as i64;
+        let tags = if note.tags.is_empty() {
+            String::new()
+        } else {
+            format!(" {} ", note.tags.join(" "))
+        };
+
+        sqlx::query("INSERT INTO notes VALUES (?, ?, ?, ?, -1, ?, ?, ?, ?, 0, '')")
+            .bind(note_id)
+            .bind(note_guid(deck.id, &note.word))
+            .bind(MODEL_ID)
+            .bind(now)
+            .bind(tags)
+            .bind(note_fields(note))
+            .bind(&note.word)
+            .bind(field_checksum(&note.word))
+            .execute(&mut *tx)
+            .await?;
+
+        // 新卡片，按单词本中的顺序出现
+        sqlx::query(
+            "INSERT INTO cards VALUES (?, ?, ?, 0, ?, -1, 0, 0, ?, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+        )
+        .bind(note_id)
+        .bind(note_id)
+        .bind(deck.id)
+        .bind(now)
+        .bind(index as i64 + 1)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    conn.close().await?;
+
+    Ok(())
+}
+
+/// 生成 .apkg 文件内容
+pub async fn build_apkg(deck: &AnkiDeck) -> AppResult<Vec<u8>> {
+    let collection_path =
+        std::env::temp_dir().join(format!("redlark-{}.anki2", uuid::Uuid::new_v4()));
+    let written = write_collection(&collection_path, deck).await;
+    let collection = written.and_then(|_| {
+        std::fs::read(&collection_path)
+            .map_err(|e| AppError::InternalError(format!("读取 Anki 数据库失败: {}", e)))
+    });
+    let _ = std::fs::remove_file(&collection_path);
+    let collection = collection?;
+
+    let zip_error =
+        |e: zip::result::ZipError| AppError::InternalError(format!("打包 Anki 牌组失败: {}", e));
+    let io_error =
+        |e: std::io::Error| AppError::InternalError(format!("打包 Anki 牌组失败: {}", e));
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    zip.start_file("collection.anki2", options)
+        .map_err(zip_error)?;
+    zip.write_all(&collection).map_err(io_error)?;
+
+    // media 文件把序号文件名映射到笔记中引用的文件名
+    let media_map: serde_json::Map<String, serde_json::Value> = deck
+        .media
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (i.to_string(), json!(m.file_name)))
+        .collect();
+    zip.start_file("media", options).map_err(zip_error)?;
+    zip.write_all(serde_json::Value::Object(media_map).to_string().as_bytes())
+        .map_err(io_error)?;
+    for (i, media) in deck.media.iter().enumerate() {
+        zip.start_file(i.to_string(), options).map_err(zip_error)?;
+        zip.write_all(&media.data).map_err(io_error)?;
+    }
+
+    Ok(zip.finish().map_err(zip_error)?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_and_guid() {
+        // sha1("cat") = 9d989e8d27dc9e0ec3389fc855f142c3d40f0c50
+        assert_eq!(field_checksum("cat"), 0x9d98_9e8d);
+        assert_eq!(note_guid(1, "Cat"), note_guid(1, " cat "));
+        assert_ne!(note_guid(1, "cat"), note_guid(2, "cat"));
+        assert_eq!(tag_name("Daily  Life"), "Daily_Life");
+    }
+
+    #[test]
+    fn test_note_fields() {
+        let note = AnkiNote {
+            word: "R&D".to_string(),
+            meaning: "研发\n<缩写>".to_string(),
+            ipa: Some("/ˌɑːr ən ˈdiː/".to_string()),
+            syllables: None,
+            phonics_rule: None,
+            audio_file: Some("rd.mp3".to_string()),
+            tags: Vec::new(),
+        };
+        let fields: Vec<String> = note_fields(&note)
+            .split('\u{1f}')
+            .map(str::to_string)
+            .collect();
+        assert_eq!(fields.len(), NOTE_FIELDS.len());
+        assert_eq!(fields[0], "R&amp;D");
+        assert_eq!(fields[1], "研发<br>&lt;缩写&gt;");
+        assert_eq!(fields[3], "");
+        assert_eq!(fields[5], "[sound:rd.mp3]");
+    }
+}
@@ -0,0 +1,541 @@
+//! 批量分析执行器
+//!
+//! 按 `BatchAnalysisConfig` 执行单词批量分析:
+//! - 最多同时运行 `max_concurrent_batches` 个批次
+//! - 每次请求受 `timeout_per_batch` 超时限制
+//! - 失败的批次按指数退避重试，每次重试对半拆分，最后一次重试拆分为单个单词
+//! - 进度计数集中维护，并发下 `EnhancedProgressManager` 的进度保持准确
+
+use crate::ai_service::PhonicsWord;
+use crate::logger::Logger;
+use crate::progress_manager::EnhancedProgressManager;
+use crate::types::word_analysis::{
+    AnalysisProgress, BatchAnalysisConfig, BatchAnalysisResult, BatchCompleteEvent, BatchInfo,
+    BatchStartEvent, WordAnalysisStatus, WordStatusUpdateEvent,
+};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 重试退避基础时长
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 重试退避最长时长
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+
+/// 批量分析过程中发送给前端的事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum BatchEvent {
+    BatchStart(BatchStartEvent),
+    WordStatus(Box<WordStatusUpdateEvent>),
+    BatchComplete(BatchCompleteEvent),
+}
+
+impl BatchEvent {
+    /// 事件名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            BatchEvent::BatchStart(_) => "batch-start",
+            BatchEvent::WordStatus(_) => "word-status-update",
+            BatchEvent::BatchComplete(_) => "batch-complete",
+        }
+    }
+}
+
+/// 第 attempt 次重试前的等待时长（指数退避）
+fn retry_delay(base: Duration, attempt: usize) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    base.saturating_mul(factor).min(RETRY_MAX_DELAY)
+}
+
+/// 拆分失败的批次：最后一次重试拆分为单个单词，否则对半拆分
+fn split_for_retry(words: Vec<String>, attempt: usize, max_retries: usize) -> Vec<Vec<String>> {
+    let chunk_size = if attempt >= max_retries {
+        1
+    } else {
+        words.len().div_ceil(2).max(1)
+    };
+    words.chunks(chunk_size).map(|chunk| chunk.to_vec()).collect()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+/// 找出 AI 未返回结果的单词
+///
+/// 按规范化后的单词逐个比较，重复或多余的结果不能抵消缺失的单词
+fn missing_words(words: &[String], results: &[PhonicsWord]) -> Vec<String> {
+    let returned: HashSet<String> = results.iter().map(|r| normalize_word(&r.word)).collect();
+    words
+        .iter()
+        .filter(|w| !returned.contains(&normalize_word(w)))
+        .cloned()
+        .collect()
+}
+
+/// 分析进度计数
+#[derive(Default)]
+struct ProgressState {
+    completed_words: usize,
+    failed_words: usize,
+    completed_batches: usize,
+    current_batch: usize,
+    current_word: Option<String>,
+}
+
+/// 批量分析执行器
+pub struct BatchExecutor<'a> {
+    config: &'a BatchAnalysisConfig,
+    progress_manager: &'a EnhancedProgressManager,
+    logger: &'a Logger,
+    batch_timeout: Duration,
+    retry_base_delay: Duration,
+    start_time: Instant,
+    total_words: usize,
+    total_batches: usize,
+    state: Mutex<ProgressState>,
+}
+
+impl<'a> BatchExecutor<'a> {
+    pub fn new(
+        config: &'a BatchAnalysisConfig,
+        progress_manager: &'a EnhancedProgressManager,
+        logger: &'a Logger,
+    ) -> Self {
+        Self {
+            config,
+            progress_manager,
+            logger,
+            batch_timeout: Duration::from_secs(config.timeout_per_batch.max(1)),
+            retry_base_delay: RETRY_BASE_DELAY,
+            start_time: Instant::now(),
+            total_words: 0,
+            total_batches: 0,
+            state: Mutex::new(ProgressState::default()),
+        }
+    }
+
+    /// 执行批量分析
+    ///
+    /// `analyze` 接收 (单词列表, 批次索引, 总批次数)，`on_event` 接收批次和单词状态事件
+    pub async fn run<A, Fut, E>(
+        mut self,
+        words: Vec<String>,
+        analyze: A,
+        on_event: E,
+    ) -> BatchAnalysisResult
+    where
+        A: Fn(Vec<String>, usize, usize) -> Fut,
+        Fut: Future<Output = Result<Vec<PhonicsWord>, String>>,
+        E: Fn(BatchEvent),
+    {
+        let batch_size = self.config.batch_size.max(1);
+        let batches: Vec<Vec<String>> = words.chunks(batch_size).map(|c| c.to_vec()).collect();
+
+        self.start_time = Instant::now();
+        self.total_words = words.len();
+        self.total_batches = batches.len();
+        self.update_progress(|_| {});
+
+        let executor = &self;
+        let analyze = &analyze;
+        let on_event = &on_event;
+        let batch_results: Vec<Vec<PhonicsWord>> = stream::iter(batches.into_iter().enumerate())
+            .map(|(batch_index, batch_words)| {
+                executor.run_batch(batch_index, batch_words, analyze, on_event)
+            })
+            .buffer_unordered(self.config.max_concurrent_batches.max(1))
+            .collect()
+            .await;
+
+        // 按原始单词顺序输出结果
+        let order: HashMap<String, usize> = words
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, word)| (normalize_word(word), index))
+            .collect();
+        let mut results: Vec<PhonicsWord> = batch_results.into_iter().flatten().collect();
+        results.sort_by_key(|r| order.get(&normalize_word(&r.word)).copied().unwrap_or(usize::MAX));
+
+        let state = self.state.lock().unwrap();
+        BatchAnalysisResult {
+            words: results,
+            total_words: self.total_words,
+            completed_words: state.completed_words,
+            failed_words: state.failed_words,
+            elapsed_seconds: self.start_time.elapsed().as_secs_f64(),
+            job_id: None,
+            cache_hits: 0,
+            cache_misses: self.total_words,
+            phonics_warnings: Vec::new(),
+        }
+    }
+
+    /// 执行单个批次（含超时和重试）
+    async fn run_batch<A, Fut, E>(
+        &self,
+        batch_index: usize,
+        words: Vec<String>,
+        analyze: &A,
+        on_event: &E,
+    ) -> Vec<PhonicsWord>
+    where
+        A: Fn(Vec<String>, usize, usize) -> Fut,
+        Fut: Future<Output = Result<Vec<PhonicsWord>, String>>,
+        E: Fn(BatchEvent),
+    {
+        on_event(BatchEvent::BatchStart(BatchStartEvent {
+            batch_index,
+            total_batches: self.total_batches,
+            words: words.clone(),
+        }));
+        for word in &words {
+            self.set_word_status(word, "analyzing", None, None, on_event);
+        }
+
+        let mut results = Vec::new();
+        let mut failed_count = 0;
+        let mut pending = vec![(words, 0usize)];
+
+        while let Some((words, attempt)) = pending.pop() {
+            if self.progress_manager.is_cancelled() {
+                self.fail_words(&words, "批量分析已取消", on_event);
+                failed_count += words.len();
+                continue;
+            }
+
+            if attempt > 0 {
+                tokio::time::sleep(retry_delay(self.retry_base_delay, attempt)).await;
+            }
+
+            self.update_progress(|state| {
+                state.current_batch = batch_index;
+                state.current_word = words.first().cloned();
+            });
+
+            let outcome = match tokio::time::timeout(
+                self.batch_timeout,
+                analyze(words.clone(), batch_index, self.total_batches),
+            )
+            .await
+            {
+                Ok(outcome) => outcome,
+                Err(_) => Err(format!(
+                    "批次分析超时（{} 秒）",
+                    self.batch_timeout.as_secs()
+                )),
+            };
+
+            let (missing, error) = match outcome {
+                Ok(batch_results) => {
+                    let missing = missing_words(&words, &batch_results);
+                    for result in &batch_results {
+                        self.set_word_status(&result.word, "completed", None, Some(result), on_event);
+                    }
+                    let completed = words.len() - missing.len();
+                    self.update_progress(|state| state.completed_words += completed);
+                    results.extend(batch_results);
+                    (missing, "AI 未返回该单词的分析结果".to_string())
+                }
+                Err(e) => (words, e),
+            };
+
+            if missing.is_empty() {
+                continue;
+            }
+
+            if self.config.retry_failed_words && attempt < self.config.max_retries {
+                self.logger.info(
+                    "WORD_ANALYSIS",
+                    &format!(
+                        "🔁 批次 {}/{} 中 {} 个单词分析失败，准备第 {} 次重试: {}",
+                        batch_index + 1,
+                        self.total_batches,
+                        missing.len(),
+                        attempt + 1,
+                        error
+                    ),
+                );
+                let retry_batches = split_for_retry(missing, attempt + 1, self.config.max_retries);
+                // 倒序入栈，保证按原顺序重试
+                for retry_words in retry_batches.into_iter().rev() {
+                    pending.push((retry_words, attempt + 1));
+                }
+            } else {
+                self.logger.error(
+                    "WORD_ANALYSIS",
+                    &format!(
+                        "❌ 批次 {}/{} 中 {} 个单词分析失败",
+                        batch_index + 1,
+                        self.total_batches,
+                        missing.len()
+                    ),
+                    Some(&error),
+                );
+                self.fail_words(&missing, &error, on_event);
+                failed_count += missing.len();
+            }
+        }
+
+        self.update_progress(|state| state.completed_batches += 1);
+        on_event(BatchEvent::BatchComplete(BatchCompleteEvent {
+            batch_index,
+            completed_words: results.len(),
+            failed_words: failed_count,
+        }));
+
+        results
+    }
+
+    /// 标记单词失败
+    fn fail_words<E: Fn(BatchEvent)>(&self, words: &[String], error: &str, on_event: &E) {
+        for word in words {
+            self.set_word_status(word, "failed", Some(error), None, on_event);
+        }
+        self.update_progress(|state| state.failed_words += words.len());
+    }
+
+    /// 更新单词状态并发送事件
+    fn set_word_status<E: Fn(BatchEvent)>(
+        &self,
+        word: &str,
+        status: &str,
+        error: Option<&str>,
+        result: Option<&PhonicsWord>,
+        on_event: &E,
+    ) {
+        self.progress_manager.update_word_status(&WordAnalysisStatus {
+            word: word.to_string(),
+            status: status.to_string(),
+            error: error.map(|e| e.to_string()),
+            result: result.cloned(),
+        });
+        on_event(BatchEvent::WordStatus(Box::new(WordStatusUpdateEvent {
+            word: word.to_string(),
+            status: status.to_string(),
+            error: error.map(|e| e.to_string()),
+            result: result.cloned(),
+        })));
+    }
+
+    /// 在锁内修改计数并同步到进度管理器，避免并发批次互相覆盖
+    fn update_progress(&self, update: impl FnOnce(&mut ProgressState)) {
+        let mut state = self.state.lock().unwrap();
+        update(&mut state);
+
+        self.progress_manager.update_analysis_progress(&AnalysisProgress {
+            total_words: self.total_words,
+            completed_words: state.completed_words,
+            failed_words: state.failed_words,
+            current_word: state.current_word.clone(),
+            batch_info: BatchInfo {
+                total_batches: self.total_batches,
+                completed_batches: state.completed_batches,
+                current_batch: state.current_batch,
+                batch_size: self.config.batch_size,
+            },
+            elapsed_seconds: self.start_time.elapsed().as_secs_f64(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn phonics_word(word: &str) -> PhonicsWord {
+        PhonicsWord {
+            word: word.to_string(),
+            frequency: 1,
+            chinese_translation: String::new(),
+            pos_abbreviation: "n.".to_string(),
+            pos_english: "noun".to_string(),
+            pos_chinese: "名词".to_string(),
+            ipa: String::new(),
+            syllables: word.to_string(),
+            phonics_rule: String::new(),
+            analysis_explanation: String::new(),
+        }
+    }
+
+    fn words(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("word{}", i)).collect()
+    }
+
+    fn test_logger() -> Logger {
+        Logger::new(&std::env::temp_dir().join("redlark-batch-executor-test")).unwrap()
+    }
+
+    fn executor<'a>(
+        config: &'a BatchAnalysisConfig,
+        progress_manager: &'a EnhancedProgressManager,
+        logger: &'a Logger,
+    ) -> BatchExecutor<'a> {
+        let mut executor = BatchExecutor::new(config, progress_manager, logger);
+        executor.retry_base_delay = Duration::from_millis(1);
+        executor.batch_timeout = Duration::from_millis(200);
+        executor
+    }
+
+    #[test]
+    fn test_split_for_retry() {
+        let halves = split_for_retry(words(5), 1, 2);
+        assert_eq!(halves.iter().map(|b| b.len()).collect::<Vec<_>>(), vec![3, 2]);
+
+        let singles = split_for_retry(words(5), 2, 2);
+        assert_eq!(singles.len(), 5);
+        assert!(retry_delay(RETRY_BASE_DELAY, 2) > retry_delay(RETRY_BASE_DELAY, 1));
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_is_bounded_and_progress_accurate() {
+        let config = BatchAnalysisConfig {
+            batch_size: 2,
+            max_concurrent_batches: 3,
+            ..Default::default()
+        };
+        let progress_manager = EnhancedProgressManager::new();
+        let logger = test_logger();
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let result = executor(&config, &progress_manager, &logger)
+            .run(
+                words(20),
+                |batch, _, _| {
+                    let in_flight = &in_flight;
+                    let peak = &peak;
+                    async move {
+                        let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(current, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        Ok(batch.iter().map(|w| phonics_word(w)).collect())
+                    }
+                },
+                |_| {},
+            )
+            .await;
+
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(result.completed_words, 20);
+        assert_eq!(result.words[0].word, "word0");
+
+        let progress = progress_manager.get_full_progress();
+        let analysis = progress.analysis_progress.unwrap();
+        assert_eq!(analysis.completed_words, 20);
+        assert_eq!(analysis.batch_info.completed_batches, 10);
+        assert_eq!(progress.status, "completed");
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_split_down_to_single_words() {
+        let config = BatchAnalysisConfig {
+            batch_size: 4,
+            max_concurrent_batches: 1,
+            retry_failed_words: true,
+            max_retries: 2,
+            timeout_per_batch: 1,
+        };
+        let progress_manager = EnhancedProgressManager::new();
+        let logger = test_logger();
+        let calls = Mutex::new(Vec::new());
+
+        let result = executor(&config, &progress_manager, &logger)
+            .run(
+                vec!["a".into(), "b".into(), "bad".into(), "c".into()],
+                |batch, _, _| {
+                    calls.lock().unwrap().push(batch.len());
+                    async move {
+                        if batch.iter().any(|w| w == "bad") {
+                            Err("parse error".to_string())
+                        } else {
+                            Ok(batch.iter().map(|w| phonics_word(w)).collect())
+                        }
+                    }
+                },
+                |_| {},
+            )
+            .await;
+
+        assert_eq!(result.completed_words, 3);
+        assert_eq!(result.failed_words, 1);
+        // 4 → 2 + 2 → 单个单词
+        assert_eq!(*calls.lock().unwrap(), vec![4, 2, 2, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_rows_do_not_hide_missing_words() {
+        let config = BatchAnalysisConfig {
+            batch_size: 3,
+            max_concurrent_batches: 1,
+            retry_failed_words: true,
+            max_retries: 1,
+            timeout_per_batch: 1,
+        };
+        let progress_manager = EnhancedProgressManager::new();
+        let logger = test_logger();
+        let calls = Mutex::new(Vec::new());
+
+        // AI 重复返回 a，漏掉 skip：结果行数与请求单词数相同
+        let result = executor(&config, &progress_manager, &logger)
+            .run(
+                vec!["a".into(), "b".into(), "skip".into()],
+                |batch, _, _| {
+                    calls.lock().unwrap().push(batch.clone());
+                    async move {
+                        let mut rows: Vec<PhonicsWord> = batch
+                            .iter()
+                            .filter(|w| *w != "skip")
+                            .map(|w| phonics_word(w))
+                            .collect();
+                        rows.push(phonics_word("A"));
+                        Ok(rows)
+                    }
+                },
+                |_| {},
+            )
+            .await;
+
+        assert_eq!(result.completed_words, 2);
+        assert_eq!(result.failed_words, 1);
+        assert_eq!(calls.lock().unwrap()[1..], [vec!["skip".to_string()]]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_timeout_marks_words_failed() {
+        let config = BatchAnalysisConfig {
+            batch_size: 2,
+            max_concurrent_batches: 2,
+            retry_failed_words: false,
+            max_retries: 0,
+            timeout_per_batch: 1,
+        };
+        let progress_manager = EnhancedProgressManager::new();
+        let logger = test_logger();
+
+        let result = executor(&config, &progress_manager, &logger)
+            .run(
+                vec!["slow".into(), "x".into(), "fast".into(), "y".into()],
+                |batch, _, _| async move {
+                    if batch.iter().any(|w| w == "slow") {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                    Ok(batch.iter().map(|w| phonics_word(w)).collect())
+                },
+                |_| {},
+            )
+            .await;
+
+        assert_eq!(result.completed_words, 2);
+        assert_eq!(result.failed_words, 2);
+        assert_eq!(progress_manager.get_full_progress().status, "completed");
+    }
+}
@@ -2,6 +2,9 @@ use crate::error::AppResult;
 use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
 use std::str::FromStr;
 
+/// 内置的数据库迁移（恢复备份时用于校验备份的迁移版本）
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
 /// 数据库连接管理器
 pub struct DatabaseManager {
     pool: SqlitePool,
@@ -27,7 +30,7 @@ impl DatabaseManager {
 
     /// 运行数据库迁移
     pub async fn migrate(&self) -> AppResult<()> {
-        sqlx::migrate!("./migrations").run(&self.pool).await?;
+        MIGRATOR.run(&self.pool).await?;
         Ok(())
     }
 }
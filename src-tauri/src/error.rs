@@ -29,6 +29,9 @@ pub enum AppError {
 
     #[error("外部服务错误: {0}")]
     ExternalServiceError(String),
+
+    #[error("状态转换错误: {0}")]
+    InvalidStatusTransition(String),
 }
 
 impl From<sqlx::Error> for AppError {
@@ -68,6 +71,7 @@ impl From<AppError> for serde_json::Value {
                 AppError::Unauthorized(_) => "UNAUTHORIZED",
                 AppError::InternalError(_) => "INTERNAL_ERROR",
                 AppError::ExternalServiceError(_) => "EXTERNAL_SERVICE_ERROR",
+                AppError::InvalidStatusTransition(_) => "INVALID_STATUS_TRANSITION",
             }
         })
     }
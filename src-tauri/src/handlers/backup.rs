@@ -0,0 +1,154 @@
+//! 数据库备份命令处理器
+//!
+//! 手动创建备份、查看备份列表、设置自动备份，以及从备份恢复数据
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::services::backup::{BackupPaths, BackupService};
+use crate::types::backup::{BackupInfo, BackupKind, BackupSettings, UpdateBackupSettingsRequest};
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// 应用的数据库、发音缓存和备份目录
+pub fn backup_paths<R: Runtime, M: Manager<R>>(manager: &M) -> AppResult<BackupPaths> {
+    let path = manager.path();
+    let app_data_dir = path
+        .app_data_dir()
+        .map_err(|e| AppError::InternalError(format!("Failed to get app data directory: {}", e)))?;
+    let app_cache_dir = path.app_cache_dir().map_err(|e| {
+        AppError::InternalError(format!("Failed to get app cache directory: {}", e))
+    })?;
+    Ok(BackupPaths::new(&app_data_dir, &app_cache_dir))
+}
+
+fn backup_service(app: &AppHandle) -> AppResult<BackupService> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+    Ok(BackupService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+        backup_paths(app)?,
+    ))
+}
+
+/// 记录命令结果
+fn log_response<T>(
+    logger: &Logger,
+    command: &str,
+    result: &AppResult<T>,
+    summary: impl Fn(&T) -> String,
+) {
+    match result {
+        Ok(value) => logger.api_response(command, true, Some(&summary(value))),
+        Err(e) => logger.api_response(command, false, Some(&e.to_string())),
+    }
+}
+
+/// 立即创建一个手动备份
+#[tauri::command]
+pub async fn create_backup(app: AppHandle) -> AppResult<BackupInfo> {
+    let logger = app.state::<Logger>();
+    logger.api_request("create_backup", None);
+
+    let result = match backup_service(&app) {
+        Ok(service) => service.create_backup(BackupKind::Manual).await,
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "create_backup", &result, |info| {
+        format!("{} ({} bytes)", info.file_name, info.file_size)
+    });
+    result
+}
+
+/// 获取备份列表（最新的在前）
+#[tauri::command]
+pub async fn get_backups(app: AppHandle) -> AppResult<Vec<BackupInfo>> {
+    let logger = app.state::<Logger>();
+    logger.api_request("get_backups", None);
+
+    let result = backup_service(&app).and_then(|service| service.list_backups());
+    log_response(&logger, "get_backups", &result, |backups| {
+        format!("{} backups", backups.len())
+    });
+    result
+}
+
+/// 获取自动备份设置
+#[tauri::command]
+pub async fn get_backup_settings(app: AppHandle) -> AppResult<BackupSettings> {
+    let logger = app.state::<Logger>();
+    logger.api_request("get_backup_settings", None);
+
+    let result = match backup_service(&app) {
+        Ok(service) => service.get_settings().await,
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "get_backup_settings", &result, |settings| {
+        format!("{:?}", settings)
+    });
+    result
+}
+
+/// 更新自动备份设置
+#[tauri::command]
+pub async fn update_backup_settings(
+    app: AppHandle,
+    request: UpdateBackupSettingsRequest,
+) -> AppResult<BackupSettings> {
+    let logger = app.state::<Logger>();
+    logger.api_request("update_backup_settings", Some(&format!("{:?}", request)));
+
+    let result = match backup_service(&app) {
+        Ok(service) => service.update_settings(&request).await,
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "update_backup_settings", &result, |settings| {
+        format!("{:?}", settings)
+    });
+    result
+}
+
+/// 从备份恢复数据库和发音缓存，成功后重启应用
+///
+/// 恢复前会校验备份的迁移版本，并自动备份当前数据
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, file_path: String) -> AppResult<()> {
+    let logger = app.state::<Logger>();
+    logger.api_request("restore_backup", Some(&format!("file: {}", file_path)));
+
+    let service = backup_service(&app)?;
+    let staged = match service.stage_restore(&PathBuf::from(&file_path)).await {
+        Ok(staged) => staged,
+        Err(e) => {
+            logger.api_response("restore_backup", false, Some(&e.to_string()));
+            return Err(e);
+        }
+    };
+    if let Err(e) = service.create_backup(BackupKind::PreRestore).await {
+        staged.discard();
+        logger.api_response("restore_backup", false, Some(&e.to_string()));
+        return Err(e);
+    }
+
+    // 关闭连接池并等待文件句柄释放后再替换数据库
+    let pool = app.state::<SqlitePool>();
+    pool.close().await;
+    logger.info("DATABASE", "✅ Database connections closed for restore");
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    // 连接池已关闭，无论替换是否成功都重启应用
+    match service.apply_restore(staged) {
+        Ok(()) => logger.api_response(
+            "restore_backup",
+            true,
+            Some("Backup restored, restarting app"),
+        ),
+        Err(e) => {
+            logger.error("BACKUP", "Failed to restore backup", Some(&e.to_string()));
+            logger.api_response("restore_backup", false, Some(&e.to_string()));
+        }
+    }
+    app.restart();
+}
@@ -257,6 +257,7 @@ pub async fn get_calendar_month_data(
             sp.id as plan_id,
             sp.name as plan_name,
             sp.unified_status,
+            sp.paused_at,
             sps.total_words_count as total_words,
             sps.new_words_count as new_words,
             sps.review_words_count as review_words,
@@ -329,6 +330,7 @@ pub async fn get_calendar_month_data(
 
         // 收集该日期的学习计划
         let mut study_plans = Vec::new();
+        let mut can_be_overdue = false; // 暂停当天及之后的日程不算逾期
         let mut total_words = 0;
         let mut new_words = 0;
         let mut review_words = 0;
@@ -343,6 +345,11 @@ pub async fn get_calendar_month_data(
                 let plan_new_words: i32 = row.get("new_words");
                 let plan_review_words: i32 = row.get("review_words");
                 let _plan_completed_words: i32 = row.get("completed_words_count");
+                let paused_at: Option<String> = row.get("paused_at");
+                can_be_overdue |= paused_at
+                    .as_deref()
+                    .and_then(crate::services::study_plan::paused_on)
+                    .is_none_or(|paused_on| current_date < paused_on);
 
                 // 转换 unified_status
                 let unified_status_enum = match unified_status.as_str() {
@@ -409,7 +416,7 @@ pub async fn get_calendar_month_data(
             "completed".to_string()
         } else if completed_words > 0 {
             "in-progress".to_string()
-        } else if current_date < today {
+        } else if current_date < today && can_be_overdue {
             "overdue".to_string()
         } else {
             "not-started".to_string()
@@ -8,9 +8,13 @@ pub mod shared;
 
 // 功能域模块
 pub mod analysis;
+pub mod backup;
 pub mod calendar;
 pub mod diagnostics;
 pub mod practice;
+pub mod prompt;
+pub mod pronunciation;
+pub mod snapshot;
 pub mod statistics;
 pub mod study_plan;
 pub mod word;
@@ -18,9 +22,13 @@ pub mod wordbook;
 
 // 重新导出所有命令,保持向后兼容
 pub use analysis::*;
+pub use backup::*;
 pub use calendar::*;
 pub use diagnostics::*;
 pub use practice::*;
+pub use prompt::*;
+pub use pronunciation::*;
+pub use snapshot::*;
 pub use statistics::*;
 pub use study_plan::*;
 pub use word::*;
@@ -377,3 +377,70 @@ pub async fn get_practice_statistics(
         }
     }
 }
+
+/// 获取学习计划的单词记忆状态
+#[tauri::command]
+pub async fn get_word_review_states(
+    app: AppHandle,
+    plan_id: i64,
+) -> AppResult<Vec<WordReviewState>> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "get_word_review_states",
+        Some(&format!("plan_id: {}", plan_id)),
+    );
+
+    let service = crate::services::PracticeService::from_pool_and_logger(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+
+    match service.get_word_review_states(plan_id).await {
+        Ok(states) => {
+            logger.api_response(
+                "get_word_review_states",
+                true,
+                Some(&format!("找到 {} 个单词记忆状态", states.len())),
+            );
+            Ok(states)
+        }
+        Err(e) => {
+            logger.api_response("get_word_review_states", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 设置学习计划的复习算法
+#[tauri::command]
+pub async fn set_review_algorithm(
+    app: AppHandle,
+    plan_id: i64,
+    algorithm: ReviewAlgorithm,
+) -> AppResult<()> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "set_review_algorithm",
+        Some(&format!("plan_id: {}, algorithm: {}", plan_id, algorithm.as_str())),
+    );
+
+    let service = crate::services::PracticeService::from_pool_and_logger(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+
+    match service.set_review_algorithm(plan_id, algorithm).await {
+        Ok(_) => {
+            logger.api_response("set_review_algorithm", true, Some("复习算法已更新"));
+            Ok(())
+        }
+        Err(e) => {
+            logger.api_response("set_review_algorithm", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! 提示词模板命令处理器
+//!
+//! 查看、自定义、恢复提示词模板及其历史版本
+
+use crate::error::AppResult;
+use crate::logger::Logger;
+use crate::services::prompt::PromptService;
+use crate::types::prompt::{PromptTemplate, PromptVersion};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+fn prompt_service(app: &AppHandle) -> PromptService {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+    PromptService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    )
+}
+
+/// 记录命令结果
+fn log_response<T>(
+    logger: &Logger,
+    command: &str,
+    result: &AppResult<T>,
+    summary: impl Fn(&T) -> String,
+) {
+    match result {
+        Ok(value) => logger.api_response(command, true, Some(&summary(value))),
+        Err(e) => logger.api_response(command, false, Some(&e.to_string())),
+    }
+}
+
+/// 获取所有提示词模板（当前生效的版本）
+#[tauri::command]
+pub async fn get_prompt_templates(app: AppHandle) -> AppResult<Vec<PromptTemplate>> {
+    let logger = app.state::<Logger>();
+    logger.api_request("get_prompt_templates", None);
+
+    let result = prompt_service(&app).get_templates().await;
+    log_response(&logger, "get_prompt_templates", &result, |templates| {
+        format!("{} templates", templates.len())
+    });
+    result
+}
+
+/// 保存自定义提示词模板
+#[tauri::command]
+pub async fn save_prompt_template(
+    app: AppHandle,
+    name: String,
+    content: String,
+) -> AppResult<PromptTemplate> {
+    let logger = app.state::<Logger>();
+    logger.api_request(
+        "save_prompt_template",
+        Some(&format!("name: {}, length: {}", name, content.len())),
+    );
+
+    let result = match PromptService::parse_name(&name) {
+        Ok(prompt_name) => {
+            prompt_service(&app)
+                .save_template(prompt_name, &content)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "save_prompt_template", &result, |template| {
+        format!("version: {}", template.version)
+    });
+    result
+}
+
+/// 恢复为内置提示词模板
+#[tauri::command]
+pub async fn reset_prompt_template(app: AppHandle, name: String) -> AppResult<PromptTemplate> {
+    let logger = app.state::<Logger>();
+    logger.api_request("reset_prompt_template", Some(&format!("name: {}", name)));
+
+    let result = match PromptService::parse_name(&name) {
+        Ok(prompt_name) => prompt_service(&app).reset_template(prompt_name).await,
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "reset_prompt_template", &result, |template| {
+        format!("version: {}", template.version)
+    });
+    result
+}
+
+/// 获取提示词的历史版本
+#[tauri::command]
+pub async fn get_prompt_versions(app: AppHandle, name: String) -> AppResult<Vec<PromptVersion>> {
+    let logger = app.state::<Logger>();
+    logger.api_request("get_prompt_versions", Some(&format!("name: {}", name)));
+
+    let result = match PromptService::parse_name(&name) {
+        Ok(prompt_name) => prompt_service(&app).get_versions(prompt_name).await,
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "get_prompt_versions", &result, |versions| {
+        format!("{} versions", versions.len())
+    });
+    result
+}
+
+/// 恢复提示词到历史版本
+#[tauri::command]
+pub async fn restore_prompt_version(
+    app: AppHandle,
+    name: String,
+    version: String,
+) -> AppResult<PromptTemplate> {
+    let logger = app.state::<Logger>();
+    logger.api_request(
+        "restore_prompt_version",
+        Some(&format!("name: {}, version: {}", name, version)),
+    );
+
+    let result = match PromptService::parse_name(&name) {
+        Ok(prompt_name) => {
+            prompt_service(&app)
+                .restore_version(prompt_name, &version)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "restore_prompt_version", &result, |template| {
+        format!("version: {}", template.version)
+    });
+    result
+}
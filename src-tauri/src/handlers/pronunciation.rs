@@ -0,0 +1,122 @@
+//! 发音词典命令处理器
+//!
+//! 导入 CMUdict 格式的发音文件，查询词典读音，校验单词音标
+
+use crate::error::AppResult;
+use crate::logger::Logger;
+use crate::services::pronunciation::PronunciationService;
+use crate::types::pronunciation::{
+    IpaCheckItem, IpaCheckReport, PronunciationImportResult, PronunciationLookup,
+};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+fn pronunciation_service(app: &AppHandle) -> PronunciationService {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+    PronunciationService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    )
+}
+
+/// 记录命令结果
+fn log_response<T>(
+    logger: &Logger,
+    command: &str,
+    result: &AppResult<T>,
+    summary: impl Fn(&T) -> String,
+) {
+    match result {
+        Ok(value) => logger.api_response(command, true, Some(&summary(value))),
+        Err(e) => logger.api_response(command, false, Some(&e.to_string())),
+    }
+}
+
+/// 导入 CMUdict 格式的发音文件内容
+#[tauri::command]
+pub async fn import_pronunciation_dictionary(
+    app: AppHandle,
+    content: String,
+) -> AppResult<PronunciationImportResult> {
+    let logger = app.state::<Logger>();
+    logger.api_request(
+        "import_pronunciation_dictionary",
+        Some(&format!("length: {}", content.len())),
+    );
+
+    let result = pronunciation_service(&app).import_cmudict(&content).await;
+    log_response(&logger, "import_pronunciation_dictionary", &result, |r| {
+        format!("imported: {}, skipped: {}", r.imported, r.skipped_lines)
+    });
+    result
+}
+
+/// 获取发音词典的条目总数
+#[tauri::command]
+pub async fn get_pronunciation_dictionary_count(app: AppHandle) -> AppResult<i64> {
+    let logger = app.state::<Logger>();
+    logger.api_request("get_pronunciation_dictionary_count", None);
+
+    let result = pronunciation_service(&app).count().await;
+    log_response(
+        &logger,
+        "get_pronunciation_dictionary_count",
+        &result,
+        |count| format!("{} entries", count),
+    );
+    result
+}
+
+/// 查询单词的词典读音（美式和英式音标）
+#[tauri::command]
+pub async fn lookup_pronunciation(
+    app: AppHandle,
+    word: String,
+) -> AppResult<Vec<PronunciationLookup>> {
+    let logger = app.state::<Logger>();
+    logger.api_request("lookup_pronunciation", Some(&format!("word: {}", word)));
+
+    let result = pronunciation_service(&app).lookup(&word).await;
+    log_response(&logger, "lookup_pronunciation", &result, |entries| {
+        format!("{} pronunciations", entries.len())
+    });
+    result
+}
+
+/// 用发音词典校验单词音标，返回不一致和可补全的单词
+#[tauri::command]
+pub async fn check_word_ipa(
+    app: AppHandle,
+    words: Vec<IpaCheckItem>,
+    convention: Option<String>,
+) -> AppResult<IpaCheckReport> {
+    let logger = app.state::<Logger>();
+    logger.api_request(
+        "check_word_ipa",
+        Some(&format!(
+            "words: {}, convention: {:?}",
+            words.len(),
+            convention
+        )),
+    );
+
+    let result = match PronunciationService::parse_convention(convention.as_deref()) {
+        Ok(convention) => {
+            pronunciation_service(&app)
+                .with_convention(convention)
+                .check(&words)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    log_response(&logger, "check_word_ipa", &result, |report| {
+        format!(
+            "checked: {}, mismatches: {}",
+            report.checked,
+            report.mismatches.len()
+        )
+    });
+    result
+}
@@ -0,0 +1,62 @@
+//! 破坏性操作撤销命令处理器
+//!
+//! 查看破坏性操作前保存的快照，撤销最近一次操作
+
+use crate::error::AppResult;
+use crate::logger::Logger;
+use crate::services::snapshot::SnapshotService;
+use crate::types::snapshot::{OperationSnapshot, UndoOperationResult};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+fn snapshot_service(app: &AppHandle) -> SnapshotService {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+    SnapshotService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    )
+}
+
+/// 记录命令结果
+fn log_response<T>(
+    logger: &Logger,
+    command: &str,
+    result: &AppResult<T>,
+    summary: impl Fn(&T) -> String,
+) {
+    match result {
+        Ok(value) => logger.api_response(command, true, Some(&summary(value))),
+        Err(e) => logger.api_response(command, false, Some(&e.to_string())),
+    }
+}
+
+/// 获取破坏性操作快照历史（最新的在前）
+#[tauri::command]
+pub async fn get_operation_snapshots(app: AppHandle) -> AppResult<Vec<OperationSnapshot>> {
+    let logger = app.state::<Logger>();
+    logger.api_request("get_operation_snapshots", None);
+
+    let result = snapshot_service(&app).get_snapshots().await;
+    log_response(&logger, "get_operation_snapshots", &result, |snapshots| {
+        format!("{} snapshots", snapshots.len())
+    });
+    result
+}
+
+/// 撤销最近一次尚未撤销的破坏性操作
+#[tauri::command]
+pub async fn undo_last_operation(app: AppHandle) -> AppResult<UndoOperationResult> {
+    let logger = app.state::<Logger>();
+    logger.api_request("undo_last_operation", None);
+
+    let result = snapshot_service(&app).undo_last().await;
+    log_response(&logger, "undo_last_operation", &result, |undo| {
+        format!(
+            "{}: {} rows restored",
+            undo.snapshot.description, undo.restored_rows
+        )
+    });
+    result
+}
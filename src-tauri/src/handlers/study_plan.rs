@@ -4,12 +4,13 @@
 
 use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
+use crate::repositories::ai_usage_repository::AIUsageRepository;
 use crate::services::study_plan::StudyPlanService;
 use crate::types::*;
 use chrono::Datelike;
 use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
 // 导入跨模块辅助函数
 
@@ -183,7 +184,7 @@ pub async fn get_study_statistics(app: AppHandle) -> AppResult<StudyStatistics>
 #[tauri::command]
 pub async fn generate_study_plan_schedule(
     app: AppHandle,
-    request: StudyPlanScheduleRequest,
+    mut request: StudyPlanScheduleRequest,
 ) -> AppResult<StudyPlanAIResult> {
     use crate::ai_service::AIService;
     use crate::types::study::{StudyPlanAIParams, StudyWordInfo};
@@ -194,11 +195,38 @@ pub async fn generate_study_plan_schedule(
     logger.api_request(
         "generate_study_plan_schedule",
         Some(&format!(
-            "name: {}, intensity: {}, period: {} days, wordbooks: {:?}",
-            request.name, request.intensity_level, request.study_period_days, request.wordbook_ids
+            "name: {}, intensity: {}, period: {} days, wordbooks: {:?}, template: {:?}",
+            request.name,
+            request.intensity_level,
+            request.study_period_days,
+            request.wordbook_ids,
+            request.template_id
         )),
     );
 
+    // 从计划模板开始：使用模板的学习设置，本地规划器还会按模板的日程形状分配新词（AI 规划器不使用形状）
+    let mut day_weights = Vec::new();
+    if let Some(template_id) = request.template_id {
+        let service = StudyPlanService::new(
+            Arc::new(pool.inner().clone()),
+            Arc::new(logger.inner().clone())
+        );
+        let template = match service.get_study_plan_template(template_id).await {
+            Ok(template) => template,
+            Err(e) => {
+                logger.api_response("generate_study_plan_schedule", false, Some(&e.to_string()));
+                return Err(e);
+            }
+        };
+        request.intensity_level = template.intensity_level;
+        request.study_period_days = template.study_period_days;
+        request.review_frequency = template.review_frequency;
+        if request.description.trim().is_empty() {
+            request.description = template.description;
+        }
+        day_weights = template.day_weights;
+    }
+
     // 验证输入参数
     if request.name.trim().is_empty() {
         let error_msg = "Study plan name cannot be empty";
@@ -267,6 +295,29 @@ pub async fn generate_study_plan_schedule(
         word_list: all_words,
     };
 
+    // 使用本地规划器，无需AI模型
+    if request.use_local_planner {
+        use crate::services::study_plan_scheduler;
+
+        return match study_plan_scheduler::generate_shaped_schedule(&ai_params, &day_weights) {
+            Ok(result) => {
+                logger.api_response(
+                    "generate_study_plan_schedule",
+                    true,
+                    Some(&format!(
+                        "Generated local schedule with {} daily plans",
+                        result.daily_plans.len()
+                    )),
+                );
+                Ok(result)
+            }
+            Err(e) => {
+                logger.api_response("generate_study_plan_schedule", false, Some(&e.to_string()));
+                Err(e)
+            }
+        };
+    }
+
     // 获取AI模型配置
     use crate::services::ai_model::AIModelService;
     let ai_model_service = AIModelService::new(
@@ -277,7 +328,10 @@ pub async fn generate_study_plan_schedule(
 
     // 创建AI服务并调用学习计划规划
     let ai_service = match AIService::from_model_config(&model_config) {
-        Ok(service) => service,
+        Ok(service) => service.with_usage_log(AIUsageRepository::new(
+            Arc::new(pool.inner().clone()),
+            Arc::new(logger.inner().clone()),
+        )),
         Err(e) => {
             let error_msg = format!("Failed to create AI service: {}", e);
             logger.api_response("generate_study_plan_schedule", false, Some(&error_msg));
@@ -285,9 +339,19 @@ pub async fn generate_study_plan_schedule(
         }
     };
 
-    // 调用AI服务生成学习计划
+    // 调用AI服务生成学习计划，每解析出一天的计划立即推送给前端
+    let on_daily_plan = |index: usize, daily_plan: &DailyStudyPlan| {
+        let event = StudyPlanDailyPlanEvent { index, daily_plan };
+        if let Err(e) = app.emit_to("main", "study-plan-daily-plan", &event) {
+            logger.error(
+                "STUDY_PLAN_SCHEDULE",
+                &format!("Failed to emit study-plan-daily-plan event: {}", e),
+                None,
+            );
+        }
+    };
     match ai_service
-        .generate_study_plan_schedule(ai_params, &model_config, &logger)
+        .generate_study_plan_schedule_streaming(ai_params, &model_config, &logger, &on_daily_plan)
         .await
     {
         Ok(result) => {
@@ -349,6 +413,46 @@ pub async fn create_study_plan_with_schedule(
     }
 }
 
+/// 校验AI学习计划规划数据
+#[tauri::command]
+pub async fn validate_study_plan_schedule(
+    app: AppHandle,
+    request: ValidateStudyPlanScheduleRequest,
+) -> AppResult<StudyPlanValidationResult> {
+    use crate::services::study_plan::StudyPlanService;
+
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "validate_study_plan_schedule",
+        Some(&format!(
+            "wordbooks: {:?}, range: {} ~ {}, repair: {}",
+            request.wordbook_ids, request.start_date, request.end_date, request.repair
+        )),
+    );
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.validate_study_plan_schedule(request).await {
+        Ok(result) => {
+            logger.api_response(
+                "validate_study_plan_schedule",
+                true,
+                Some(&format!("Found {} violations", result.violations.len())),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("validate_study_plan_schedule", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
 /// 获取学习计划的单词列表（显示原始单词本单词，而不是学习日程单词）
 #[tauri::command]
 pub async fn get_study_plan_words(app: AppHandle, plan_id: i64) -> AppResult<Vec<StudyPlanWord>> {
@@ -576,6 +680,391 @@ pub async fn terminate_study_plan(app: AppHandle, plan_id: i64) -> AppResult<()>
     }
 }
 
+/// 暂停学习计划
+#[tauri::command]
+pub async fn pause_study_plan(app: AppHandle, plan_id: i64) -> AppResult<()> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("pause_study_plan", Some(&format!("plan_id: {}", plan_id)));
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.pause_study_plan(plan_id).await {
+        Ok(_) => {
+            logger.api_response("pause_study_plan", true, Some("学习计划已暂停"));
+            Ok(())
+        }
+        Err(e) => {
+            logger.api_response("pause_study_plan", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 恢复学习计划（未完成的日程按暂停天数后移）
+#[tauri::command]
+pub async fn resume_study_plan(app: AppHandle, plan_id: i64) -> AppResult<ResumeStudyPlanResult> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("resume_study_plan", Some(&format!("plan_id: {}", plan_id)));
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.resume_study_plan(plan_id).await {
+        Ok(result) => {
+            logger.api_response(
+                "resume_study_plan",
+                true,
+                Some(&format!(
+                    "学习计划已恢复，{} 个日程后移 {} 天",
+                    result.shifted_schedules, result.paused_days
+                )),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("resume_study_plan", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 预览逾期日程重新分配（不修改数据）
+#[tauri::command]
+pub async fn preview_rebalance_study_plan(
+    app: AppHandle,
+    plan_id: i64,
+) -> AppResult<StudyPlanRebalancePreview> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("preview_rebalance_study_plan", Some(&format!("plan_id: {}", plan_id)));
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.preview_rebalance_study_plan(plan_id).await {
+        Ok(preview) => {
+            logger.api_response(
+                "preview_rebalance_study_plan",
+                true,
+                Some(&format!(
+                    "{} 个单词待移动，{} 个复习不再安排，延长 {} 天",
+                    preview.moved_words, preview.dropped_reviews, preview.added_days
+                )),
+            );
+            Ok(preview)
+        }
+        Err(e) => {
+            logger.api_response("preview_rebalance_study_plan", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 重新分配逾期日程（未完成的单词分配到今天及之后，必要时延长计划）
+///
+/// `fingerprint` 为预览返回的指纹，日程在预览后变化时拒绝应用
+#[tauri::command]
+pub async fn rebalance_study_plan(
+    app: AppHandle,
+    plan_id: i64,
+    fingerprint: String,
+) -> AppResult<StudyPlanRebalancePreview> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("rebalance_study_plan", Some(&format!("plan_id: {}", plan_id)));
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.rebalance_study_plan(plan_id, &fingerprint).await {
+        Ok(result) => {
+            logger.api_response(
+                "rebalance_study_plan",
+                true,
+                Some(&format!(
+                    "已移动 {} 个单词，结束日期 {:?}",
+                    result.moved_words, result.new_end_date
+                )),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("rebalance_study_plan", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 向草稿或进行中的学习计划追加单词或单词本
+///
+/// 只对新增的单词做一次增量规划（本地规划器或AI），结果写入今天之后未完成的日程
+#[tauri::command]
+pub async fn add_words_to_study_plan(
+    app: AppHandle,
+    request: AddPlanWordsRequest,
+) -> AppResult<AddPlanWordsResult> {
+    use crate::ai_service::AIService;
+    use crate::services::ai_model::AIModelService;
+
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "add_words_to_study_plan",
+        Some(&format!(
+            "plan_id: {}, words: {}, wordbooks: {:?}, local: {}",
+            request.plan_id,
+            request.word_ids.len(),
+            request.wordbook_ids,
+            request.use_local_planner
+        )),
+    );
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    let addition = match service.prepare_plan_word_addition(&request).await {
+        Ok(addition) => addition,
+        Err(e) => {
+            logger.api_response("add_words_to_study_plan", false, Some(&e.to_string()));
+            return Err(e);
+        }
+    };
+
+    // AI 增量规划：只规划新增的单词，日期由服务对齐到可用日程
+    let ai_plans = if request.use_local_planner {
+        None
+    } else {
+        let ai_model_service = AIModelService::new(
+            Arc::new(pool.inner().clone()),
+            Arc::new(logger.inner().clone())
+        );
+        let model_config = ai_model_service.get_model_config(request.model_id).await?;
+
+        let ai_service = match AIService::from_model_config(&model_config) {
+            Ok(service) => service.with_usage_log(AIUsageRepository::new(
+                Arc::new(pool.inner().clone()),
+                Arc::new(logger.inner().clone()),
+            )),
+            Err(e) => {
+                let error_msg = format!("Failed to create AI service: {}", e);
+                logger.api_response("add_words_to_study_plan", false, Some(&error_msg));
+                return Err(AppError::InternalError(error_msg));
+            }
+        };
+
+        match ai_service
+            .generate_study_plan_schedule(addition.params.clone(), &model_config, &logger)
+            .await
+        {
+            Ok(result) => Some(result.daily_plans),
+            Err(e) => {
+                let error_msg = format!("Failed to plan additional words: {}", e);
+                logger.api_response("add_words_to_study_plan", false, Some(&error_msg));
+                return Err(AppError::InternalError(error_msg));
+            }
+        }
+    };
+
+    match service.add_words_to_plan(&addition, ai_plans).await {
+        Ok(result) => {
+            logger.api_response(
+                "add_words_to_study_plan",
+                true,
+                Some(&format!(
+                    "已添加 {} 个单词，跳过 {} 个，结束日期 {:?}",
+                    result.added_words, result.skipped_words, result.end_date
+                )),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("add_words_to_study_plan", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 复制学习计划（沿用设置和日程形状，学习新的单词本）
+#[tauri::command]
+pub async fn clone_study_plan(app: AppHandle, request: CloneStudyPlanRequest) -> AppResult<Id> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "clone_study_plan",
+        Some(&format!(
+            "plan_id: {}, start_date: {}, wordbooks: {:?}",
+            request.plan_id, request.start_date, request.wordbook_ids
+        )),
+    );
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.clone_study_plan(request).await {
+        Ok(plan_id) => {
+            logger.api_response(
+                "clone_study_plan",
+                true,
+                Some(&format!("新计划 ID: {}", plan_id)),
+            );
+            Ok(plan_id)
+        }
+        Err(e) => {
+            logger.api_response("clone_study_plan", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 获取所有学习计划模板
+#[tauri::command]
+pub async fn get_study_plan_templates(app: AppHandle) -> AppResult<Vec<StudyPlanTemplate>> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("get_study_plan_templates", None);
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.get_study_plan_templates().await {
+        Ok(templates) => {
+            logger.api_response(
+                "get_study_plan_templates",
+                true,
+                Some(&format!("共 {} 个模板", templates.len())),
+            );
+            Ok(templates)
+        }
+        Err(e) => {
+            logger.api_response("get_study_plan_templates", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 创建学习计划模板
+#[tauri::command]
+pub async fn create_study_plan_template(
+    app: AppHandle,
+    request: CreateStudyPlanTemplateRequest,
+) -> AppResult<Id> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "create_study_plan_template",
+        Some(&format!("name: {}", request.name)),
+    );
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.create_study_plan_template(request).await {
+        Ok(template_id) => {
+            logger.api_response(
+                "create_study_plan_template",
+                true,
+                Some(&format!("模板 ID: {}", template_id)),
+            );
+            Ok(template_id)
+        }
+        Err(e) => {
+            logger.api_response("create_study_plan_template", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 把学习计划保存为模板
+#[tauri::command]
+pub async fn save_study_plan_as_template(
+    app: AppHandle,
+    plan_id: i64,
+    name: String,
+) -> AppResult<Id> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "save_study_plan_as_template",
+        Some(&format!("plan_id: {}, name: {}", plan_id, name)),
+    );
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.save_study_plan_as_template(plan_id, name).await {
+        Ok(template_id) => {
+            logger.api_response(
+                "save_study_plan_as_template",
+                true,
+                Some(&format!("模板 ID: {}", template_id)),
+            );
+            Ok(template_id)
+        }
+        Err(e) => {
+            logger.api_response("save_study_plan_as_template", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 删除学习计划模板
+#[tauri::command]
+pub async fn delete_study_plan_template(app: AppHandle, template_id: i64) -> AppResult<()> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "delete_study_plan_template",
+        Some(&format!("template_id: {}", template_id)),
+    );
+
+    let service = StudyPlanService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.delete_study_plan_template(template_id).await {
+        Ok(()) => {
+            logger.api_response("delete_study_plan_template", true, None);
+            Ok(())
+        }
+        Err(e) => {
+            logger.api_response("delete_study_plan_template", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
 /// 重新学习计划（从已完成或已终止状态重新开始）
 #[tauri::command]
 pub async fn restart_study_plan(app: AppHandle, plan_id: i64) -> AppResult<()> {
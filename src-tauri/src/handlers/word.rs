@@ -56,6 +56,46 @@ pub async fn get_words_by_book(
 }
 
 
+/// 全文搜索所有单词本中的单词（按相关度排序，带高亮）
+#[tauri::command]
+pub async fn search_words(
+    app: AppHandle,
+    query: WordSearchQuery,
+) -> AppResult<PaginatedResponse<Word>> {
+    use crate::services::word::WordService;
+
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "search_words",
+        Some(&format!(
+            "query: {:?}, book_id: {:?}, theme_tag_id: {:?}, part_of_speech: {:?}, page: {:?}",
+            query.query, query.book_id, query.theme_tag_id, query.part_of_speech, query.page
+        )),
+    );
+
+    let service = WordService::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone())
+    );
+
+    match service.search_words(query).await {
+        Ok(result) => {
+            logger.api_response(
+                "search_words",
+                true,
+                Some(&format!("Found {} words, total: {}", result.data.len(), result.total)),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("search_words", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
 /// 添加单词到单词本
 #[tauri::command]
 pub async fn add_word_to_book(
@@ -4,6 +4,8 @@
 
 use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
+use crate::services::word_export::WordExportService;
+use crate::services::word_import::WordImportService;
 use crate::services::wordbook::WordBookService;
 use crate::types::wordbook::WordTypeDistribution;
 use crate::types::*;
@@ -277,6 +279,158 @@ pub async fn create_word_book(app: AppHandle, request: CreateWordBookRequest) ->
     }
 }
 
+/// 预览单词导入（列映射、重复行和冲突）
+#[tauri::command]
+pub async fn preview_word_import(
+    app: AppHandle,
+    request: WordImportRequest,
+) -> AppResult<WordImportPreview> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "preview_word_import",
+        Some(&format!(
+            "book_id: {:?}, format: {:?}, size: {}",
+            request.book_id,
+            request.format,
+            request.content.len()
+        )),
+    );
+
+    let service = WordImportService::new(Arc::new(pool.inner().clone()), Arc::new(logger.inner().clone()));
+
+    match service.preview(&request).await {
+        Ok(preview) => {
+            logger.api_response(
+                "preview_word_import",
+                true,
+                Some(&format!(
+                    "{} rows, {} new, {} conflicts",
+                    preview.total_rows,
+                    preview.new_count,
+                    preview.conflicts.len()
+                )),
+            );
+            Ok(preview)
+        }
+        Err(e) => {
+            logger.api_response("preview_word_import", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 从 CSV、TSV 或 Anki 纯文本导入单词
+#[tauri::command]
+pub async fn import_words(app: AppHandle, request: WordImportRequest) -> AppResult<WordSaveResult> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "import_words",
+        Some(&format!(
+            "book_id: {:?}, format: {:?}, conflict_strategy: {:?}",
+            request.book_id, request.format, request.conflict_strategy
+        )),
+    );
+
+    let service = WordImportService::new(Arc::new(pool.inner().clone()), Arc::new(logger.inner().clone()));
+
+    match service.import(request).await {
+        Ok(result) => {
+            logger.api_response(
+                "import_words",
+                true,
+                Some(&format!(
+                    "book {}: {} added, {} updated, {} skipped",
+                    result.book_id, result.added_count, result.updated_count, result.skipped_count
+                )),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("import_words", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 导入单词本 JSON 导出包
+#[tauri::command]
+pub async fn import_word_book_bundle(app: AppHandle, content: String) -> AppResult<WordSaveResult> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("import_word_book_bundle", Some(&format!("size: {}", content.len())));
+
+    let service = WordImportService::new(Arc::new(pool.inner().clone()), Arc::new(logger.inner().clone()));
+
+    match service.import_bundle(&content).await {
+        Ok(result) => {
+            logger.api_response(
+                "import_word_book_bundle",
+                true,
+                Some(&format!(
+                    "book {}: {} added, {} skipped",
+                    result.book_id, result.added_count, result.skipped_count
+                )),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("import_word_book_bundle", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 导出单词本为 CSV、JSON 导出包或 Anki 牌组
+#[tauri::command]
+pub async fn export_word_book(
+    app: AppHandle,
+    book_id: Id,
+    request: WordBookExportRequest,
+) -> AppResult<WordBookExportResult> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "export_word_book",
+        Some(&format!(
+            "book_id: {}, format: {:?}, include_audio: {}",
+            book_id, request.format, request.include_audio
+        )),
+    );
+
+    // 默认保存到下载目录，没有下载目录时保存到应用数据目录
+    let default_dir = match app.path().download_dir() {
+        Ok(dir) => dir,
+        Err(_) => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::InternalError(e.to_string()))?
+            .join("exports"),
+    };
+
+    let service = WordExportService::new(Arc::new(pool.inner().clone()), Arc::new(logger.inner().clone()));
+
+    match service.export_to_file(book_id, &request, &default_dir).await {
+        Ok(result) => {
+            logger.api_response(
+                "export_word_book",
+                true,
+                Some(&format!("{} words -> {}", result.word_count, result.file_path)),
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            logger.api_response("export_word_book", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
 /// 更新单词本
 #[tauri::command]
 pub async fn update_word_book(
@@ -1,17 +1,26 @@
 mod database;
-mod error;
+pub mod error;
 mod handlers;
-mod logger;
-mod repositories;
-mod services;
-mod types;
+pub mod logger;
+pub mod repositories;
+pub mod services;
+pub mod types;
 
 mod ai_model_handlers;
-mod ai_service;
+pub mod ai_service;
+pub mod anki_package;
+mod batch_executor;
+pub mod llm_provider;
 mod progress_manager;
+pub mod prompt_registry;
+mod stream_parser;
 mod tts_handlers;
 mod tts_service;
 mod word_analysis_handlers;
+pub mod word_extractor;
+pub mod word_import;
+pub mod phonics_rules;
+pub mod pronunciation;
 
 #[cfg(test)]
 mod test_statistics;
@@ -58,6 +67,12 @@ pub fn run() {
                 #[cfg(debug_assertions)]
                 logger.info("APP", "Running in development mode with DevTools enabled");
 
+                // 初始化提示词模板注册表（应用数据目录中的自定义模板优先）
+                let prompt_registry = prompt_registry::init_prompt_registry(&app_data_dir);
+                if let Some(dir) = prompt_registry.dir() {
+                    logger.info("APP", &format!("Prompt template directory: {}", dir.display()));
+                }
+
                 // 构建数据库路径
                 let db_path = app_data_dir.join("vocabulary.db");
                 let db_url = format!("sqlite:{}", db_path.to_string_lossy());
@@ -86,6 +101,43 @@ pub fn run() {
                         }
 
                         let pool = db_manager.pool().clone();
+
+                        // 记录当前提示词模板版本（包括手动修改的模板文件）
+                        if let Err(e) = services::prompt::PromptService::new(
+                            std::sync::Arc::new(pool.clone()),
+                            std::sync::Arc::new(logger.clone()),
+                        )
+                        .record_current_versions()
+                        .await
+                        {
+                            logger.error(
+                                "APP",
+                                "Failed to record prompt versions",
+                                Some(&e.to_string()),
+                            );
+                        }
+
+                        // 启动自动备份
+                        match handlers::backup::backup_paths(app) {
+                            Ok(paths) => {
+                                tauri::async_runtime::spawn(
+                                    services::backup::BackupService::new(
+                                        std::sync::Arc::new(pool.clone()),
+                                        std::sync::Arc::new(logger.clone()),
+                                        paths,
+                                    )
+                                    .run_scheduler(),
+                                );
+                            }
+                            Err(e) => {
+                                logger.error(
+                                    "BACKUP",
+                                    "Failed to start scheduled backups",
+                                    Some(&e.to_string()),
+                                );
+                            }
+                        }
+
                         app.manage(pool);
                         app.manage(logger);
                     }
@@ -118,6 +170,7 @@ pub fn run() {
             update_word_book,
             delete_word_book,
             get_words_by_book,
+            search_words,
             add_word_to_book,
             update_word,
             delete_word,
@@ -134,6 +187,16 @@ pub fn run() {
             start_study_plan,
             complete_study_plan,
             terminate_study_plan,
+            pause_study_plan,
+            resume_study_plan,
+            preview_rebalance_study_plan,
+            rebalance_study_plan,
+            add_words_to_study_plan,
+            clone_study_plan,
+            get_study_plan_templates,
+            create_study_plan_template,
+            save_study_plan_as_template,
+            delete_study_plan_template,
             restart_study_plan,
             edit_study_plan,
             publish_study_plan,
@@ -141,6 +204,11 @@ pub fn run() {
             get_study_plan_status_history,
             get_system_logs,
             create_word_book_from_analysis,
+            // 单词导入导出命令
+            preview_word_import,
+            import_words,
+            import_word_book_bundle,
+            export_word_book,
             ai_model_handlers::get_ai_providers,
             ai_model_handlers::get_ai_models,
             ai_model_handlers::get_all_ai_providers,
@@ -155,6 +223,19 @@ pub fn run() {
             ai_model_handlers::delete_ai_model,
             ai_model_handlers::analyze_phonics_with_model,
             ai_model_handlers::test_ai_model,
+            ai_model_handlers::update_ai_model_pricing,
+            ai_model_handlers::get_ai_usage_report,
+            // 提示词模板命令
+            get_prompt_templates,
+            save_prompt_template,
+            reset_prompt_template,
+            get_prompt_versions,
+            restore_prompt_version,
+            // 发音词典命令
+            import_pronunciation_dictionary,
+            get_pronunciation_dictionary_count,
+            lookup_pronunciation,
+            check_word_ipa,
             get_analysis_progress,
             clear_analysis_progress,
             cancel_analysis,
@@ -162,11 +243,18 @@ pub fn run() {
             word_analysis_handlers::extract_words_from_text,
             word_analysis_handlers::analyze_extracted_words,
             word_analysis_handlers::analyze_text_with_batching,
+            word_analysis_handlers::get_extraction_word_lists,
+            word_analysis_handlers::set_extraction_word_list,
+            word_analysis_handlers::add_extraction_words,
+            word_analysis_handlers::check_word_phonics,
+            word_analysis_handlers::get_batch_analysis_jobs,
+            word_analysis_handlers::resume_batch_analysis,
             word_analysis_handlers::get_batch_analysis_progress,
             word_analysis_handlers::cancel_batch_analysis,
             // 学习计划AI规划命令
             generate_study_plan_schedule,
             create_study_plan_with_schedule,
+            validate_study_plan_schedule,
             // 新增的学习计划单词管理命令
             get_study_plan_words,
             get_study_plan_word_books,
@@ -185,6 +273,14 @@ pub fn run() {
             reset_user_data,
             reset_selected_tables,
             delete_database_and_restart,
+            get_operation_snapshots,
+            undo_last_operation,
+            // 数据库备份命令
+            create_backup,
+            get_backups,
+            get_backup_settings,
+            update_backup_settings,
+            restore_backup,
             // 单词练习相关命令
             start_practice_session,
             submit_step_result,
@@ -196,6 +292,8 @@ pub fn run() {
             get_practice_session_detail,
             get_plan_practice_sessions,
             get_practice_statistics,
+            get_word_review_states,
+            set_review_algorithm,
             get_study_plan_schedules,
             // TTS相关命令
             tts_handlers::text_to_speech,
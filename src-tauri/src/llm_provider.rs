@@ -0,0 +1,954 @@
+//! LLM 提供商抽象
+//!
+//! `AIService` 通过 `LlmProvider` trait 调用大模型，具体实现由 `ai_providers.provider_type` 决定:
+//! - `openai`: OpenAI 兼容接口（OpenRouter、月之暗面等）
+//! - `anthropic`: Anthropic Messages API
+//! - `ollama`: Ollama 原生 `/api/chat` 接口
+//! - `mock`: 按脚本文件（`base_url` 为文件路径）返回固定内容，用于离线调试和测试
+//!
+//! 请求可附带 `ResponseSchema` 约束输出为符合 JSON Schema 的 JSON:
+//! OpenAI 兼容接口和 Anthropic 通过强制调用工具实现，Ollama 使用 `format` 字段；
+//! 不支持的提供商（`supports_structured_output` 为 false）忽略该约束
+//!
+//! 流式响应除增量文本外还转发提供商返回的 token 用量:
+//! OpenAI 兼容接口请求 `stream_options.include_usage`，Anthropic 读取 `message_start`/`message_delta`，
+//! Ollama 读取最后一个分块的 `prompt_eval_count`/`eval_count`
+
+use async_openai::{
+    config::OpenAIConfig,
+    types::{
+        ChatCompletionNamedToolChoice, ChatCompletionRequestAssistantMessageArgs,
+        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
+        ChatCompletionRequestUserMessage, ChatCompletionRequestUserMessageContent,
+        ChatCompletionTool, ChatCompletionToolChoiceOption, ChatCompletionToolType,
+        CreateChatCompletionRequest, CreateChatCompletionRequestArgs, FunctionName,
+        FunctionObject, Role,
+    },
+    Client,
+};
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream};
+use futures::{FutureExt, StreamExt};
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+/// LLM 调用结果（错误需要跨线程传递）
+pub type LlmResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 流式响应
+pub type LlmStream = BoxStream<'static, LlmResult<LlmStreamEvent>>;
+
+/// 流式响应中的一项
+#[derive(Debug, Clone, PartialEq)]
+pub enum LlmStreamEvent {
+    /// 增量文本
+    Delta(String),
+    /// 提供商返回的 token 用量（可能分多次返回，后到的值覆盖先到的值）
+    Usage {
+        prompt_tokens: Option<u32>,
+        completion_tokens: Option<u32>,
+    },
+}
+
+/// 按行切分的响应正文
+type LineStream = BoxStream<'static, LlmResult<String>>;
+
+/// Anthropic API 版本
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// 提供商类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProviderType {
+    #[serde(rename = "openai")]
+    OpenAiCompatible,
+    Anthropic,
+    Ollama,
+    Mock, // 脚本化 Mock，base_url 为脚本文件路径
+}
+
+impl LlmProviderType {
+    /// 数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmProviderType::OpenAiCompatible => "openai",
+            LlmProviderType::Anthropic => "anthropic",
+            LlmProviderType::Ollama => "ollama",
+            LlmProviderType::Mock => "mock",
+        }
+    }
+
+    /// 从数据库值解析，未知值按 OpenAI 兼容处理
+    pub fn from_db(value: &str) -> Self {
+        Self::parse(value).unwrap_or(LlmProviderType::OpenAiCompatible)
+    }
+
+    /// 严格解析，用于校验用户输入
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "openai" => Some(LlmProviderType::OpenAiCompatible),
+            "anthropic" => Some(LlmProviderType::Anthropic),
+            "ollama" => Some(LlmProviderType::Ollama),
+            "mock" => Some(LlmProviderType::Mock),
+            _ => None,
+        }
+    }
+}
+
+/// 对话消息
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LlmMessage {
+    /// system / user / assistant
+    pub role: String,
+    pub content: String,
+}
+
+impl LlmMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: "system".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// 结构化输出约束
+#[derive(Debug, Clone)]
+pub struct ResponseSchema {
+    /// 工具名称（只能包含字母、数字、下划线和连字符）
+    pub name: String,
+    pub description: String,
+    /// 输出需要符合的 JSON Schema（object 类型，子结构已内联）
+    pub schema: Value,
+}
+
+impl ResponseSchema {
+    /// 从类型定义生成 Schema
+    pub fn of<T: JsonSchema>(name: &str, description: &str) -> Self {
+        // 部分提供商不支持 $ref，子结构全部内联
+        let generator = SchemaSettings::draft07()
+            .with(|settings| {
+                settings.inline_subschemas = true;
+                settings.option_add_null_type = false;
+            })
+            .into_generator();
+
+        let mut schema = serde_json::to_value(generator.into_root_schema_for::<T>())
+            .unwrap_or_else(|_| json!({ "type": "object" }));
+        if let Value::Object(ref mut obj) = schema {
+            obj.remove("$schema");
+            obj.remove("title");
+        }
+
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            schema,
+        }
+    }
+}
+
+/// 对话请求
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    pub model: String,
+    pub messages: Vec<LlmMessage>,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    /// 结构化输出约束，None 时为自由文本
+    pub response_schema: Option<ResponseSchema>,
+}
+
+/// 对话响应
+#[derive(Debug, Clone, Default)]
+pub struct LlmResponse {
+    pub content: String,
+    /// 输入 token 数（提供商未返回时为 None）
+    pub prompt_tokens: Option<u32>,
+    /// 输出 token 数（提供商未返回时为 None）
+    pub completion_tokens: Option<u32>,
+}
+
+/// LLM 提供商
+pub trait LlmProvider: Send + Sync {
+    /// 提供商类型
+    fn provider_type(&self) -> LlmProviderType;
+
+    /// 是否支持 `LlmRequest::response_schema` 约束输出
+    fn supports_structured_output(&self) -> bool;
+
+    /// 非流式对话
+    fn complete(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmResponse>>;
+
+    /// 流式对话，丢弃返回的流即中断请求
+    fn complete_stream(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmStream>>;
+}
+
+/// 根据提供商配置创建 LLM 提供商
+pub fn create_provider(
+    provider_type: LlmProviderType,
+    base_url: &str,
+    api_key: &str,
+) -> Arc<dyn LlmProvider> {
+    match provider_type {
+        LlmProviderType::OpenAiCompatible => Arc::new(OpenAiCompatibleProvider::new(base_url, api_key)),
+        LlmProviderType::Anthropic => Arc::new(AnthropicProvider::new(base_url, api_key)),
+        LlmProviderType::Ollama => Arc::new(OllamaProvider::new(base_url)),
+        LlmProviderType::Mock => Arc::new(MockLlmProvider::from_script_file(base_url)),
+    }
+}
+
+// ==================== OpenAI 兼容接口 ====================
+
+/// OpenAI 兼容提供商
+///
+/// 流式请求直接发送 HTTP 请求，async-openai 不支持 `stream_options`，无法取得流式用量
+pub struct OpenAiCompatibleProvider {
+    client: Client<OpenAIConfig>,
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        let config = OpenAIConfig::new()
+            .with_api_key(api_key)
+            .with_api_base(base_url);
+
+        Self {
+            client: Client::with_config(config),
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// 构建 OpenAI 请求
+    fn build_request(request: LlmRequest, stream: bool) -> LlmResult<CreateChatCompletionRequest> {
+        let mut messages = Vec::with_capacity(request.messages.len());
+        for message in request.messages {
+            let message = match message.role.as_str() {
+                "system" => ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+                    content: message.content,
+                    role: Role::System,
+                    name: None,
+                }),
+                "assistant" => ChatCompletionRequestMessage::Assistant(
+                    ChatCompletionRequestAssistantMessageArgs::default()
+                        .content(message.content)
+                        .build()?,
+                ),
+                _ => ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                    content: ChatCompletionRequestUserMessageContent::Text(message.content),
+                    role: Role::User,
+                    name: None,
+                }),
+            };
+            messages.push(message);
+        }
+
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.model(request.model)
+            .messages(messages)
+            .max_tokens(request.max_tokens.min(65535) as u16)
+            .temperature(request.temperature)
+            .stream(stream);
+
+        // 强制调用唯一的工具，工具参数即结构化输出
+        if let Some(schema) = request.response_schema {
+            args.tools(vec![ChatCompletionTool {
+                r#type: ChatCompletionToolType::Function,
+                function: FunctionObject {
+                    name: schema.name.clone(),
+                    description: Some(schema.description),
+                    parameters: Some(schema.schema),
+                },
+            }])
+            .tool_choice(ChatCompletionToolChoiceOption::Named(
+                ChatCompletionNamedToolChoice {
+                    r#type: ChatCompletionToolType::Function,
+                    function: FunctionName { name: schema.name },
+                },
+            ));
+        }
+
+        Ok(args.build()?)
+    }
+
+    /// 构建流式请求体，要求在最后一个分块中返回用量
+    fn build_stream_body(request: LlmRequest) -> LlmResult<Value> {
+        let mut body = serde_json::to_value(Self::build_request(request, true)?)?;
+        body["stream_options"] = json!({ "include_usage": true });
+        Ok(body)
+    }
+
+    /// 解析一行 SSE，可能同时包含增量文本和用量
+    fn parse_stream_line(line: &str) -> Vec<LlmResult<LlmStreamEvent>> {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return Vec::new();
+        };
+        let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+            // 包括结束标记 [DONE]
+            return Vec::new();
+        };
+        if chunk["error"].is_object() {
+            return vec![Err(format!(
+                "OpenAI stream error: {}",
+                chunk["error"]["message"].as_str().unwrap_or("unknown")
+            )
+            .into())];
+        }
+
+        let mut events = Vec::new();
+        // 结构化输出时取工具调用参数增量
+        let delta = &chunk["choices"][0]["delta"];
+        let text = delta["tool_calls"][0]["function"]["arguments"]
+            .as_str()
+            .or_else(|| delta["content"].as_str());
+        if let Some(text) = text.filter(|text| !text.is_empty()) {
+            events.push(Ok(LlmStreamEvent::Delta(text.to_string())));
+        }
+        // 其余分块的 usage 为 null，最后一个分块（choices 为空）携带整个请求的用量
+        if chunk["usage"].is_object() {
+            events.push(Ok(LlmStreamEvent::Usage {
+                prompt_tokens: token_count(&chunk["usage"]["prompt_tokens"]),
+                completion_tokens: token_count(&chunk["usage"]["completion_tokens"]),
+            }));
+        }
+        events
+    }
+}
+
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn provider_type(&self) -> LlmProviderType {
+        LlmProviderType::OpenAiCompatible
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn complete(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmResponse>> {
+        async move {
+            let request = Self::build_request(request, false)?;
+            let response = self.client.chat().create(request).await?;
+
+            // 结构化输出时取工具调用参数
+            let content = response
+                .choices
+                .first()
+                .and_then(|choice| {
+                    choice
+                        .message
+                        .tool_calls
+                        .as_ref()
+                        .and_then(|calls| calls.first())
+                        .map(|call| call.function.arguments.clone())
+                        .or_else(|| choice.message.content.clone())
+                })
+                .ok_or("No content in response")?;
+
+            Ok(LlmResponse {
+                content,
+                prompt_tokens: response.usage.as_ref().map(|u| u.prompt_tokens),
+                completion_tokens: response.usage.as_ref().map(|u| u.completion_tokens),
+            })
+        }
+        .boxed()
+    }
+
+    fn complete_stream(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmStream>> {
+        async move {
+            let body = Self::build_stream_body(request)?;
+            let response = self
+                .http
+                .post(format!("{}/chat/completions", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&body)
+                .send()
+                .await?;
+            let response = ensure_success(response).await?;
+
+            Ok(parse_lines(response, Self::parse_stream_line))
+        }
+        .boxed()
+    }
+}
+
+// ==================== Anthropic Messages API ====================
+
+/// Anthropic 提供商
+pub struct AnthropicProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// 构建请求体，system 消息放入顶层 system 字段
+    fn build_body(request: LlmRequest, stream: bool) -> Value {
+        let mut system_parts = Vec::new();
+        let mut messages = Vec::new();
+        for message in request.messages {
+            if message.role == "system" {
+                system_parts.push(message.content);
+            } else {
+                messages.push(json!({ "role": message.role, "content": message.content }));
+            }
+        }
+
+        // Messages API 至少需要一条 user 消息，仅有 system 提示词时改为 user 消息发送
+        if messages.is_empty() {
+            messages.push(json!({ "role": "user", "content": system_parts.join("\n\n") }));
+            system_parts.clear();
+        }
+
+        let mut body = json!({
+            "model": request.model,
+            "max_tokens": request.max_tokens,
+            "temperature": request.temperature,
+            "messages": messages,
+            "stream": stream,
+        });
+        if !system_parts.is_empty() {
+            body["system"] = Value::String(system_parts.join("\n\n"));
+        }
+        // 强制调用唯一的工具，工具输入即结构化输出
+        if let Some(schema) = request.response_schema {
+            body["tools"] = json!([{
+                "name": schema.name,
+                "description": schema.description,
+                "input_schema": schema.schema,
+            }]);
+            body["tool_choice"] = json!({ "type": "tool", "name": schema.name });
+        }
+        body
+    }
+
+    async fn send(&self, body: Value) -> LlmResult<reqwest::Response> {
+        let response = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&body)
+            .send()
+            .await?;
+
+        ensure_success(response).await
+    }
+
+    /// 解析一行 SSE: 文本或工具输入增量，以及 message_start / message_delta 中的用量
+    fn parse_stream_line(line: &str) -> Vec<LlmResult<LlmStreamEvent>> {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return Vec::new();
+        };
+        let Ok(event) = serde_json::from_str::<Value>(data) else {
+            return Vec::new();
+        };
+        let item = match event["type"].as_str() {
+            Some("content_block_delta") => event["delta"]["text"]
+                .as_str()
+                .or_else(|| event["delta"]["partial_json"].as_str())
+                .map(|text| Ok(LlmStreamEvent::Delta(text.to_string()))),
+            // message_delta 中的 output_tokens 为累计值
+            Some("message_start") | Some("message_delta") => {
+                let usage = match event.get("message") {
+                    Some(message) => &message["usage"],
+                    None => &event["usage"],
+                };
+                Some(Ok(LlmStreamEvent::Usage {
+                    prompt_tokens: token_count(&usage["input_tokens"]),
+                    completion_tokens: token_count(&usage["output_tokens"]),
+                }))
+            }
+            Some("error") => Some(Err(format!(
+                "Anthropic stream error: {}",
+                event["error"]["message"].as_str().unwrap_or("unknown")
+            )
+            .into())),
+            _ => None,
+        };
+        item.into_iter().collect()
+    }
+}
+
+impl LlmProvider for AnthropicProvider {
+    fn provider_type(&self) -> LlmProviderType {
+        LlmProviderType::Anthropic
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn complete(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmResponse>> {
+        async move {
+            let response = self.send(Self::build_body(request, false)).await?;
+            let body: Value = response.json().await?;
+
+            let blocks = body["content"].as_array().cloned().unwrap_or_default();
+            let content: String = match blocks.iter().find(|block| block["type"] == "tool_use") {
+                Some(tool_use) => tool_use["input"].to_string(),
+                None => blocks
+                    .iter()
+                    .filter_map(|block| block["text"].as_str())
+                    .collect(),
+            };
+
+            if content.is_empty() {
+                return Err("No content in response".into());
+            }
+
+            Ok(LlmResponse {
+                content,
+                prompt_tokens: body["usage"]["input_tokens"].as_u64().map(|n| n as u32),
+                completion_tokens: body["usage"]["output_tokens"].as_u64().map(|n| n as u32),
+            })
+        }
+        .boxed()
+    }
+
+    fn complete_stream(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmStream>> {
+        async move {
+            let response = self.send(Self::build_body(request, true)).await?;
+            Ok(parse_lines(response, Self::parse_stream_line))
+        }
+        .boxed()
+    }
+}
+
+// ==================== Ollama 原生接口 ====================
+
+/// Ollama 提供商
+pub struct OllamaProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl OllamaProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn build_body(request: LlmRequest, stream: bool) -> Value {
+        let mut body = json!({
+            "model": request.model,
+            "messages": request.messages,
+            "stream": stream,
+            "options": {
+                "temperature": request.temperature,
+                "num_predict": request.max_tokens,
+            },
+        });
+        if let Some(schema) = request.response_schema {
+            body["format"] = schema.schema;
+        }
+        body
+    }
+
+    async fn send(&self, body: Value) -> LlmResult<reqwest::Response> {
+        let response = self
+            .client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&body)
+            .send()
+            .await?;
+
+        ensure_success(response).await
+    }
+
+    /// 解析一行 NDJSON，最后一个分块（done 为 true）携带用量
+    fn parse_stream_line(line: &str) -> Vec<LlmResult<LlmStreamEvent>> {
+        let Ok(chunk) = serde_json::from_str::<Value>(line.trim()) else {
+            return Vec::new();
+        };
+        if let Some(error) = chunk["error"].as_str() {
+            return vec![Err(format!("Ollama stream error: {}", error).into())];
+        }
+
+        let mut events = Vec::new();
+        if let Some(text) = chunk["message"]["content"].as_str().filter(|text| !text.is_empty()) {
+            events.push(Ok(LlmStreamEvent::Delta(text.to_string())));
+        }
+        if chunk["done"].as_bool().unwrap_or(false) {
+            events.push(Ok(LlmStreamEvent::Usage {
+                prompt_tokens: token_count(&chunk["prompt_eval_count"]),
+                completion_tokens: token_count(&chunk["eval_count"]),
+            }));
+        }
+        events
+    }
+}
+
+impl LlmProvider for OllamaProvider {
+    fn provider_type(&self) -> LlmProviderType {
+        LlmProviderType::Ollama
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn complete(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmResponse>> {
+        async move {
+            let response = self.send(Self::build_body(request, false)).await?;
+            let body: Value = response.json().await?;
+
+            let content = body["message"]["content"]
+                .as_str()
+                .ok_or("No content in response")?
+                .to_string();
+
+            Ok(LlmResponse {
+                content,
+                prompt_tokens: body["prompt_eval_count"].as_u64().map(|n| n as u32),
+                completion_tokens: body["eval_count"].as_u64().map(|n| n as u32),
+            })
+        }
+        .boxed()
+    }
+
+    fn complete_stream(&self, request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmStream>> {
+        async move {
+            let response = self.send(Self::build_body(request, true)).await?;
+            Ok(parse_lines(response, Self::parse_stream_line))
+        }
+        .boxed()
+    }
+}
+
+// ==================== 脚本化 Mock ====================
+
+/// 脚本化 Mock 提供商
+///
+/// 按顺序返回脚本中的响应，脚本用尽后重复最后一条
+pub struct MockLlmProvider {
+    responses: Vec<String>,
+    cursor: Mutex<usize>,
+    load_error: Option<String>, // 脚本文件读取失败的原因，每次请求返回该错误
+}
+
+impl MockLlmProvider {
+    pub fn new(responses: Vec<String>) -> Self {
+        Self {
+            responses,
+            cursor: Mutex::new(0),
+            load_error: None,
+        }
+    }
+
+    /// 从脚本文件创建：内容为 JSON 字符串数组时每项是一条响应，否则整个文件作为唯一响应
+    pub fn from_script_file(path: &str) -> Self {
+        match std::fs::read_to_string(path.trim()) {
+            Ok(script) => Self::new(
+                serde_json::from_str::<Vec<String>>(&script).unwrap_or_else(|_| vec![script]),
+            ),
+            Err(e) => Self {
+                load_error: Some(format!("无法读取 Mock 脚本 {}: {}", path, e)),
+                ..Self::new(Vec::new())
+            },
+        }
+    }
+
+    fn next_response(&self) -> LlmResult<String> {
+        if let Some(error) = &self.load_error {
+            return Err(error.clone().into());
+        }
+        let mut cursor = self.cursor.lock().unwrap();
+        let index = (*cursor).min(self.responses.len().saturating_sub(1));
+        *cursor += 1;
+
+        self.responses
+            .get(index)
+            .cloned()
+            .ok_or_else(|| "Mock provider has no scripted response".into())
+    }
+}
+
+impl LlmProvider for MockLlmProvider {
+    fn provider_type(&self) -> LlmProviderType {
+        LlmProviderType::Mock
+    }
+
+    /// 脚本内容原样返回，无法保证符合 Schema
+    fn supports_structured_output(&self) -> bool {
+        false
+    }
+
+    fn complete(&self, _request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmResponse>> {
+        let result = self.next_response().map(|content| LlmResponse {
+            content,
+            prompt_tokens: None,
+            completion_tokens: None,
+        });
+        async move { result }.boxed()
+    }
+
+    fn complete_stream(&self, _request: LlmRequest) -> BoxFuture<'_, LlmResult<LlmStream>> {
+        let result = self.next_response().map(|content| {
+            // 按 16 个字符切分，模拟流式增量（不返回用量）
+            let chars: Vec<char> = content.chars().collect();
+            let chunks: Vec<LlmResult<LlmStreamEvent>> = chars
+                .chunks(16)
+                .map(|chunk| Ok(LlmStreamEvent::Delta(chunk.iter().collect())))
+                .collect();
+            stream::iter(chunks).boxed()
+        });
+        async move { result }.boxed()
+    }
+}
+
+// ==================== 辅助函数 ====================
+
+/// 非 2xx 响应转换为错误（包含响应正文便于排查）
+async fn ensure_success(response: reqwest::Response) -> LlmResult<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("HTTP {}: {}", status.as_u16(), body).into())
+}
+
+/// 提供商返回的 token 数
+fn token_count(value: &Value) -> Option<u32> {
+    value.as_u64().map(|n| n as u32)
+}
+
+/// 按行解析流式响应，每行可产生多项
+fn parse_lines(
+    response: reqwest::Response,
+    parse_line: fn(&str) -> Vec<LlmResult<LlmStreamEvent>>,
+) -> LlmStream {
+    response_lines(response)
+        .flat_map(move |line| {
+            let events = match line {
+                Ok(line) => parse_line(&line),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(events)
+        })
+        .boxed()
+}
+
+/// 将响应正文按行切分为流（按字节缓冲，避免多字节字符被截断）
+fn response_lines(response: reqwest::Response) -> LineStream {
+    stream::unfold(
+        (Some(response), Vec::<u8>::new()),
+        |(mut response, mut buffer)| async move {
+            loop {
+                if let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    let line = String::from_utf8_lossy(&line).trim_end().to_string();
+                    return Some((Ok(line), (response, buffer)));
+                }
+
+                let current = match response.as_mut() {
+                    Some(current) => current,
+                    None if buffer.is_empty() => return None,
+                    None => {
+                        let line = String::from_utf8_lossy(&buffer).trim_end().to_string();
+                        return Some((Ok(line), (None, Vec::new())));
+                    }
+                };
+
+                match current.chunk().await {
+                    Ok(Some(bytes)) => buffer.extend_from_slice(&bytes),
+                    Ok(None) => response = None,
+                    Err(e) => return Some((Err(e.into()), (None, Vec::new()))),
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(messages: Vec<LlmMessage>) -> LlmRequest {
+        LlmRequest {
+            model: "test-model".to_string(),
+            messages,
+            max_tokens: 100,
+            temperature: 0.1,
+            response_schema: None,
+        }
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Outer {
+        #[serde(rename = "innerItems")]
+        inner_items: Vec<Inner>,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Inner {
+        name: String,
+        count: Option<i32>,
+    }
+
+    #[test]
+    fn test_response_schema_is_inlined_and_sent() {
+        let schema = ResponseSchema::of::<Outer>("submit_outer", "Submit the result");
+        assert_eq!(schema.schema["type"], "object");
+        assert!(schema.schema.get("$schema").is_none());
+        assert!(schema.schema.get("definitions").is_none());
+        let inner = &schema.schema["properties"]["innerItems"]["items"];
+        assert_eq!(inner["properties"]["name"]["type"], "string");
+        assert_eq!(inner["required"], json!(["name"]));
+
+        let mut structured = request(vec![LlmMessage::user("q")]);
+        structured.response_schema = Some(schema.clone());
+
+        let body = AnthropicProvider::build_body(structured.clone(), false);
+        assert_eq!(body["tools"][0]["input_schema"], schema.schema);
+        assert_eq!(body["tool_choice"]["name"], "submit_outer");
+
+        let body = OllamaProvider::build_body(structured.clone(), false);
+        assert_eq!(body["format"], schema.schema);
+
+        let openai = OpenAiCompatibleProvider::build_request(structured, false).unwrap();
+        let openai = serde_json::to_value(openai).unwrap();
+        assert_eq!(openai["tools"][0]["function"]["parameters"], schema.schema);
+        assert_eq!(openai["tool_choice"]["function"]["name"], "submit_outer");
+
+        // 未指定约束时不发送工具
+        let body = AnthropicProvider::build_body(request(vec![LlmMessage::user("q")]), false);
+        assert!(body.get("tools").is_none());
+        assert!(!MockLlmProvider::new(vec![]).supports_structured_output());
+    }
+
+    #[test]
+    fn test_anthropic_moves_system_prompt() {
+        let body = AnthropicProvider::build_body(
+            request(vec![LlmMessage::system("be brief"), LlmMessage::user("hi")]),
+            false,
+        );
+        assert_eq!(body["system"], "be brief");
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+
+        // 仅有 system 提示词时作为 user 消息发送
+        let body = AnthropicProvider::build_body(request(vec![LlmMessage::system("prompt")]), false);
+        assert!(body.get("system").is_none());
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["messages"][0]["content"], "prompt");
+    }
+
+    #[test]
+    fn test_stream_lines_report_usage() {
+        fn collect(events: Vec<LlmResult<LlmStreamEvent>>) -> Vec<LlmStreamEvent> {
+            events.into_iter().map(|event| event.unwrap()).collect()
+        }
+        let usage = |prompt, completion| LlmStreamEvent::Usage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+        };
+
+        let body = OpenAiCompatibleProvider::build_stream_body(request(vec![LlmMessage::user("q")])).unwrap();
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["stream_options"]["include_usage"], true);
+
+        let openai = OpenAiCompatibleProvider::parse_stream_line;
+        assert_eq!(
+            collect(openai(r#"data: {"choices":[{"delta":{"content":"hi"}}],"usage":null}"#)),
+            vec![LlmStreamEvent::Delta("hi".to_string())]
+        );
+        assert_eq!(
+            collect(openai(r#"data: {"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":5}}"#)),
+            vec![usage(Some(12), Some(5))]
+        );
+        assert!(openai("data: [DONE]").is_empty());
+        assert!(openai(r#"data: {"error":{"message":"rate limited"}}"#)[0].is_err());
+
+        let anthropic = AnthropicProvider::parse_stream_line;
+        assert_eq!(
+            collect(anthropic(
+                r#"data: {"type":"message_start","message":{"usage":{"input_tokens":20,"output_tokens":1}}}"#
+            )),
+            vec![usage(Some(20), Some(1))]
+        );
+        assert_eq!(
+            collect(anthropic(r#"data: {"type":"content_block_delta","delta":{"text":"hi"}}"#)),
+            vec![LlmStreamEvent::Delta("hi".to_string())]
+        );
+        assert_eq!(
+            collect(anthropic(r#"data: {"type":"message_delta","usage":{"output_tokens":7}}"#)),
+            vec![usage(None, Some(7))]
+        );
+        assert!(anthropic("event: ping").is_empty());
+
+        let ollama = OllamaProvider::parse_stream_line;
+        assert_eq!(
+            collect(ollama(r#"{"message":{"content":"hi"},"done":false}"#)),
+            vec![LlmStreamEvent::Delta("hi".to_string())]
+        );
+        assert_eq!(
+            collect(ollama(
+                r#"{"message":{"content":""},"done":true,"prompt_eval_count":9,"eval_count":3}"#
+            )),
+            vec![usage(Some(9), Some(3))]
+        );
+    }
+
+    #[test]
+    fn test_mock_follows_script() {
+        let mock = MockLlmProvider::new(vec!["first".to_string(), "second".to_string()]);
+        let contents: Vec<String> = (0..3)
+            .map(|_| {
+                futures::executor::block_on(mock.complete(request(vec![LlmMessage::user("q")])))
+                    .unwrap()
+                    .content
+            })
+            .collect();
+
+        assert_eq!(contents, vec!["first", "second", "second"]);
+        assert_eq!(LlmProviderType::from_db("unknown"), LlmProviderType::OpenAiCompatible);
+        assert_eq!(LlmProviderType::from_db("mock"), LlmProviderType::Mock);
+        assert_eq!(LlmProviderType::parse("Mock"), Some(LlmProviderType::Mock));
+    }
+
+    #[test]
+    fn test_mock_reads_script_file() {
+        let path = std::env::temp_dir().join("redlark-mock-script.json");
+        std::fs::write(&path, r#"["first", "second"]"#).unwrap();
+        let mock = create_provider(LlmProviderType::Mock, path.to_str().unwrap(), "");
+        let content = futures::executor::block_on(mock.complete(request(vec![LlmMessage::user("q")])))
+            .unwrap()
+            .content;
+        assert_eq!(content, "first");
+
+        // 脚本文件不存在时每次请求都返回读取错误
+        let missing = create_provider(LlmProviderType::Mock, "/nonexistent/mock.json", "");
+        let error = futures::executor::block_on(missing.complete(request(vec![LlmMessage::user("q")])))
+            .unwrap_err();
+        assert!(error.to_string().contains("/nonexistent/mock.json"));
+    }
+}
@@ -0,0 +1,815 @@
+//! 基于规则的音节划分与自然拼读切分
+//!
+//! 不请求 AI 模型，按批量分析提示词中的自然拼读规则库在本地完成:
+//! - 字母组合切分：辅音字母组合、元音组合、r 控制元音、魔法 e、辅音+le 等
+//! - 音节划分：V/CV、VC/CV 规则，辅音连读和后缀保持完整
+//! - 规则识别：单词适用的全部拼读规则以及主要规则
+//!
+//! 结果用于补全空缺的 `syllables` / `phonics_segments` / `phonics_rule`，
+//! 并检查 AI 输出是否与规则冲突
+
+use crate::ai_service::PhonicsWord;
+use crate::types::word_analysis::{PhonicsDisagreement, RulePhonicsAnalysis};
+
+/// 需要整体记忆的高频词
+const SIGHT_WORDS: &[&str] = &[
+    "the", "a", "is", "was", "said", "of", "to", "you", "i", "have", "are", "they", "one", "do",
+    "been", "two", "who", "could", "should", "would", "where", "there", "their", "come", "some",
+    "done", "give", "live", "eye", "bye", "eight", "eleven", "twelve", "what", "does", "were",
+    "once", "any", "many",
+];
+
+/// 可以作为音节开头的辅音连读
+const ONSET_CLUSTERS: &[&str] = &[
+    "bl", "br", "cl", "cr", "dr", "dw", "fl", "fr", "gl", "gr", "pl", "pr", "sc", "sk", "sl", "sm",
+    "sn", "sp", "st", "sw", "tr", "tw", "str", "spl", "spr", "scr", "squ", "thr", "shr", "sch",
+    "phr", "chr",
+];
+
+/// 只出现在音节结尾的辅音连读
+const FINAL_BLENDS: &[&str] = &[
+    "mp", "nd", "nt", "nk", "st", "sk", "sp", "ft", "lt", "ld", "lk", "lp", "pt", "ct", "xt",
+];
+
+/// 魔法 e 后允许出现的词尾
+const SILENT_E_ENDINGS: &[&str] = &["", "s", "d", "ly", "ful", "ness", "ment", "less"];
+
+/// 自然拼读规则（名称与批量分析提示词的规则对照表一致）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhonicsRule {
+    SightWord,
+    Vce,
+    RControlled,
+    VowelTeams,
+    Diphthongs,
+    ConsonantLe,
+    SilentLetters,
+    ConsonantDigraphs,
+    SoftHardCG,
+    ConsonantBlends,
+    SuffixRules,
+    OpenSyllable,
+    Cvc,
+    SyllableDivision,
+}
+
+impl PhonicsRule {
+    /// 双重描述格式的规则名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            PhonicsRule::SightWord => "Sight Word | 高频词",
+            PhonicsRule::Vce => "VCE Pattern | 魔法e规则",
+            PhonicsRule::RControlled => "R-Controlled Vowel | r控制元音",
+            PhonicsRule::VowelTeams => "Vowel Teams | 元音组合",
+            PhonicsRule::Diphthongs => "Diphthongs | 双元音",
+            PhonicsRule::ConsonantLe => "Consonant-le | 辅音+le结尾",
+            PhonicsRule::SilentLetters => "Silent Letters | 不发音字母",
+            PhonicsRule::ConsonantDigraphs => "Consonant Digraphs | 辅音字母组合",
+            PhonicsRule::SoftHardCG => "Soft/Hard C,G | 软硬音规则",
+            PhonicsRule::ConsonantBlends => "Consonant Blends | 辅音连读",
+            PhonicsRule::SuffixRules => "Suffix Rules | 后缀规则",
+            PhonicsRule::OpenSyllable => "Open Syllable | 开音节规则",
+            PhonicsRule::Cvc => "CVC Pattern | 短元音规则",
+            PhonicsRule::SyllableDivision => "Syllable Division | 音节划分",
+        }
+    }
+
+    /// 识别 AI 输出的规则描述，无法识别时返回 None
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.to_lowercase();
+        // 顺序有意义：silent e 属于魔法 e，consonant-le 不属于辅音连读
+        let keywords: &[(&[&str], PhonicsRule)] = &[
+            (
+                &["sight word", "irregular", "high frequency", "高频词"],
+                PhonicsRule::SightWord,
+            ),
+            (
+                &["vce", "magic e", "magic-e", "silent e", "魔法e"],
+                PhonicsRule::Vce,
+            ),
+            (
+                &["consonant-le", "consonant le", "+le"],
+                PhonicsRule::ConsonantLe,
+            ),
+            (
+                &["r-controlled", "r controlled", "bossy r", "r控制"],
+                PhonicsRule::RControlled,
+            ),
+            (&["vowel team", "元音组合"], PhonicsRule::VowelTeams),
+            (&["diphthong", "双元音"], PhonicsRule::Diphthongs),
+            (&["silent letter", "不发音"], PhonicsRule::SilentLetters),
+            (&["digraph", "辅音字母组合"], PhonicsRule::ConsonantDigraphs),
+            (
+                &["soft", "hard c", "hard g", "软硬音"],
+                PhonicsRule::SoftHardCG,
+            ),
+            (&["blend", "辅音连读"], PhonicsRule::ConsonantBlends),
+            (&["suffix", "后缀"], PhonicsRule::SuffixRules),
+            (&["open syllable", "开音节"], PhonicsRule::OpenSyllable),
+            (
+                &["cvc", "short vowel", "closed syllable", "短元音", "闭音节"],
+                PhonicsRule::Cvc,
+            ),
+            (
+                &["syllable division", "音节划分"],
+                PhonicsRule::SyllableDivision,
+            ),
+        ];
+
+        keywords
+            .iter()
+            .find(|(words, _)| words.iter().any(|w| label.contains(w)))
+            .map(|(_, rule)| *rule)
+    }
+}
+
+/// 字母组合类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// 单个辅音字母
+    Consonant,
+    /// 辅音字母组合（sh、ch、ck、ng、qu 等）
+    Digraph,
+    /// 含不发音字母的组合（kn、wr、gn、mb）
+    SilentCombo,
+    /// 单个元音字母（包括在辅音后作元音的 y）
+    ShortVowel,
+    /// 魔法 e 控制的长元音（显示为 a_e）
+    MagicVowel,
+    /// 魔法 e 本身（切分结果中并入 a_e）
+    MagicE,
+    /// 其他不发音的 e
+    SilentE,
+    VowelTeam,
+    Diphthong,
+    RControlled,
+    /// 辅音+le 结尾中的 le
+    ConsonantLe,
+    /// 自成音节的后缀（tion、sion、cian、ture）
+    SuffixUnit,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Grapheme {
+    start: usize,
+    end: usize,
+    kind: Kind,
+}
+
+impl Grapheme {
+    fn is_nucleus(&self) -> bool {
+        matches!(
+            self.kind,
+            Kind::ShortVowel
+                | Kind::MagicVowel
+                | Kind::VowelTeam
+                | Kind::Diphthong
+                | Kind::RControlled
+                | Kind::ConsonantLe
+                | Kind::SuffixUnit
+        )
+    }
+
+    fn is_consonant(&self) -> bool {
+        matches!(
+            self.kind,
+            Kind::Consonant | Kind::Digraph | Kind::SilentCombo
+        )
+    }
+
+    fn letters<'a>(&self, word: &'a str) -> &'a str {
+        &word[self.start..self.end]
+    }
+
+    /// 能否作为音节开头（ck、ng、x 等只出现在音节结尾）
+    fn can_start_syllable(&self, word: &str) -> bool {
+        match self.kind {
+            Kind::Consonant => self.letters(word) != "x",
+            Kind::Digraph => !matches!(self.letters(word), "ck" | "ng" | "tch" | "dge" | "gh"),
+            Kind::SilentCombo => true,
+            _ => false,
+        }
+    }
+}
+
+fn is_vowel(c: u8) -> bool {
+    matches!(c, b'a' | b'e' | b'i' | b'o' | b'u')
+}
+
+fn match_prefix(rest: &[u8], candidates: &[&str]) -> Option<usize> {
+    candidates
+        .iter()
+        .find(|c| rest.starts_with(c.as_bytes()))
+        .map(|c| c.len())
+}
+
+/// 把小写单词切分为字母组合
+fn segment_graphemes(word: &str) -> Vec<Grapheme> {
+    let w = word.as_bytes();
+    let n = w.len();
+    let mut graphemes: Vec<Grapheme> = Vec::new();
+    let mut i = 0;
+
+    while i < n {
+        let rest = &w[i..];
+        let next_is_vowel = |len: usize| i + len < n && is_vowel(w[i + len]);
+        let previous_is_consonant = graphemes.last().is_some_and(|g| g.is_consonant());
+
+        let (len, kind) = if i > 1
+            && (rest == b"le" || rest == b"les")
+            && !is_vowel(w[i - 1])
+            && w[..i - 1].iter().any(|&c| is_vowel(c) || c == b'y')
+        {
+            (2, Kind::ConsonantLe)
+        } else if i > 0 && match_prefix(rest, &["tion", "sion", "cian", "ture"]).is_some() {
+            (4, Kind::SuffixUnit)
+        } else if let Some(len) = match_prefix(rest, &["eigh", "augh", "ough", "igh"]) {
+            (len, Kind::VowelTeam)
+        } else if i + 3 == n && match_prefix(rest, &["are", "ire", "ore", "ure", "ere"]).is_some() {
+            (3, Kind::RControlled)
+        } else if let Some(len) = match_prefix(rest, &["air", "ear", "eer", "oor", "our"])
+            .filter(|&len| !next_is_vowel(len))
+        {
+            (len, Kind::RControlled)
+        } else if let Some(len) = match_prefix(rest, &["tch", "dge"]) {
+            (len, Kind::Digraph)
+        } else if let Some(len) =
+            match_prefix(rest, &["ar", "or", "er", "ir", "ur"]).filter(|&len| !next_is_vowel(len))
+        {
+            (len, Kind::RControlled)
+        } else if let Some(len) = match_prefix(
+            rest,
+            &[
+                "ai", "ay", "ee", "ea", "ie", "oa", "oe", "ue", "ew", "oo", "ey", "ei",
+            ],
+        ) {
+            (len, Kind::VowelTeam)
+        } else if let Some(len) = match_prefix(rest, &["oi", "oy", "ou", "ow", "au", "aw"]) {
+            (len, Kind::Diphthong)
+        } else if let Some(len) = match_prefix(rest, &["kn", "wr", "gn"]).filter(|_| i == 0) {
+            (len, Kind::SilentCombo)
+        } else if rest == b"mb" {
+            (2, Kind::SilentCombo)
+        } else if let Some(len) = match_prefix(
+            rest,
+            &["sh", "ch", "th", "ph", "wh", "ck", "ng", "qu", "gh"],
+        ) {
+            (len, Kind::Digraph)
+        } else if is_vowel(w[i]) || (w[i] == b'y' && previous_is_consonant) {
+            (1, Kind::ShortVowel)
+        } else {
+            (1, Kind::Consonant)
+        };
+
+        graphemes.push(Grapheme {
+            start: i,
+            end: i + len,
+            kind,
+        });
+        i += len;
+    }
+
+    mark_silent_e(word, &mut graphemes);
+    graphemes
+}
+
+/// 标记不发音的 e：词尾的 e（或 e 后只跟 s、d、ly 等词尾）在前面已有元音时不发音，
+/// 形如“元音-辅音-e”时标记为魔法 e
+fn mark_silent_e(word: &str, graphemes: &mut [Grapheme]) {
+    let Some(index) = graphemes.iter().rposition(|g| {
+        g.kind == Kind::ShortVowel
+            && g.letters(word) == "e"
+            && SILENT_E_ENDINGS.contains(&&word[g.end..])
+    }) else {
+        return;
+    };
+    if !graphemes[..index].iter().any(|g| g.is_nucleus()) {
+        return;
+    }
+
+    // -ed 在 t/d 后、-es 在 s/x/z/c/g/ch/sh 后自成音节（wanted、boxes、pages）
+    let ending = &word[graphemes[index].end..];
+    let before = &word[..graphemes[index].start];
+    let syllabic = match ending {
+        "d" => before.ends_with('t') || before.ends_with('d'),
+        "s" => ["s", "x", "z", "c", "g", "ch", "sh"]
+            .iter()
+            .any(|suffix| before.ends_with(suffix)),
+        _ => false,
+    };
+    if syllabic {
+        return;
+    }
+
+    let magic = index >= 2 && {
+        let consonant = graphemes[index - 1];
+        let vowel = graphemes[index - 2];
+        let single_consonant = (consonant.kind == Kind::Consonant
+            && !matches!(consonant.letters(word), "w" | "x" | "y"))
+            || matches!(consonant.letters(word), "th" | "ch");
+        single_consonant && vowel.kind == Kind::ShortVowel
+    };
+
+    if magic {
+        graphemes[index - 2].kind = Kind::MagicVowel;
+        graphemes[index].kind = Kind::MagicE;
+    } else {
+        graphemes[index].kind = Kind::SilentE;
+    }
+}
+
+/// 韵核是否为词尾后缀 -ing / -ed / -es 的元音
+fn is_suffix_nucleus(word: &str, graphemes: &[Grapheme], index: usize) -> bool {
+    let g = graphemes[index];
+    let tail = &word[g.start..];
+    g.kind == Kind::ShortVowel && matches!(tail, "ing" | "ed" | "es")
+}
+
+/// 按字母组合划分音节，返回每个音节的字节范围
+fn syllable_spans(word: &str, graphemes: &[Grapheme]) -> Vec<(usize, usize)> {
+    let nuclei: Vec<usize> = graphemes
+        .iter()
+        .enumerate()
+        .filter(|(_, g)| g.is_nucleus())
+        .map(|(i, _)| i)
+        .collect();
+    if nuclei.len() <= 1 {
+        return vec![(0, word.len())];
+    }
+
+    let mut starts = vec![0];
+    for pair in nuclei.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let cluster = &graphemes[a + 1..b];
+
+        let split = if cluster.is_empty() || graphemes[b].kind == Kind::SuffixUnit {
+            // 元音相邻时直接划分；-tion 等后缀自带辅音
+            b
+        } else if cluster.len() == 1 {
+            // V/CV：单辅音归后一音节（ti-ger），只能出现在结尾的辅音归前一音节（box-es）
+            if cluster[0].can_start_syllable(word) {
+                a + 1
+            } else {
+                b
+            }
+        } else {
+            let doubled =
+                cluster.len() == 2 && cluster[0].letters(word) == cluster[1].letters(word);
+            if is_suffix_nucleus(word, graphemes, b) && !doubled {
+                // 后缀前的辅音连读保留在词根中（jump-ing、want-ed）
+                b
+            } else {
+                // VC/CV：短元音后至少一个辅音归前一音节（长元音、r 控制元音后不需要），
+                // 其余能组成音节开头的辅音连读归后一音节（mon-ster、but-ter-fly）
+                let min_coda = usize::from(graphemes[a].kind == Kind::ShortVowel);
+                (min_coda..cluster.len())
+                    .find(|&k| is_onset(word, &cluster[k..]))
+                    .map(|k| a + 1 + k)
+                    .unwrap_or(b)
+            }
+        };
+        starts.push(graphemes[split].start);
+    }
+
+    let mut spans: Vec<(usize, usize)> = starts.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    spans.push((*starts.last().unwrap(), word.len()));
+    spans
+}
+
+fn is_onset(word: &str, cluster: &[Grapheme]) -> bool {
+    if !cluster.iter().all(|g| g.is_consonant()) {
+        return false;
+    }
+    if cluster.len() == 1 {
+        return cluster[0].can_start_syllable(word);
+    }
+    let letters: String = cluster.iter().map(|g| g.letters(word)).collect();
+    ONSET_CLUSTERS.contains(&letters.as_str())
+}
+
+/// 去掉 e 加 -ing / -ed 的单音节词根（baking、hoped）
+fn has_dropped_magic_e(word: &str, graphemes: &[Grapheme]) -> bool {
+    let nuclei = graphemes.iter().filter(|g| g.is_nucleus()).count();
+    let Some(stem) = word.strip_suffix("ing").or_else(|| word.strip_suffix("ed")) else {
+        return false;
+    };
+    let s = stem.as_bytes();
+    nuclei == 2
+        && s.len() >= 3
+        && !is_vowel(s[s.len() - 1])
+        && !matches!(s[s.len() - 1], b'w' | b'x' | b'y' | b'r')
+        && is_vowel(s[s.len() - 2])
+        && !is_vowel(s[s.len() - 3])
+}
+
+/// 单词适用的全部规则（按主要规则的优先级排序）和主要规则
+fn detect_rules(
+    word: &str,
+    graphemes: &[Grapheme],
+    spans: &[(usize, usize)],
+) -> (Vec<PhonicsRule>, PhonicsRule) {
+    let has_kind = |kind: Kind| graphemes.iter().any(|g| g.kind == kind);
+    let in_syllable = |g: &Grapheme| spans.iter().position(|&(s, e)| g.start >= s && g.end <= e);
+
+    let sight_word = SIGHT_WORDS.contains(&word);
+    let vce = has_kind(Kind::MagicVowel) || has_dropped_magic_e(word, graphemes);
+    let silent = has_kind(Kind::SilentCombo)
+        || graphemes
+            .iter()
+            .any(|g| g.start > 0 && g.letters(word) == "gh");
+    let digraph = graphemes.iter().any(|g| {
+        matches!(
+            g.letters(word),
+            "sh" | "ch" | "th" | "ph" | "wh" | "ck" | "tch"
+        ) || (g.letters(word) == "ng" && !word.ends_with("ing"))
+    });
+    let followed_by = |i: usize, letters: &[u8]| {
+        graphemes
+            .get(i + 1)
+            .is_some_and(|next| letters.contains(&word.as_bytes()[next.start]))
+    };
+    let c_or_g = |g: &Grapheme| g.kind == Kind::Consonant && matches!(g.letters(word), "c" | "g");
+    let soft_c_g = graphemes
+        .iter()
+        .enumerate()
+        .any(|(i, g)| c_or_g(g) && followed_by(i, b"eiy"));
+    let c_g_before_vowel = graphemes
+        .iter()
+        .enumerate()
+        .any(|(i, g)| c_or_g(g) && followed_by(i, b"aeiouy"));
+    let blend = graphemes.windows(2).any(|pair| {
+        let letters = format!("{}{}", pair[0].letters(word), pair[1].letters(word));
+        pair[0].kind == Kind::Consonant
+            && pair[1].kind == Kind::Consonant
+            && in_syllable(&pair[0]) == in_syllable(&pair[1])
+            && (ONSET_CLUSTERS.contains(&letters.as_str())
+                || FINAL_BLENDS.contains(&letters.as_str()))
+    });
+    let suffix = [
+        "ing", "tion", "sion", "ly", "ful", "ness", "ment", "less", "est",
+    ]
+    .iter()
+    .any(|s| word.len() > s.len() + 2 && word.ends_with(s))
+        || (word.len() > 4
+            && word.ends_with("ed")
+            && graphemes
+                .iter()
+                .any(|g| g.start == word.len() - 2 && g.letters(word) == "e"));
+
+    // 每个音节的类型：以单个元音字母结尾为开音节，元音字母后跟辅音为闭音节
+    let syllable_types: Vec<(bool, bool)> = spans
+        .iter()
+        .map(|&(s, e)| {
+            let syllable: Vec<&Grapheme> = graphemes
+                .iter()
+                .filter(|g| g.start >= s && g.end <= e && g.kind != Kind::MagicE)
+                .collect();
+            let open = syllable.last().is_some_and(|g| g.kind == Kind::ShortVowel);
+            let closed = syllable
+                .windows(2)
+                .any(|pair| pair[0].kind == Kind::ShortVowel && pair[1].is_consonant());
+            (open, closed)
+        })
+        .collect();
+    let open_syllable = syllable_types.iter().any(|&(open, _)| open);
+    let cvc = syllable_types.iter().any(|&(_, closed)| closed);
+
+    let candidates = [
+        (sight_word, PhonicsRule::SightWord),
+        (vce, PhonicsRule::Vce),
+        (has_kind(Kind::RControlled), PhonicsRule::RControlled),
+        (has_kind(Kind::VowelTeam), PhonicsRule::VowelTeams),
+        (has_kind(Kind::Diphthong), PhonicsRule::Diphthongs),
+        (has_kind(Kind::ConsonantLe), PhonicsRule::ConsonantLe),
+        (silent, PhonicsRule::SilentLetters),
+        (digraph, PhonicsRule::ConsonantDigraphs),
+        (c_g_before_vowel, PhonicsRule::SoftHardCG),
+        (blend, PhonicsRule::ConsonantBlends),
+        (suffix, PhonicsRule::SuffixRules),
+        (open_syllable, PhonicsRule::OpenSyllable),
+        (cvc, PhonicsRule::Cvc),
+        (spans.len() > 1, PhonicsRule::SyllableDivision),
+    ];
+    let detected: Vec<PhonicsRule> = candidates
+        .iter()
+        .filter(|(applies, _)| *applies)
+        .map(|(_, rule)| *rule)
+        .collect();
+
+    // 主要规则：高频词和元音类规则优先，其次看第一个音节是开音节还是闭音节
+    let primary = candidates[..8]
+        .iter()
+        .find(|(applies, _)| *applies)
+        .map(|(_, rule)| *rule)
+        .or_else(|| soft_c_g.then_some(PhonicsRule::SoftHardCG))
+        .or_else(|| match syllable_types.first() {
+            Some(&(_, true)) => Some(PhonicsRule::Cvc),
+            Some(&(true, _)) => Some(PhonicsRule::OpenSyllable),
+            _ => None,
+        })
+        .unwrap_or(if spans.len() > 1 {
+            PhonicsRule::SyllableDivision
+        } else {
+            PhonicsRule::Cvc
+        });
+
+    (detected, primary)
+}
+
+/// 单个纯字母单词的分析结果
+struct PartAnalysis {
+    syllables: Vec<String>,
+    segments: Vec<String>,
+    detected: Vec<PhonicsRule>,
+    primary: PhonicsRule,
+}
+
+fn analyze_part(part: &str) -> PartAnalysis {
+    let lower = part.to_ascii_lowercase();
+    let graphemes = segment_graphemes(&lower);
+    let spans = syllable_spans(&lower, &graphemes);
+    let (detected, primary) = detect_rules(&lower, &graphemes, &spans);
+
+    let segments = graphemes
+        .iter()
+        .filter(|g| g.kind != Kind::MagicE)
+        .map(|g| match g.kind {
+            Kind::MagicVowel => format!("{}_e", &part[g.start..g.end]),
+            _ => part[g.start..g.end].to_string(),
+        })
+        .collect();
+
+    PartAnalysis {
+        syllables: spans.iter().map(|&(s, e)| part[s..e].to_string()).collect(),
+        segments,
+        detected,
+        primary,
+    }
+}
+
+/// 按规则分析单词，单词不含英文字母时返回 None
+///
+/// 词组和连字符单词按每个部分分别分析；音节以 `-` 连接，拼读片段以 `, ` 连接
+pub fn analyze(word: &str) -> Option<RulePhonicsAnalysis> {
+    let parts: Vec<PartAnalysis> = word
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|part| !part.is_empty())
+        .map(analyze_part)
+        .collect();
+    let first = parts.first()?;
+
+    let mut detected: Vec<PhonicsRule> = Vec::new();
+    for rule in parts.iter().flat_map(|p| p.detected.iter()) {
+        if !detected.contains(rule) {
+            detected.push(*rule);
+        }
+    }
+    let syllables: Vec<&str> = parts
+        .iter()
+        .flat_map(|p| p.syllables.iter().map(String::as_str))
+        .collect();
+    let segments: Vec<&str> = parts
+        .iter()
+        .flat_map(|p| p.segments.iter().map(String::as_str))
+        .collect();
+
+    Some(RulePhonicsAnalysis {
+        word: word.to_string(),
+        syllables: syllables.join("-"),
+        syllable_count: syllables.len(),
+        phonics_segments: segments.join(", "),
+        phonics_rule: first.primary.label().to_string(),
+        detected_rules: detected.iter().map(|r| r.label().to_string()).collect(),
+    })
+}
+
+/// 检查 AI 给出的音节划分和拼读规则是否与规则分析一致
+///
+/// 音节只比较拼写和数量（划分位置存在合理差异）；规则只在 AI 给出的规则完全不适用时报告，
+/// 高频词和无法识别的规则描述不报告
+pub fn check(
+    word: &str,
+    syllables: Option<&str>,
+    phonics_rule: Option<&str>,
+) -> Vec<PhonicsDisagreement> {
+    let Some(analysis) = analyze(word) else {
+        return Vec::new();
+    };
+    let mut disagreements = Vec::new();
+    let disagreement =
+        |field: &str, ai_value: &str, rule_value: &str, message: String| PhonicsDisagreement {
+            word: word.to_string(),
+            field: field.to_string(),
+            ai_value: ai_value.to_string(),
+            rule_value: rule_value.to_string(),
+            message,
+        };
+
+    if let Some(syllables) = syllables.map(str::trim).filter(|s| !s.is_empty()) {
+        let parts: Vec<&str> = syllables
+            .split(|c: char| matches!(c, '-' | '·' | '•' | '|' | '/') || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .collect();
+        let letters = |s: &str| -> String {
+            s.chars()
+                .filter(|c| c.is_ascii_alphabetic())
+                .map(|c| c.to_ascii_lowercase())
+                .collect()
+        };
+
+        if letters(syllables) != letters(word) {
+            disagreements.push(disagreement(
+                "syllables",
+                syllables,
+                &analysis.syllables,
+                "音节拼写与单词不一致".to_string(),
+            ));
+        } else if parts.len() != analysis.syllable_count {
+            disagreements.push(disagreement(
+                "syllables",
+                syllables,
+                &analysis.syllables,
+                format!(
+                    "音节数量不一致：AI 划分为 {} 个，规则划分为 {} 个",
+                    parts.len(),
+                    analysis.syllable_count
+                ),
+            ));
+        }
+    }
+
+    if let Some(label) = phonics_rule.map(str::trim).filter(|s| !s.is_empty()) {
+        if let Some(rule) = PhonicsRule::from_label(label) {
+            let applies = rule == PhonicsRule::SightWord
+                || analysis.detected_rules.iter().any(|r| r == rule.label());
+            if !applies {
+                disagreements.push(disagreement(
+                    "phonics_rule",
+                    label,
+                    &analysis.phonics_rule,
+                    format!("规则分析中该单词不适用 {}", rule.label()),
+                ));
+            }
+        }
+    }
+
+    disagreements
+}
+
+/// 为空的字段用规则分析补全，返回是否补全了字段
+pub fn fill_missing(
+    word: &str,
+    syllables: &mut Option<String>,
+    phonics_segments: &mut Option<String>,
+    phonics_rule: &mut Option<String>,
+) -> bool {
+    let Some(analysis) = analyze(word) else {
+        return false;
+    };
+    let mut filled = false;
+    for (field, value) in [
+        (syllables, analysis.syllables),
+        (phonics_segments, analysis.phonics_segments),
+        (phonics_rule, analysis.phonics_rule),
+    ] {
+        if field.as_deref().is_none_or(|v| v.trim().is_empty()) {
+            *field = Some(value);
+            filled = true;
+        }
+    }
+    filled
+}
+
+/// 检查 AI 批量分析结果：空缺的音节和规则用规则分析补全，已有内容与规则冲突时返回差异
+pub fn check_phonics_word(result: &mut PhonicsWord) -> Vec<PhonicsDisagreement> {
+    let disagreements = check(
+        &result.word,
+        Some(&result.syllables),
+        Some(&result.phonics_rule),
+    );
+
+    let mut syllables = Some(result.syllables.clone());
+    let mut phonics_rule = Some(result.phonics_rule.clone());
+    fill_missing(&result.word, &mut syllables, &mut None, &mut phonics_rule);
+    result.syllables = syllables.unwrap_or_default();
+    result.phonics_rule = phonics_rule.unwrap_or_default();
+
+    disagreements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syllables(word: &str) -> String {
+        analyze(word).unwrap().syllables
+    }
+
+    fn segments(word: &str) -> String {
+        analyze(word).unwrap().phonics_segments
+    }
+
+    fn rule(word: &str) -> String {
+        analyze(word).unwrap().phonics_rule
+    }
+
+    #[test]
+    fn test_syllable_division() {
+        let cases = [
+            ("cat", "cat"),
+            ("tiger", "ti-ger"),
+            ("paper", "pa-per"),
+            ("letter", "let-ter"),
+            ("rabbit", "rab-bit"),
+            ("garden", "gar-den"),
+            ("monster", "mon-ster"),
+            ("butterfly", "but-ter-fly"),
+            ("pumpkin", "pump-kin"),
+            ("table", "ta-ble"),
+            ("apple", "ap-ple"),
+            ("nation", "na-tion"),
+            ("action", "ac-tion"),
+            ("jumping", "jump-ing"),
+            ("running", "run-ning"),
+            ("baking", "ba-king"),
+            ("singing", "sing-ing"),
+            ("wanted", "want-ed"),
+            ("jumped", "jumped"),
+            ("boxes", "box-es"),
+            ("cake", "cake"),
+            ("hopeful", "hope-ful"),
+            ("Ice-cream", "Ice-cream"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(syllables(word), expected, "syllables({})", word);
+        }
+    }
+
+    #[test]
+    fn test_phonics_segments() {
+        assert_eq!(segments("cake"), "c, a_e, k");
+        assert_eq!(segments("ship"), "sh, i, p");
+        assert_eq!(segments("night"), "n, igh, t");
+        assert_eq!(segments("car"), "c, ar");
+        assert_eq!(segments("know"), "kn, ow");
+        assert_eq!(segments("table"), "t, a, b, le");
+        assert_eq!(segments("happy"), "h, a, p, p, y");
+    }
+
+    #[test]
+    fn test_primary_rule() {
+        assert_eq!(rule("cat"), PhonicsRule::Cvc.label());
+        assert_eq!(rule("cake"), PhonicsRule::Vce.label());
+        assert_eq!(rule("baking"), PhonicsRule::Vce.label());
+        assert_eq!(rule("car"), PhonicsRule::RControlled.label());
+        assert_eq!(rule("rain"), PhonicsRule::VowelTeams.label());
+        assert_eq!(rule("boy"), PhonicsRule::Diphthongs.label());
+        assert_eq!(rule("table"), PhonicsRule::ConsonantLe.label());
+        assert_eq!(rule("ship"), PhonicsRule::ConsonantDigraphs.label());
+        assert_eq!(rule("robot"), PhonicsRule::OpenSyllable.label());
+        assert_eq!(rule("jumping"), PhonicsRule::Cvc.label());
+        assert_eq!(rule("said"), PhonicsRule::SightWord.label());
+    }
+
+    #[test]
+    fn test_check_flags_disagreements() {
+        // 与规则一致（包括提示词之外的规则写法）
+        assert!(check("jumping", Some("jump-ing"), Some("Short Vowel | 短元音")).is_empty());
+        assert!(check("garden", Some("gar-den"), Some("R-Controlled Vowel")).is_empty());
+        assert!(check("baking", Some("ba-king"), Some("VCE Pattern | 魔法e规则")).is_empty());
+        // 高频词和无法识别的规则不报告
+        assert!(check("one", None, Some("Sight Word | 高频词")).is_empty());
+        assert!(check("cat", None, Some("Unknown Word")).is_empty());
+
+        let issues = check("cat", Some("ca-t"), Some("VCE Pattern | 魔法e规则"));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].field, "syllables");
+        assert_eq!(issues[0].rule_value, "cat");
+        assert_eq!(issues[1].field, "phonics_rule");
+        assert_eq!(issues[1].rule_value, PhonicsRule::Cvc.label());
+
+        let issues = check("garden", Some("gar-dan"), None);
+        assert_eq!(issues[0].message, "音节拼写与单词不一致");
+    }
+
+    #[test]
+    fn test_fill_missing_keeps_existing_values() {
+        let mut syllables = Some("tig-er".to_string());
+        let mut segments = None;
+        let mut phonics_rule = Some(String::new());
+        assert!(fill_missing(
+            "tiger",
+            &mut syllables,
+            &mut segments,
+            &mut phonics_rule
+        ));
+        assert_eq!(syllables.as_deref(), Some("tig-er"));
+        assert_eq!(segments.as_deref(), Some("t, i, g, er"));
+        assert_eq!(
+            phonics_rule.as_deref(),
+            Some(PhonicsRule::RControlled.label())
+        );
+
+        assert!(analyze("123").is_none());
+    }
+}
@@ -84,7 +84,8 @@ impl EnhancedProgressManager {
 
         BatchAnalysisProgress {
             status: if let Some(ref analysis) = analysis {
-                if analysis.completed_words == analysis.total_words {
+                // 失败的单词同样计入已处理
+                if analysis.completed_words + analysis.failed_words >= analysis.total_words {
                     "completed".to_string()
                 } else {
                     "analyzing".to_string()
@@ -97,7 +98,8 @@ impl EnhancedProgressManager {
             current_step: if let Some(ref analysis) = analysis {
                 format!(
                     "分析批次 {}/{}",
-                    analysis.batch_info.completed_batches + 1,
+                    (analysis.batch_info.completed_batches + 1)
+                        .min(analysis.batch_info.total_batches),
                     analysis.batch_info.total_batches
                 )
             } else if let Some(ref extraction) = extraction {
@@ -0,0 +1,182 @@
+//! 提示词模板注册表
+//!
+//! 模板优先从应用数据目录的 `prompts/<name>.md` 读取，不存在或缺少必需占位符时使用内置模板。
+//! 版本号为模板内容的哈希，分析缓存、批量分析任务和学习计划按版本号追溯使用的模板
+
+use crate::error::{AppError, AppResult};
+use crate::types::prompt::{PromptName, PromptSource, PromptTemplate};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// 自定义模板所在的子目录
+const PROMPTS_DIR_NAME: &str = "prompts";
+
+// 全局提示词注册表实例（未初始化时只使用内置模板）
+static GLOBAL_PROMPT_REGISTRY: OnceLock<Arc<PromptRegistry>> = OnceLock::new();
+
+/// 使用应用数据目录初始化全局注册表（应用启动时调用一次）
+pub fn init_prompt_registry(app_data_dir: &Path) -> Arc<PromptRegistry> {
+    let registry = Arc::new(PromptRegistry::new(app_data_dir));
+    let _ = GLOBAL_PROMPT_REGISTRY.set(Arc::clone(&registry));
+    get_prompt_registry()
+}
+
+/// 获取全局注册表
+pub fn get_prompt_registry() -> Arc<PromptRegistry> {
+    Arc::clone(GLOBAL_PROMPT_REGISTRY.get_or_init(|| Arc::new(PromptRegistry::embedded())))
+}
+
+/// 内置模板
+pub fn default_template(name: PromptName) -> &'static str {
+    match name {
+        PromptName::WordExtraction => include_str!("prompts/word_extraction_agent.md"),
+        PromptName::Phonics => include_str!("prompts/phonics_agent.md"),
+        PromptName::BatchPhonics => include_str!("prompts/batch_phonics_agent.md"),
+        PromptName::StudyPlan => include_str!("prompts/study_plan_agent.md"),
+    }
+}
+
+/// 模板必须包含的占位符（可选的补充说明类占位符不在此列）
+pub fn required_placeholders(name: PromptName) -> &'static [&'static str] {
+    match name {
+        PromptName::WordExtraction => &["{original_text}"],
+        PromptName::Phonics => &["{original_text}"],
+        PromptName::BatchPhonics => &["{word_list}"],
+        PromptName::StudyPlan => &[
+            "{word_list}",
+            "{total_words}",
+            "{period_days}",
+            "{start_date}",
+        ],
+    }
+}
+
+/// 模板缺少的必需占位符
+pub fn missing_placeholders(name: PromptName, content: &str) -> Vec<&'static str> {
+    required_placeholders(name)
+        .iter()
+        .copied()
+        .filter(|placeholder| !content.contains(placeholder))
+        .collect()
+}
+
+/// 模板版本号（内容 SHA-256 前 12 位）
+pub fn prompt_version(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())[..12].to_string()
+}
+
+/// 提示词模板注册表
+#[derive(Debug, Clone)]
+pub struct PromptRegistry {
+    /// 自定义模板目录，None 时只使用内置模板
+    dir: Option<PathBuf>,
+}
+
+impl PromptRegistry {
+    /// 从应用数据目录加载自定义模板
+    pub fn new(app_data_dir: &Path) -> Self {
+        Self {
+            dir: Some(app_data_dir.join(PROMPTS_DIR_NAME)),
+        }
+    }
+
+    /// 只使用内置模板
+    pub fn embedded() -> Self {
+        Self { dir: None }
+    }
+
+    /// 自定义模板目录
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    fn custom_path(&self, name: PromptName) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.md", name.as_str())))
+    }
+
+    /// 读取当前生效的模板
+    pub fn load(&self, name: PromptName) -> PromptTemplate {
+        let Some(path) = self.custom_path(name).filter(|path| path.exists()) else {
+            return Self::builtin(name, None);
+        };
+
+        let custom_error = match fs::read_to_string(&path) {
+            Ok(content) => {
+                let missing = missing_placeholders(name, &content);
+                if missing.is_empty() {
+                    return Self::template(name, content, PromptSource::Custom, None);
+                }
+                format!("自定义模板缺少占位符: {}", missing.join(", "))
+            }
+            Err(e) => format!("读取自定义模板失败: {}", e),
+        };
+
+        Self::builtin(name, Some(custom_error))
+    }
+
+    /// 保存自定义模板（校验必需占位符）
+    pub fn save(&self, name: PromptName, content: &str) -> AppResult<PromptTemplate> {
+        let missing = missing_placeholders(name, content);
+        if !missing.is_empty() {
+            return Err(AppError::ValidationError(format!(
+                "提示词模板缺少必需的占位符: {}",
+                missing.join(", ")
+            )));
+        }
+
+        let path = self.custom_path(name).ok_or_else(|| {
+            AppError::InternalError("未配置提示词模板目录，无法保存自定义模板".to_string())
+        })?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| AppError::InternalError(format!("创建提示词目录失败: {}", e)))?;
+        }
+        fs::write(&path, content)
+            .map_err(|e| AppError::InternalError(format!("保存提示词模板失败: {}", e)))?;
+
+        Ok(self.load(name))
+    }
+
+    /// 删除自定义模板，恢复为内置模板
+    pub fn reset(&self, name: PromptName) -> AppResult<PromptTemplate> {
+        if let Some(path) = self.custom_path(name).filter(|path| path.exists()) {
+            fs::remove_file(&path)
+                .map_err(|e| AppError::InternalError(format!("删除自定义提示词模板失败: {}", e)))?;
+        }
+        Ok(self.load(name))
+    }
+
+    fn builtin(name: PromptName, custom_error: Option<String>) -> PromptTemplate {
+        Self::template(
+            name,
+            default_template(name).to_string(),
+            PromptSource::Default,
+            custom_error,
+        )
+    }
+
+    fn template(
+        name: PromptName,
+        content: String,
+        source: PromptSource,
+        custom_error: Option<String>,
+    ) -> PromptTemplate {
+        PromptTemplate {
+            name,
+            version: prompt_version(&content),
+            content,
+            source,
+            required_placeholders: required_placeholders(name)
+                .iter()
+                .map(|placeholder| placeholder.to_string())
+                .collect(),
+            custom_error,
+        }
+    }
+}
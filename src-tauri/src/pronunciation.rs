@@ -0,0 +1,410 @@
+//! 离线发音词典
+//!
+//! - 解析 CMUdict 格式的发音文件（`WORD  W ER1 D`，多个读音写作 `WORD(1)`）
+//! - ARPABET 转 IPA，支持美式和英式两种音标体系
+//! - 音标比较：忽略重音、长音符号和常见的等价写法，用于校验 AI 给出的音标
+//!
+//! CMUdict 只收录美式发音，英式音标按非元音前 r 不发音等规则近似转换
+
+use crate::types::pronunciation::{IpaConvention, PronunciationEntry};
+
+/// ARPABET 元音
+const VOWELS: &[&str] = &[
+    "AA", "AE", "AH", "AO", "AW", "AY", "EH", "ER", "EY", "IH", "IY", "OW", "OY", "UH", "UW",
+];
+
+/// ARPABET 辅音及其 IPA（美式与英式相同）
+const CONSONANTS: &[(&str, &str)] = &[
+    ("B", "b"),
+    ("CH", "tʃ"),
+    ("D", "d"),
+    ("DH", "ð"),
+    ("F", "f"),
+    ("G", "ɡ"),
+    ("HH", "h"),
+    ("JH", "dʒ"),
+    ("K", "k"),
+    ("L", "l"),
+    ("M", "m"),
+    ("N", "n"),
+    ("NG", "ŋ"),
+    ("P", "p"),
+    ("R", "r"),
+    ("S", "s"),
+    ("SH", "ʃ"),
+    ("T", "t"),
+    ("TH", "θ"),
+    ("V", "v"),
+    ("W", "w"),
+    ("Y", "j"),
+    ("Z", "z"),
+    ("ZH", "ʒ"),
+];
+
+/// 可以作为音节开头的辅音组合（用于确定重音符号的位置）
+const ONSETS: &[&str] = &[
+    "P R", "T R", "K R", "B R", "D R", "G R", "F R", "TH R", "SH R", "P L", "K L", "B L", "G L",
+    "F L", "S L", "S P", "S T", "S K", "S M", "S N", "S W", "K W", "T W", "D W", "G W", "P Y",
+    "K Y", "B Y", "F Y", "M Y", "V Y", "HH Y", "S P R", "S T R", "S K R", "S P L", "S K W",
+];
+
+/// 单个音素
+#[derive(Debug, Clone, Copy)]
+struct Phone<'a> {
+    base: &'a str,
+    stress: Option<u8>,
+}
+
+impl Phone<'_> {
+    fn is_vowel(&self) -> bool {
+        self.stress.is_some()
+    }
+}
+
+/// 解析 ARPABET 音素，遇到未知音素时返回 None
+fn parse_phones(arpabet: &str) -> Option<Vec<Phone<'_>>> {
+    arpabet
+        .split_whitespace()
+        .map(|token| {
+            let (base, stress) = match token.char_indices().last() {
+                Some((i, c)) if c.is_ascii_digit() => (&token[..i], Some(c as u8 - b'0')),
+                _ => (token, None),
+            };
+            if VOWELS.contains(&base) {
+                // CMUdict 的元音总带重音标记，缺省时按非重读处理
+                Some(Phone {
+                    base,
+                    stress: Some(stress.unwrap_or(0)),
+                })
+            } else if stress.is_none() && CONSONANTS.iter().any(|(c, _)| *c == base) {
+                Some(Phone { base, stress: None })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 元音的 IPA
+fn vowel_ipa(base: &str, stressed: bool, convention: IpaConvention) -> &'static str {
+    match (base, convention) {
+        ("AA", _) => "ɑː",
+        ("AE", _) => "æ",
+        ("AH", _) => {
+            if stressed {
+                "ʌ"
+            } else {
+                "ə"
+            }
+        }
+        ("AO", _) => "ɔː",
+        ("AW", _) => "aʊ",
+        ("AY", _) => "aɪ",
+        ("EH", _) => "e",
+        ("ER", IpaConvention::Us) => {
+            if stressed {
+                "ɜːr"
+            } else {
+                "ər"
+            }
+        }
+        ("ER", IpaConvention::Uk) => {
+            if stressed {
+                "ɜː"
+            } else {
+                "ə"
+            }
+        }
+        ("EY", _) => "eɪ",
+        ("IH", _) => "ɪ",
+        ("IY", _) => {
+            if stressed {
+                "iː"
+            } else {
+                "i"
+            }
+        }
+        ("OW", IpaConvention::Us) => "oʊ",
+        ("OW", IpaConvention::Uk) => "əʊ",
+        ("OY", _) => "ɔɪ",
+        ("UH", _) => "ʊ",
+        ("UW", _) => {
+            if stressed {
+                "uː"
+            } else {
+                "u"
+            }
+        }
+        _ => "",
+    }
+}
+
+/// 英式音标中 r 不发音时元音的变化（car → kɑː、near → nɪə）
+fn uk_vowel_before_silent_r(base: &str) -> Option<&'static str> {
+    match base {
+        "AA" => Some("ɑː"),
+        "AO" | "OW" => Some("ɔː"),
+        "EH" | "EY" | "AE" => Some("eə"),
+        "IH" | "IY" => Some("ɪə"),
+        "UH" | "UW" => Some("ʊə"),
+        "AY" => Some("aɪə"),
+        "AW" => Some("aʊə"),
+        _ => None,
+    }
+}
+
+/// 把 ARPABET 转换为 IPA（不含两侧的斜线），包含未知音素时返回 None
+///
+/// 多音节词在重读音节前标注 ˈ，主重音之前的次重音标注 ˌ，单音节词不标重音
+pub fn arpabet_to_ipa(arpabet: &str, convention: IpaConvention) -> Option<String> {
+    let phones = parse_phones(arpabet)?;
+    if phones.is_empty() {
+        return None;
+    }
+    let syllables = phones.iter().filter(|p| p.is_vowel()).count();
+    let next_is_vowel = |i: usize| phones.get(i + 1).is_some_and(|p| p.is_vowel());
+
+    let mut ipa = String::new();
+    let mut primary_seen = false;
+    let mut i = 0;
+    while i < phones.len() {
+        let phone = phones[i];
+        if let Some(stress) = phone.stress {
+            let marked = stress == 1 || (stress == 2 && !primary_seen);
+            primary_seen |= stress == 1;
+            if syllables > 1 && marked {
+                let onset = onset_start(&phones, i);
+                let mark = if stress == 1 { "ˈ" } else { "ˌ" };
+                let byte = onset_byte_offset(&ipa, &phones[onset..i]);
+                ipa.insert_str(byte, mark);
+            }
+
+            let r_follows = phones.get(i + 1).is_some_and(|p| p.base == "R");
+            let r_is_silent = r_follows && !next_is_vowel(i + 1);
+            if convention == IpaConvention::Uk && r_is_silent {
+                if let Some(vowel) = uk_vowel_before_silent_r(phone.base) {
+                    ipa.push_str(vowel);
+                    i += 2;
+                    continue;
+                }
+            }
+            ipa.push_str(vowel_ipa(phone.base, stress > 0, convention));
+            // 英式音标中 ER 后接元音时 r 仍然发音（interesting）
+            if convention == IpaConvention::Uk && phone.base == "ER" && next_is_vowel(i) {
+                ipa.push('r');
+            }
+        } else if !(convention == IpaConvention::Uk && phone.base == "R" && !next_is_vowel(i)) {
+            ipa.push_str(consonant_ipa(phone.base));
+        }
+        i += 1;
+    }
+
+    Some(ipa)
+}
+
+fn consonant_ipa(base: &str) -> &'static str {
+    CONSONANTS
+        .iter()
+        .find(|(c, _)| *c == base)
+        .map(|(_, ipa)| *ipa)
+        .unwrap_or("")
+}
+
+/// 重读元音所在音节的起始音素位置（前面的辅音尽量组成合法的音节开头）
+fn onset_start(phones: &[Phone], vowel: usize) -> usize {
+    let cluster_start = phones[..vowel]
+        .iter()
+        .rposition(|p| p.is_vowel())
+        .map_or(0, |i| i + 1);
+    if cluster_start == 0 {
+        return 0;
+    }
+    (cluster_start..vowel)
+        .find(|&start| {
+            let cluster: Vec<&str> = phones[start..vowel].iter().map(|p| p.base).collect();
+            cluster.len() <= 1 || ONSETS.contains(&cluster.join(" ").as_str())
+        })
+        .unwrap_or(vowel)
+}
+
+/// 音节开头的辅音在已生成 IPA 中的字节位置
+fn onset_byte_offset(ipa: &str, onset: &[Phone]) -> usize {
+    let onset_len: usize = onset.iter().map(|p| consonant_ipa(p.base).len()).sum();
+    ipa.len() - onset_len.min(ipa.len())
+}
+
+/// 加上两侧斜线的音标（与 AI 输出格式一致）
+pub fn format_ipa(ipa: &str) -> String {
+    format!("/{}/", ipa)
+}
+
+/// 发音文件解析结果
+#[derive(Debug, Default)]
+pub struct CmudictParseResult {
+    pub entries: Vec<PronunciationEntry>,
+    pub skipped_lines: usize,
+}
+
+/// 解析 CMUdict 格式的发音文件
+///
+/// 支持 `;;;` 和 `#` 注释、`WORD(1)` 多读音写法；包含未知音素的行计入跳过行数
+pub fn parse_cmudict(content: &str) -> CmudictParseResult {
+    let mut result = CmudictParseResult::default();
+
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or("").trim();
+        if line.is_empty() || line.starts_with(";;;") || line.starts_with('#') {
+            continue;
+        }
+
+        let mut tokens = line.splitn(2, char::is_whitespace);
+        let head = tokens.next().unwrap_or("");
+        let arpabet = tokens
+            .next()
+            .unwrap_or("")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let (word, variant) = match head.strip_suffix(')').and_then(|h| h.split_once('(')) {
+            Some((word, n)) => match n.parse::<i64>() {
+                Ok(n) => (word, n),
+                Err(_) => {
+                    result.skipped_lines += 1;
+                    continue;
+                }
+            },
+            None => (head, 0),
+        };
+
+        if word.is_empty() || arpabet.is_empty() || parse_phones(&arpabet).is_none() {
+            result.skipped_lines += 1;
+            continue;
+        }
+
+        result.entries.push(PronunciationEntry {
+            word: word.to_lowercase(),
+            variant,
+            arpabet,
+        });
+    }
+
+    result
+}
+
+/// 用于比较的音标形式：去掉斜线、重音和长音符号，合并常见的等价写法
+pub fn ipa_comparison_key(ipa: &str) -> String {
+    let mut key = String::new();
+    for c in ipa.chars() {
+        match c {
+            '/' | '[' | ']' | '(' | ')' | 'ˈ' | 'ˌ' | '\'' | 'ː' | 'ˑ' | ':' | '.' | '‿' | '-'
+            | '\u{0329}' | '\u{030D}' | '\u{032F}' => {}
+            c if c.is_whitespace() => {}
+            'ɡ' => key.push('g'),
+            'ɹ' | 'ɾ' => key.push('r'),
+            'ɝ' | 'ɚ' => key.push_str("ər"),
+            'ɜ' => key.push('ə'),
+            'ʤ' => key.push_str("dʒ"),
+            'ʧ' => key.push_str("tʃ"),
+            'ɒ' => key.push('ɑ'),
+            'ɛ' => key.push('e'),
+            'ɐ' => key.push('ʌ'),
+            'ᵻ' => key.push('ɪ'),
+            'ɫ' => key.push('l'),
+            c => key.push(c),
+        }
+    }
+
+    let key = key.replace("əʊ", "oʊ");
+    let chars: Vec<char> = key.chars().collect();
+    let is_vowel = |c: char| "aeiouæɑɒɔəɪʊʌ".contains(c);
+    let mut normalized = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        // 成音节辅音：garden 的 /dən/ 与 /dn/ 视为相同
+        let syllabic = c == 'ə'
+            && i > 0
+            && !is_vowel(chars[i - 1])
+            && matches!(chars.get(i + 1), Some('l' | 'n' | 'm'))
+            && chars.get(i + 2).is_none_or(|&next| !is_vowel(next));
+        if syllabic {
+            continue;
+        }
+        // 词尾的 ɪ 与 i 视为相同（happy）
+        if c == 'ɪ' && i + 1 == chars.len() {
+            normalized.push('i');
+            continue;
+        }
+        normalized.push(c);
+    }
+    normalized
+}
+
+/// 音标是否与任一词典读音一致
+pub fn ipa_matches(ipa: &str, candidates: &[String]) -> bool {
+    let key = ipa_comparison_key(ipa);
+    candidates.iter().any(|c| ipa_comparison_key(c) == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arpabet_to_ipa_conventions() {
+        let cases = [
+            ("K AE1 T", "kæt", "kæt"),
+            ("G AA1 R D AH0 N", "ˈɡɑːrdən", "ˈɡɑːdən"),
+            ("JH AH1 M P IH0 NG", "ˈdʒʌmpɪŋ", "ˈdʒʌmpɪŋ"),
+            ("HH OW1 M", "hoʊm", "həʊm"),
+            ("B ER1 D", "bɜːrd", "bɜːd"),
+            ("N IH1 R", "nɪr", "nɪə"),
+            ("K AH0 M P Y UW1 T ER0", "kəmˈpjuːtər", "kəmˈpjuːtə"),
+            ("IH1 N T R AH0 S T IH0 NG", "ˈɪntrəstɪŋ", "ˈɪntrəstɪŋ"),
+            ("HH AE1 P IY0", "ˈhæpi", "ˈhæpi"),
+            ("T AH0 M EY1 T OW2", "təˈmeɪtoʊ", "təˈmeɪtəʊ"),
+            (
+                "EH2 K S P L AH0 N EY1 SH AH0 N",
+                "ˌekspləˈneɪʃən",
+                "ˌekspləˈneɪʃən",
+            ),
+        ];
+        for (arpabet, us, uk) in cases {
+            assert_eq!(
+                arpabet_to_ipa(arpabet, IpaConvention::Us).as_deref(),
+                Some(us),
+                "{}",
+                arpabet
+            );
+            assert_eq!(
+                arpabet_to_ipa(arpabet, IpaConvention::Uk).as_deref(),
+                Some(uk),
+                "{}",
+                arpabet
+            );
+        }
+        assert!(arpabet_to_ipa("K XX1 T", IpaConvention::Us).is_none());
+    }
+
+    #[test]
+    fn test_parse_cmudict() {
+        let content = ";;; comment\nCAT  K AE1 T\nREAD  R IY1 D\nREAD(1)  R EH1 D\nlive(2) L IH1 V # verb\nBAD  B Q1 D\n\nEMPTY\n";
+        let result = parse_cmudict(content);
+        assert_eq!(result.skipped_lines, 2);
+        assert_eq!(result.entries.len(), 4);
+        assert_eq!(result.entries[2].word, "read");
+        assert_eq!(result.entries[2].variant, 1);
+        assert_eq!(result.entries[3].word, "live");
+        assert_eq!(result.entries[3].arpabet, "L IH1 V");
+    }
+
+    #[test]
+    fn test_ipa_matches_ignores_notation_differences() {
+        let garden = vec![format_ipa("ˈɡɑːrdən"), format_ipa("ˈɡɑːdən")];
+        assert!(ipa_matches("/ˈɡɑːrdn/", &garden));
+        assert!(ipa_matches("[ˈgɑrdən]", &garden));
+        assert!(ipa_matches("/ˈhæpɪ/", &["/ˈhæpi/".to_string()]));
+        assert!(ipa_matches("/hɒt/", &["/hɑːt/".to_string()]));
+        assert!(!ipa_matches("/ˈɡɑːrdiːn/", &garden));
+        assert!(!ipa_matches("/kɑt/", &["/kæt/".to_string()]));
+    }
+}
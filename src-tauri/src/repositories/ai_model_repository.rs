@@ -28,7 +28,7 @@ impl AIModelRepository {
         use crate::types::ai_model::AIProvider;
 
         let query = r#"
-            SELECT m.*, p.name as provider_name, p.base_url, p.api_key
+            SELECT m.*, p.name as provider_name, p.base_url, p.api_key, p.provider_type
             FROM ai_models m
             JOIN ai_providers p ON m.provider_id = p.id
             WHERE m.id = ? AND m.is_active = 1 AND p.is_active = 1
@@ -61,6 +61,7 @@ impl AIModelRepository {
                         display_name: row.get("provider_name"),
                         base_url: row.get("base_url"),
                         api_key: row.get("api_key"),
+                        provider_type: row.get("provider_type"),
                         description: None,
                         is_active: true,
                         created_at: row.get("created_at"),
@@ -72,6 +73,8 @@ impl AIModelRepository {
                     description: row.get("description"),
                     max_tokens: row.get("max_tokens"),
                     temperature: row.get("temperature"),
+                    input_price_per_million: row.get("input_price_per_million"),
+                    output_price_per_million: row.get("output_price_per_million"),
                     is_active: row.get("is_active"),
                     is_default: row.get("is_default"),
                     created_at: row.get("created_at"),
@@ -87,7 +90,7 @@ impl AIModelRepository {
         use crate::types::ai_model::AIProvider;
 
         let query = r#"
-            SELECT m.*, p.name as provider_name, p.base_url, p.api_key
+            SELECT m.*, p.name as provider_name, p.base_url, p.api_key, p.provider_type
             FROM ai_models m
             JOIN ai_providers p ON m.provider_id = p.id
             WHERE m.is_default = 1 AND m.is_active = 1 AND p.is_active = 1
@@ -119,6 +122,7 @@ impl AIModelRepository {
                         display_name: row.get("provider_name"),
                         base_url: row.get("base_url"),
                         api_key: row.get("api_key"),
+                        provider_type: row.get("provider_type"),
                         description: None,
                         is_active: true,
                         created_at: row.get("created_at"),
@@ -130,6 +134,8 @@ impl AIModelRepository {
                     description: row.get("description"),
                     max_tokens: row.get("max_tokens"),
                     temperature: row.get("temperature"),
+                    input_price_per_million: row.get("input_price_per_million"),
+                    output_price_per_million: row.get("output_price_per_million"),
                     is_active: row.get("is_active"),
                     is_default: row.get("is_default"),
                     created_at: row.get("created_at"),
@@ -139,4 +145,43 @@ impl AIModelRepository {
             None => Ok(None),
         }
     }
+
+    /// 更新模型价格（每百万 token 美元价格，None 表示未配置）
+    pub async fn update_pricing(
+        &self,
+        model_id: Id,
+        input_price_per_million: Option<f64>,
+        output_price_per_million: Option<f64>,
+    ) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE ai_models
+            SET input_price_per_million = ?, output_price_per_million = ?, updated_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(input_price_per_million)
+        .bind(output_price_per_million)
+        .bind(model_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("UPDATE", "ai_models", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("AI模型 {} 不存在", model_id)));
+        }
+
+        self.logger.database_operation(
+            "UPDATE",
+            "ai_models",
+            true,
+            Some(&format!("Updated pricing of AI model {}", model_id)),
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,130 @@
+//! AI 调用用量数据访问层
+//!
+//! 记录每次 LLM 调用的 token 用量、耗时和估算费用，并按时间、提供商、用途汇总
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::ai_model::{AIUsageBucket, AIUsageRecord};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+
+/// 用量汇总方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGrouping {
+    /// 按天（本地时间）
+    Day,
+    /// 按月（本地时间）
+    Month,
+    /// 按提供商
+    Provider,
+    /// 按用途
+    Purpose,
+    /// 不分组
+    Total,
+}
+
+impl UsageGrouping {
+    /// 分组使用的 SQL 表达式
+    fn key_expression(&self) -> &'static str {
+        match self {
+            UsageGrouping::Day => "date(created_at, 'localtime')",
+            UsageGrouping::Month => "strftime('%Y-%m', created_at, 'localtime')",
+            UsageGrouping::Provider => "provider_name",
+            UsageGrouping::Purpose => "purpose",
+            UsageGrouping::Total => "'total'",
+        }
+    }
+}
+
+/// AI 调用用量仓储
+#[derive(Clone)]
+pub struct AIUsageRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl AIUsageRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    /// 写入一条调用记录
+    pub async fn record(&self, record: &AIUsageRecord) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ai_usage_log (
+                model_id, provider_id, model_name, provider_name, purpose, prompt_tokens,
+                completion_tokens, tokens_estimated, latency_ms, success, estimated_cost
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(record.model_id)
+        .bind(record.provider_id)
+        .bind(&record.model_name)
+        .bind(&record.provider_name)
+        .bind(record.purpose.as_str())
+        .bind(record.prompt_tokens.map(|t| t as i64))
+        .bind(record.completion_tokens.map(|t| t as i64))
+        .bind(record.tokens_estimated)
+        .bind(record.latency_ms as i64)
+        .bind(record.success)
+        .bind(record.estimated_cost)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("INSERT", "ai_usage_log", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// 汇总日期范围内（本地日期，含首尾）的用量
+    pub async fn summarize(
+        &self,
+        grouping: UsageGrouping,
+        start_date: &str,
+        end_date: &str,
+    ) -> AppResult<Vec<AIUsageBucket>> {
+        let key = grouping.key_expression();
+        let query = format!(
+            r#"
+            SELECT {key} as usage_key,
+                   COUNT(*) as calls,
+                   SUM(CASE WHEN success = 0 THEN 1 ELSE 0 END) as failed_calls,
+                   COALESCE(SUM(prompt_tokens), 0) as prompt_tokens,
+                   COALESCE(SUM(completion_tokens), 0) as completion_tokens,
+                   COALESCE(SUM(estimated_cost), 0.0) as estimated_cost
+            FROM ai_usage_log
+            WHERE date(created_at, 'localtime') BETWEEN ? AND ?
+            GROUP BY usage_key
+            ORDER BY usage_key
+            "#
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(start_date)
+            .bind(end_date)
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "ai_usage_log", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(rows
+            .iter()
+            .map(|row| AIUsageBucket {
+                key: row.get("usage_key"),
+                calls: row.get("calls"),
+                failed_calls: row.get("failed_calls"),
+                prompt_tokens: row.get("prompt_tokens"),
+                completion_tokens: row.get("completion_tokens"),
+                estimated_cost: row.get("estimated_cost"),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,337 @@
+//! 批量分析任务数据访问层
+//!
+//! 持久化批量分析的输入文本、单词列表以及每个单词的分析状态和结果
+
+use crate::ai_service::PhonicsWord;
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::common::Id;
+use crate::types::word_analysis::{AnalysisJobWord, BatchAnalysisJob};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+
+/// 列表中输入文本预览的最大字符数
+const INPUT_PREVIEW_LENGTH: i64 = 100;
+
+/// 批量分析任务仓储
+pub struct AnalysisJobRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl AnalysisJobRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    fn db_error(&self, operation: &str, table: &str, e: sqlx::Error) -> AppError {
+        self.logger
+            .database_operation(operation, table, false, Some(&e.to_string()));
+        AppError::DatabaseError(e.to_string())
+    }
+
+    /// 创建批量分析任务
+    pub async fn create_job(
+        &self,
+        input_text: Option<&str>,
+        extraction_mode: &str,
+        local_extraction: bool,
+        model_id: Option<Id>,
+        prompt_version: &str,
+    ) -> AppResult<Id> {
+        let result = sqlx::query(
+            "INSERT INTO analysis_jobs (input_text, extraction_mode, local_extraction, model_id, prompt_version, status) VALUES (?, ?, ?, ?, ?, 'extracting')",
+        )
+        .bind(input_text)
+        .bind(extraction_mode)
+        .bind(local_extraction)
+        .bind(model_id)
+        .bind(prompt_version)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("INSERT", "analysis_jobs", e))?;
+
+        let job_id = result.last_insert_rowid();
+        self.logger.database_operation(
+            "INSERT",
+            "analysis_jobs",
+            true,
+            Some(&format!("Created analysis job {}", job_id)),
+        );
+
+        Ok(job_id)
+    }
+
+    /// 保存提取到的单词列表，任务进入分析阶段
+    pub async fn save_words(&self, job_id: Id, words: &[String]) -> AppResult<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| self.db_error("BEGIN", "transaction", e))?;
+
+        sqlx::query("DELETE FROM analysis_job_words WHERE job_id = ?")
+            .bind(job_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, word) in words.iter().enumerate() {
+            sqlx::query("INSERT INTO analysis_job_words (job_id, position, word) VALUES (?, ?, ?)")
+                .bind(job_id)
+                .bind(position as i64)
+                .bind(word)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE analysis_jobs
+            SET status = 'analyzing', total_words = ?, completed_words = 0, failed_words = 0,
+                error_message = NULL, updated_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(words.len() as i64)
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| self.db_error("COMMIT", "analysis_job_words", e))?;
+
+        self.logger.database_operation(
+            "INSERT",
+            "analysis_job_words",
+            true,
+            Some(&format!("Saved {} words for analysis job {}", words.len(), job_id)),
+        );
+
+        Ok(())
+    }
+
+    /// 保存单词分析结果（单词, 结果）
+    pub async fn save_results(&self, job_id: Id, results: &[(String, PhonicsWord)]) -> AppResult<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| self.db_error("BEGIN", "transaction", e))?;
+
+        for (word, result) in results {
+            let result_json = serde_json::to_string(result)
+                .map_err(|e| AppError::InternalError(format!("序列化分析结果失败: {}", e)))?;
+
+            sqlx::query(
+                r#"
+                UPDATE analysis_job_words
+                SET status = 'completed', result_json = ?, error_message = NULL, updated_at = datetime('now')
+                WHERE job_id = ? AND word = ?
+                "#,
+            )
+            .bind(result_json)
+            .bind(job_id)
+            .bind(word)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        Self::refresh_counts(&mut tx, job_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| self.db_error("COMMIT", "analysis_job_words", e))?;
+
+        Ok(())
+    }
+
+    /// 标记单词分析失败（单词, 错误信息）
+    pub async fn mark_words_failed(&self, job_id: Id, failures: &[(String, String)]) -> AppResult<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| self.db_error("BEGIN", "transaction", e))?;
+
+        for (word, error) in failures {
+            sqlx::query(
+                r#"
+                UPDATE analysis_job_words
+                SET status = 'failed', error_message = ?, updated_at = datetime('now')
+                WHERE job_id = ? AND word = ? AND status != 'completed'
+                "#,
+            )
+            .bind(error)
+            .bind(job_id)
+            .bind(word)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        Self::refresh_counts(&mut tx, job_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| self.db_error("COMMIT", "analysis_job_words", e))?;
+
+        Ok(())
+    }
+
+    /// 重新统计任务的完成和失败单词数
+    async fn refresh_counts(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        job_id: Id,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE analysis_jobs
+            SET completed_words = (SELECT COUNT(*) FROM analysis_job_words WHERE job_id = ?1 AND status = 'completed'),
+                failed_words = (SELECT COUNT(*) FROM analysis_job_words WHERE job_id = ?1 AND status = 'failed'),
+                updated_at = datetime('now')
+            WHERE id = ?1
+            "#,
+        )
+        .bind(job_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 更新任务状态
+    pub async fn update_status(&self, job_id: Id, status: &str, error_message: Option<&str>) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE analysis_jobs SET status = ?, error_message = ?, updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(status)
+        .bind(error_message)
+        .bind(job_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("UPDATE", "analysis_jobs", e))?;
+
+        self.logger.database_operation(
+            "UPDATE",
+            "analysis_jobs",
+            true,
+            Some(&format!("Set status of analysis job {} to {}", job_id, status)),
+        );
+
+        Ok(())
+    }
+
+    /// 更新任务使用的提示词版本（恢复任务时模板可能已被修改）
+    pub async fn update_prompt_version(&self, job_id: Id, prompt_version: &str) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE analysis_jobs SET prompt_version = ?, updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(prompt_version)
+        .bind(job_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("UPDATE", "analysis_jobs", e))?;
+
+        self.logger.database_operation(
+            "UPDATE",
+            "analysis_jobs",
+            true,
+            Some(&format!("Set prompt version of analysis job {} to {}", job_id, prompt_version)),
+        );
+
+        Ok(())
+    }
+
+    /// 查询任务列表（最近更新的在前）
+    pub async fn find_all(&self, limit: i64) -> AppResult<Vec<BatchAnalysisJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, substr(input_text, 1, ?) as input_preview, extraction_mode, local_extraction, model_id, prompt_version, status,
+                   total_words, completed_words, failed_words, error_message, created_at, updated_at
+            FROM analysis_jobs
+            ORDER BY updated_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(INPUT_PREVIEW_LENGTH)
+        .bind(limit)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", "analysis_jobs", e))?;
+
+        Ok(rows.iter().map(Self::row_to_job).collect())
+    }
+
+    /// 查询单个任务
+    pub async fn find_by_id(&self, job_id: Id) -> AppResult<Option<BatchAnalysisJob>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, substr(input_text, 1, ?) as input_preview, extraction_mode, local_extraction, model_id, prompt_version, status,
+                   total_words, completed_words, failed_words, error_message, created_at, updated_at
+            FROM analysis_jobs
+            WHERE id = ?
+            "#,
+        )
+        .bind(INPUT_PREVIEW_LENGTH)
+        .bind(job_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", "analysis_jobs", e))?;
+
+        Ok(row.as_ref().map(Self::row_to_job))
+    }
+
+    /// 查询任务的完整输入文本
+    pub async fn find_input_text(&self, job_id: Id) -> AppResult<Option<String>> {
+        let row = sqlx::query("SELECT input_text FROM analysis_jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("SELECT", "analysis_jobs", e))?;
+
+        Ok(row.and_then(|row| row.get("input_text")))
+    }
+
+    /// 查询任务的单词列表（按提取顺序）
+    pub async fn find_words(&self, job_id: Id) -> AppResult<Vec<AnalysisJobWord>> {
+        let rows = sqlx::query(
+            "SELECT word, status, result_json, error_message FROM analysis_job_words WHERE job_id = ? ORDER BY position",
+        )
+        .bind(job_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", "analysis_job_words", e))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let result_json: Option<String> = row.get("result_json");
+                AnalysisJobWord {
+                    word: row.get("word"),
+                    status: row.get("status"),
+                    result: result_json.and_then(|json| serde_json::from_str(&json).ok()),
+                    error: row.get("error_message"),
+                }
+            })
+            .collect())
+    }
+
+    fn row_to_job(row: &sqlx::sqlite::SqliteRow) -> BatchAnalysisJob {
+        BatchAnalysisJob {
+            id: row.get("id"),
+            input_preview: row.get("input_preview"),
+            extraction_mode: row.get("extraction_mode"),
+            local_extraction: row.get("local_extraction"),
+            model_id: row.get("model_id"),
+            prompt_version: row.get("prompt_version"),
+            status: row.get("status"),
+            total_words: row.get::<i64, _>("total_words") as usize,
+            completed_words: row.get::<i64, _>("completed_words") as usize,
+            failed_words: row.get::<i64, _>("failed_words") as usize,
+            error_message: row.get("error_message"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! 数据库备份数据访问层
+//!
+//! 读写自动备份设置，并通过 `VACUUM INTO` 在线复制数据库
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::backup::{BackupSettings, UpdateBackupSettingsRequest};
+use sqlx::{Row, SqlitePool};
+use std::path::Path;
+use std::sync::Arc;
+
+/// 数据库备份仓储
+pub struct BackupRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl BackupRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    fn db_error(&self, operation: &str, e: sqlx::Error) -> AppError {
+        self.logger
+            .database_operation(operation, "backup_settings", false, Some(&e.to_string()));
+        AppError::DatabaseError(e.to_string())
+    }
+
+    /// 读取自动备份设置
+    pub async fn get_settings(&self) -> AppResult<BackupSettings> {
+        let row = sqlx::query(
+            r#"
+            SELECT enabled, interval_hours, retention_count, last_backup_at
+            FROM backup_settings
+            WHERE id = 1
+            "#,
+        )
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", e))?;
+
+        Ok(match row {
+            Some(row) => BackupSettings {
+                enabled: row.get("enabled"),
+                interval_hours: row.get("interval_hours"),
+                retention_count: row.get("retention_count"),
+                last_backup_at: row.get("last_backup_at"),
+            },
+            None => BackupSettings {
+                enabled: true,
+                interval_hours: 24,
+                retention_count: 7,
+                last_backup_at: None,
+            },
+        })
+    }
+
+    /// 更新自动备份设置（只更新请求中有值的字段）
+    pub async fn update_settings(
+        &self,
+        request: &UpdateBackupSettingsRequest,
+    ) -> AppResult<BackupSettings> {
+        sqlx::query(
+            r#"
+            INSERT INTO backup_settings (id, enabled, interval_hours, retention_count)
+            VALUES (1, COALESCE(?1, 1), COALESCE(?2, 24), COALESCE(?3, 7))
+            ON CONFLICT(id) DO UPDATE SET
+                enabled = COALESCE(?1, enabled),
+                interval_hours = COALESCE(?2, interval_hours),
+                retention_count = COALESCE(?3, retention_count),
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(request.enabled)
+        .bind(request.interval_hours)
+        .bind(request.retention_count)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("UPDATE", e))?;
+
+        self.logger
+            .database_operation("UPDATE", "backup_settings", true, None);
+        self.get_settings().await
+    }
+
+    /// 记录最近一次备份时间
+    pub async fn mark_backup(&self, backup_at: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO backup_settings (id, last_backup_at) VALUES (1, ?1)
+            ON CONFLICT(id) DO UPDATE SET last_backup_at = ?1
+            "#,
+        )
+        .bind(backup_at)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("UPDATE", e))?;
+        Ok(())
+    }
+
+    /// 数据库已成功执行的最新迁移版本
+    pub async fn current_migration_version(&self) -> AppResult<i64> {
+        let version: Option<i64> =
+            sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+                .fetch_one(self.pool.as_ref())
+                .await
+                .map_err(|e| self.db_error("SELECT", e))?;
+        Ok(version.unwrap_or(0))
+    }
+
+    /// 在线复制数据库到指定文件（WAL 模式下也能得到一致的快照）
+    ///
+    /// 使用 `VACUUM INTO` 而不是 sqlite3_backup API：sqlx 没有提供后者的接口，
+    /// `VACUUM INTO` 在单个读事务中完成复制，不阻塞写入，得到的文件同时经过压缩整理
+    pub async fn vacuum_into(&self, target: &Path) -> AppResult<()> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(target.to_string_lossy().to_string())
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("VACUUM INTO", e))?;
+        Ok(())
+    }
+}
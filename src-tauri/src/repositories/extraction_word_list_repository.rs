@@ -0,0 +1,139 @@
+//! 单词提取用户词表数据访问层
+//!
+//! 保存用户自定义的停用词和已掌握单词，本地单词提取时用于过滤
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::word_analysis::{ExtractionWordListType, ExtractionWordLists};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// 规范化词表中的单词（去除空白、小写、去重、排序）
+fn normalize_words(words: &[String]) -> BTreeSet<String> {
+    words
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// 单词提取用户词表仓储
+pub struct ExtractionWordListRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl ExtractionWordListRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    fn db_error(&self, operation: &str, e: sqlx::Error) -> AppError {
+        self.logger.database_operation(
+            operation,
+            "extraction_word_lists",
+            false,
+            Some(&e.to_string()),
+        );
+        AppError::DatabaseError(e.to_string())
+    }
+
+    /// 获取所有词表
+    pub async fn find_all(&self) -> AppResult<ExtractionWordLists> {
+        let rows = sqlx::query("SELECT list_type, word FROM extraction_word_lists ORDER BY word")
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("SELECT", e))?;
+
+        let mut lists = ExtractionWordLists::default();
+        for row in rows {
+            let word: String = row.get("word");
+            match ExtractionWordListType::parse(row.get("list_type")) {
+                Some(ExtractionWordListType::Stop) => lists.stop_words.push(word),
+                Some(ExtractionWordListType::Known) => lists.known_words.push(word),
+                None => {}
+            }
+        }
+
+        Ok(lists)
+    }
+
+    /// 用新的单词列表替换词表，返回保存后的单词数
+    pub async fn replace(
+        &self,
+        list_type: ExtractionWordListType,
+        words: &[String],
+    ) -> AppResult<usize> {
+        let words = normalize_words(words);
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| self.db_error("BEGIN", e))?;
+
+        sqlx::query("DELETE FROM extraction_word_lists WHERE list_type = ?")
+            .bind(list_type.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| self.db_error("DELETE", e))?;
+
+        for word in &words {
+            sqlx::query("INSERT INTO extraction_word_lists (list_type, word) VALUES (?, ?)")
+                .bind(list_type.as_str())
+                .bind(word)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| self.db_error("INSERT", e))?;
+        }
+
+        tx.commit().await.map_err(|e| self.db_error("COMMIT", e))?;
+
+        self.logger.database_operation(
+            "REPLACE",
+            "extraction_word_lists",
+            true,
+            Some(&format!(
+                "Saved {} words to {} list",
+                words.len(),
+                list_type.as_str()
+            )),
+        );
+
+        Ok(words.len())
+    }
+
+    /// 向词表追加单词（已存在的忽略），返回新增的单词数
+    pub async fn add(
+        &self,
+        list_type: ExtractionWordListType,
+        words: &[String],
+    ) -> AppResult<usize> {
+        let mut added = 0;
+        for word in normalize_words(words) {
+            let result = sqlx::query(
+                "INSERT OR IGNORE INTO extraction_word_lists (list_type, word) VALUES (?, ?)",
+            )
+            .bind(list_type.as_str())
+            .bind(&word)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("INSERT", e))?;
+            added += result.rows_affected() as usize;
+        }
+
+        self.logger.database_operation(
+            "INSERT",
+            "extraction_word_lists",
+            true,
+            Some(&format!(
+                "Added {} words to {} list",
+                added,
+                list_type.as_str()
+            )),
+        );
+
+        Ok(added)
+    }
+}
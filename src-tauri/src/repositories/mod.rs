@@ -1,11 +1,21 @@
 // 数据访问层 - Repository 模式
 
 pub mod ai_model_repository;
+pub mod ai_usage_repository;
+pub mod analysis_job_repository;
+pub mod backup_repository;
 pub mod calendar_repository;
 pub mod diagnostics_repository;
+pub mod extraction_word_list_repository;
+pub mod operation_snapshot_repository;
+pub mod phonics_cache_repository;
 pub mod practice_repository;
+pub mod prompt_version_repository;
+pub mod pronunciation_repository;
+pub mod review_state_repository;
 pub mod statistics_repository;
 pub mod study_plan_repository;
+pub mod study_plan_template_repository;
 pub mod study_schedule_repository;
 pub mod theme_tag_repository;
 pub mod word_repository;
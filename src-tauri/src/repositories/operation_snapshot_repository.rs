@@ -0,0 +1,576 @@
+//! 破坏性操作快照数据访问层
+//!
+//! 快照用"表 + 条件"描述受影响的行，并沿外键找出会被级联删除或置空的子表行一起保存；
+//! 撤销时在同一事务中写回（延迟外键检查，写回顺序不受约束影响）：
+//! 软删除和外键置空的行按主键覆盖，已删除的行重新插入，主键已被新数据占用时拒绝撤销
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::common::Id;
+use crate::types::snapshot::{OperationSnapshot, SnapshotOperation};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 保留的快照数量
+pub const SNAPSHOT_HISTORY_LIMIT: i64 = 10;
+
+/// 不参与快照的表（快照自身）
+const EXCLUDED_TABLES: [&str; 2] = ["operation_snapshots", "operation_snapshot_tables"];
+
+/// 沿外键查找级联行的最大深度
+const MAX_CASCADE_DEPTH: usize = 8;
+
+/// 整张表的条件
+const ALL_ROWS: &str = "1 = 1";
+
+const SELECT_SNAPSHOTS: &str = r#"
+    SELECT s.id, s.operation, s.target_id, s.description, s.row_count, s.created_at, s.undone_at,
+           (SELECT group_concat(t.table_name, ',') FROM operation_snapshot_tables t
+            WHERE t.snapshot_id = s.id) AS tables
+    FROM operation_snapshots s
+"#;
+
+/// 快照范围：一张表中满足条件的行
+#[derive(Debug, Clone)]
+pub struct SnapshotScope {
+    table: String,
+    condition: String, // 只由程序生成，不包含用户输入
+    cascade: bool,     // 是否同时保存会被级联删除或置空的子表行
+    keeps_rows: bool,  // 操作后这些行是否仍然存在（软删除、外键置空）
+}
+
+impl SnapshotScope {
+    /// 整张表的全部行（连同级联行）
+    pub fn table(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            condition: ALL_ROWS.to_string(),
+            cascade: true,
+            keeps_rows: false,
+        }
+    }
+
+    /// 指定列取值在 `ids` 中的行（只保存这些行本身）
+    pub fn rows(table: &str, column: &str, ids: &[Id]) -> Self {
+        Self {
+            table: table.to_string(),
+            condition: id_condition(column, ids),
+            cascade: false,
+            keeps_rows: false,
+        }
+    }
+
+    /// 标记为软删除：操作后这些行仍然存在，撤销时按主键覆盖
+    pub fn soft_deleted(mut self) -> Self {
+        self.keeps_rows = true;
+        self
+    }
+
+    /// 追加一个列取值条件
+    pub fn and(mut self, column: &str, ids: &[Id]) -> Self {
+        self.condition = format!("{} AND {}", self.condition, id_condition(column, ids));
+        self
+    }
+
+    /// 追加子查询条件：`column` 的值在 `parent_table` 满足 `parent_column IN ids` 的行的 id 中
+    pub fn and_parent(
+        mut self,
+        column: &str,
+        parent_table: &str,
+        parent_column: &str,
+        ids: &[Id],
+    ) -> Self {
+        self.condition = format!(
+            "{} AND {} IN (SELECT id FROM {} WHERE {})",
+            self.condition,
+            quote_ident(column),
+            quote_ident(parent_table),
+            id_condition(parent_column, ids)
+        );
+        self
+    }
+
+    /// 同时保存会被级联删除或置空的子表行（适用于物理删除）
+    pub fn with_cascade(mut self) -> Self {
+        self.cascade = true;
+        self
+    }
+}
+
+/// 外键关系
+struct ForeignKey {
+    child: String,
+    from: String,
+    parent: String,
+    to: Option<String>,
+    on_delete: String,
+}
+
+/// 破坏性操作快照仓储
+pub struct OperationSnapshotRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl OperationSnapshotRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    fn db_error(&self, operation: &str, e: sqlx::Error) -> AppError {
+        self.logger.database_operation(
+            operation,
+            "operation_snapshots",
+            false,
+            Some(&e.to_string()),
+        );
+        AppError::DatabaseError(e.to_string())
+    }
+
+    /// 保存快照，返回快照 ID（超出保留数量的旧快照会被删除）
+    pub async fn capture(
+        &self,
+        operation: SnapshotOperation,
+        target_id: Option<Id>,
+        description: &str,
+        scopes: &[SnapshotScope],
+    ) -> AppResult<Id> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| self.db_error("BEGIN", e))?;
+
+        let tables = expand_scopes(&mut tx, scopes)
+            .await
+            .map_err(|e| self.db_error("SELECT", e))?;
+
+        let snapshot_id = sqlx::query(
+            "INSERT INTO operation_snapshots (operation, target_id, description) VALUES (?, ?, ?)",
+        )
+        .bind(operation.as_str())
+        .bind(target_id)
+        .bind(description)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| self.db_error("INSERT", e))?
+        .last_insert_rowid();
+
+        for (table, keeps_rows, condition) in &tables {
+            let columns = table_columns(&mut tx, table)
+                .await
+                .map_err(|e| self.db_error("SELECT", e))?;
+            let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+            let pairs = names
+                .iter()
+                .map(|name| format!("{}, {}", quote_literal(name), quote_ident(name)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let sql = format!(
+                r#"
+                INSERT INTO operation_snapshot_tables
+                (snapshot_id, table_name, columns, row_count, rows_json, keeps_rows)
+                SELECT ?, ?, ?, COUNT(*), json_group_array(json_object({})), ?
+                FROM {} WHERE {}
+                HAVING COUNT(*) > 0
+                "#,
+                pairs,
+                quote_ident(table),
+                condition
+            );
+            sqlx::query(&sql)
+                .bind(snapshot_id)
+                .bind(table)
+                .bind(serde_json::to_string(&names).unwrap_or_default())
+                .bind(keeps_rows)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| self.db_error("INSERT", e))?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE operation_snapshots
+            SET row_count = (SELECT COALESCE(SUM(row_count), 0) FROM operation_snapshot_tables WHERE snapshot_id = ?1)
+            WHERE id = ?1
+            "#,
+        )
+        .bind(snapshot_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| self.db_error("UPDATE", e))?;
+
+        // 只保留最近的快照
+        for sql in [
+            "DELETE FROM operation_snapshot_tables WHERE snapshot_id NOT IN (SELECT id FROM operation_snapshots ORDER BY id DESC LIMIT ?)",
+            "DELETE FROM operation_snapshots WHERE id NOT IN (SELECT id FROM operation_snapshots ORDER BY id DESC LIMIT ?)",
+        ] {
+            sqlx::query(sql)
+                .bind(SNAPSHOT_HISTORY_LIMIT)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| self.db_error("DELETE", e))?;
+        }
+
+        tx.commit().await.map_err(|e| self.db_error("COMMIT", e))?;
+
+        self.logger.database_operation(
+            "INSERT",
+            "operation_snapshots",
+            true,
+            Some(&format!(
+                "Captured {} snapshot {} ({} tables)",
+                operation.as_str(),
+                snapshot_id,
+                tables.len()
+            )),
+        );
+
+        Ok(snapshot_id)
+    }
+
+    /// 删除快照（操作失败时使用）
+    pub async fn discard(&self, snapshot_id: Id) -> AppResult<()> {
+        for sql in [
+            "DELETE FROM operation_snapshot_tables WHERE snapshot_id = ?",
+            "DELETE FROM operation_snapshots WHERE id = ?",
+        ] {
+            sqlx::query(sql)
+                .bind(snapshot_id)
+                .execute(self.pool.as_ref())
+                .await
+                .map_err(|e| self.db_error("DELETE", e))?;
+        }
+        Ok(())
+    }
+
+    /// 查询快照历史（最新的在前）
+    pub async fn find_all(&self) -> AppResult<Vec<OperationSnapshot>> {
+        let rows = sqlx::query(&format!("{} ORDER BY s.id DESC", SELECT_SNAPSHOTS))
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("SELECT", e))?;
+
+        Ok(rows.iter().filter_map(Self::row_to_snapshot).collect())
+    }
+
+    /// 查询快照
+    pub async fn find_by_id(&self, snapshot_id: Id) -> AppResult<Option<OperationSnapshot>> {
+        let row = sqlx::query(&format!("{} WHERE s.id = ?", SELECT_SNAPSHOTS))
+            .bind(snapshot_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("SELECT", e))?;
+
+        Ok(row.as_ref().and_then(Self::row_to_snapshot))
+    }
+
+    /// 查询最近一个未撤销的快照
+    pub async fn find_latest_active(&self) -> AppResult<Option<OperationSnapshot>> {
+        let row = sqlx::query(&format!(
+            "{} WHERE s.undone_at IS NULL ORDER BY s.id DESC LIMIT 1",
+            SELECT_SNAPSHOTS
+        ))
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", e))?;
+
+        Ok(row.as_ref().and_then(Self::row_to_snapshot))
+    }
+
+    /// 把快照中的行写回并标记为已撤销，返回写回的行数
+    ///
+    /// 已删除的行先重新插入，再按主键覆盖仍然存在的行；
+    /// 已删除的行的主键被新数据占用时返回验证错误，事务回滚，不覆盖新数据
+    pub async fn restore(&self, snapshot_id: Id) -> AppResult<i64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| self.db_error("BEGIN", e))?;
+
+        // 子表可能先于父表写回，外键在提交时再检查
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| self.db_error("PRAGMA", e))?;
+
+        let tables = sqlx::query(
+            "SELECT table_name, columns, rows_json, keeps_rows FROM operation_snapshot_tables
+             WHERE snapshot_id = ? ORDER BY keeps_rows, id",
+        )
+        .bind(snapshot_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| self.db_error("SELECT", e))?;
+
+        let mut restored = 0i64;
+        for row in &tables {
+            let table: String = row.get("table_name");
+            let saved: Vec<String> =
+                serde_json::from_str(row.get::<&str, _>("columns")).unwrap_or_default();
+            let columns: Vec<(String, bool)> = table_columns(&mut tx, &table)
+                .await
+                .map_err(|e| self.db_error("SELECT", e))?
+                .into_iter()
+                .filter(|(name, _)| saved.contains(name))
+                .collect();
+            if columns.is_empty() {
+                self.logger.info(
+                    "SNAPSHOT",
+                    &format!("⚠️ Table {} no longer exists, skipping restore", table),
+                );
+                continue;
+            }
+
+            let keeps_rows: bool = row.get("keeps_rows");
+            let result = sqlx::query(&restore_sql(&table, &columns, keeps_rows))
+                .bind(row.get::<&str, _>("rows_json"))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    self.logger
+                        .database_operation("INSERT", &table, false, Some(&e.to_string()));
+                    match &e {
+                        sqlx::Error::Database(db) if db.is_unique_violation() => {
+                            AppError::ValidationError(format!(
+                                "数据表 {} 中已有新的数据使用了相同的主键，无法撤销",
+                                table
+                            ))
+                        }
+                        _ => AppError::DatabaseError(format!("恢复数据表 {} 失败: {}", table, e)),
+                    }
+                })?;
+            restored += result.rows_affected() as i64;
+        }
+
+        sqlx::query("UPDATE operation_snapshots SET undone_at = datetime('now') WHERE id = ?")
+            .bind(snapshot_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| self.db_error("UPDATE", e))?;
+
+        tx.commit().await.map_err(|e| self.db_error("COMMIT", e))?;
+
+        self.logger.database_operation(
+            "UPDATE",
+            "operation_snapshots",
+            true,
+            Some(&format!(
+                "Restored {} rows from snapshot {}",
+                restored, snapshot_id
+            )),
+        );
+
+        Ok(restored)
+    }
+
+    fn row_to_snapshot(row: &sqlx::sqlite::SqliteRow) -> Option<OperationSnapshot> {
+        let tables: Option<String> = row.get("tables");
+        Some(OperationSnapshot {
+            id: row.get("id"),
+            operation: SnapshotOperation::parse(row.get("operation"))?,
+            target_id: row.get("target_id"),
+            description: row.get("description"),
+            row_count: row.get("row_count"),
+            tables: tables
+                .map(|t| t.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+            created_at: row.get("created_at"),
+            undone_at: row.get("undone_at"),
+        })
+    }
+}
+
+/// `column IN (ids)` 条件（ID 都是整数，可以直接写入 SQL）
+fn id_condition(column: &str, ids: &[Id]) -> String {
+    if ids.is_empty() {
+        return "0 = 1".to_string();
+    }
+    format!(
+        "{} IN ({})",
+        quote_ident(column),
+        ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// 表的列名和是否为主键列（表不存在时为空）
+async fn table_columns(
+    conn: &mut SqliteConnection,
+    table: &str,
+) -> Result<Vec<(String, bool)>, sqlx::Error> {
+    let rows = sqlx::query("SELECT name, pk FROM pragma_table_info(?) ORDER BY cid")
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("name"), row.get::<i64, _>("pk") > 0))
+        .collect())
+}
+
+/// 展开快照范围：沿外键加入会被级联删除或置空的子表行，
+/// 同一张表中操作后是否仍然存在相同的行的条件合并，返回 (表, 行是否仍然存在, 条件)
+async fn expand_scopes(
+    conn: &mut SqliteConnection,
+    scopes: &[SnapshotScope],
+) -> Result<Vec<(String, bool, String)>, sqlx::Error> {
+    let existing: HashSet<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .collect();
+    let foreign_keys: Vec<ForeignKey> = sqlx::query(
+        r#"
+        SELECT m.name AS child, p."from" AS from_column, p."table" AS parent,
+               p."to" AS to_column, p.on_delete
+        FROM sqlite_master m
+        JOIN pragma_foreign_key_list(m.name) p
+        WHERE m.type = 'table'
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .iter()
+    .map(|row| ForeignKey {
+        child: row.get("child"),
+        from: row.get("from_column"),
+        parent: row.get("parent"),
+        to: row.get("to_column"),
+        on_delete: row.get("on_delete"),
+    })
+    .collect();
+
+    let mut pending: Vec<(SnapshotScope, usize)> =
+        scopes.iter().cloned().map(|scope| (scope, 0)).collect();
+    let mut tables: Vec<(String, bool, Vec<String>)> = Vec::new();
+    let mut i = 0;
+    while i < pending.len() {
+        let (scope, depth) = pending[i].clone();
+        i += 1;
+        if !existing.contains(&scope.table) || EXCLUDED_TABLES.contains(&scope.table.as_str()) {
+            continue;
+        }
+
+        match tables
+            .iter_mut()
+            .find(|(table, keeps_rows, _)| *table == scope.table && *keeps_rows == scope.keeps_rows)
+        {
+            Some((_, _, conditions)) if conditions.contains(&scope.condition) => continue,
+            Some((_, _, conditions)) => conditions.push(scope.condition.clone()),
+            None => tables.push((
+                scope.table.clone(),
+                scope.keeps_rows,
+                vec![scope.condition.clone()],
+            )),
+        }
+
+        if !scope.cascade || depth >= MAX_CASCADE_DEPTH {
+            continue;
+        }
+        for fk in foreign_keys.iter().filter(|fk| {
+            fk.parent.eq_ignore_ascii_case(&scope.table)
+                && matches!(
+                    fk.on_delete.as_str(),
+                    "CASCADE" | "SET NULL" | "SET DEFAULT"
+                )
+        }) {
+            let condition = format!(
+                "{} IN (SELECT {} FROM {} WHERE {})",
+                quote_ident(&fk.from),
+                fk.to
+                    .as_deref()
+                    .map(quote_ident)
+                    .unwrap_or("rowid".to_string()),
+                quote_ident(&scope.table),
+                scope.condition
+            );
+            pending.push((
+                SnapshotScope {
+                    table: fk.child.clone(),
+                    condition,
+                    cascade: true,
+                    // 外键置空的子表行在操作后仍然存在
+                    keeps_rows: scope.keeps_rows || fk.on_delete != "CASCADE",
+                },
+                depth + 1,
+            ));
+        }
+    }
+
+    Ok(tables
+        .into_iter()
+        .map(|(table, keeps_rows, conditions)| {
+            let condition = if conditions.iter().any(|c| c == ALL_ROWS) {
+                ALL_ROWS.to_string()
+            } else {
+                conditions
+                    .iter()
+                    .map(|c| format!("({})", c))
+                    .collect::<Vec<_>>()
+                    .join(" OR ")
+            };
+            (table, keeps_rows, condition)
+        })
+        .collect())
+}
+
+/// 从 JSON 数组写回行的语句：`overwrite` 为 true 且有主键时按主键覆盖已有的行，
+/// 否则为普通插入（主键冲突时报错）
+fn restore_sql(table: &str, columns: &[(String, bool)], overwrite: bool) -> String {
+    let names = columns
+        .iter()
+        .map(|(name, _)| quote_ident(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = columns
+        .iter()
+        .map(|(name, _)| {
+            format!(
+                "json_extract(value, {})",
+                quote_literal(&format!("$.\"{}\"", name))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!(
+        "INSERT INTO {} ({}) SELECT {} FROM json_each(?) WHERE true",
+        quote_ident(table),
+        names,
+        values
+    );
+
+    let keys: Vec<String> = columns
+        .iter()
+        .filter(|(_, pk)| *pk)
+        .map(|(name, _)| quote_ident(name))
+        .collect();
+    if overwrite && !keys.is_empty() {
+        let updates: Vec<String> = columns
+            .iter()
+            .filter(|(_, pk)| !*pk)
+            .map(|(name, _)| format!("{0} = excluded.{0}", quote_ident(name)))
+            .collect();
+        sql.push_str(&format!(" ON CONFLICT({}) DO ", keys.join(", ")));
+        if updates.is_empty() {
+            sql.push_str("NOTHING");
+        } else {
+            sql.push_str(&format!("UPDATE SET {}", updates.join(", ")));
+        }
+    }
+    sql
+}
@@ -0,0 +1,205 @@
+//! 自然拼读分析缓存数据访问层
+//!
+//! 按 (规范化单词, 模型ID, 提示词版本) 缓存 `PhonicsWord` 分析结果
+
+use crate::ai_service::PhonicsWord;
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::common::Id;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 单次查询的最大单词数（SQLite 参数数量有限制）
+const LOOKUP_CHUNK_SIZE: usize = 500;
+
+/// 规范化单词作为缓存键
+pub fn normalize_word(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+/// 自然拼读分析缓存仓储
+pub struct PhonicsCacheRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl PhonicsCacheRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    /// 批量查询缓存，返回 规范化单词 -> 分析结果，并记录命中
+    pub async fn find_many(
+        &self,
+        model_id: Id,
+        prompt_version: &str,
+        words: &[String],
+    ) -> AppResult<HashMap<String, PhonicsWord>> {
+        let mut keys: Vec<String> = words.iter().map(|w| normalize_word(w)).collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut cached = HashMap::new();
+        for chunk in keys.chunks(LOOKUP_CHUNK_SIZE) {
+            let placeholders: Vec<&str> = chunk.iter().map(|_| "?").collect();
+            let query = format!(
+                r#"
+                SELECT normalized_word, word, frequency, chinese_translation, pos_abbreviation,
+                       pos_english, pos_chinese, ipa, syllables, phonics_rule, analysis_explanation
+                FROM phonics_analysis_cache
+                WHERE model_id = ? AND prompt_version = ? AND normalized_word IN ({})
+                "#,
+                placeholders.join(",")
+            );
+
+            let mut query_builder = sqlx::query(&query).bind(model_id).bind(prompt_version);
+            for key in chunk {
+                query_builder = query_builder.bind(key);
+            }
+
+            let rows = query_builder
+                .fetch_all(self.pool.as_ref())
+                .await
+                .map_err(|e| {
+                    self.logger.database_operation(
+                        "SELECT",
+                        "phonics_analysis_cache",
+                        false,
+                        Some(&e.to_string()),
+                    );
+                    AppError::DatabaseError(e.to_string())
+                })?;
+
+            for row in rows {
+                cached.insert(row.get("normalized_word"), Self::row_to_word(&row));
+            }
+        }
+
+        if !cached.is_empty() {
+            self.record_hits(model_id, prompt_version, cached.keys()).await?;
+        }
+
+        Ok(cached)
+    }
+
+    /// 更新命中次数和最近使用时间
+    async fn record_hits<'a>(
+        &self,
+        model_id: Id,
+        prompt_version: &str,
+        keys: impl Iterator<Item = &'a String>,
+    ) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            self.logger
+                .database_operation("BEGIN", "transaction", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        for key in keys {
+            sqlx::query(
+                r#"
+                UPDATE phonics_analysis_cache
+                SET hit_count = hit_count + 1, last_used_at = datetime('now')
+                WHERE normalized_word = ? AND model_id = ? AND prompt_version = ?
+                "#,
+            )
+            .bind(key)
+            .bind(model_id)
+            .bind(prompt_version)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 写入分析结果（单词, 结果），已存在的缓存会被覆盖
+    pub async fn save_many(
+        &self,
+        model_id: Id,
+        prompt_version: &str,
+        results: &[(String, PhonicsWord)],
+    ) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            self.logger
+                .database_operation("BEGIN", "transaction", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        for (word, result) in results {
+            sqlx::query(
+                r#"
+                INSERT INTO phonics_analysis_cache (
+                    normalized_word, model_id, prompt_version, word, frequency, chinese_translation,
+                    pos_abbreviation, pos_english, pos_chinese, ipa, syllables, phonics_rule,
+                    analysis_explanation
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(normalized_word, model_id, prompt_version) DO UPDATE SET
+                    word = excluded.word,
+                    frequency = excluded.frequency,
+                    chinese_translation = excluded.chinese_translation,
+                    pos_abbreviation = excluded.pos_abbreviation,
+                    pos_english = excluded.pos_english,
+                    pos_chinese = excluded.pos_chinese,
+                    ipa = excluded.ipa,
+                    syllables = excluded.syllables,
+                    phonics_rule = excluded.phonics_rule,
+                    analysis_explanation = excluded.analysis_explanation,
+                    last_used_at = datetime('now')
+                "#,
+            )
+            .bind(normalize_word(word))
+            .bind(model_id)
+            .bind(prompt_version)
+            .bind(&result.word)
+            .bind(result.frequency)
+            .bind(&result.chinese_translation)
+            .bind(&result.pos_abbreviation)
+            .bind(&result.pos_english)
+            .bind(&result.pos_chinese)
+            .bind(&result.ipa)
+            .bind(&result.syllables)
+            .bind(&result.phonics_rule)
+            .bind(&result.analysis_explanation)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            self.logger.database_operation(
+                "COMMIT",
+                "phonics_analysis_cache",
+                false,
+                Some(&e.to_string()),
+            );
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        self.logger.database_operation(
+            "INSERT",
+            "phonics_analysis_cache",
+            true,
+            Some(&format!("Cached {} phonics results for model {}", results.len(), model_id)),
+        );
+
+        Ok(())
+    }
+
+    fn row_to_word(row: &sqlx::sqlite::SqliteRow) -> PhonicsWord {
+        PhonicsWord {
+            word: row.get("word"),
+            frequency: row.get("frequency"),
+            chinese_translation: row.get("chinese_translation"),
+            pos_abbreviation: row.get("pos_abbreviation"),
+            pos_english: row.get("pos_english"),
+            pos_chinese: row.get("pos_chinese"),
+            ipa: row.get("ipa"),
+            syllables: row.get("syllables"),
+            phonics_rule: row.get("phonics_rule"),
+            analysis_explanation: row.get("analysis_explanation"),
+        }
+    }
+}
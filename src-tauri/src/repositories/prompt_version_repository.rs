@@ -0,0 +1,114 @@
+//! 提示词版本历史数据访问层
+//!
+//! 按 (提示词名称, 版本号) 保存模板内容，同一版本只保存一次
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::prompt::{PromptName, PromptSource, PromptTemplate, PromptVersion};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+
+/// 提示词版本仓储
+pub struct PromptVersionRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl PromptVersionRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    fn db_error(&self, operation: &str, e: sqlx::Error) -> AppError {
+        self.logger
+            .database_operation(operation, "prompt_versions", false, Some(&e.to_string()));
+        AppError::DatabaseError(e.to_string())
+    }
+
+    /// 记录模板版本，已存在时忽略，返回是否为新版本
+    pub async fn record(&self, template: &PromptTemplate) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO prompt_versions (prompt_name, version, content, source)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(template.name.as_str())
+        .bind(&template.version)
+        .bind(&template.content)
+        .bind(template.source.as_str())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("INSERT", e))?;
+
+        let inserted = result.rows_affected() > 0;
+        if inserted {
+            self.logger.database_operation(
+                "INSERT",
+                "prompt_versions",
+                true,
+                Some(&format!(
+                    "Recorded {} prompt version {}",
+                    template.name.as_str(),
+                    template.version
+                )),
+            );
+        }
+
+        Ok(inserted)
+    }
+
+    /// 查询提示词的历史版本（最新的在前）
+    pub async fn find_by_name(&self, name: PromptName) -> AppResult<Vec<PromptVersion>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT version, content, source, created_at
+            FROM prompt_versions
+            WHERE prompt_name = ?
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(name.as_str())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", e))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| Self::row_to_version(name, row))
+            .collect())
+    }
+
+    /// 查询指定版本
+    pub async fn find_version(
+        &self,
+        name: PromptName,
+        version: &str,
+    ) -> AppResult<Option<PromptVersion>> {
+        let row = sqlx::query(
+            r#"
+            SELECT version, content, source, created_at
+            FROM prompt_versions
+            WHERE prompt_name = ? AND version = ?
+            "#,
+        )
+        .bind(name.as_str())
+        .bind(version)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", e))?;
+
+        Ok(row.as_ref().map(|row| Self::row_to_version(name, row)))
+    }
+
+    fn row_to_version(name: PromptName, row: &sqlx::sqlite::SqliteRow) -> PromptVersion {
+        PromptVersion {
+            name,
+            version: row.get("version"),
+            content: row.get("content"),
+            source: PromptSource::from_db(row.get("source")),
+            created_at: row.get("created_at"),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! 发音词典数据访问层
+//!
+//! 按 (单词, 读音序号) 保存 ARPABET 读音，重复导入时覆盖已有读音
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::pronunciation::PronunciationEntry;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 发音词典仓储
+pub struct PronunciationRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl PronunciationRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    fn db_error(&self, operation: &str, e: sqlx::Error) -> AppError {
+        self.logger.database_operation(
+            operation,
+            "pronunciation_dictionary",
+            false,
+            Some(&e.to_string()),
+        );
+        AppError::DatabaseError(e.to_string())
+    }
+
+    /// 在一个事务中导入读音，返回写入的条目数
+    pub async fn import(&self, entries: &[PronunciationEntry]) -> AppResult<usize> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| self.db_error("BEGIN", e))?;
+
+        for entry in entries {
+            sqlx::query(
+                r#"
+                INSERT INTO pronunciation_dictionary (word, variant, arpabet)
+                VALUES (?, ?, ?)
+                ON CONFLICT(word, variant) DO UPDATE SET arpabet = excluded.arpabet
+                "#,
+            )
+            .bind(&entry.word)
+            .bind(entry.variant)
+            .bind(&entry.arpabet)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| self.db_error("INSERT", e))?;
+        }
+
+        tx.commit().await.map_err(|e| self.db_error("COMMIT", e))?;
+
+        self.logger.database_operation(
+            "INSERT",
+            "pronunciation_dictionary",
+            true,
+            Some(&format!("Imported {} pronunciations", entries.len())),
+        );
+
+        Ok(entries.len())
+    }
+
+    /// 查询单词的全部读音（按读音序号排序）
+    pub async fn find_by_word(&self, word: &str) -> AppResult<Vec<PronunciationEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT word, variant, arpabet
+            FROM pronunciation_dictionary
+            WHERE word = ?
+            ORDER BY variant
+            "#,
+        )
+        .bind(word.trim().to_lowercase())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| self.db_error("SELECT", e))?;
+
+        Ok(rows.iter().map(Self::row_to_entry).collect())
+    }
+
+    /// 批量查询读音，返回 小写单词 -> 读音列表
+    pub async fn find_by_words(
+        &self,
+        words: &[String],
+    ) -> AppResult<HashMap<String, Vec<PronunciationEntry>>> {
+        let mut result: HashMap<String, Vec<PronunciationEntry>> = HashMap::new();
+        // SQLite 绑定参数数量有限，分批查询
+        for chunk in words.chunks(500) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "SELECT word, variant, arpabet FROM pronunciation_dictionary WHERE word IN ({}) ORDER BY word, variant",
+                placeholders
+            );
+            let mut query = sqlx::query(&sql);
+            for word in chunk {
+                query = query.bind(word.trim().to_lowercase());
+            }
+            let rows = query
+                .fetch_all(self.pool.as_ref())
+                .await
+                .map_err(|e| self.db_error("SELECT", e))?;
+
+            for row in &rows {
+                let entry = Self::row_to_entry(row);
+                result.entry(entry.word.clone()).or_default().push(entry);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 词典中的条目总数
+    pub async fn count(&self) -> AppResult<i64> {
+        sqlx::query("SELECT COUNT(*) AS count FROM pronunciation_dictionary")
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map(|row| row.get("count"))
+            .map_err(|e| self.db_error("SELECT", e))
+    }
+
+    fn row_to_entry(row: &sqlx::sqlite::SqliteRow) -> PronunciationEntry {
+        PronunciationEntry {
+            word: row.get("word"),
+            variant: row.get("variant"),
+            arpabet: row.get("arpabet"),
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! 单词记忆状态数据访问层
+//!
+//! 提供间隔重复状态的持久化以及复习日程的重新安排
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::{common::Id, study::*};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+
+/// 单词记忆状态仓储
+pub struct ReviewStateRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl ReviewStateRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    /// 查询学习计划使用的复习算法
+    pub async fn find_algorithm(&self, plan_id: Id) -> AppResult<ReviewAlgorithm> {
+        let row = sqlx::query("SELECT review_algorithm FROM study_plans WHERE id = ?")
+            .bind(plan_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "study_plans", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        match row {
+            Some(row) => {
+                let algorithm: String = row.get("review_algorithm");
+                Ok(ReviewAlgorithm::from_db(&algorithm))
+            }
+            None => Err(AppError::NotFound(format!("学习计划 {} 不存在", plan_id))),
+        }
+    }
+
+    /// 更新学习计划使用的复习算法
+    pub async fn update_algorithm(&self, plan_id: Id, algorithm: ReviewAlgorithm) -> AppResult<()> {
+        let result = sqlx::query("UPDATE study_plans SET review_algorithm = ? WHERE id = ?")
+            .bind(algorithm.as_str())
+            .bind(plan_id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("UPDATE", "study_plans", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("学习计划 {} 不存在", plan_id)));
+        }
+
+        self.logger.database_operation(
+            "UPDATE",
+            "study_plans",
+            true,
+            Some(&format!("Set review algorithm of plan {} to {}", plan_id, algorithm.as_str())),
+        );
+
+        Ok(())
+    }
+
+    /// 查询学习计划最后一天的日程日期
+    pub async fn find_last_schedule_date(&self, plan_id: Id) -> AppResult<Option<String>> {
+        let row = sqlx::query(
+            "SELECT MAX(schedule_date) as last_date FROM study_plan_schedules WHERE plan_id = ?",
+        )
+        .bind(plan_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("SELECT", "study_plan_schedules", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(row.get("last_date"))
+    }
+
+    /// 查询学习计划下所有单词的记忆状态
+    pub async fn find_by_plan(&self, plan_id: Id) -> AppResult<Vec<WordReviewState>> {
+        let query = r#"
+            SELECT plan_id, word_id, ease_factor, interval_days, repetitions,
+                   stability, difficulty, lapses, last_grade, last_reviewed_at, due_date
+            FROM word_review_states
+            WHERE plan_id = ?
+            ORDER BY due_date, word_id
+        "#;
+
+        let rows = sqlx::query(query)
+            .bind(plan_id)
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "word_review_states", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(rows.iter().map(Self::row_to_state).collect())
+    }
+
+    /// 查询单个单词的记忆状态
+    pub async fn find_state(&self, plan_id: Id, word_id: Id) -> AppResult<Option<WordReviewState>> {
+        let query = r#"
+            SELECT plan_id, word_id, ease_factor, interval_days, repetitions,
+                   stability, difficulty, lapses, last_grade, last_reviewed_at, due_date
+            FROM word_review_states
+            WHERE plan_id = ? AND word_id = ?
+        "#;
+
+        let row = sqlx::query(query)
+            .bind(plan_id)
+            .bind(word_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "word_review_states", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(row.as_ref().map(Self::row_to_state))
+    }
+
+    /// 保存记忆状态并重新安排后续复习
+    ///
+    /// 在同一事务中:
+    /// 1. 写入记忆状态
+    /// 2. 同步 study_plan_words 的学习进度
+    /// 3. 删除 `after_date` 之后未开始日程中该单词的复习安排
+    /// 4. 按 `review_dates` 放入对应（或之后最近的）未开始日程，
+    ///    多个复习日落到同一日程时，后面的复习顺延到下一个日程
+    /// 5. 重新统计受影响日程的单词数
+    pub async fn save_state_and_replan(
+        &self,
+        state: &WordReviewState,
+        passed: bool,
+        mastery_score: f64,
+        after_date: &str,
+        review_dates: &[String],
+    ) -> AppResult<usize> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            self.logger
+                .database_operation("BEGIN", "transaction", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        // 1. 写入记忆状态
+        sqlx::query(
+            r#"
+            INSERT INTO word_review_states (
+                plan_id, word_id, ease_factor, interval_days, repetitions,
+                stability, difficulty, lapses, last_grade, last_reviewed_at, due_date,
+                created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+            ON CONFLICT(plan_id, word_id) DO UPDATE SET
+                ease_factor = excluded.ease_factor,
+                interval_days = excluded.interval_days,
+                repetitions = excluded.repetitions,
+                stability = excluded.stability,
+                difficulty = excluded.difficulty,
+                lapses = excluded.lapses,
+                last_grade = excluded.last_grade,
+                last_reviewed_at = excluded.last_reviewed_at,
+                due_date = excluded.due_date,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(state.plan_id)
+        .bind(state.word_id)
+        .bind(state.ease_factor)
+        .bind(state.interval_days)
+        .bind(state.repetitions)
+        .bind(state.stability)
+        .bind(state.difficulty)
+        .bind(state.lapses)
+        .bind(state.last_grade)
+        .bind(&state.last_reviewed_at)
+        .bind(&state.due_date)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("UPSERT", "word_review_states", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        // 2. 同步学习进度
+        sqlx::query(
+            r#"
+            UPDATE study_plan_words SET
+                learned = CASE WHEN ? THEN 1 ELSE learned END,
+                correct_count = correct_count + CASE WHEN ? THEN 1 ELSE 0 END,
+                total_attempts = total_attempts + 1,
+                last_studied = datetime('now'),
+                next_review = ?,
+                mastery_score = ?
+            WHERE plan_id = ? AND word_id = ?
+            "#,
+        )
+        .bind(passed)
+        .bind(passed)
+        .bind(&state.due_date)
+        .bind(mastery_score)
+        .bind(state.plan_id)
+        .bind(state.word_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("UPDATE", "study_plan_words", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        // 3. 删除后续未开始日程中的复习安排
+        sqlx::query(
+            r#"
+            DELETE FROM study_plan_schedule_words
+            WHERE word_id = ? AND is_review = 1
+            AND schedule_id IN (
+                SELECT id FROM study_plan_schedules
+                WHERE plan_id = ? AND schedule_date > ? AND status = 'not-started'
+            )
+            "#,
+        )
+        .bind(state.word_id)
+        .bind(state.plan_id)
+        .bind(after_date)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            self.logger.database_operation(
+                "DELETE",
+                "study_plan_schedule_words",
+                false,
+                Some(&e.to_string()),
+            );
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        // 4. 重新放入复习安排（同一天最多复习一次）
+        let mut placed = 0usize;
+        let mut last_placed_date = after_date.to_string();
+        for (index, review_date) in review_dates.iter().enumerate() {
+            let schedule_row = sqlx::query(
+                r#"
+                SELECT id, schedule_date FROM study_plan_schedules
+                WHERE plan_id = ? AND schedule_date >= ? AND schedule_date > ?
+                AND status = 'not-started'
+                ORDER BY schedule_date
+                LIMIT 1
+                "#,
+            )
+            .bind(state.plan_id)
+            .bind(review_date)
+            .bind(&last_placed_date)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "study_plan_schedules", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+            let schedule_id: Id = match schedule_row {
+                Some(row) => {
+                    last_placed_date = row.get("schedule_date");
+                    row.get("id")
+                }
+                // 超出计划范围，只保留在记忆状态中
+                None => break,
+            };
+
+            let result = sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO study_plan_schedule_words (
+                    schedule_id, word_id, wordbook_id, is_review, review_count,
+                    priority, difficulty_level, created_at
+                )
+                SELECT ?, w.id, w.word_book_id, 1, ?, ?, ?, datetime('now')
+                FROM words w
+                WHERE w.id = ? AND w.word_book_id IS NOT NULL
+                "#,
+            )
+            .bind(schedule_id)
+            .bind(state.repetitions + index as i32 + 1)
+            .bind(if state.lapses > 0 { "high" } else { "medium" })
+            .bind(if state.lapses > 0 { 3 } else { 2 })
+            .bind(state.word_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                self.logger.database_operation(
+                    "INSERT",
+                    "study_plan_schedule_words",
+                    false,
+                    Some(&e.to_string()),
+                );
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+            placed += result.rows_affected() as usize;
+        }
+
+        // 5. 重新统计未开始日程的单词数
+        sqlx::query(
+            r#"
+            UPDATE study_plan_schedules SET
+                new_words_count = (
+                    SELECT COUNT(*) FROM study_plan_schedule_words
+                    WHERE schedule_id = study_plan_schedules.id AND is_review = 0
+                ),
+                review_words_count = (
+                    SELECT COUNT(*) FROM study_plan_schedule_words
+                    WHERE schedule_id = study_plan_schedules.id AND is_review = 1
+                ),
+                total_words_count = (
+                    SELECT COUNT(*) FROM study_plan_schedule_words
+                    WHERE schedule_id = study_plan_schedules.id
+                ),
+                updated_at = datetime('now')
+            WHERE plan_id = ? AND schedule_date > ? AND status = 'not-started'
+            "#,
+        )
+        .bind(state.plan_id)
+        .bind(after_date)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("UPDATE", "study_plan_schedules", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        tx.commit().await.map_err(|e| {
+            self.logger
+                .database_operation("COMMIT", "transaction", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        self.logger.database_operation(
+            "UPSERT",
+            "word_review_states",
+            true,
+            Some(&format!(
+                "Word {} in plan {}: due {:?}, {} review(s) replanned",
+                state.word_id, state.plan_id, state.due_date, placed
+            )),
+        );
+
+        Ok(placed)
+    }
+
+    /// 将数据库行转换为记忆状态
+    fn row_to_state(row: &sqlx::sqlite::SqliteRow) -> WordReviewState {
+        WordReviewState {
+            plan_id: row.get("plan_id"),
+            word_id: row.get("word_id"),
+            ease_factor: row.get("ease_factor"),
+            interval_days: row.get("interval_days"),
+            repetitions: row.get("repetitions"),
+            stability: row.get("stability"),
+            difficulty: row.get("difficulty"),
+            lapses: row.get("lapses"),
+            last_grade: row.get("last_grade"),
+            last_reviewed_at: row.get("last_reviewed_at"),
+            due_date: row.get("due_date"),
+        }
+    }
+}
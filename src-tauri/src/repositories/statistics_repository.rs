@@ -10,6 +10,18 @@ use crate::types::*;
 use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
 
+/// 重置用户数据时清空的表（按删除顺序）
+pub const USER_DATA_TABLES: [&str; 8] = [
+    "word_practice_records",
+    "practice_sessions",
+    "word_review_states",
+    "study_plan_words",
+    "study_plan_schedules",
+    "study_plans",
+    "words",
+    "word_books",
+];
+
 /// 统计仓储
 ///
 /// 负责统计数据的数据访问逻辑,封装所有数据库操作
@@ -200,7 +212,7 @@ impl StatisticsRepository {
     pub async fn get_database_statistics(&self) -> AppResult<DatabaseOverview> {
         use crate::handlers::shared::classify_table_type;
 
-        let all_tables_query = "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations' ORDER BY name";
+        let all_tables_query = "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations' AND name NOT LIKE 'words_fts%' ORDER BY name";
         let table_rows = sqlx::query(all_tables_query)
             .fetch_all(self.pool.as_ref())
             .await
@@ -265,15 +277,7 @@ impl StatisticsRepository {
 
     /// 重置用户数据
     pub async fn reset_user_data(&self) -> AppResult<ResetResult> {
-        let user_data_tables = vec![
-            "word_practice_records",
-            "practice_sessions",
-            "study_plan_words",
-            "study_plan_schedules",
-            "study_plans",
-            "words",
-            "word_books",
-        ];
+        let user_data_tables = USER_DATA_TABLES;
 
         let mut deleted_records = 0i64;
         let mut affected_tables = Vec::new();
@@ -668,7 +672,10 @@ impl StatisticsRepository {
                     SELECT COUNT(*) as overdue_count
                     FROM study_plan_schedules sps
                     WHERE sps.plan_id = ?
-                    AND sps.schedule_date < date('now')
+                    AND sps.schedule_date < COALESCE(
+                        (SELECT date(paused_at, 'localtime') FROM study_plans WHERE id = sps.plan_id),
+                        date('now')
+                    )
                     AND (sps.completed_words_count IS NULL OR sps.completed_words_count < sps.total_words_count)
                 "#;
 
@@ -343,28 +343,12 @@ impl StudyPlanRepository {
         Ok(id)
     }
 
-    /// 更新学习计划
+    /// 更新学习计划（状态字段只能通过 `transition` 修改，这里不写入）
     pub async fn update(&self, id: Id, plan: &StudyPlan) -> AppResult<()> {
-        let unified_status_str = plan
-            .unified_status
-            .as_ref()
-            .map(|s| match s {
-                UnifiedStudyPlanStatus::Draft => "Draft",
-                UnifiedStudyPlanStatus::Pending => "Pending",
-                UnifiedStudyPlanStatus::Active => "Active",
-                UnifiedStudyPlanStatus::Paused => "Paused",
-                UnifiedStudyPlanStatus::Completed => "Completed",
-                UnifiedStudyPlanStatus::Terminated => "Terminated",
-                UnifiedStudyPlanStatus::Deleted => "Deleted",
-            })
-            .unwrap_or("Draft");
-
         let query = r#"
             UPDATE study_plans SET
                 name = ?,
                 description = ?,
-                status = ?,
-                unified_status = ?,
                 total_words = ?,
                 mastery_level = ?,
                 intensity_level = ?,
@@ -383,8 +367,6 @@ impl StudyPlanRepository {
         let result = sqlx::query(query)
             .bind(&plan.name)
             .bind(&plan.description)
-            .bind(&plan.status)
-            .bind(unified_status_str)
             .bind(plan.total_words)
             .bind(plan.mastery_level)
             .bind(plan.intensity_level.as_ref().map(|s| s.as_str()))
@@ -419,91 +401,6 @@ impl StudyPlanRepository {
         Ok(())
     }
 
-    /// 更新学习计划状态
-    pub async fn update_status(
-        &self,
-        id: Id,
-        unified_status: &str,
-        set_actual_start_date: bool,
-        set_actual_end_date: bool,
-        set_actual_terminated_date: bool,
-    ) -> AppResult<()> {
-        let mut query = String::from(
-            "UPDATE study_plans SET unified_status = ?, updated_at = datetime('now')",
-        );
-
-        if set_actual_start_date {
-            query.push_str(", actual_start_date = datetime('now')");
-        }
-        if set_actual_end_date {
-            query.push_str(", actual_end_date = datetime('now')");
-        }
-        if set_actual_terminated_date {
-            query.push_str(", actual_terminated_date = datetime('now')");
-        }
-
-        query.push_str(" WHERE id = ?");
-
-        let result = sqlx::query(&query)
-            .bind(unified_status)
-            .bind(id)
-            .execute(self.pool.as_ref())
-            .await
-            .map_err(|e| {
-                self.logger
-                    .database_operation("UPDATE", "study_plans", false, Some(&e.to_string()));
-                AppError::DatabaseError(e.to_string())
-            })?;
-
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound(format!("学习计划 {} 不存在", id)));
-        }
-
-        self.logger.database_operation(
-            "UPDATE",
-            "study_plans",
-            true,
-            Some(&format!("Updated study plan {} status to {}", id, unified_status)),
-        );
-
-        Ok(())
-    }
-
-    /// 软删除学习计划
-    pub async fn delete(&self, id: Id) -> AppResult<()> {
-        let query = r#"
-            UPDATE study_plans
-            SET status = 'deleted',
-                unified_status = 'Deleted',
-                deleted_at = datetime('now'),
-                updated_at = datetime('now')
-            WHERE id = ?
-        "#;
-
-        let result = sqlx::query(query)
-            .bind(id)
-            .execute(self.pool.as_ref())
-            .await
-            .map_err(|e| {
-                self.logger
-                    .database_operation("UPDATE", "study_plans", false, Some(&e.to_string()));
-                AppError::DatabaseError(e.to_string())
-            })?;
-
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound(format!("学习计划 {} 不存在", id)));
-        }
-
-        self.logger.database_operation(
-            "UPDATE",
-            "study_plans",
-            true,
-            Some(&format!("Deleted study plan {}", id)),
-        );
-
-        Ok(())
-    }
-
     /// 添加状态变更历史
     pub async fn add_status_history(
         &self,
@@ -597,274 +494,88 @@ impl StudyPlanRepository {
         Ok(history)
     }
 
-    /// 重置学习计划（清空所有相关数据）
-    pub async fn reset_plan_data(&self, plan_id: Id) -> AppResult<()> {
-        // 使用事务
-        let mut tx = self.pool.begin().await.map_err(|e| {
-            self.logger
-                .database_operation("BEGIN", "transaction", false, Some(&e.to_string()));
-            AppError::DatabaseError(e.to_string())
-        })?;
-
-        // 清空学习日程
-        sqlx::query("DELETE FROM study_plan_schedules WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 清空学习记录
-        sqlx::query("DELETE FROM study_sessions WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 清空练习会话记录
-        sqlx::query("DELETE FROM practice_sessions WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 清空学习计时器记录
-        sqlx::query("DELETE FROM study_timer_records WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 重置学习进度
-        sqlx::query("UPDATE study_plan_words SET learned = 0 WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        tx.commit().await.map_err(|e| {
-            self.logger
-                .database_operation("COMMIT", "transaction", false, Some(&e.to_string()));
-            AppError::DatabaseError(e.to_string())
-        })?;
-
-        self.logger.database_operation(
-            "DELETE/UPDATE",
-            "plan_data",
-            true,
-            Some(&format!("Reset all data for plan {}", plan_id)),
-        );
-
-        Ok(())
-    }
-
-    /// 重置学习计划进度（保留单词关联）
-    pub async fn reset_plan_progress(&self, plan_id: Id) -> AppResult<()> {
-        // 使用事务
-        let mut tx = self.pool.begin().await.map_err(|e| {
-            self.logger
-                .database_operation("BEGIN", "transaction", false, Some(&e.to_string()));
-            AppError::DatabaseError(e.to_string())
-        })?;
-
-        // 清空学习日程
-        sqlx::query("DELETE FROM study_plan_schedules WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 清空学习记录
-        sqlx::query("DELETE FROM study_sessions WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 清空练习会话记录
-        sqlx::query("DELETE FROM practice_sessions WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 清空学习计时器记录
-        sqlx::query("DELETE FROM study_timer_records WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // 重置学习进度字段（保留单词关联）
-        sqlx::query("UPDATE study_plan_words SET learned = 0, correct_count = 0, total_attempts = 0, mastery_score = 0.0 WHERE plan_id = ?")
-            .bind(plan_id)
-            .execute(&mut *tx)
-            .await?;
-
-        tx.commit().await.map_err(|e| {
-            self.logger
-                .database_operation("COMMIT", "transaction", false, Some(&e.to_string()));
-            AppError::DatabaseError(e.to_string())
-        })?;
-
-        self.logger.database_operation(
-            "UPDATE",
-            "plan_progress",
-            true,
-            Some(&format!("Reset progress for plan {}", plan_id)),
-        );
-
-        Ok(())
-    }
-
-    /// 更新状态并清空实际日期
-    pub async fn update_status_and_clear_dates(
+    /// 部分更新学习计划
+    ///
+    /// 状态变更按状态转换表执行，与字段更新在同一事务中完成
+    pub async fn partial_update(
         &self,
         id: Id,
-        unified_status: &str,
+        status: Option<UnifiedStudyPlanStatus>,
+        name: Option<&str>,
+        description: Option<&str>,
+        intensity_level: Option<&str>,
+        review_frequency: Option<i32>,
     ) -> AppResult<()> {
-        let query = r#"
-            UPDATE study_plans
-            SET unified_status = ?,
-                actual_start_date = NULL,
-                actual_end_date = NULL,
-                actual_terminated_date = NULL,
-                updated_at = datetime('now')
-            WHERE id = ?
-        "#;
+        let db_error = |operation: &str, table: &str, e: sqlx::Error| {
+            self.logger
+                .database_operation(operation, table, false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        };
 
-        let result = sqlx::query(query)
-            .bind(unified_status)
-            .bind(id)
-            .execute(self.pool.as_ref())
-            .await
-            .map_err(|e| {
-                self.logger
-                    .database_operation("UPDATE", "study_plans", false, Some(&e.to_string()));
-                AppError::DatabaseError(e.to_string())
-            })?;
+        let mut tx = self.begin_transaction().await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound(format!("学习计划 {} 不存在", id)));
+        let current = sqlx::query_scalar::<_, Option<UnifiedStudyPlanStatus>>(
+            "SELECT unified_status FROM study_plans WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| db_error("SELECT", "study_plans", e))?
+        .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", id)))?
+        .unwrap_or(UnifiedStudyPlanStatus::Draft);
+
+        if let Some(target) = status.filter(|target| *target != current) {
+            let transition = StudyPlanTransition::between(current, target).ok_or_else(|| {
+                AppError::InvalidStatusTransition(format!(
+                    "学习计划当前为「{}」状态，不能转为「{}」",
+                    current.label(),
+                    target.label()
+                ))
+            })?;
+            self.transition_in_tx(&mut tx, id, transition, "用户更新学习计划状态")
+                .await?;
         }
 
-        self.logger.database_operation(
-            "UPDATE",
-            "study_plans",
-            true,
-            Some(&format!("Updated study plan {} status to {} and cleared dates", id, unified_status)),
-        );
-
-        Ok(())
-    }
-
-    /// 更新状态（发布）
-    pub async fn update_status_for_publish(&self, id: Id) -> AppResult<()> {
-        let query = r#"
-            UPDATE study_plans
-            SET status = 'normal',
-                unified_status = 'Pending',
-                updated_at = datetime('now')
-            WHERE id = ?
-        "#;
-
-        let result = sqlx::query(query)
+        if name.is_some()
+            || description.is_some()
+            || intensity_level.is_some()
+            || review_frequency.is_some()
+        {
+            sqlx::query(
+                r#"
+                UPDATE study_plans SET
+                    name = COALESCE(?, name),
+                    description = COALESCE(?, description),
+                    intensity_level = COALESCE(?, intensity_level),
+                    review_frequency = COALESCE(?, review_frequency),
+                    updated_at = datetime('now')
+                WHERE id = ?
+                "#,
+            )
+            .bind(name)
+            .bind(description)
+            .bind(intensity_level)
+            .bind(review_frequency)
             .bind(id)
-            .execute(self.pool.as_ref())
+            .execute(&mut *tx)
             .await
-            .map_err(|e| {
-                self.logger
-                    .database_operation("UPDATE", "study_plans", false, Some(&e.to_string()));
-                AppError::DatabaseError(e.to_string())
-            })?;
-
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound(format!("学习计划 {} 不存在", id)));
+            .map_err(|e| db_error("UPDATE", "study_plans", e))?;
         }
 
+        tx.commit()
+            .await
+            .map_err(|e| db_error("COMMIT", "transaction", e))?;
+
         self.logger.database_operation(
             "UPDATE",
             "study_plans",
             true,
-            Some(&format!("Published study plan {}", id)),
+            Some(&format!("Partially updated study plan {}", id)),
         );
 
         Ok(())
     }
 
-    /// 部分更新学习计划（动态字段）
-    pub async fn partial_update(
-        &self,
-        id: Id,
-        name: Option<&str>,
-        description: Option<&str>,
-        status: Option<&str>,
-        unified_status: Option<&str>,
-        intensity_level: Option<&str>,
-        review_frequency: Option<i32>,
-    ) -> AppResult<()> {
-        // 如果只更新状态，使用简化方法
-        if status.is_some() && name.is_none() && description.is_none() && intensity_level.is_none() && review_frequency.is_none() {
-            let unified_status_str = unified_status.unwrap_or_else(|| {
-                match status.unwrap() {
-                    "draft" => "Draft",
-                    "normal" => "Pending",
-                    _ => "Draft",
-                }
-            });
-            
-            let query = "UPDATE study_plans SET status = ?, unified_status = ?, updated_at = datetime('now') WHERE id = ?";
-            let result = sqlx::query(query)
-                .bind(status.unwrap())
-                .bind(unified_status_str)
-                .bind(id)
-                .execute(self.pool.as_ref())
-                .await
-                .map_err(|e| {
-                    self.logger.database_operation("UPDATE", "study_plans", false, Some(&e.to_string()));
-                    AppError::DatabaseError(e.to_string())
-                })?;
-
-            if result.rows_affected() == 0 {
-                return Err(AppError::NotFound(format!("学习计划 {} 不存在", id)));
-            }
-
-            self.logger.database_operation(
-                "UPDATE",
-                "study_plans",
-                true,
-                Some(&format!("Updated study plan {} status", id)),
-            );
-
-            return Ok(());
-        }
-
-        // 对于其他字段，需要先获取现有数据，然后更新
-        let existing = self.find_by_id(id).await?
-            .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", id)))?;
-
-        let mut updated = existing.clone();
-        if let Some(name) = name {
-            updated.name = name.to_string();
-        }
-        if let Some(description) = description {
-            updated.description = description.to_string();
-        }
-        if let Some(status) = status {
-            updated.status = status.to_string();
-        }
-        if let Some(unified_status) = unified_status {
-            updated.unified_status = match unified_status {
-                "Draft" => Some(UnifiedStudyPlanStatus::Draft),
-                "Pending" => Some(UnifiedStudyPlanStatus::Pending),
-                "Active" => Some(UnifiedStudyPlanStatus::Active),
-                "Paused" => Some(UnifiedStudyPlanStatus::Paused),
-                "Completed" => Some(UnifiedStudyPlanStatus::Completed),
-                "Terminated" => Some(UnifiedStudyPlanStatus::Terminated),
-                "Deleted" => Some(UnifiedStudyPlanStatus::Deleted),
-                _ => None,
-            };
-        }
-        if let Some(intensity_level) = intensity_level {
-            updated.intensity_level = Some(intensity_level.to_string());
-        }
-        if let Some(review_frequency) = review_frequency {
-            updated.review_frequency = Some(review_frequency);
-        }
-
-        self.update(id, &updated).await
-    }
-
     /// 查询学习计划的单词
     pub async fn find_plan_words(&self, plan_id: Id) -> AppResult<Vec<StudyPlanWord>> {
         let query = r#"
@@ -1263,6 +974,300 @@ impl StudyPlanRepository {
         Ok(())
     }
 
+    // ==================== 状态转换 ====================
+
+    /// 执行状态转换（独立事务），返回（原状态, 新状态）
+    pub async fn transition(
+        &self,
+        id: Id,
+        transition: StudyPlanTransition,
+        reason: &str,
+    ) -> AppResult<(UnifiedStudyPlanStatus, UnifiedStudyPlanStatus)> {
+        let mut tx = self.begin_transaction().await?;
+        let change = self.transition_in_tx(&mut tx, id, transition, reason).await?;
+        tx.commit().await.map_err(|e| {
+            self.logger
+                .database_operation("COMMIT", "transaction", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+        Ok(change)
+    }
+
+    /// 在事务中执行状态转换
+    ///
+    /// 按状态转换表校验当前状态，同时更新统一状态、管理状态和相关日期，
+    /// 重新学习和编辑会清空学习进度，最后在同一事务中写入状态变更历史。返回（原状态, 新状态）
+    pub async fn transition_in_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        id: Id,
+        transition: StudyPlanTransition,
+        reason: &str,
+    ) -> AppResult<(UnifiedStudyPlanStatus, UnifiedStudyPlanStatus)> {
+        let db_error = |operation: &str, table: &str, e: sqlx::Error| {
+            self.logger
+                .database_operation(operation, table, false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        };
+
+        let from = sqlx::query_scalar::<_, Option<UnifiedStudyPlanStatus>>(
+            "SELECT unified_status FROM study_plans WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| db_error("SELECT", "study_plans", e))?
+        .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", id)))?
+        .unwrap_or(UnifiedStudyPlanStatus::Draft);
+
+        let to = transition.target(from).ok_or_else(|| {
+            AppError::InvalidStatusTransition(format!(
+                "学习计划当前为「{}」状态，不能{}",
+                from.label(),
+                transition.label()
+            ))
+        })?;
+
+        let mut assignments = vec!["unified_status = ?", "status = ?"];
+        match transition {
+            StudyPlanTransition::Start => assignments.push("actual_start_date = datetime('now')"),
+            StudyPlanTransition::Complete => assignments.push("actual_end_date = datetime('now')"),
+            StudyPlanTransition::Terminate => {
+                assignments.push("actual_terminated_date = datetime('now')");
+                assignments.push("paused_at = NULL");
+            }
+            StudyPlanTransition::Pause => assignments.push("paused_at = datetime('now')"),
+            StudyPlanTransition::Resume => assignments.push("paused_at = NULL"),
+            StudyPlanTransition::Restart | StudyPlanTransition::Edit => {
+                assignments.push("actual_start_date = NULL");
+                assignments.push("actual_end_date = NULL");
+                assignments.push("actual_terminated_date = NULL");
+                assignments.push("paused_at = NULL");
+            }
+            StudyPlanTransition::Delete => assignments.push("deleted_at = datetime('now')"),
+            StudyPlanTransition::Publish => {}
+        }
+        assignments.push("updated_at = datetime('now')");
+
+        let query = format!("UPDATE study_plans SET {} WHERE id = ?", assignments.join(", "));
+        sqlx::query(&query)
+            .bind(to)
+            .bind(to.management_status())
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| db_error("UPDATE", "study_plans", e))?;
+
+        // 重新学习清空全部进度，编辑还会清空单词的答题统计
+        if matches!(
+            transition,
+            StudyPlanTransition::Restart | StudyPlanTransition::Edit
+        ) {
+            for table in [
+                "study_plan_schedules",
+                "study_sessions",
+                "practice_sessions",
+                "study_timer_records",
+                "word_review_states",
+            ] {
+                sqlx::query(&format!("DELETE FROM {} WHERE plan_id = ?", table))
+                    .bind(id)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| db_error("DELETE", table, e))?;
+            }
+
+            let reset_words = if transition == StudyPlanTransition::Edit {
+                "UPDATE study_plan_words SET learned = 0, correct_count = 0, total_attempts = 0, mastery_score = 0.0 WHERE plan_id = ?"
+            } else {
+                "UPDATE study_plan_words SET learned = 0 WHERE plan_id = ?"
+            };
+            sqlx::query(reset_words)
+                .bind(id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| db_error("UPDATE", "study_plan_words", e))?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO study_plan_status_history
+            (plan_id, from_status, to_status, reason)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(from)
+        .bind(to)
+        .bind(reason)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("INSERT", "study_plan_status_history", e))?;
+
+        self.logger.database_operation(
+            "UPDATE",
+            "study_plans",
+            true,
+            Some(&format!(
+                "Study plan {} {:?}: {} -> {}",
+                id,
+                transition,
+                from.as_str(),
+                to.as_str()
+            )),
+        );
+
+        Ok((from, to))
+    }
+
+    // ==================== 暂停与恢复 ====================
+
+    /// 查询学习计划的暂停时间（UTC）
+    pub async fn find_paused_at(&self, id: Id) -> AppResult<Option<String>> {
+        sqlx::query_scalar::<_, Option<String>>("SELECT paused_at FROM study_plans WHERE id = ?")
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map(Option::flatten)
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "study_plans", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// 恢复学习计划：未完成的日程整体后移 shift_days 天，结束日期更新为最后一个日程的日期
+    ///
+    /// 状态转换与日程后移在同一事务中完成。后移后的日期保持原有顺序，遇到已完成日程占用的日期时顺延。返回（后移的日程数, 新的结束日期）
+    pub async fn resume_with_shift(
+        &self,
+        id: Id,
+        shift_days: i64,
+        reason: &str,
+    ) -> AppResult<(usize, Option<String>)> {
+        let db_error = |operation: &str, table: &str, e: sqlx::Error| {
+            self.logger
+                .database_operation(operation, table, false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        };
+
+        let mut tx = self.begin_transaction().await?;
+        self.transition_in_tx(&mut tx, id, StudyPlanTransition::Resume, reason)
+            .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, schedule_date,
+                   (status = 'completed'
+                    OR (total_words_count > 0 AND completed_words_count >= total_words_count)) AS completed
+            FROM study_plan_schedules
+            WHERE plan_id = ?
+            ORDER BY schedule_date
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| db_error("SELECT", "study_plan_schedules", e))?;
+
+        let mut occupied = std::collections::HashSet::new();
+        let mut pending = Vec::new();
+        for row in &rows {
+            let schedule_date: String = row.get("schedule_date");
+            let date = chrono::NaiveDate::parse_from_str(&schedule_date, "%Y-%m-%d").map_err(|e| {
+                AppError::ValidationError(format!("日程日期格式错误 {}: {}", schedule_date, e))
+            })?;
+            if row.get::<bool, _>("completed") {
+                occupied.insert(date);
+            } else {
+                pending.push((row.get::<Id, _>("id"), date));
+            }
+        }
+
+        let mut shifted = Vec::new();
+        if shift_days > 0 {
+            let mut previous: Option<chrono::NaiveDate> = None;
+            for (schedule_id, date) in &pending {
+                let mut new_date = *date + chrono::Duration::days(shift_days);
+                if let Some(previous) = previous {
+                    new_date = new_date.max(previous + chrono::Duration::days(1));
+                }
+                while occupied.contains(&new_date) {
+                    new_date += chrono::Duration::days(1);
+                }
+                previous = Some(new_date);
+                shifted.push((*schedule_id, new_date.format("%Y-%m-%d").to_string()));
+            }
+        }
+
+        // 从最晚的日程开始更新，避免与尚未后移的日程发生 UNIQUE(plan_id, schedule_date) 冲突
+        for (schedule_id, new_date) in shifted.iter().rev() {
+            sqlx::query(
+                r#"
+                UPDATE study_plan_schedules
+                SET schedule_date = ?,
+                    status = CASE WHEN status = 'overdue' THEN 'not-started' ELSE status END,
+                    updated_at = datetime('now')
+                WHERE id = ?
+                "#,
+            )
+            .bind(new_date)
+            .bind(schedule_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("UPDATE", "study_plan_schedules", e))?;
+
+            sqlx::query("UPDATE practice_sessions SET schedule_date = ? WHERE schedule_id = ?")
+                .bind(new_date)
+                .bind(schedule_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_error("UPDATE", "practice_sessions", e))?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE study_plans
+            SET end_date = COALESCE(
+                    (SELECT MAX(schedule_date) FROM study_plan_schedules WHERE plan_id = study_plans.id),
+                    end_date
+                ),
+                updated_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("UPDATE", "study_plans", e))?;
+
+        let end_date: Option<String> =
+            sqlx::query_scalar("SELECT end_date FROM study_plans WHERE id = ?")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| db_error("SELECT", "study_plans", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| db_error("COMMIT", "transaction", e))?;
+
+        self.logger.database_operation(
+            "UPDATE",
+            "study_plan_schedules",
+            true,
+            Some(&format!(
+                "Resumed study plan {}: shifted {} schedules by {} days, end date {:?}",
+                id,
+                shifted.len(),
+                shift_days,
+                end_date
+            )),
+        );
+
+        Ok((shifted.len(), end_date))
+    }
+
     // ==================== 辅助方法 ====================
 
     /// 将数据库行转换为 StudyPlanWithProgress
@@ -1307,19 +1312,10 @@ impl StudyPlanRepository {
 
     /// 将数据库行转换为 StudyPlan
     fn row_to_study_plan(&self, row: sqlx::sqlite::SqliteRow) -> AppResult<StudyPlan> {
-        let unified_status: Option<String> = row.get("unified_status");
-        let unified_status_enum = unified_status.and_then(|s| {
-            match s.as_str() {
-                "Draft" => Some(UnifiedStudyPlanStatus::Draft),
-                "Pending" => Some(UnifiedStudyPlanStatus::Pending),
-                "Active" => Some(UnifiedStudyPlanStatus::Active),
-                "Paused" => Some(UnifiedStudyPlanStatus::Paused),
-                "Completed" => Some(UnifiedStudyPlanStatus::Completed),
-                "Terminated" => Some(UnifiedStudyPlanStatus::Terminated),
-                "Deleted" => Some(UnifiedStudyPlanStatus::Deleted),
-                _ => None,
-            }
-        });
+        let unified_status_enum = row
+            .try_get::<Option<UnifiedStudyPlanStatus>, _>("unified_status")
+            .ok()
+            .flatten();
 
         Ok(StudyPlan {
             id: row.get("id"),
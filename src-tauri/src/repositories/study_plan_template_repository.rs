@@ -0,0 +1,138 @@
+//! 学习计划模板数据访问层
+//!
+//! 模板名称唯一，日程形状以 JSON 数组保存
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::types::common::Id;
+use crate::types::study::{CreateStudyPlanTemplateRequest, StudyPlanTemplate};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+
+/// 学习计划模板仓储
+pub struct StudyPlanTemplateRepository {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl StudyPlanTemplateRepository {
+    /// 创建新的仓储实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    fn db_error(&self, operation: &str, e: sqlx::Error) -> AppError {
+        self.logger.database_operation(
+            operation,
+            "study_plan_templates",
+            false,
+            Some(&e.to_string()),
+        );
+        AppError::DatabaseError(e.to_string())
+    }
+
+    /// 保存模板，名称重复时返回验证错误
+    pub async fn create(
+        &self,
+        request: &CreateStudyPlanTemplateRequest,
+        source_plan_id: Option<Id>,
+    ) -> AppResult<Id> {
+        let day_weights = serde_json::to_string(&request.day_weights)
+            .map_err(|e| AppError::InternalError(format!("序列化日程形状失败: {}", e)))?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO study_plan_templates
+            (name, description, intensity_level, study_period_days, review_frequency, day_weights, source_plan_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(request.name.trim())
+        .bind(&request.description)
+        .bind(&request.intensity_level)
+        .bind(request.study_period_days)
+        .bind(request.review_frequency)
+        .bind(day_weights)
+        .bind(source_plan_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                AppError::ValidationError(format!("模板名称「{}」已存在", request.name.trim()))
+            }
+            _ => self.db_error("INSERT", e),
+        })?;
+
+        let id = result.last_insert_rowid();
+        self.logger.database_operation(
+            "INSERT",
+            "study_plan_templates",
+            true,
+            Some(&format!(
+                "Created study plan template {} ({})",
+                id, request.name
+            )),
+        );
+
+        Ok(id)
+    }
+
+    /// 查询所有模板（按名称排序）
+    pub async fn find_all(&self) -> AppResult<Vec<StudyPlanTemplate>> {
+        let rows = sqlx::query("SELECT * FROM study_plan_templates ORDER BY name")
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("SELECT", e))?;
+
+        rows.iter().map(Self::row_to_template).collect()
+    }
+
+    /// 根据ID查询模板
+    pub async fn find_by_id(&self, id: Id) -> AppResult<Option<StudyPlanTemplate>> {
+        let row = sqlx::query("SELECT * FROM study_plan_templates WHERE id = ?")
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("SELECT", e))?;
+
+        row.as_ref().map(Self::row_to_template).transpose()
+    }
+
+    /// 删除模板，返回是否存在
+    pub async fn delete(&self, id: Id) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM study_plan_templates WHERE id = ?")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| self.db_error("DELETE", e))?;
+
+        let deleted = result.rows_affected() > 0;
+        if deleted {
+            self.logger.database_operation(
+                "DELETE",
+                "study_plan_templates",
+                true,
+                Some(&format!("Deleted study plan template {}", id)),
+            );
+        }
+
+        Ok(deleted)
+    }
+
+    fn row_to_template(row: &sqlx::sqlite::SqliteRow) -> AppResult<StudyPlanTemplate> {
+        let day_weights: String = row.get("day_weights");
+        Ok(StudyPlanTemplate {
+            id: row.get("id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            intensity_level: row.get("intensity_level"),
+            study_period_days: row.get("study_period_days"),
+            review_frequency: row.get("review_frequency"),
+            day_weights: serde_json::from_str(&day_weights)
+                .map_err(|e| AppError::InternalError(format!("解析日程形状失败: {}", e)))?,
+            source_plan_id: row.get("source_plan_id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
@@ -6,6 +6,9 @@
 
 use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
+use crate::services::study_plan_rebalancer::{
+    rebalance_fingerprint, RebalanceEntry, RebalanceOutcome, RebalanceSchedule,
+};
 use crate::types::{common::Id, study::*};
 use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
@@ -24,6 +27,15 @@ impl StudyScheduleRepository {
         Self { pool, logger }
     }
 
+    /// 从连接池获取连接
+    async fn acquire(&self) -> AppResult<sqlx::pool::PoolConnection<sqlx::Sqlite>> {
+        self.pool.acquire().await.map_err(|e| {
+            self.logger
+                .database_operation("ACQUIRE", "connection", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })
+    }
+
     // ==================== 学习日程基本操作 ====================
 
     /// 查找单个日程
@@ -329,6 +341,518 @@ impl StudyScheduleRepository {
         Ok(())
     }
 
+    /// 累加日程完成单词数，达到日程单词总数时标记为已完成
+    pub async fn add_progress(&self, schedule_id: Id, completed_words: i32) -> AppResult<()> {
+        let query = r#"
+            UPDATE study_plan_schedules SET
+                completed_words_count = MIN(completed_words_count + ?, total_words_count),
+                status = CASE
+                    WHEN total_words_count > 0 AND completed_words_count + ? >= total_words_count
+                    THEN 'completed'
+                    ELSE 'in-progress'
+                END,
+                updated_at = datetime('now')
+            WHERE id = ?
+        "#;
+
+        sqlx::query(query)
+            .bind(completed_words)
+            .bind(completed_words)
+            .bind(schedule_id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("UPDATE", "study_plan_schedules", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        self.logger.database_operation(
+            "UPDATE",
+            "study_plan_schedules",
+            true,
+            Some(&format!(
+                "Schedule {} progress: {} more words completed",
+                schedule_id, completed_words
+            )),
+        );
+
+        Ok(())
+    }
+
+    // ==================== 逾期日程重新分配 ====================
+
+    /// 查找计划的全部日程，标记哪些日程可以重新分配
+    ///
+    /// 未开始或逾期、没有完成任何单词且没有练习会话的日程才可以调整
+    pub async fn find_rebalance_schedules(&self, plan_id: Id) -> AppResult<Vec<RebalanceSchedule>> {
+        let mut conn = self.acquire().await?;
+        self.fetch_rebalance_schedules(&mut conn, plan_id).await
+    }
+
+    async fn fetch_rebalance_schedules(
+        &self,
+        conn: &mut sqlx::SqliteConnection,
+        plan_id: Id,
+    ) -> AppResult<Vec<RebalanceSchedule>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT s.id, s.schedule_date,
+                   (s.status IN ('not-started', 'overdue')
+                    AND s.completed_words_count = 0
+                    AND NOT EXISTS (SELECT 1 FROM practice_sessions ps WHERE ps.schedule_id = s.id)) AS movable
+            FROM study_plan_schedules s
+            WHERE s.plan_id = ?
+            ORDER BY s.schedule_date
+            "#,
+        )
+        .bind(plan_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("SELECT", "study_plan_schedules", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        rows.iter()
+            .map(|row| {
+                let schedule_date: String = row.get("schedule_date");
+                let date = chrono::NaiveDate::parse_from_str(&schedule_date, "%Y-%m-%d").map_err(|e| {
+                    AppError::ValidationError(format!("日程日期格式错误 {}: {}", schedule_date, e))
+                })?;
+                Ok(RebalanceSchedule {
+                    id: row.get("id"),
+                    date,
+                    movable: row.get("movable"),
+                })
+            })
+            .collect()
+    }
+
+    /// 查找日程中的单词安排
+    pub async fn find_rebalance_entries(&self, schedule_ids: &[Id]) -> AppResult<Vec<RebalanceEntry>> {
+        let mut conn = self.acquire().await?;
+        self.fetch_rebalance_entries(&mut conn, schedule_ids).await
+    }
+
+    async fn fetch_rebalance_entries(
+        &self,
+        conn: &mut sqlx::SqliteConnection,
+        schedule_ids: &[Id],
+    ) -> AppResult<Vec<RebalanceEntry>> {
+        if schedule_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; schedule_ids.len()].join(", ");
+        let query = format!(
+            r#"
+            SELECT sw.id, sw.schedule_id, sw.word_id, COALESCE(w.word, '') AS word,
+                   sw.is_review, sw.review_count
+            FROM study_plan_schedule_words sw
+            LEFT JOIN words w ON w.id = sw.word_id
+            WHERE sw.schedule_id IN ({})
+            ORDER BY sw.id
+            "#,
+            placeholders
+        );
+
+        let mut query_builder = sqlx::query(&query);
+        for schedule_id in schedule_ids {
+            query_builder = query_builder.bind(schedule_id);
+        }
+
+        let rows = query_builder
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| {
+                self.logger.database_operation(
+                    "SELECT",
+                    "study_plan_schedule_words",
+                    false,
+                    Some(&e.to_string()),
+                );
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(rows
+            .iter()
+            .map(|row| RebalanceEntry {
+                id: row.get("id"),
+                schedule_id: row.get("schedule_id"),
+                word_id: row.get("word_id"),
+                word: row.get("word"),
+                is_review: row.get("is_review"),
+                review_count: row.get("review_count"),
+            })
+            .collect())
+    }
+
+    /// 在一个事务中应用重新分配方案
+    ///
+    /// 先在事务中重新读取日程并计算指纹，与 `fingerprint`（计算方案时的指纹）不一致时拒绝应用；
+    /// 然后新增延长的日程，移动单词安排，删除不再安排的复习，重新统计单词数，
+    /// 删除被清空的日程，并把计划结束日期更新为最后一个日程的日期。返回新的结束日期
+    pub async fn apply_rebalance(
+        &self,
+        plan_id: Id,
+        today: chrono::NaiveDate,
+        max_new_per_day: usize,
+        fingerprint: &str,
+        outcome: &RebalanceOutcome,
+    ) -> AppResult<Option<String>> {
+        let db_error = |operation: &str, table: &str, e: sqlx::Error| {
+            self.logger
+                .database_operation(operation, table, false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        };
+
+        let mut tx = self.pool.begin().await.map_err(|e| db_error("BEGIN", "transaction", e))?;
+
+        // 0. 确认日程在计算方案后没有变化
+        let schedules = self.fetch_rebalance_schedules(&mut tx, plan_id).await?;
+        let movable_ids: Vec<Id> = schedules.iter().filter(|s| s.movable).map(|s| s.id).collect();
+        let entries = self.fetch_rebalance_entries(&mut tx, &movable_ids).await?;
+        if rebalance_fingerprint(today, max_new_per_day, &schedules, &entries) != fingerprint {
+            return Err(AppError::ValidationError(
+                "学习计划的日程在预览后已变化，请重新预览".to_string(),
+            ));
+        }
+
+        // 1. 延长计划：在最后一天之后追加日程
+        let mut schedule_ids: std::collections::HashMap<chrono::NaiveDate, Id> = schedules
+            .iter()
+            .filter(|s| s.movable)
+            .map(|s| (s.date, s.id))
+            .collect();
+        let max_day: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(day_number), 0) FROM study_plan_schedules WHERE plan_id = ?",
+        )
+        .bind(plan_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_error("SELECT", "study_plan_schedules", e))?;
+        for (index, date) in outcome.added_dates.iter().enumerate() {
+            let schedule_id = sqlx::query(
+                r#"
+                INSERT INTO study_plan_schedules (plan_id, day_number, schedule_date, status)
+                VALUES (?, ?, ?, 'not-started')
+                "#,
+            )
+            .bind(plan_id)
+            .bind(max_day + index as i64 + 1)
+            .bind(date.format("%Y-%m-%d").to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("INSERT", "study_plan_schedules", e))?
+            .last_insert_rowid();
+            schedule_ids.insert(*date, schedule_id);
+        }
+
+        // 2. 删除不再安排的复习，移动其余单词安排
+        for entry_id in &outcome.dropped {
+            sqlx::query("DELETE FROM study_plan_schedule_words WHERE id = ?")
+                .bind(entry_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_error("DELETE", "study_plan_schedule_words", e))?;
+        }
+        for (entry_id, date) in &outcome.placements {
+            let schedule_id = schedule_ids.get(date).ok_or_else(|| {
+                AppError::InternalError(format!("重新分配的目标日期 {} 没有对应的日程", date))
+            })?;
+            sqlx::query("UPDATE study_plan_schedule_words SET schedule_id = ? WHERE id = ?")
+                .bind(schedule_id)
+                .bind(entry_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_error("UPDATE", "study_plan_schedule_words", e))?;
+        }
+
+        // 3. 重新统计受影响日程的单词数，逾期状态重置为未开始
+        let affected: Vec<Id> = schedule_ids.values().copied().collect();
+        for schedule_id in &affected {
+            sqlx::query(
+                r#"
+                UPDATE study_plan_schedules
+                SET new_words_count = (
+                        SELECT COUNT(*) FROM study_plan_schedule_words
+                        WHERE schedule_id = study_plan_schedules.id AND is_review = 0
+                    ),
+                    review_words_count = (
+                        SELECT COUNT(*) FROM study_plan_schedule_words
+                        WHERE schedule_id = study_plan_schedules.id AND is_review = 1
+                    ),
+                    total_words_count = (
+                        SELECT COUNT(*) FROM study_plan_schedule_words
+                        WHERE schedule_id = study_plan_schedules.id
+                    ),
+                    status = 'not-started',
+                    updated_at = datetime('now')
+                WHERE id = ?
+                "#,
+            )
+            .bind(schedule_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("UPDATE", "study_plan_schedules", e))?;
+        }
+
+        // 4. 删除被清空的日程
+        for schedule_id in &affected {
+            sqlx::query("DELETE FROM study_plan_schedules WHERE id = ? AND total_words_count = 0")
+                .bind(schedule_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_error("DELETE", "study_plan_schedules", e))?;
+        }
+
+        // 5. 更新计划结束日期
+        sqlx::query(
+            r#"
+            UPDATE study_plans
+            SET end_date = COALESCE(
+                    (SELECT MAX(schedule_date) FROM study_plan_schedules WHERE plan_id = study_plans.id),
+                    end_date
+                ),
+                updated_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(plan_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("UPDATE", "study_plans", e))?;
+
+        let end_date: Option<String> =
+            sqlx::query_scalar("SELECT end_date FROM study_plans WHERE id = ?")
+                .bind(plan_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| db_error("SELECT", "study_plans", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| db_error("COMMIT", "transaction", e))?;
+
+        self.logger.database_operation(
+            "UPDATE",
+            "study_plan_schedule_words",
+            true,
+            Some(&format!(
+                "Rebalanced study plan {}: {} entries placed, {} dropped, {} days added",
+                plan_id,
+                outcome.placements.len(),
+                outcome.dropped.len(),
+                outcome.added_dates.len()
+            )),
+        );
+
+        Ok(end_date)
+    }
+
+    // ==================== 追加单词 ====================
+
+    /// 查询计划的日程负载：(日期, 已有新词数, 是否已完成)
+    pub async fn find_schedule_load(
+        &self,
+        plan_id: Id,
+    ) -> AppResult<Vec<(chrono::NaiveDate, i32, bool)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT schedule_date, new_words_count,
+                   (status = 'completed'
+                    OR (total_words_count > 0 AND completed_words_count >= total_words_count)) AS completed
+            FROM study_plan_schedules
+            WHERE plan_id = ?
+            ORDER BY schedule_date
+            "#,
+        )
+        .bind(plan_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| {
+            self.logger
+                .database_operation("SELECT", "study_plan_schedules", false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        rows.iter()
+            .map(|row| {
+                let schedule_date: String = row.get("schedule_date");
+                let date = chrono::NaiveDate::parse_from_str(&schedule_date, "%Y-%m-%d").map_err(|e| {
+                    AppError::ValidationError(format!("日程日期格式错误 {}: {}", schedule_date, e))
+                })?;
+                Ok((date, row.get("new_words_count"), row.get("completed")))
+            })
+            .collect()
+    }
+
+    /// 在一个事务中把追加的单词写入计划
+    ///
+    /// 关联计划单词，按日期写入日程单词（没有日程的日期新建日程），重新统计受影响日程的单词数，
+    /// 并更新计划的单词总数和结束日期。返回（新建的日程数, 新的结束日期）
+    pub async fn add_planned_words(
+        &self,
+        plan_id: Id,
+        word_ids: &[Id],
+        daily_plans: &[DailyStudyPlan],
+    ) -> AppResult<(usize, Option<String>)> {
+        let db_error = |operation: &str, table: &str, e: sqlx::Error| {
+            self.logger
+                .database_operation(operation, table, false, Some(&e.to_string()));
+            AppError::DatabaseError(e.to_string())
+        };
+        let parse_id = |value: &str| {
+            value
+                .parse::<Id>()
+                .map_err(|_| AppError::ValidationError(format!("无效的 ID: {}", value)))
+        };
+
+        let mut tx = self.pool.begin().await.map_err(|e| db_error("BEGIN", "transaction", e))?;
+
+        for word_id in word_ids {
+            sqlx::query("INSERT OR IGNORE INTO study_plan_words (plan_id, word_id) VALUES (?, ?)")
+                .bind(plan_id)
+                .bind(word_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_error("INSERT", "study_plan_words", e))?;
+        }
+
+        let mut max_day: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(day_number), 0) FROM study_plan_schedules WHERE plan_id = ?",
+        )
+        .bind(plan_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_error("SELECT", "study_plan_schedules", e))?;
+
+        let mut created_schedules = 0;
+        for daily_plan in daily_plans {
+            let existing: Option<Id> = sqlx::query_scalar(
+                "SELECT id FROM study_plan_schedules WHERE plan_id = ? AND schedule_date = ?",
+            )
+            .bind(plan_id)
+            .bind(&daily_plan.date)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| db_error("SELECT", "study_plan_schedules", e))?;
+
+            let schedule_id = match existing {
+                Some(schedule_id) => schedule_id,
+                None => {
+                    max_day += 1;
+                    created_schedules += 1;
+                    sqlx::query(
+                        r#"
+                        INSERT INTO study_plan_schedules (plan_id, day_number, schedule_date, status)
+                        VALUES (?, ?, ?, 'not-started')
+                        "#,
+                    )
+                    .bind(plan_id)
+                    .bind(max_day)
+                    .bind(&daily_plan.date)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| db_error("INSERT", "study_plan_schedules", e))?
+                    .last_insert_rowid()
+                }
+            };
+
+            for word in &daily_plan.words {
+                sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO study_plan_schedule_words
+                    (schedule_id, word_id, wordbook_id, is_review, review_count, priority, difficulty_level)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(schedule_id)
+                .bind(parse_id(&word.word_id)?)
+                .bind(parse_id(&word.wordbook_id)?)
+                .bind(word.is_review)
+                .bind(word.review_count)
+                .bind(&word.priority)
+                .bind(word.difficulty_level)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_error("INSERT", "study_plan_schedule_words", e))?;
+            }
+
+            sqlx::query(
+                r#"
+                UPDATE study_plan_schedules
+                SET new_words_count = (
+                        SELECT COUNT(*) FROM study_plan_schedule_words
+                        WHERE schedule_id = study_plan_schedules.id AND is_review = 0
+                    ),
+                    review_words_count = (
+                        SELECT COUNT(*) FROM study_plan_schedule_words
+                        WHERE schedule_id = study_plan_schedules.id AND is_review = 1
+                    ),
+                    total_words_count = (
+                        SELECT COUNT(*) FROM study_plan_schedule_words
+                        WHERE schedule_id = study_plan_schedules.id
+                    ),
+                    updated_at = datetime('now')
+                WHERE id = ?
+                "#,
+            )
+            .bind(schedule_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("UPDATE", "study_plan_schedules", e))?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE study_plans
+            SET total_words = (SELECT COUNT(*) FROM study_plan_words WHERE plan_id = study_plans.id),
+                start_date = COALESCE(
+                    start_date,
+                    (SELECT MIN(schedule_date) FROM study_plan_schedules WHERE plan_id = study_plans.id)
+                ),
+                end_date = COALESCE(
+                    (SELECT MAX(schedule_date) FROM study_plan_schedules WHERE plan_id = study_plans.id),
+                    end_date
+                ),
+                updated_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(plan_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("UPDATE", "study_plans", e))?;
+
+        let end_date: Option<String> =
+            sqlx::query_scalar("SELECT end_date FROM study_plans WHERE id = ?")
+                .bind(plan_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| db_error("SELECT", "study_plans", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| db_error("COMMIT", "transaction", e))?;
+
+        self.logger.database_operation(
+            "INSERT",
+            "study_plan_schedule_words",
+            true,
+            Some(&format!(
+                "Added {} words to study plan {} across {} days ({} new schedules)",
+                word_ids.len(),
+                plan_id,
+                daily_plans.len(),
+                created_schedules
+            )),
+        );
+
+        Ok((created_schedules, end_date))
+    }
 }
 
 // ==================== 辅助类型定义 ====================
@@ -8,11 +8,31 @@
 
 
 use crate::{
-    error::AppError, error::AppResult, logger::Logger, types::common::Id, types::wordbook::Word,
+    error::AppError,
+    error::AppResult,
+    logger::Logger,
+    types::common::Id,
+    types::wordbook::{Word, WordHighlight, WordSearchQuery},
 };
 use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
 
+/// 搜索结果高亮标记
+pub const HIGHLIGHT_OPEN: &str = "<mark>";
+pub const HIGHLIGHT_CLOSE: &str = "</mark>";
+
+/// trigram 分词可以匹配的最短关键词长度
+const TRIGRAM_MIN_CHARS: usize = 3;
+
+/// 全文搜索覆盖的字段（与 words_fts 的列一致）
+const SEARCH_COLUMNS: [&str; 5] = [
+    "word",
+    "meaning",
+    "description",
+    "phonics_rule",
+    "analysis_explanation",
+];
+
 /// 单词数据仓库
 pub struct WordRepository {
     pool: Arc<SqlitePool>,
@@ -35,32 +55,34 @@ impl WordRepository {
             return Ok(std::collections::HashMap::new());
         }
 
-        // 构建 IN 查询
-        let placeholders: Vec<String> = (0..word_list.len()).map(|_| "?".to_string()).collect();
-        let query = format!(
-            "SELECT id, LOWER(word) as word_lower FROM words WHERE word_book_id = ? AND LOWER(word) IN ({})",
-            placeholders.join(",")
-        );
+        let mut result = std::collections::HashMap::new();
+        // SQLite 绑定参数数量有限，分批查询
+        for chunk in word_list.chunks(500) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let query = format!(
+                "SELECT id, LOWER(word) as word_lower FROM words WHERE word_book_id = ? AND LOWER(word) IN ({})",
+                placeholders
+            );
 
-        let mut query_builder = sqlx::query(&query).bind(book_id);
-        for word in word_list {
-            query_builder = query_builder.bind(word.to_lowercase());
-        }
+            let mut query_builder = sqlx::query(&query).bind(book_id);
+            for word in chunk {
+                query_builder = query_builder.bind(word.to_lowercase());
+            }
 
-        let rows = query_builder
-            .fetch_all(self.pool.as_ref())
-            .await
-            .map_err(|e| {
-                self.logger
-                    .database_operation("SELECT", "words", false, Some(&e.to_string()));
-                AppError::DatabaseError(e.to_string())
-            })?;
+            let rows = query_builder
+                .fetch_all(self.pool.as_ref())
+                .await
+                .map_err(|e| {
+                    self.logger
+                        .database_operation("SELECT", "words", false, Some(&e.to_string()));
+                    AppError::DatabaseError(e.to_string())
+                })?;
 
-        let mut result = std::collections::HashMap::new();
-        for row in rows {
-            let id: Id = row.get("id");
-            let word_lower: String = row.get("word_lower");
-            result.insert(word_lower, id);
+            for row in rows {
+                let id: Id = row.get("id");
+                let word_lower: String = row.get("word_lower");
+                result.insert(word_lower, id);
+            }
         }
 
         Ok(result)
@@ -235,6 +257,46 @@ impl WordRepository {
         Ok(words)
     }
 
+    /// 根据单词ID列表查询单词，返回 (单词ID, 单词, 单词本ID)
+    pub async fn find_words_by_ids(&self, word_ids: &[Id]) -> AppResult<Vec<(Id, String, Id)>> {
+        if word_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders: Vec<String> = (0..word_ids.len()).map(|_| "?".to_string()).collect();
+        let query = format!(
+            r#"
+            SELECT id, word, word_book_id
+            FROM words
+            WHERE id IN ({})
+                AND word_book_id IN (
+                    SELECT id FROM word_books WHERE status = 'normal'
+                )
+            ORDER BY word_book_id, id
+            "#,
+            placeholders.join(",")
+        );
+
+        let mut query_builder = sqlx::query(&query);
+        for word_id in word_ids {
+            query_builder = query_builder.bind(word_id);
+        }
+
+        let rows = query_builder
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "words", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("id"), row.get("word"), row.get("word_book_id")))
+            .collect())
+    }
+
     /// 验证单词ID是否存在
     pub async fn validate_word_ids(&self, word_ids: &[Id]) -> AppResult<usize> {
         if word_ids.is_empty() {
@@ -469,6 +531,32 @@ impl WordRepository {
         Ok(())
     }
 
+    /// 查询单词本中的全部单词（按单词排序，用于导出）
+    pub async fn find_all_by_book(&self, book_id: Id) -> AppResult<Vec<Word>> {
+        let query = r#"
+            SELECT
+                id, word, meaning, description, ipa, syllables, phonics_segments,
+                image_path, audio_path, part_of_speech, category_id, word_book_id,
+                pos_abbreviation, pos_english, pos_chinese, phonics_rule,
+                analysis_explanation, created_at, updated_at
+            FROM words
+            WHERE word_book_id = ?
+            ORDER BY word COLLATE NOCASE, id
+        "#;
+
+        let rows = sqlx::query(query)
+            .bind(book_id)
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "words", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        rows.into_iter().map(|row| self.row_to_word(row)).collect()
+    }
+
     /// 分页查询单词本中的单词
     pub async fn find_by_book_paginated(
         &self,
@@ -587,6 +675,221 @@ impl WordRepository {
         Ok((words, total as u32))
     }
 
+    /// 全文搜索所有未删除单词本中的单词，按相关度排序并高亮匹配内容
+    ///
+    /// 不少于 3 个字符的关键词走 FTS5 trigram 索引（bm25 排序）；更短的关键词（例如两个字的中文释义）
+    /// 无法使用 trigram 索引，改用 LIKE 子串匹配，按单词匹配程度排序
+    pub async fn search(
+        &self,
+        query: &WordSearchQuery,
+        page: u32,
+        page_size: u32,
+    ) -> AppResult<(Vec<Word>, u32)> {
+        let offset = (page - 1) * page_size;
+        let (fts_terms, short_terms): (Vec<String>, Vec<String>) = query
+            .query
+            .split_whitespace()
+            .map(|term| term.to_string())
+            .partition(|term| term.chars().count() >= TRIGRAM_MIN_CHARS);
+        let use_fts = !fts_terms.is_empty();
+
+        let mut joins = String::from(
+            "JOIN word_books b ON b.id = w.word_book_id AND b.deleted_at IS NULL",
+        );
+        let mut where_conditions = Vec::new();
+        let mut params = Vec::new();
+
+        if use_fts {
+            joins.push_str(" JOIN words_fts ON words_fts.rowid = w.id");
+            where_conditions.push("words_fts MATCH ?".to_string());
+            params.push(
+                fts_terms
+                    .iter()
+                    .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        for term in &short_terms {
+            let conditions: Vec<String> = SEARCH_COLUMNS
+                .iter()
+                .map(|column| format!("w.{} LIKE ? ESCAPE '\\'", column))
+                .collect();
+            where_conditions.push(format!("({})", conditions.join(" OR ")));
+            let pattern = format!("%{}%", escape_like(term));
+            params.extend(std::iter::repeat_n(pattern, SEARCH_COLUMNS.len()));
+        }
+        if let Some(book_id) = query.book_id {
+            where_conditions.push(format!("w.word_book_id = {}", book_id));
+        }
+        if let Some(theme_tag_id) = query.theme_tag_id {
+            where_conditions.push(format!(
+                "EXISTS (SELECT 1 FROM word_book_theme_tags t WHERE t.word_book_id = w.word_book_id AND t.theme_tag_id = {})",
+                theme_tag_id
+            ));
+        }
+        if let Some(pos) = query.part_of_speech.as_deref() {
+            if !pos.trim().is_empty() && pos != "all" {
+                where_conditions.push("w.part_of_speech = ?".to_string());
+                params.push(pos.to_string());
+            }
+        }
+        let where_clause = if where_conditions.is_empty() {
+            "1 = 1".to_string()
+        } else {
+            where_conditions.join(" AND ")
+        };
+
+        let (highlight_columns, order_by, order_params) = if use_fts {
+            // 单词与关键词完全相同或以其开头的排在最前，其余按 bm25 相关度
+            let whole_query = escape_like(query.query.trim());
+            (
+                format!(
+                    "highlight(words_fts, 0, '{open}', '{close}') AS word_highlight,
+                     highlight(words_fts, 1, '{open}', '{close}') AS meaning_highlight,
+                     snippet(words_fts, 2, '{open}', '{close}', '…', 16) AS description_snippet,
+                     snippet(words_fts, 3, '{open}', '{close}', '…', 16) AS phonics_rule_snippet,
+                     snippet(words_fts, 4, '{open}', '{close}', '…', 16) AS analysis_snippet",
+                    open = HIGHLIGHT_OPEN,
+                    close = HIGHLIGHT_CLOSE
+                ),
+                "CASE WHEN w.word LIKE ? ESCAPE '\\' THEN 0
+                      WHEN w.word LIKE ? ESCAPE '\\' THEN 1
+                      ELSE 2 END,
+                 bm25(words_fts, 10.0, 5.0, 1.0, 2.0, 1.0),
+                 w.word COLLATE NOCASE"
+                    .to_string(),
+                vec![whole_query.clone(), format!("{}%", whole_query)],
+            )
+        } else {
+            // 完全匹配单词 > 单词前缀 > 单词包含 > 释义包含 > 其他字段包含
+            let term = escape_like(&short_terms[0]);
+            (
+                "NULL AS word_highlight, NULL AS meaning_highlight, NULL AS description_snippet,
+                 NULL AS phonics_rule_snippet, NULL AS analysis_snippet"
+                    .to_string(),
+                "CASE WHEN w.word LIKE ? ESCAPE '\\' THEN 0
+                      WHEN w.word LIKE ? ESCAPE '\\' THEN 1
+                      WHEN w.word LIKE ? ESCAPE '\\' THEN 2
+                      WHEN w.meaning LIKE ? ESCAPE '\\' THEN 3
+                      ELSE 4 END,
+                 w.word COLLATE NOCASE"
+                    .to_string(),
+                vec![
+                    term.clone(),
+                    format!("{}%", term),
+                    format!("%{}%", term),
+                    format!("%{}%", term),
+                ],
+            )
+        };
+
+        let query_sql = format!(
+            r#"
+            SELECT
+                w.id, w.word, w.meaning, w.description, w.ipa, w.syllables, w.phonics_segments,
+                w.image_path, w.audio_path, w.part_of_speech, w.category_id, w.word_book_id,
+                w.pos_abbreviation, w.pos_english, w.pos_chinese, w.phonics_rule,
+                w.analysis_explanation, w.created_at, w.updated_at,
+                {}
+            FROM words w
+            {}
+            WHERE {}
+            ORDER BY {}, w.id
+            LIMIT ? OFFSET ?
+            "#,
+            highlight_columns, joins, where_clause, order_by
+        );
+
+        let mut query_builder = sqlx::query(&query_sql);
+        for param in params.iter().chain(&order_params) {
+            query_builder = query_builder.bind(param);
+        }
+        let rows = query_builder
+            .bind(page_size as i64)
+            .bind(offset as i64)
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "words_fts", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        let mut words = Vec::with_capacity(rows.len());
+        for row in rows {
+            let snippets: [Option<String>; 3] = [
+                row.get("description_snippet"),
+                row.get("phonics_rule_snippet"),
+                row.get("analysis_snippet"),
+            ];
+            let word_highlight: Option<String> = row.get("word_highlight");
+            let meaning_highlight: Option<String> = row.get("meaning_highlight");
+            let mut word = self.row_to_word(row)?;
+
+            let highlight = if use_fts {
+                WordHighlight {
+                    word: word_highlight.unwrap_or_else(|| word.word.clone()),
+                    meaning: meaning_highlight.unwrap_or_else(|| word.meaning.clone()),
+                    snippet: snippets
+                        .into_iter()
+                        .flatten()
+                        .find(|snippet| snippet.contains(HIGHLIGHT_OPEN)),
+                }
+            } else {
+                WordHighlight {
+                    word: mark_terms(&word.word, &short_terms)
+                        .unwrap_or_else(|| word.word.clone()),
+                    meaning: mark_terms(&word.meaning, &short_terms)
+                        .unwrap_or_else(|| word.meaning.clone()),
+                    snippet: [
+                        &word.description,
+                        &word.phonics_rule,
+                        &word.analysis_explanation,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|text| mark_terms(text, &short_terms)),
+                }
+            };
+            word.highlight = Some(highlight);
+            words.push(word);
+        }
+
+        let count_query = format!(
+            "SELECT COUNT(*) as count FROM words w {} WHERE {}",
+            joins, where_clause
+        );
+        let mut count_query_builder = sqlx::query(&count_query);
+        for param in &params {
+            count_query_builder = count_query_builder.bind(param);
+        }
+        let count_row = count_query_builder
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                self.logger
+                    .database_operation("SELECT", "words_fts", false, Some(&e.to_string()));
+                AppError::DatabaseError(e.to_string())
+            })?;
+        let total: i64 = count_row.get("count");
+
+        self.logger.database_operation(
+            "SELECT",
+            "words_fts",
+            true,
+            Some(&format!(
+                "Search {:?} found {} words (page {}, total: {})",
+                query.query,
+                words.len(),
+                page,
+                total
+            )),
+        );
+
+        Ok((words, total as u32))
+    }
+
     /// 将数据库行转换为Word对象
     fn row_to_word(&self, row: sqlx::sqlite::SqliteRow) -> AppResult<Word> {
         Ok(Word {
@@ -609,6 +912,47 @@ impl WordRepository {
             analysis_explanation: row.get("analysis_explanation"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            highlight: None,
         })
     }
 }
+
+/// 转义 LIKE 通配符（配合 ESCAPE '\'）
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 标记文本中出现的关键词（与 LIKE 一致，只忽略 ASCII 大小写），没有匹配时返回 None
+fn mark_terms(text: &str, terms: &[String]) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+    let mut marked = vec![false; chars.len()];
+    for term in terms {
+        let term: Vec<char> = term.chars().map(|c| c.to_ascii_lowercase()).collect();
+        if term.is_empty() || term.len() > lower.len() {
+            continue;
+        }
+        for start in 0..=lower.len() - term.len() {
+            if lower[start..start + term.len()] == term[..] {
+                marked[start..start + term.len()].fill(true);
+            }
+        }
+    }
+    if !marked.contains(&true) {
+        return None;
+    }
+
+    let mut result = String::with_capacity(text.len());
+    for (i, c) in chars.iter().enumerate() {
+        if marked[i] && (i == 0 || !marked[i - 1]) {
+            result.push_str(HIGHLIGHT_OPEN);
+        }
+        result.push(*c);
+        if marked[i] && (i + 1 == chars.len() || !marked[i + 1]) {
+            result.push_str(HIGHLIGHT_CLOSE);
+        }
+    }
+    Some(result)
+}
@@ -57,4 +57,32 @@ impl AIModelService {
 
         Ok(model_config)
     }
+
+    /// 配置模型价格（每百万 token 美元价格）
+    pub async fn update_model_pricing(
+        &self,
+        model_id: Id,
+        input_price_per_million: Option<f64>,
+        output_price_per_million: Option<f64>,
+    ) -> AppResult<()> {
+        for price in [input_price_per_million, output_price_per_million].into_iter().flatten() {
+            if !price.is_finite() || price < 0.0 {
+                return Err(AppError::ValidationError(format!("无效的模型价格: {}", price)));
+            }
+        }
+
+        self.repository
+            .update_pricing(model_id, input_price_per_million, output_price_per_million)
+            .await?;
+
+        self.logger.info(
+            "AI_MODEL_SERVICE",
+            &format!(
+                "Model {} pricing set to input {:?} / output {:?} USD per million tokens",
+                model_id, input_price_per_million, output_price_per_million
+            ),
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,138 @@
+//! AI 用量统计业务逻辑服务
+//!
+//! 按日/月汇总 LLM 调用的 token 用量和估算费用，并按提供商、功能拆分
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::repositories::ai_usage_repository::{AIUsageRepository, UsageGrouping};
+use crate::services::spaced_repetition::DATE_FORMAT;
+use crate::types::ai_model::{AIUsageReport, AIUsageSummary};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+/// 按日统计时默认的天数
+const DEFAULT_DAILY_RANGE_DAYS: i64 = 30;
+/// 按月统计时默认的月数
+const DEFAULT_MONTHLY_RANGE_MONTHS: u32 = 12;
+
+/// AI 用量统计服务
+pub struct AIUsageService {
+    repository: AIUsageRepository,
+    logger: Arc<Logger>,
+}
+
+impl AIUsageService {
+    /// 创建新的服务实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self {
+            repository: AIUsageRepository::new(pool, logger.clone()),
+            logger,
+        }
+    }
+
+    /// 获取用量报表
+    ///
+    /// `granularity` 为 daily 或 monthly；未指定日期时，按日默认最近 30 天，按月默认最近 12 个月
+    pub async fn get_usage_report(
+        &self,
+        granularity: Option<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> AppResult<AIUsageReport> {
+        let granularity = granularity.unwrap_or("daily");
+        let period_grouping = match granularity {
+            "daily" => UsageGrouping::Day,
+            "monthly" => UsageGrouping::Month,
+            other => {
+                return Err(AppError::ValidationError(format!(
+                    "不支持的统计粒度: {}（可选 daily / monthly）",
+                    other
+                )))
+            }
+        };
+
+        let end = match end_date {
+            Some(date) => parse_date(date)?,
+            None => Local::now().date_naive(),
+        };
+        let start = match start_date {
+            Some(date) => parse_date(date)?,
+            None => default_start_date(period_grouping, end),
+        };
+        if start > end {
+            return Err(AppError::ValidationError(
+                "开始日期不能晚于结束日期".to_string(),
+            ));
+        }
+
+        let start = start.format(DATE_FORMAT).to_string();
+        let end = end.format(DATE_FORMAT).to_string();
+
+        let total = self
+            .repository
+            .summarize(UsageGrouping::Total, &start, &end)
+            .await?
+            .into_iter()
+            .next()
+            .map(AIUsageSummary::from)
+            .unwrap_or_else(|| AIUsageSummary {
+                key: "total".to_string(),
+                ..Default::default()
+            });
+
+        let by_period = self.summaries(period_grouping, &start, &end).await?;
+        let by_provider = self.summaries(UsageGrouping::Provider, &start, &end).await?;
+        let by_feature = self.summaries(UsageGrouping::Purpose, &start, &end).await?;
+
+        self.logger.info(
+            "AI_USAGE_SERVICE",
+            &format!(
+                "Usage report {} {}..{}: {} calls, {} tokens",
+                granularity, start, end, total.calls, total.total_tokens
+            ),
+        );
+
+        Ok(AIUsageReport {
+            granularity: granularity.to_string(),
+            start_date: start,
+            end_date: end,
+            total,
+            by_period,
+            by_provider,
+            by_feature,
+        })
+    }
+
+    async fn summaries(
+        &self,
+        grouping: UsageGrouping,
+        start: &str,
+        end: &str,
+    ) -> AppResult<Vec<AIUsageSummary>> {
+        Ok(self
+            .repository
+            .summarize(grouping, start, end)
+            .await?
+            .into_iter()
+            .map(AIUsageSummary::from)
+            .collect())
+    }
+}
+
+fn parse_date(date: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .map_err(|_| AppError::ValidationError(format!("无效的日期格式: {}", date)))
+}
+
+/// 默认统计起始日期
+fn default_start_date(grouping: UsageGrouping, end: NaiveDate) -> NaiveDate {
+    match grouping {
+        UsageGrouping::Month => {
+            let months = end.year() * 12 + end.month0() as i32 - (DEFAULT_MONTHLY_RANGE_MONTHS as i32 - 1);
+            NaiveDate::from_ymd_opt(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+                .unwrap_or(end)
+        }
+        _ => end - Duration::days(DEFAULT_DAILY_RANGE_DAYS - 1),
+    }
+}
@@ -0,0 +1,654 @@
+//! 数据库备份与恢复业务逻辑服务
+//!
+//! 备份文件是保存在备份目录中的 zip 压缩包，包含：
+//! - manifest.json：备份类型、时间和数据库迁移版本
+//! - vocabulary.db：通过 `VACUUM INTO`（而不是 sqlite3_backup API）在线复制的数据库（WAL 模式下同样安全）
+//! - tts/：TTS 发音缓存文件
+//!
+//! 恢复分两步：先解压到临时目录并校验数据库完整性和迁移版本，
+//! 关闭连接池后再替换数据库文件和发音缓存目录；替换时原文件先移到临时目录，失败时移回
+
+use crate::database::MIGRATOR;
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::repositories::backup_repository::BackupRepository;
+use crate::types::backup::*;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, Row, SqliteConnection, SqlitePool};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const DATABASE_NAME: &str = "vocabulary.db";
+const TTS_DIR_NAME: &str = "tts";
+/// 恢复时暂存原文件的子目录（位于恢复临时目录中）
+const PREVIOUS_DIR_NAME: &str = "previous";
+const FILE_PREFIX: &str = "redlark-backup-";
+
+/// 自动备份检查间隔
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 备份涉及的文件路径
+#[derive(Debug, Clone)]
+pub struct BackupPaths {
+    pub database: PathBuf,      // 数据库文件
+    pub tts_cache_dir: PathBuf, // TTS 发音缓存目录
+    pub backup_dir: PathBuf,    // 备份文件保存目录
+}
+
+impl BackupPaths {
+    /// 按应用的目录约定生成路径
+    pub fn new(app_data_dir: &Path, app_cache_dir: &Path) -> Self {
+        Self {
+            database: app_data_dir.join(DATABASE_NAME),
+            tts_cache_dir: app_cache_dir.join(TTS_DIR_NAME),
+            backup_dir: app_data_dir.join("backups"),
+        }
+    }
+}
+
+/// 已解压并校验通过、等待替换的备份
+#[derive(Debug)]
+pub struct StagedRestore {
+    pub manifest: BackupManifest,
+    staging_dir: PathBuf,
+}
+
+impl StagedRestore {
+    /// 放弃恢复，删除临时目录
+    pub fn discard(self) {
+        let _ = std::fs::remove_dir_all(&self.staging_dir);
+    }
+}
+
+/// 数据库备份服务
+pub struct BackupService {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+    paths: BackupPaths,
+}
+
+impl BackupService {
+    /// 创建新的服务实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>, paths: BackupPaths) -> Self {
+        Self {
+            pool,
+            logger,
+            paths,
+        }
+    }
+
+    fn repository(&self) -> BackupRepository {
+        BackupRepository::new(self.pool.clone(), self.logger.clone())
+    }
+
+    /// 创建备份（数据库 + TTS 发音缓存）
+    pub async fn create_backup(&self, kind: BackupKind) -> AppResult<BackupInfo> {
+        let repository = self.repository();
+        std::fs::create_dir_all(&self.paths.backup_dir)
+            .map_err(|e| AppError::InternalError(format!("创建备份目录失败: {}", e)))?;
+
+        let now = chrono::Local::now();
+        let file_name = format!(
+            "{}{}-{}.zip",
+            FILE_PREFIX,
+            now.format("%Y%m%d-%H%M%S-%3f"),
+            kind.as_str()
+        );
+        let archive_path = self.paths.backup_dir.join(&file_name);
+        let snapshot = self
+            .paths
+            .backup_dir
+            .join(format!(".snapshot-{}.db", uuid::Uuid::new_v4()));
+
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            kind,
+            created_at: now.to_rfc3339(),
+            migration_version: repository.current_migration_version().await?,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            tts_file_count: 0,
+        };
+
+        let result = match repository.vacuum_into(&snapshot).await {
+            Ok(()) => {
+                let snapshot = snapshot.clone();
+                let tts_dir = self.paths.tts_cache_dir.clone();
+                tokio::task::spawn_blocking(move || {
+                    write_archive(&archive_path, &snapshot, &tts_dir, manifest)
+                })
+                .await
+                .map_err(|e| AppError::InternalError(format!("写入备份文件失败: {}", e)))
+                .and_then(|result| result)
+            }
+            Err(e) => Err(e),
+        };
+        let _ = std::fs::remove_file(&snapshot);
+        let info = result?;
+
+        repository.mark_backup(&info.created_at).await?;
+        self.logger.info(
+            "BACKUP",
+            &format!(
+                "💾 Created {} backup {} ({} bytes, migration {}, {} audio files)",
+                kind.as_str(),
+                info.file_name,
+                info.file_size,
+                info.migration_version,
+                info.tts_file_count
+            ),
+        );
+
+        Ok(info)
+    }
+
+    /// 列出备份目录中的备份（最新的在前）
+    pub fn list_backups(&self) -> AppResult<Vec<BackupInfo>> {
+        let entries = match std::fs::read_dir(&self.paths.backup_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::InternalError(format!("读取备份目录失败: {}", e))),
+        };
+
+        let mut backups = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.starts_with(FILE_PREFIX) || !file_name.ends_with(".zip") {
+                continue;
+            }
+            match read_manifest(&path) {
+                Ok(manifest) => {
+                    let file_size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    backups.push(backup_info(&path, file_size, manifest));
+                }
+                Err(e) => self.logger.info(
+                    "BACKUP",
+                    &format!("⚠️ Skipping unreadable backup {}: {}", file_name, e),
+                ),
+            }
+        }
+
+        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(backups)
+    }
+
+    /// 清理超出保留数量的自动备份，返回删除的数量（手动备份不清理）
+    pub fn prune(&self, retention_count: usize) -> AppResult<usize> {
+        let expired: Vec<BackupInfo> = self
+            .list_backups()?
+            .into_iter()
+            .filter(|b| b.kind == BackupKind::Scheduled)
+            .skip(retention_count)
+            .collect();
+
+        for backup in &expired {
+            std::fs::remove_file(&backup.file_path)
+                .map_err(|e| AppError::InternalError(format!("删除过期备份失败: {}", e)))?;
+        }
+        if !expired.is_empty() {
+            self.logger.info(
+                "BACKUP",
+                &format!("🧹 Removed {} expired scheduled backups", expired.len()),
+            );
+        }
+
+        Ok(expired.len())
+    }
+
+    /// 获取自动备份设置
+    pub async fn get_settings(&self) -> AppResult<BackupSettings> {
+        self.repository().get_settings().await
+    }
+
+    /// 更新自动备份设置
+    pub async fn update_settings(
+        &self,
+        request: &UpdateBackupSettingsRequest,
+    ) -> AppResult<BackupSettings> {
+        if request.interval_hours.is_some_and(|hours| hours <= 0) {
+            return Err(AppError::ValidationError(
+                "自动备份间隔必须大于 0 小时".to_string(),
+            ));
+        }
+        if request.retention_count.is_some_and(|count| count <= 0) {
+            return Err(AppError::ValidationError(
+                "保留的自动备份数量必须大于 0".to_string(),
+            ));
+        }
+
+        self.repository().update_settings(request).await
+    }
+
+    /// 距上次备份已超过设置的间隔时创建自动备份并清理过期备份
+    pub async fn run_scheduled_if_due(&self) -> AppResult<Option<BackupInfo>> {
+        let settings = self.get_settings().await?;
+        if !settings.enabled {
+            return Ok(None);
+        }
+
+        let due = match settings
+            .last_backup_at
+            .as_deref()
+            .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+        {
+            Some(last) => {
+                chrono::Utc::now().signed_duration_since(last)
+                    >= chrono::Duration::hours(settings.interval_hours)
+            }
+            None => true,
+        };
+        if !due {
+            return Ok(None);
+        }
+
+        let info = self.create_backup(BackupKind::Scheduled).await?;
+        self.prune(settings.retention_count as usize)?;
+        Ok(Some(info))
+    }
+
+    /// 后台定时检查自动备份，连接池关闭后退出
+    pub async fn run_scheduler(self) {
+        while !self.pool.is_closed() {
+            if let Err(e) = self.run_scheduled_if_due().await {
+                self.logger
+                    .error("BACKUP", "Scheduled backup failed", Some(&e.to_string()));
+            }
+            tokio::time::sleep(SCHEDULER_INTERVAL).await;
+        }
+    }
+
+    /// 解压备份到临时目录并校验，校验通过后才能替换当前数据
+    pub async fn stage_restore(&self, archive_path: &Path) -> AppResult<StagedRestore> {
+        let manifest = read_manifest(archive_path)?;
+        let staging_dir = self
+            .paths
+            .backup_dir
+            .join(format!(".restore-{}", uuid::Uuid::new_v4()));
+        let staged = StagedRestore {
+            manifest,
+            staging_dir: staging_dir.clone(),
+        };
+
+        let archive = archive_path.to_path_buf();
+        let extracted =
+            tokio::task::spawn_blocking(move || extract_archive(&archive, &staging_dir))
+                .await
+                .map_err(|e| AppError::InternalError(format!("解压备份文件失败: {}", e)))
+                .and_then(|result| result);
+        let validated = match extracted {
+            Ok(()) => {
+                validate_database(
+                    &staged.staging_dir.join(DATABASE_NAME),
+                    &self.paths.tts_cache_dir,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        match validated {
+            Ok(version) => {
+                self.logger.info(
+                    "BACKUP",
+                    &format!(
+                        "📦 Staged backup {} (migration {}) for restore",
+                        archive_path.display(),
+                        version
+                    ),
+                );
+                Ok(staged)
+            }
+            Err(e) => {
+                staged.discard();
+                Err(e)
+            }
+        }
+    }
+
+    /// 用已校验的备份替换数据库和发音缓存（调用前必须关闭连接池）
+    pub fn apply_restore(&self, staged: StagedRestore) -> AppResult<()> {
+        let result = self.replace_files(&staged);
+        let manifest = staged.manifest.clone();
+        staged.discard();
+        result?;
+
+        self.logger.info(
+            "BACKUP",
+            &format!(
+                "♻️ Restored backup created at {} (migration {})",
+                manifest.created_at, manifest.migration_version
+            ),
+        );
+        Ok(())
+    }
+
+    /// 替换数据库和发音缓存文件
+    ///
+    /// 原文件（含 WAL 文件）先移到恢复临时目录，随临时目录一起删除；
+    /// 任一步失败时删除已移入的文件并把原文件移回，不会留下新旧混合的数据
+    fn replace_files(&self, staged: &StagedRestore) -> AppResult<()> {
+        let previous_dir = staged.staging_dir.join(PREVIOUS_DIR_NAME);
+        let mut set_aside = Vec::new();
+        let mut moved_in = Vec::new();
+
+        let result = self
+            .set_aside_current_files(&previous_dir, &mut set_aside)
+            .and_then(|_| self.move_in_staged_files(staged, &mut moved_in));
+        if let Err(e) = result {
+            if let Err(rollback_error) = roll_back_files(&moved_in, &set_aside) {
+                self.logger.error(
+                    "BACKUP",
+                    "Failed to put back the original files after a failed restore",
+                    Some(&rollback_error.to_string()),
+                );
+            }
+            return Err(AppError::InternalError(format!("恢复备份失败: {}", e)));
+        }
+
+        Ok(())
+    }
+
+    /// 将现有的数据库文件和发音缓存移到 `previous_dir`，记录 (原路径, 暂存路径)
+    fn set_aside_current_files(
+        &self,
+        previous_dir: &Path,
+        set_aside: &mut Vec<(PathBuf, PathBuf)>,
+    ) -> std::io::Result<()> {
+        std::fs::create_dir_all(previous_dir.join(TTS_DIR_NAME))?;
+
+        // 旧的 WAL 文件不能留给新数据库
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = self.paths.database.clone().into_os_string();
+            path.push(suffix);
+            let path = PathBuf::from(path);
+            if path.exists() {
+                let aside = previous_dir.join(path.file_name().unwrap_or_default());
+                move_file(&path, &aside)?;
+                set_aside.push((path, aside));
+            }
+        }
+
+        let tts_dir = &self.paths.tts_cache_dir;
+        if tts_dir.exists() {
+            for entry in std::fs::read_dir(tts_dir)?.flatten() {
+                if entry.path().is_file() {
+                    let aside = previous_dir.join(TTS_DIR_NAME).join(entry.file_name());
+                    move_file(&entry.path(), &aside)?;
+                    set_aside.push((entry.path(), aside));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 将校验过的备份文件移入，记录移入的路径
+    fn move_in_staged_files(
+        &self,
+        staged: &StagedRestore,
+        moved_in: &mut Vec<PathBuf>,
+    ) -> std::io::Result<()> {
+        move_file(
+            &staged.staging_dir.join(DATABASE_NAME),
+            &self.paths.database,
+        )?;
+        moved_in.push(self.paths.database.clone());
+
+        let tts_dir = &self.paths.tts_cache_dir;
+        let staged_tts = staged.staging_dir.join(TTS_DIR_NAME);
+        if staged_tts.exists() {
+            std::fs::create_dir_all(tts_dir)?;
+            for entry in std::fs::read_dir(&staged_tts)?.flatten() {
+                let target = tts_dir.join(entry.file_name());
+                move_file(&entry.path(), &target)?;
+                moved_in.push(target);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 恢复失败时删除已移入的文件，并把暂存的原文件移回原处
+fn roll_back_files(moved_in: &[PathBuf], set_aside: &[(PathBuf, PathBuf)]) -> std::io::Result<()> {
+    for path in moved_in {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    for (original, aside) in set_aside.iter().rev() {
+        move_file(aside, original)?;
+    }
+    Ok(())
+}
+
+/// 移动文件（跨文件系统时改为复制）
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+fn backup_info(path: &Path, file_size: u64, manifest: BackupManifest) -> BackupInfo {
+    BackupInfo {
+        file_name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: path.to_string_lossy().to_string(),
+        file_size,
+        kind: manifest.kind,
+        created_at: manifest.created_at,
+        migration_version: manifest.migration_version,
+        app_version: manifest.app_version,
+        tts_file_count: manifest.tts_file_count,
+    }
+}
+
+/// 写入备份压缩包（先写临时文件，完成后再改名，避免留下不完整的备份）
+fn write_archive(
+    archive_path: &Path,
+    snapshot: &Path,
+    tts_dir: &Path,
+    mut manifest: BackupManifest,
+) -> AppResult<BackupInfo> {
+    let zip_error =
+        |e: zip::result::ZipError| AppError::InternalError(format!("写入备份文件失败: {}", e));
+    let io_error = |e: std::io::Error| AppError::InternalError(format!("写入备份文件失败: {}", e));
+
+    let mut audio_files: Vec<PathBuf> = match std::fs::read_dir(tts_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    audio_files.sort();
+    manifest.tts_file_count = audio_files.len();
+
+    let mut partial = archive_path.to_path_buf().into_os_string();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+
+    let write = || -> AppResult<()> {
+        let deflated = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .large_file(true);
+        // 音频已经是压缩格式，直接存储
+        let stored = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+
+        let mut zip = zip::ZipWriter::new(File::create(&partial).map_err(io_error)?);
+        zip.start_file(MANIFEST_NAME, deflated).map_err(zip_error)?;
+        serde_json::to_writer_pretty(&mut zip, &manifest)
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        zip.start_file(DATABASE_NAME, deflated).map_err(zip_error)?;
+        std::io::copy(&mut File::open(snapshot).map_err(io_error)?, &mut zip).map_err(io_error)?;
+
+        for path in &audio_files {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            zip.start_file(format!("{}/{}", TTS_DIR_NAME, name), stored)
+                .map_err(zip_error)?;
+            std::io::copy(&mut File::open(path).map_err(io_error)?, &mut zip).map_err(io_error)?;
+        }
+
+        zip.finish().map_err(zip_error)?;
+        std::fs::rename(&partial, archive_path).map_err(io_error)
+    };
+    if let Err(e) = write() {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    let file_size = std::fs::metadata(archive_path)
+        .map(|m| m.len())
+        .unwrap_or(0);
+    Ok(backup_info(archive_path, file_size, manifest))
+}
+
+/// 读取备份压缩包中的清单
+pub fn read_manifest(archive_path: &Path) -> AppResult<BackupManifest> {
+    let invalid = |e: String| AppError::ValidationError(format!("不是有效的备份文件: {}", e));
+
+    let file = File::open(archive_path)
+        .map_err(|e| AppError::NotFound(format!("备份文件不存在: {}", e)))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| invalid(e.to_string()))?;
+    let manifest: BackupManifest = serde_json::from_reader(
+        archive
+            .by_name(MANIFEST_NAME)
+            .map_err(|e| invalid(e.to_string()))?,
+    )
+    .map_err(|e| invalid(e.to_string()))?;
+
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::ValidationError(format!(
+            "备份文件格式版本 {} 高于当前应用支持的版本，请升级应用后再恢复",
+            manifest.format_version
+        )));
+    }
+    Ok(manifest)
+}
+
+/// 解压数据库和发音缓存到临时目录（只接受备份中约定的文件）
+fn extract_archive(archive_path: &Path, staging_dir: &Path) -> AppResult<()> {
+    let invalid = |e: String| AppError::ValidationError(format!("不是有效的备份文件: {}", e));
+    let io_error = |e: std::io::Error| AppError::InternalError(format!("解压备份文件失败: {}", e));
+
+    let file = File::open(archive_path)
+        .map_err(|e| AppError::NotFound(format!("备份文件不存在: {}", e)))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| invalid(e.to_string()))?;
+    std::fs::create_dir_all(staging_dir.join(TTS_DIR_NAME)).map_err(io_error)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| invalid(e.to_string()))?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = if name == Path::new(DATABASE_NAME) {
+            staging_dir.join(DATABASE_NAME)
+        } else if entry.is_file()
+            && name.parent() == Some(Path::new(TTS_DIR_NAME))
+            && name.file_name().is_some()
+        {
+            staging_dir.join(&name)
+        } else {
+            continue;
+        };
+
+        let mut output = File::create(&target).map_err(io_error)?;
+        std::io::copy(&mut entry, &mut output).map_err(io_error)?;
+    }
+
+    if !staging_dir.join(DATABASE_NAME).exists() {
+        return Err(invalid("缺少数据库文件".to_string()));
+    }
+    Ok(())
+}
+
+/// 校验备份数据库的完整性和迁移版本，并把发音缓存路径改为当前缓存目录，返回最新迁移版本
+async fn validate_database(database: &Path, tts_dir: &Path) -> AppResult<i64> {
+    let mut conn = SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(database))
+        .await
+        .map_err(|e| AppError::ValidationError(format!("无法打开备份数据库: {}", e)))?;
+    let result = check_database(&mut conn, tts_dir).await;
+    let _ = conn.close().await;
+    result
+}
+
+async fn check_database(conn: &mut SqliteConnection, tts_dir: &Path) -> AppResult<i64> {
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::ValidationError(format!("备份数据库已损坏: {}", e)))?;
+    if integrity != "ok" {
+        return Err(AppError::ValidationError(format!(
+            "备份数据库已损坏: {}",
+            integrity
+        )));
+    }
+
+    let applied: Vec<(i64, Vec<u8>)> = sqlx::query_as(
+        "SELECT version, checksum FROM _sqlx_migrations WHERE success = 1 ORDER BY version",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|_| AppError::ValidationError("备份数据库缺少迁移记录".to_string()))?;
+
+    // 备份中的每个迁移都必须是当前应用内置的同一个迁移，缺少的迁移在重启后补上
+    for (version, checksum) in &applied {
+        match MIGRATOR
+            .iter()
+            .find(|m| m.version == *version && !m.migration_type.is_down_migration())
+        {
+            None => {
+                return Err(AppError::ValidationError(format!(
+                    "备份的数据库版本（迁移 {}）高于当前应用，请升级应用后再恢复",
+                    version
+                )))
+            }
+            Some(migration) if migration.checksum.as_ref() != checksum.as_slice() => {
+                return Err(AppError::ValidationError(format!(
+                    "备份中的迁移 {} 与当前应用不一致，无法恢复",
+                    version
+                )))
+            }
+            Some(_) => {}
+        }
+    }
+    let Some((latest, _)) = applied.last() else {
+        return Err(AppError::ValidationError(
+            "备份数据库缺少迁移记录".to_string(),
+        ));
+    };
+
+    // 发音缓存记录的是绝对路径，改为恢复后的缓存目录
+    let cached = sqlx::query("SELECT id, text_hash, file_path FROM tts_cache")
+        .fetch_all(&mut *conn)
+        .await?;
+    for row in cached {
+        let id: i64 = row.get("id");
+        let text_hash: String = row.get("text_hash");
+        let file_path: String = row.get("file_path");
+        let file_name = file_path
+            .rsplit(['/', '\\'])
+            .next()
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}.mp3", text_hash));
+        sqlx::query("UPDATE tts_cache SET file_path = ? WHERE id = ?")
+            .bind(tts_dir.join(file_name).to_string_lossy().to_string())
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(*latest)
+}
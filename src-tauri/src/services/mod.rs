@@ -7,14 +7,25 @@
 //! - 数据验证和转换
 
 pub mod ai_model;
+pub mod ai_usage;
 pub mod analysis;
+pub mod backup;
 pub mod calendar;
 pub mod diagnostics;
 pub mod practice;
+pub mod prompt;
+pub mod pronunciation;
+pub mod snapshot;
+pub mod spaced_repetition;
 pub mod statistics;
 pub mod study_plan;
+pub mod study_plan_rebalancer;
+pub mod study_plan_scheduler;
+pub mod study_plan_validator;
 pub mod theme_tag;
 pub mod word;
+pub mod word_export;
+pub mod word_import;
 pub mod wordbook;
 
 // 重新导出服务
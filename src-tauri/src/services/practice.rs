@@ -13,11 +13,15 @@ use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
 use crate::repositories::{
     practice_repository::PracticeRepository,
+    review_state_repository::ReviewStateRepository,
     study_plan_repository::StudyPlanRepository,
     study_schedule_repository::StudyScheduleRepository,
 };
+use crate::services::spaced_repetition::{self, DATE_FORMAT};
 use crate::types::study::*;
+use chrono::NaiveDate;
 use sqlx::SqlitePool;
+use std::collections::HashSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -28,6 +32,7 @@ pub struct PracticeService {
     practice_repo: PracticeRepository,
     schedule_repo: StudyScheduleRepository,
     plan_repo: StudyPlanRepository,
+    review_repo: ReviewStateRepository,
 }
 
 impl PracticeService {
@@ -36,11 +41,13 @@ impl PracticeService {
         practice_repo: PracticeRepository,
         schedule_repo: StudyScheduleRepository,
         plan_repo: StudyPlanRepository,
+        review_repo: ReviewStateRepository,
     ) -> Self {
         Self {
             practice_repo,
             schedule_repo,
             plan_repo,
+            review_repo,
         }
     }
 
@@ -48,8 +55,9 @@ impl PracticeService {
     pub fn from_pool_and_logger(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
         let practice_repo = PracticeRepository::new(pool.clone(), logger.clone());
         let schedule_repo = StudyScheduleRepository::new(pool.clone(), logger.clone());
-        let plan_repo = StudyPlanRepository::new(pool, logger);
-        Self::new(practice_repo, schedule_repo, plan_repo)
+        let plan_repo = StudyPlanRepository::new(pool.clone(), logger.clone());
+        let review_repo = ReviewStateRepository::new(pool, logger);
+        Self::new(practice_repo, schedule_repo, plan_repo, review_repo)
     }
 
     /// 开始练习会话
@@ -387,6 +395,16 @@ impl PracticeService {
         Ok(stats)
     }
 
+    /// 获取学习计划的单词记忆状态
+    pub async fn get_word_review_states(&self, plan_id: i64) -> AppResult<Vec<WordReviewState>> {
+        self.review_repo.find_by_plan(plan_id).await
+    }
+
+    /// 设置学习计划的复习算法
+    pub async fn set_review_algorithm(&self, plan_id: i64, algorithm: ReviewAlgorithm) -> AppResult<()> {
+        self.review_repo.update_algorithm(plan_id, algorithm).await
+    }
+
     // ==================== 辅助方法 ====================
 
     /// 获取单词练习状态 (保留用于向后兼容)
@@ -462,10 +480,72 @@ impl PracticeService {
         })
     }
 
-    /// 更新日程进度
-    async fn update_schedule_progress(&self, _schedule_id: i64, _result: &PracticeResult) -> AppResult<()> {
-        // TODO: 实现日程进度更新逻辑
-        // 这个需要使用 StudyScheduleRepository
+    /// 更新日程进度，并根据练习结果更新记忆状态、重新安排后续复习
+    async fn update_schedule_progress(&self, schedule_id: i64, result: &PracticeResult) -> AppResult<()> {
+        let word_states: Vec<&WordPracticeState> = result
+            .passed_words_list
+            .iter()
+            .chain(result.difficult_words.iter())
+            .collect();
+
+        // 1. 累加日程完成单词数，完成数达到日程单词总数时日程才算完成
+        let completed_count = word_states.iter().filter(|w| w.completed).count();
+        self.schedule_repo
+            .add_progress(schedule_id, completed_count as i32)
+            .await?;
+
+        // 2. 间隔重复调度
+        let algorithm = self.review_repo.find_algorithm(result.plan_id).await?;
+        let today = chrono::Local::now().date_naive();
+        // 提前练习未来日程时，以日程日期为准，避免把复习排回已练习的日期之前
+        let reviewed_on = NaiveDate::parse_from_str(&result.schedule_date, DATE_FORMAT)
+            .map(|d| d.max(today))
+            .unwrap_or(today);
+        let after_date = reviewed_on.format(DATE_FORMAT).to_string();
+        let plan_last_date = self
+            .review_repo
+            .find_last_schedule_date(result.plan_id)
+            .await?
+            .and_then(|d| NaiveDate::parse_from_str(&d, DATE_FORMAT).ok())
+            .unwrap_or(reviewed_on);
+
+        let mut seen_words = HashSet::new();
+        for word_state in word_states {
+            let grade = match spaced_repetition::grade_from_practice(word_state) {
+                Some(grade) => grade,
+                None => continue,
+            };
+            // 同一日程中单词可能同时作为新词和复习词出现，只调度一次
+            if !seen_words.insert(word_state.word_id) {
+                continue;
+            }
+
+            let mut review_state = self
+                .review_repo
+                .find_state(result.plan_id, word_state.word_id)
+                .await?
+                .unwrap_or_else(|| WordReviewState::new(result.plan_id, word_state.word_id));
+
+            spaced_repetition::apply_review(algorithm, &mut review_state, grade, reviewed_on);
+
+            let review_dates: Vec<String> =
+                spaced_repetition::project_review_dates(algorithm, &review_state, plan_last_date)
+                    .into_iter()
+                    .map(|d| d.format(DATE_FORMAT).to_string())
+                    .collect();
+            let mastery = spaced_repetition::mastery_score(algorithm, &review_state);
+
+            self.review_repo
+                .save_state_and_replan(
+                    &review_state,
+                    word_state.passed,
+                    mastery,
+                    &after_date,
+                    &review_dates,
+                )
+                .await?;
+        }
+
         Ok(())
     }
 
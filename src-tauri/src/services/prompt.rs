@@ -0,0 +1,142 @@
+//! 提示词模板业务逻辑服务
+//!
+//! 管理应用数据目录中的自定义提示词模板，并把用过的每个版本记录到历史中
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::prompt_registry::{default_template, get_prompt_registry, PromptRegistry};
+use crate::repositories::prompt_version_repository::PromptVersionRepository;
+use crate::types::prompt::{PromptName, PromptTemplate, PromptVersion};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+/// 提示词模板服务
+pub struct PromptService {
+    registry: Arc<PromptRegistry>,
+    repository: PromptVersionRepository,
+    logger: Arc<Logger>,
+}
+
+impl PromptService {
+    /// 使用全局注册表创建服务实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self::with_registry(pool, logger, get_prompt_registry())
+    }
+
+    /// 使用指定注册表创建服务实例
+    pub fn with_registry(
+        pool: Arc<SqlitePool>,
+        logger: Arc<Logger>,
+        registry: Arc<PromptRegistry>,
+    ) -> Self {
+        Self {
+            registry,
+            repository: PromptVersionRepository::new(pool, logger.clone()),
+            logger,
+        }
+    }
+
+    /// 解析提示词名称
+    pub fn parse_name(name: &str) -> AppResult<PromptName> {
+        PromptName::parse(name)
+            .ok_or_else(|| AppError::ValidationError(format!("未知的提示词: {}", name)))
+    }
+
+    /// 获取所有当前生效的模板，并记录其版本
+    pub async fn get_templates(&self) -> AppResult<Vec<PromptTemplate>> {
+        let mut templates = Vec::with_capacity(PromptName::ALL.len());
+        for name in PromptName::ALL {
+            templates.push(self.current(name).await?);
+        }
+        Ok(templates)
+    }
+
+    /// 获取当前生效的模板，并记录其版本（手动修改的模板文件在此时进入历史）
+    pub async fn current(&self, name: PromptName) -> AppResult<PromptTemplate> {
+        let template = self.registry.load(name);
+        if let Some(error) = &template.custom_error {
+            self.logger.info(
+                "PROMPT_SERVICE",
+                &format!("⚠️  Using built-in {} prompt: {}", name.as_str(), error),
+            );
+        }
+        self.repository.record(&template).await?;
+        Ok(template)
+    }
+
+    /// 保存自定义模板，旧版本保留在历史中
+    pub async fn save_template(
+        &self,
+        name: PromptName,
+        content: &str,
+    ) -> AppResult<PromptTemplate> {
+        // 先记录当前版本，保证被覆盖的内容可以找回
+        self.current(name).await?;
+
+        let template = self.registry.save(name, content)?;
+        self.repository.record(&template).await?;
+        self.logger.info(
+            "PROMPT_SERVICE",
+            &format!(
+                "Saved custom {} prompt version {}",
+                name.as_str(),
+                template.version
+            ),
+        );
+        Ok(template)
+    }
+
+    /// 恢复为内置模板
+    pub async fn reset_template(&self, name: PromptName) -> AppResult<PromptTemplate> {
+        self.current(name).await?;
+
+        let template = self.registry.reset(name)?;
+        self.repository.record(&template).await?;
+        self.logger.info(
+            "PROMPT_SERVICE",
+            &format!(
+                "Reset {} prompt to built-in version {}",
+                name.as_str(),
+                template.version
+            ),
+        );
+        Ok(template)
+    }
+
+    /// 获取历史版本（最新的在前）
+    pub async fn get_versions(&self, name: PromptName) -> AppResult<Vec<PromptVersion>> {
+        self.current(name).await?;
+        self.repository.find_by_name(name).await
+    }
+
+    /// 恢复到历史版本
+    pub async fn restore_version(
+        &self,
+        name: PromptName,
+        version: &str,
+    ) -> AppResult<PromptTemplate> {
+        let history = self
+            .repository
+            .find_version(name, version)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "提示词 {} 的版本 {} 不存在",
+                    name.as_str(),
+                    version
+                ))
+            })?;
+
+        // 恢复内置版本时删除自定义模板，避免内置模板更新后仍停留在旧内容
+        if history.content == default_template(name) {
+            self.reset_template(name).await
+        } else {
+            self.save_template(name, &history.content).await
+        }
+    }
+
+    /// 记录所有模板的当前版本（应用启动时调用）
+    pub async fn record_current_versions(&self) -> AppResult<()> {
+        self.get_templates().await.map(|_| ())
+    }
+}
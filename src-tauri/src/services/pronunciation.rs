@@ -0,0 +1,248 @@
+//! 发音词典业务逻辑服务
+//!
+//! 导入 CMUdict 格式的发音文件，查询单词的美式/英式音标，
+//! 并在保存单词前用词典补全空缺的音标、校验 AI 给出的音标
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::pronunciation::{arpabet_to_ipa, format_ipa, ipa_matches, parse_cmudict};
+use crate::repositories::pronunciation_repository::PronunciationRepository;
+use crate::types::pronunciation::{
+    IpaCheckItem, IpaCheckReport, IpaConvention, IpaMismatch, PronunciationEntry,
+    PronunciationImportResult, PronunciationLookup,
+};
+use crate::types::wordbook::{AnalyzedWord, CreateWordRequest};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 单个单词的校验结果
+enum IpaResolution {
+    /// 没有音标，可用词典读音补全
+    Fill(String),
+    Matched,
+    Mismatch(IpaMismatch),
+    NotFound,
+}
+
+/// 发音词典服务
+pub struct PronunciationService {
+    repository: PronunciationRepository,
+    logger: Arc<Logger>,
+    convention: IpaConvention,
+}
+
+impl PronunciationService {
+    /// 创建新的服务实例（补全音标时使用美式音标）
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self {
+            repository: PronunciationRepository::new(pool, logger.clone()),
+            logger,
+            convention: IpaConvention::default(),
+        }
+    }
+
+    /// 指定补全音标时使用的音标体系
+    pub fn with_convention(mut self, convention: IpaConvention) -> Self {
+        self.convention = convention;
+        self
+    }
+
+    /// 解析音标体系，未指定时使用美式音标
+    pub fn parse_convention(convention: Option<&str>) -> AppResult<IpaConvention> {
+        match convention {
+            None => Ok(IpaConvention::default()),
+            Some(value) => IpaConvention::parse(value)
+                .ok_or_else(|| AppError::ValidationError(format!("未知的音标体系: {}", value))),
+        }
+    }
+
+    /// 导入 CMUdict 格式的发音文件
+    pub async fn import_cmudict(&self, content: &str) -> AppResult<PronunciationImportResult> {
+        let parsed = parse_cmudict(content);
+        if parsed.entries.is_empty() {
+            return Err(AppError::ValidationError(
+                "发音文件中没有可导入的读音".to_string(),
+            ));
+        }
+
+        let imported = self.repository.import(&parsed.entries).await?;
+        let total_entries = self.repository.count().await?;
+        self.logger.info(
+            "PRONUNCIATION",
+            &format!(
+                "📖 Imported {} pronunciations ({} lines skipped)",
+                imported, parsed.skipped_lines
+            ),
+        );
+
+        Ok(PronunciationImportResult {
+            imported,
+            skipped_lines: parsed.skipped_lines,
+            total_entries,
+        })
+    }
+
+    /// 词典中的条目总数
+    pub async fn count(&self) -> AppResult<i64> {
+        self.repository.count().await
+    }
+
+    /// 查询单词的全部读音
+    pub async fn lookup(&self, word: &str) -> AppResult<Vec<PronunciationLookup>> {
+        let entries = self.repository.find_by_word(word).await?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| {
+                Some(PronunciationLookup {
+                    us_ipa: format_ipa(&arpabet_to_ipa(&entry.arpabet, IpaConvention::Us)?),
+                    uk_ipa: format_ipa(&arpabet_to_ipa(&entry.arpabet, IpaConvention::Uk)?),
+                    word: entry.word,
+                    variant: entry.variant,
+                    arpabet: entry.arpabet,
+                })
+            })
+            .collect())
+    }
+
+    /// 校验音标，不修改输入
+    pub async fn check(&self, items: &[IpaCheckItem]) -> AppResult<IpaCheckReport> {
+        let dictionary = self
+            .find_entries(items.iter().map(|i| i.word.as_str()))
+            .await?;
+        let mut report = IpaCheckReport::default();
+
+        for item in items {
+            match self.resolve(&item.word, item.ipa.as_deref(), &dictionary) {
+                IpaResolution::Fill(ipa) => report.filled.push(IpaCheckItem {
+                    word: item.word.clone(),
+                    ipa: Some(ipa),
+                }),
+                IpaResolution::Matched => {
+                    report.checked += 1;
+                    report.matched += 1;
+                }
+                IpaResolution::Mismatch(mismatch) => {
+                    report.checked += 1;
+                    report.mismatches.push(mismatch);
+                }
+                IpaResolution::NotFound => report.not_found.push(item.word.clone()),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 空缺的音标用词典补全；已有音标与词典不一致时返回差异（保留原音标）
+    pub async fn fill_or_check(
+        &self,
+        word: &str,
+        ipa: &mut Option<String>,
+    ) -> AppResult<Option<IpaMismatch>> {
+        let dictionary = self.find_entries(std::iter::once(word)).await?;
+        Ok(self.apply(word, ipa, &dictionary))
+    }
+
+    /// 批量处理 AI 分析结果的音标，返回与词典不一致的单词
+    pub async fn fill_or_check_analyzed(
+        &self,
+        words: &mut [AnalyzedWord],
+    ) -> AppResult<Vec<IpaMismatch>> {
+        self.fill_or_check_batch(words.iter_mut().map(|w| (w.word.as_str(), &mut w.ipa)))
+            .await
+    }
+
+    /// 批量处理待保存单词的音标，返回与词典不一致的单词
+    pub async fn fill_or_check_words(
+        &self,
+        words: &mut [CreateWordRequest],
+    ) -> AppResult<Vec<IpaMismatch>> {
+        self.fill_or_check_batch(words.iter_mut().map(|w| (w.word.as_str(), &mut w.ipa)))
+            .await
+    }
+
+    async fn fill_or_check_batch<'a>(
+        &self,
+        words: impl Iterator<Item = (&'a str, &'a mut Option<String>)>,
+    ) -> AppResult<Vec<IpaMismatch>> {
+        let mut words: Vec<_> = words.collect();
+        let dictionary = self.find_entries(words.iter().map(|(w, _)| *w)).await?;
+        let mismatches: Vec<IpaMismatch> = words
+            .iter_mut()
+            .filter_map(|(word, ipa)| self.apply(word, ipa, &dictionary))
+            .collect();
+
+        if !mismatches.is_empty() {
+            self.logger.info(
+                "PRONUNCIATION",
+                &format!("⚠️  {} 个单词的音标与发音词典不一致", mismatches.len()),
+            );
+        }
+        Ok(mismatches)
+    }
+
+    async fn find_entries<'a>(
+        &self,
+        words: impl Iterator<Item = &'a str>,
+    ) -> AppResult<HashMap<String, Vec<PronunciationEntry>>> {
+        let words: Vec<String> = words.map(|w| w.trim().to_lowercase()).collect();
+        self.repository.find_by_words(&words).await
+    }
+
+    fn apply(
+        &self,
+        word: &str,
+        ipa: &mut Option<String>,
+        dictionary: &HashMap<String, Vec<PronunciationEntry>>,
+    ) -> Option<IpaMismatch> {
+        match self.resolve(word, ipa.as_deref(), dictionary) {
+            IpaResolution::Fill(value) => {
+                *ipa = Some(value);
+                None
+            }
+            IpaResolution::Mismatch(mismatch) => Some(mismatch),
+            IpaResolution::Matched | IpaResolution::NotFound => None,
+        }
+    }
+
+    fn resolve(
+        &self,
+        word: &str,
+        ipa: Option<&str>,
+        dictionary: &HashMap<String, Vec<PronunciationEntry>>,
+    ) -> IpaResolution {
+        let Some(entries) = dictionary.get(&word.trim().to_lowercase()) else {
+            return IpaResolution::NotFound;
+        };
+
+        // 首选音标体系的读音在前，补全时使用默认读音
+        let other = match self.convention {
+            IpaConvention::Us => IpaConvention::Uk,
+            IpaConvention::Uk => IpaConvention::Us,
+        };
+        let mut candidates: Vec<String> = Vec::new();
+        for convention in [self.convention, other] {
+            for entry in entries {
+                if let Some(value) = arpabet_to_ipa(&entry.arpabet, convention) {
+                    let value = format_ipa(&value);
+                    if !candidates.contains(&value) {
+                        candidates.push(value);
+                    }
+                }
+            }
+        }
+        let Some(preferred) = candidates.first().cloned() else {
+            return IpaResolution::NotFound;
+        };
+
+        match ipa.map(str::trim).filter(|v| !v.is_empty()) {
+            None => IpaResolution::Fill(preferred),
+            Some(value) if ipa_matches(value, &candidates) => IpaResolution::Matched,
+            Some(value) => IpaResolution::Mismatch(IpaMismatch {
+                word: word.to_string(),
+                ai_ipa: value.to_string(),
+                dictionary_ipa: candidates,
+            }),
+        }
+    }
+}
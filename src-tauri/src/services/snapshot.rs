@@ -0,0 +1,112 @@
+//! 破坏性操作快照与撤销业务逻辑服务
+//!
+//! 重置数据、删除单词本/学习计划、批量移除计划单词之前先保存受影响的行，
+//! 之后可以撤销最近一次尚未撤销的操作
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::repositories::operation_snapshot_repository::{
+    OperationSnapshotRepository, SnapshotScope,
+};
+use crate::repositories::study_plan_repository::StudyPlanRepository;
+use crate::types::common::Id;
+use crate::types::snapshot::*;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+/// 操作快照服务
+pub struct SnapshotService {
+    repository: OperationSnapshotRepository,
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl SnapshotService {
+    /// 创建新的服务实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self {
+            repository: OperationSnapshotRepository::new(pool.clone(), logger.clone()),
+            pool,
+            logger,
+        }
+    }
+
+    /// 在破坏性操作之前保存快照
+    pub async fn capture(
+        &self,
+        operation: SnapshotOperation,
+        target_id: Option<Id>,
+        description: &str,
+        scopes: &[SnapshotScope],
+    ) -> AppResult<Id> {
+        let snapshot_id = self
+            .repository
+            .capture(operation, target_id, description, scopes)
+            .await?;
+        self.logger.info(
+            "SNAPSHOT",
+            &format!("📸 Snapshot {} saved before {}", snapshot_id, description),
+        );
+        Ok(snapshot_id)
+    }
+
+    /// 操作结束后调用：操作没有成功时删除快照，避免撤销一个没有发生的操作
+    pub async fn finish(&self, snapshot_id: Id, succeeded: bool) {
+        if succeeded {
+            return;
+        }
+        if let Err(e) = self.repository.discard(snapshot_id).await {
+            self.logger.error(
+                "SNAPSHOT",
+                &format!("Failed to discard snapshot {}", snapshot_id),
+                Some(&e.to_string()),
+            );
+        }
+    }
+
+    /// 获取快照历史（最新的在前）
+    pub async fn get_snapshots(&self) -> AppResult<Vec<OperationSnapshot>> {
+        self.repository.find_all().await
+    }
+
+    /// 撤销最近一次尚未撤销的破坏性操作
+    pub async fn undo_last(&self) -> AppResult<UndoOperationResult> {
+        let snapshot = self
+            .repository
+            .find_latest_active()
+            .await?
+            .ok_or_else(|| AppError::NotFound("没有可以撤销的操作".to_string()))?;
+
+        let restored_rows = self.repository.restore(snapshot.id).await?;
+
+        // 删除学习计划时记录过状态历史，撤销时补一条反向记录
+        if let (SnapshotOperation::DeleteStudyPlan, Some(plan_id)) =
+            (snapshot.operation, snapshot.target_id)
+        {
+            let plan_repository = StudyPlanRepository::new(self.pool.clone(), self.logger.clone());
+            if let Some((_, unified_status)) = plan_repository.find_status(plan_id).await? {
+                plan_repository
+                    .add_status_history(plan_id, "Deleted", &unified_status, "撤销删除学习计划")
+                    .await?;
+            }
+        }
+
+        self.logger.info(
+            "SNAPSHOT",
+            &format!(
+                "↩️ Undid {} ({} rows restored)",
+                snapshot.description, restored_rows
+            ),
+        );
+
+        let snapshot = self
+            .repository
+            .find_by_id(snapshot.id)
+            .await?
+            .unwrap_or(snapshot);
+        Ok(UndoOperationResult {
+            snapshot,
+            restored_rows,
+        })
+    }
+}
@@ -0,0 +1,265 @@
+//! 间隔重复调度算法
+//!
+//! 根据练习结果更新单词的记忆状态并计算下次复习日期
+//!
+//! # 算法
+//! - SM-2: 经典 SuperMemo 算法，维护难度因子和复习间隔
+//! - FSRS: 基于记忆稳定性/难度的调度（FSRS-4.5 默认参数）
+//!
+//! 本模块只包含纯计算逻辑，不涉及数据库访问
+
+use crate::types::study::{ReviewAlgorithm, WordPracticeState, WordReviewState};
+use chrono::{Duration, NaiveDate};
+
+/// 日期格式 YYYY-MM-DD
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 预测后续复习时假定的评分（正常记住）
+const PROJECTED_GRADE: i32 = 4;
+
+/// 单个单词最多预排的复习次数
+const MAX_PROJECTED_REVIEWS: usize = 8;
+
+/// SM-2 难度因子下限
+const MIN_EASE_FACTOR: f64 = 1.3;
+
+/// FSRS-4.5 默认参数
+const FSRS_WEIGHTS: [f64; 17] = [
+    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461,
+    2.1072, 0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+];
+const FSRS_DECAY: f64 = -0.5;
+const FSRS_FACTOR: f64 = 19.0 / 81.0;
+const FSRS_DESIRED_RETENTION: f64 = 0.9;
+
+/// 根据三步练习结果计算评分（0-5）
+///
+/// 未完成三步的单词返回 None，不参与调度
+pub fn grade_from_practice(state: &WordPracticeState) -> Option<i32> {
+    if !state.completed {
+        return None;
+    }
+
+    let correct_steps = state.step_results.iter().filter(|&&r| r).count();
+    let retried = state.step_attempts.iter().any(|&a| a > 1);
+
+    let grade = match correct_steps {
+        3 if !retried => 5,
+        3 => 4,
+        2 => 3,
+        1 => 2,
+        _ => 1,
+    };
+
+    Some(grade)
+}
+
+/// 应用一次复习，更新记忆状态并设置下次到期日期
+pub fn apply_review(
+    algorithm: ReviewAlgorithm,
+    state: &mut WordReviewState,
+    grade: i32,
+    today: NaiveDate,
+) {
+    let grade = grade.clamp(0, 5);
+
+    match algorithm {
+        ReviewAlgorithm::Sm2 => sm2_review(state, grade),
+        ReviewAlgorithm::Fsrs => fsrs_review(state, grade, today),
+    }
+
+    if grade < 3 {
+        state.lapses += 1;
+    }
+
+    state.last_grade = Some(grade);
+    state.last_reviewed_at = Some(today.format(DATE_FORMAT).to_string());
+    state.due_date =
+        Some((today + Duration::days(state.interval_days as i64)).format(DATE_FORMAT).to_string());
+}
+
+/// 预测截止日期前的后续复习日期
+///
+/// 从当前到期日开始，假定每次复习都正常记住，依次推算后续复习
+pub fn project_review_dates(
+    algorithm: ReviewAlgorithm,
+    state: &WordReviewState,
+    until: NaiveDate,
+) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    let mut projected = state.clone();
+
+    while dates.len() < MAX_PROJECTED_REVIEWS {
+        let due = match projected
+            .due_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).ok())
+        {
+            Some(due) => due,
+            None => break,
+        };
+
+        if due > until {
+            break;
+        }
+
+        dates.push(due);
+        apply_review(algorithm, &mut projected, PROJECTED_GRADE, due);
+    }
+
+    dates
+}
+
+/// 计算掌握度（0.0-1.0）
+pub fn mastery_score(algorithm: ReviewAlgorithm, state: &WordReviewState) -> f64 {
+    let score = match algorithm {
+        // 间隔达到 21 天视为完全掌握
+        ReviewAlgorithm::Sm2 => state.interval_days as f64 / 21.0,
+        ReviewAlgorithm::Fsrs => state.stability / 21.0,
+    };
+    score.clamp(0.0, 1.0)
+}
+
+/// SM-2 调度
+fn sm2_review(state: &mut WordReviewState, grade: i32) {
+    if grade >= 3 {
+        state.interval_days = match state.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (state.interval_days as f64 * state.ease_factor).round() as i32,
+        };
+        state.repetitions += 1;
+    } else {
+        // 答错：重新开始，次日复习
+        state.repetitions = 0;
+        state.interval_days = 1;
+    }
+
+    let q = (5 - grade) as f64;
+    state.ease_factor = (state.ease_factor + (0.1 - q * (0.08 + q * 0.02))).max(MIN_EASE_FACTOR);
+}
+
+/// FSRS 调度
+fn fsrs_review(state: &mut WordReviewState, grade: i32, today: NaiveDate) {
+    let w = &FSRS_WEIGHTS;
+    // 0-5 评分映射为 FSRS 的 Again/Hard/Good/Easy (1-4)
+    let rating = match grade {
+        0..=2 => 1,
+        3 => 2,
+        4 => 3,
+        _ => 4,
+    };
+
+    if state.stability <= 0.0 {
+        // 首次复习
+        state.stability = w[rating - 1];
+        state.difficulty = fsrs_initial_difficulty(rating);
+    } else {
+        let elapsed_days = state
+            .last_reviewed_at
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).ok())
+            .map(|last| (today - last).num_days().max(0) as f64)
+            .unwrap_or(0.0);
+        let retrievability = (1.0 + FSRS_FACTOR * elapsed_days / state.stability).powf(FSRS_DECAY);
+
+        let next_difficulty = state.difficulty - w[6] * (rating as f64 - 3.0);
+        state.difficulty = (w[7] * fsrs_initial_difficulty(3) + (1.0 - w[7]) * next_difficulty)
+            .clamp(1.0, 10.0);
+
+        state.stability = if rating == 1 {
+            let forget_stability = w[11]
+                * state.difficulty.powf(-w[12])
+                * ((state.stability + 1.0).powf(w[13]) - 1.0)
+                * (w[14] * (1.0 - retrievability)).exp();
+            forget_stability.min(state.stability)
+        } else {
+            let hard_penalty = if rating == 2 { w[15] } else { 1.0 };
+            let easy_bonus = if rating == 4 { w[16] } else { 1.0 };
+            state.stability
+                * (w[8].exp()
+                    * (11.0 - state.difficulty)
+                    * state.stability.powf(-w[9])
+                    * ((w[10] * (1.0 - retrievability)).exp() - 1.0)
+                    * hard_penalty
+                    * easy_bonus
+                    + 1.0)
+        };
+    }
+
+    if rating == 1 {
+        state.repetitions = 0;
+    } else {
+        state.repetitions += 1;
+    }
+
+    let interval = state.stability / FSRS_FACTOR
+        * (FSRS_DESIRED_RETENTION.powf(1.0 / FSRS_DECAY) - 1.0);
+    state.interval_days = (interval.round() as i32).max(1);
+}
+
+/// FSRS 初始难度
+fn fsrs_initial_difficulty(rating: usize) -> f64 {
+    (FSRS_WEIGHTS[4] - (rating as f64 - 3.0) * FSRS_WEIGHTS[5]).clamp(1.0, 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_sm2_intervals_grow_on_success() {
+        let mut state = WordReviewState::new(1, 1);
+        apply_review(ReviewAlgorithm::Sm2, &mut state, 5, date("2025-01-01"));
+        assert_eq!(state.interval_days, 1);
+        assert_eq!(state.due_date.as_deref(), Some("2025-01-02"));
+
+        apply_review(ReviewAlgorithm::Sm2, &mut state, 5, date("2025-01-02"));
+        assert_eq!(state.interval_days, 6);
+
+        apply_review(ReviewAlgorithm::Sm2, &mut state, 5, date("2025-01-08"));
+        assert!(state.interval_days > 6);
+        assert_eq!(state.repetitions, 3);
+    }
+
+    #[test]
+    fn test_sm2_failure_resets_interval() {
+        let mut state = WordReviewState::new(1, 1);
+        apply_review(ReviewAlgorithm::Sm2, &mut state, 5, date("2025-01-01"));
+        apply_review(ReviewAlgorithm::Sm2, &mut state, 5, date("2025-01-02"));
+        apply_review(ReviewAlgorithm::Sm2, &mut state, 1, date("2025-01-08"));
+
+        assert_eq!(state.repetitions, 0);
+        assert_eq!(state.interval_days, 1);
+        assert_eq!(state.lapses, 1);
+        assert!(state.ease_factor >= MIN_EASE_FACTOR);
+    }
+
+    #[test]
+    fn test_fsrs_failed_word_returns_sooner() {
+        let mut good = WordReviewState::new(1, 1);
+        let mut bad = WordReviewState::new(1, 2);
+        for day in ["2025-01-01", "2025-01-04"] {
+            apply_review(ReviewAlgorithm::Fsrs, &mut good, 4, date(day));
+            apply_review(ReviewAlgorithm::Fsrs, &mut bad, 1, date(day));
+        }
+
+        assert!(good.interval_days > bad.interval_days);
+        assert!(good.stability > bad.stability);
+    }
+
+    #[test]
+    fn test_project_review_dates_stops_at_limit() {
+        let mut state = WordReviewState::new(1, 1);
+        apply_review(ReviewAlgorithm::Sm2, &mut state, 4, date("2025-01-01"));
+
+        let dates = project_review_dates(ReviewAlgorithm::Sm2, &state, date("2025-01-14"));
+        assert_eq!(dates.first(), Some(&date("2025-01-02")));
+        assert!(dates.iter().all(|d| *d <= date("2025-01-14")));
+        assert!(dates.windows(2).all(|w| w[0] < w[1]));
+    }
+}
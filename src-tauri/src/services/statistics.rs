@@ -4,7 +4,9 @@
 
 use crate::error::AppResult;
 use crate::logger::Logger;
-use crate::repositories::statistics_repository::StatisticsRepository;
+use crate::repositories::operation_snapshot_repository::SnapshotScope;
+use crate::repositories::statistics_repository::{StatisticsRepository, USER_DATA_TABLES};
+use crate::services::snapshot::SnapshotService;
 use crate::types::*;
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -14,6 +16,7 @@ use std::sync::Arc;
 /// 负责统计的业务逻辑处理
 pub struct StatisticsService {
     repository: StatisticsRepository,
+    snapshots: SnapshotService,
     logger: Arc<Logger>,
 }
 
@@ -21,7 +24,8 @@ impl StatisticsService {
     /// 创建新的服务实例
     pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
         Self {
-            repository: StatisticsRepository::new(pool, logger.clone()),
+            repository: StatisticsRepository::new(pool.clone(), logger.clone()),
+            snapshots: SnapshotService::new(pool, logger.clone()),
             logger,
         }
     }
@@ -36,14 +40,45 @@ impl StatisticsService {
         self.repository.get_database_statistics().await
     }
 
-    /// 重置用户数据
+    /// 重置用户数据（先保存快照，可撤销）
     pub async fn reset_user_data(&self) -> AppResult<ResetResult> {
-        self.repository.reset_user_data().await
+        let scopes: Vec<SnapshotScope> = USER_DATA_TABLES
+            .iter()
+            .map(|table| SnapshotScope::table(table))
+            .collect();
+        let snapshot_id = self
+            .snapshots
+            .capture(SnapshotOperation::ResetUserData, None, "重置用户数据", &scopes)
+            .await?;
+
+        let result = self.repository.reset_user_data().await;
+        self.snapshots
+            .finish(snapshot_id, matches!(&result, Ok(r) if r.success))
+            .await;
+        result
     }
 
-    /// 重置选定的表
+    /// 重置选定的表（先保存快照，可撤销）
     pub async fn reset_selected_tables(&self, table_names: &[String]) -> AppResult<ResetResult> {
-        self.repository.reset_selected_tables(table_names).await
+        let scopes: Vec<SnapshotScope> = table_names
+            .iter()
+            .map(|table| SnapshotScope::table(table))
+            .collect();
+        let snapshot_id = self
+            .snapshots
+            .capture(
+                SnapshotOperation::ResetSelectedTables,
+                None,
+                &format!("重置数据表 {}", table_names.join(", ")),
+                &scopes,
+            )
+            .await?;
+
+        let result = self.repository.reset_selected_tables(table_names).await;
+        self.snapshots
+            .finish(snapshot_id, matches!(&result, Ok(r) if r.success))
+            .await;
+        result
     }
 
     /// 获取全局单词本统计
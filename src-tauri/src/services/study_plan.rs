@@ -9,8 +9,15 @@
 
 use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
+use crate::repositories::operation_snapshot_repository::SnapshotScope;
 use crate::repositories::study_plan_repository::StudyPlanRepository;
+use crate::services::snapshot::SnapshotService;
+use crate::services::spaced_repetition::DATE_FORMAT;
+use crate::services::study_plan_rebalancer::{self, RebalanceOutcome};
+use crate::services::study_plan_scheduler::PlanWordAddition;
+use crate::services::study_plan_validator;
 use crate::types::common::Id;
+use crate::types::snapshot::SnapshotOperation;
 use crate::types::study::*;
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -51,223 +58,725 @@ impl StudyPlanService {
 
     /// 开始学习计划
     pub async fn start_study_plan(&self, plan_id: Id) -> AppResult<()> {
-        // 检查状态
-        let (_, current_unified_status) = self
-            .repository
-            .find_status(plan_id)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", plan_id)))?;
-
-        // 验证状态转换
-        if current_unified_status != "Pending" {
-            let error_msg = match current_unified_status.as_str() {
-                "Draft" => "学习计划还未完成配置，请先完成配置",
-                "Active" => "学习计划已经在进行中",
-                "Paused" => "学习计划已暂停，请使用恢复功能",
-                "Completed" => "学习计划已完成",
-                "Terminated" => "学习计划已终止",
-                "Deleted" => "学习计划已删除",
-                _ => "学习计划状态不允许开始学习",
-            };
-            return Err(AppError::ValidationError(error_msg.to_string()));
-        }
+        self.repository
+            .transition(plan_id, StudyPlanTransition::Start, "用户手动开始学习")
+            .await?;
+        Ok(())
+    }
 
-        // 更新状态
+    /// 完成学习计划
+    pub async fn complete_study_plan(&self, plan_id: Id) -> AppResult<()> {
         self.repository
-            .update_status(plan_id, "Active", true, false, false)
+            .transition(plan_id, StudyPlanTransition::Complete, "用户手动完成学习")
             .await?;
+        Ok(())
+    }
 
-        // 记录状态变更历史
+    /// 终止学习计划
+    pub async fn terminate_study_plan(&self, plan_id: Id) -> AppResult<()> {
         self.repository
-            .add_status_history(plan_id, &current_unified_status, "Active", "用户手动开始学习")
+            .transition(plan_id, StudyPlanTransition::Terminate, "用户手动终止学习")
             .await?;
+        Ok(())
+    }
 
+    /// 暂停学习计划（暂停期间不计逾期）
+    pub async fn pause_study_plan(&self, plan_id: Id) -> AppResult<()> {
+        self.repository
+            .transition(plan_id, StudyPlanTransition::Pause, "用户暂停学习")
+            .await?;
         Ok(())
     }
 
-    /// 完成学习计划
-    pub async fn complete_study_plan(&self, plan_id: Id) -> AppResult<()> {
-        // 检查状态
-        let (current_status, current_unified_status) = self
+    /// 恢复学习计划（未完成的日程按暂停天数后移，并更新结束日期）
+    pub async fn resume_study_plan(&self, plan_id: Id) -> AppResult<ResumeStudyPlanResult> {
+        // 暂停天数：从暂停当天到今天
+        let today = chrono::Local::now().date_naive();
+        let paused_days = self
             .repository
-            .find_status(plan_id)
+            .find_paused_at(plan_id)
             .await?
-            .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", plan_id)))?;
+            .as_deref()
+            .and_then(paused_on)
+            .map(|paused_on| (today - paused_on).num_days().max(0))
+            .unwrap_or(0);
 
-        // 验证状态转换
-        if current_status != "normal" {
-            return Err(AppError::ValidationError(
-                "只有正常状态的学习计划才能完成".to_string(),
-            ));
-        }
+        // 恢复状态并后移未完成的日程
+        let (shifted_schedules, end_date) = self
+            .repository
+            .resume_with_shift(
+                plan_id,
+                paused_days,
+                &format!("用户恢复学习，未完成的日程后移 {} 天", paused_days),
+            )
+            .await?;
+
+        self.logger.info(
+            "STUDY_PLAN_SERVICE",
+            &format!(
+                "▶️ Resumed plan {} after {} days, {} schedules shifted",
+                plan_id, paused_days, shifted_schedules
+            ),
+        );
 
-        if current_unified_status != "Active" {
+        Ok(ResumeStudyPlanResult {
+            paused_days,
+            shifted_schedules: shifted_schedules as i32,
+            end_date,
+        })
+    }
+
+    /// 预览逾期日程重新分配（不修改数据）
+    pub async fn preview_rebalance_study_plan(
+        &self,
+        plan_id: Id,
+    ) -> AppResult<StudyPlanRebalancePreview> {
+        let today = chrono::Local::now().date_naive();
+        let (preview, _) = self.plan_rebalance(plan_id, today).await?;
+        Ok(preview)
+    }
+
+    /// 把逾期和剩余日程中未完成的单词重新分配到今天及之后的日程，必要时延长计划
+    ///
+    /// `fingerprint` 为预览返回的指纹，日程在预览后发生变化时拒绝应用
+    pub async fn rebalance_study_plan(
+        &self,
+        plan_id: Id,
+        fingerprint: &str,
+    ) -> AppResult<StudyPlanRebalancePreview> {
+        use crate::repositories::study_schedule_repository::StudyScheduleRepository;
+
+        let today = chrono::Local::now().date_naive();
+        let (mut preview, outcome) = self.plan_rebalance(plan_id, today).await?;
+        if preview.fingerprint != fingerprint {
             return Err(AppError::ValidationError(
-                "只有进行中的学习计划才能完成".to_string(),
+                "学习计划的日程在预览后已变化，请重新预览".to_string(),
             ));
         }
+        if preview.moves.is_empty() && preview.added_days == 0 {
+            return Ok(preview);
+        }
 
-        // 更新状态
-        self.repository
-            .update_status(plan_id, "Completed", false, true, false)
+        // 应用时在事务中再次核对指纹，防止计算方案后日程被并发修改
+        let schedule_repo = StudyScheduleRepository::new(self.pool.clone(), self.logger.clone());
+        preview.new_end_date = schedule_repo
+            .apply_rebalance(
+                plan_id,
+                today,
+                preview.max_new_words_per_day as usize,
+                fingerprint,
+                &outcome,
+            )
             .await?;
 
-        // 记录状态变更历史
-        self.repository
-            .add_status_history(plan_id, &current_unified_status, "Completed", "用户手动完成学习")
-            .await?;
+        self.logger.info(
+            "STUDY_PLAN_SERVICE",
+            &format!(
+                "🔀 Rebalanced plan {}: {} words moved, {} reviews dropped, {} days added",
+                plan_id, preview.moved_words, preview.dropped_reviews, preview.added_days
+            ),
+        );
 
-        Ok(())
+        Ok(preview)
     }
 
-    /// 终止学习计划
-    pub async fn terminate_study_plan(&self, plan_id: Id) -> AppResult<()> {
-        // 检查状态
-        let (_, current_unified_status) = self
+    /// 计算重新分配方案及其预览
+    async fn plan_rebalance(
+        &self,
+        plan_id: Id,
+        today: chrono::NaiveDate,
+    ) -> AppResult<(StudyPlanRebalancePreview, RebalanceOutcome)> {
+        use crate::repositories::study_schedule_repository::StudyScheduleRepository;
+        use crate::services::study_plan_scheduler::{daily_new_word_range, MAX_NEW_WORDS_PER_DAY};
+        use std::collections::{BTreeMap, HashMap};
+
+        let plan = self
             .repository
-            .find_status(plan_id)
+            .find_by_id(plan_id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", plan_id)))?;
-
-        // 验证状态转换
-        if current_unified_status != "Active" && current_unified_status != "Paused" {
+        if plan.unified_status != Some(UnifiedStudyPlanStatus::Active) {
             return Err(AppError::ValidationError(
-                "只有Active或Paused状态的学习计划才能终止".to_string(),
+                "只有进行中的学习计划才能重新分配日程".to_string(),
             ));
         }
 
-        // 更新状态
-        self.repository
-            .update_status(plan_id, "Terminated", false, false, true)
-            .await?;
+        // 每日新词上限取学习强度的上限
+        let max_new_words_per_day = plan
+            .intensity_level
+            .as_deref()
+            .and_then(daily_new_word_range)
+            .map(|(_, max)| max)
+            .unwrap_or(MAX_NEW_WORDS_PER_DAY);
+
+        let schedule_repo = StudyScheduleRepository::new(self.pool.clone(), self.logger.clone());
+        let schedules = schedule_repo.find_rebalance_schedules(plan_id).await?;
+        let movable_ids: Vec<Id> = schedules.iter().filter(|s| s.movable).map(|s| s.id).collect();
+        let entries = schedule_repo.find_rebalance_entries(&movable_ids).await?;
+
+        let fingerprint = study_plan_rebalancer::rebalance_fingerprint(
+            today,
+            max_new_words_per_day as usize,
+            &schedules,
+            &entries,
+        );
+        let outcome = study_plan_rebalancer::plan_rebalance(
+            today,
+            max_new_words_per_day as usize,
+            &schedules,
+            &entries,
+        );
 
-        // 记录状态变更历史
-        self.repository
-            .add_status_history(plan_id, &current_unified_status, "Terminated", "用户手动终止学习")
-            .await?;
+        // 按日期统计调整前后的单词数
+        let format_date = |date: chrono::NaiveDate| date.format("%Y-%m-%d").to_string();
+        let empty_day = |date: chrono::NaiveDate, schedule_id: Option<Id>| RebalanceDayDiff {
+            date: format_date(date),
+            schedule_id,
+            before_new_words: 0,
+            before_review_words: 0,
+            after_new_words: 0,
+            after_review_words: 0,
+        };
+        let dates: HashMap<Id, chrono::NaiveDate> =
+            schedules.iter().map(|s| (s.id, s.date)).collect();
+        let mut days: BTreeMap<chrono::NaiveDate, RebalanceDayDiff> = schedules
+            .iter()
+            .filter(|s| s.movable)
+            .map(|s| (s.date, empty_day(s.date, Some(s.id))))
+            .collect();
+        for date in &outcome.added_dates {
+            days.insert(*date, empty_day(*date, None));
+        }
 
-        Ok(())
+        let mut moves = Vec::new();
+        for entry in &entries {
+            let from = dates[&entry.schedule_id];
+            let to = outcome.placements.get(&entry.id).copied();
+            if let Some(day) = days.get_mut(&from) {
+                if entry.is_review {
+                    day.before_review_words += 1;
+                } else {
+                    day.before_new_words += 1;
+                }
+            }
+            if let Some(day) = to.and_then(|to| days.get_mut(&to)) {
+                if entry.is_review {
+                    day.after_review_words += 1;
+                } else {
+                    day.after_new_words += 1;
+                }
+            }
+            if to != Some(from) {
+                moves.push((
+                    from,
+                    to,
+                    RebalanceWordMove {
+                        word_id: entry.word_id,
+                        word: entry.word.clone(),
+                        is_review: entry.is_review,
+                        review_count: entry.review_count,
+                        from_date: format_date(from),
+                        to_date: to.map(format_date),
+                    },
+                ));
+            }
+        }
+        moves.sort_by_key(|(from, to, _)| (*from, *to));
+
+        // 被清空的可调整日程会被删除，结束日期取剩余日程的最后一天
+        let new_end_date = schedules
+            .iter()
+            .filter(|s| !s.movable)
+            .map(|s| s.date)
+            .chain(
+                days.iter()
+                    .filter(|(_, day)| day.after_new_words + day.after_review_words > 0)
+                    .map(|(date, _)| *date),
+            )
+            .max()
+            .map(format_date)
+            .or(plan.end_date.clone());
+        let overdue_schedules = days
+            .iter()
+            .filter(|(date, day)| {
+                **date < today && day.before_new_words + day.before_review_words > 0
+            })
+            .count();
+
+        let preview = StudyPlanRebalancePreview {
+            plan_id,
+            max_new_words_per_day,
+            overdue_schedules: overdue_schedules as i32,
+            moved_words: moves.iter().filter(|(_, to, _)| to.is_some()).count() as i32,
+            dropped_reviews: outcome.dropped.len() as i32,
+            added_days: outcome.added_dates.len() as i32,
+            old_end_date: plan.end_date,
+            new_end_date,
+            days: days
+                .into_values()
+                .filter(|day| {
+                    (day.before_new_words, day.before_review_words)
+                        != (day.after_new_words, day.after_review_words)
+                })
+                .collect(),
+            moves: moves.into_iter().map(|(_, _, word_move)| word_move).collect(),
+            fingerprint,
+        };
+
+        Ok((preview, outcome))
     }
 
-    /// 删除学习计划（软删除）
-    pub async fn delete_study_plan(&self, plan_id: Id) -> AppResult<()> {
-        // 获取当前状态
-        let (_, current_unified_status) = self
+    /// 准备向计划追加单词：校验计划状态，收集尚未加入计划的单词，读取日程负载
+    pub async fn prepare_plan_word_addition(
+        &self,
+        request: &AddPlanWordsRequest,
+    ) -> AppResult<PlanWordAddition> {
+        use crate::repositories::study_schedule_repository::StudyScheduleRepository;
+        use crate::repositories::word_repository::WordRepository;
+        use crate::services::study_plan_scheduler::{
+            daily_new_word_range, review_offsets, AdditionCalendar, MAX_NEW_WORDS_PER_DAY,
+        };
+        use std::collections::HashSet;
+
+        let plan_id = request.plan_id;
+        let plan = self
             .repository
-            .find_status(plan_id)
+            .find_by_id(plan_id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", plan_id)))?;
+        let status = plan.unified_status.unwrap_or(UnifiedStudyPlanStatus::Draft);
+        if !matches!(
+            status,
+            UnifiedStudyPlanStatus::Draft | UnifiedStudyPlanStatus::Active
+        ) {
+            return Err(AppError::ValidationError(
+                "只有草稿或进行中的学习计划才能添加单词".to_string(),
+            ));
+        }
+        if request.word_ids.is_empty() && request.wordbook_ids.is_empty() {
+            return Err(AppError::ValidationError(
+                "请选择要添加的单词或单词本".to_string(),
+            ));
+        }
 
-        // 删除
-        self.repository.delete(plan_id).await?;
+        let word_repo = WordRepository::new(self.pool.clone(), self.logger.clone());
+        let mut words = word_repo.find_words_by_ids(&request.word_ids).await?;
+        words.extend(
+            word_repo
+                .find_words_by_wordbook_ids(&request.wordbook_ids)
+                .await?,
+        );
 
-        // 记录状态变更历史
-        self.repository
-            .add_status_history(plan_id, &current_unified_status, "Deleted", "用户删除学习计划")
-            .await?;
+        let existing: HashSet<Id> = self
+            .repository
+            .find_plan_words(plan_id)
+            .await?
+            .into_iter()
+            .map(|w| w.id)
+            .collect();
+        let mut seen = HashSet::new();
+        words.retain(|(word_id, _, _)| seen.insert(*word_id));
+        let found = words.len();
+        words.retain(|(word_id, _, _)| !existing.contains(word_id));
+        if words.is_empty() {
+            return Err(AppError::ValidationError("没有可以添加的新单词".to_string()));
+        }
 
-        Ok(())
-    }
+        // 已完成的日程不再安排；进行中的计划从今天开始安排
+        let schedule_repo = StudyScheduleRepository::new(self.pool.clone(), self.logger.clone());
+        let load = schedule_repo.find_schedule_load(plan_id).await?;
+        let today = chrono::Local::now().date_naive();
+        let start_date = plan
+            .start_date
+            .as_deref()
+            .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+        let first_date = match (status, start_date) {
+            (UnifiedStudyPlanStatus::Active, Some(start)) => start.max(today),
+            (UnifiedStudyPlanStatus::Active, None) => today,
+            (_, start) => start.unwrap_or(today),
+        };
+        let mut calendar = AdditionCalendar {
+            first_date,
+            last_date: load.iter().map(|(date, _, _)| *date).max(),
+            ..Default::default()
+        };
+        for (date, new_words, completed) in load {
+            if completed {
+                calendar.blocked_days.insert(date);
+            } else {
+                calendar.open_days.insert(date, new_words);
+            }
+        }
 
-    /// 获取状态变更历史
-    pub async fn get_status_history(&self, plan_id: Id) -> AppResult<Vec<StudyPlanStatusHistory>> {
-        self.repository.find_status_history(plan_id).await
+        let intensity_level = plan
+            .intensity_level
+            .clone()
+            .unwrap_or_else(|| "normal".to_string());
+        let period_days = plan.study_period_days.unwrap_or(7);
+        let review_frequency = plan.review_frequency.unwrap_or(3);
+        let word_list: Vec<StudyWordInfo> = words
+            .into_iter()
+            .map(|(word_id, word, wordbook_id)| StudyWordInfo {
+                word,
+                word_id: word_id.to_string(),
+                wordbook_id: wordbook_id.to_string(),
+            })
+            .collect();
+
+        Ok(PlanWordAddition {
+            plan_id,
+            max_new_words_per_day: daily_new_word_range(&intensity_level)
+                .map(|(_, max)| max)
+                .unwrap_or(MAX_NEW_WORDS_PER_DAY),
+            review_offsets: review_offsets(period_days, review_frequency).unwrap_or_default(),
+            skipped_words: (found - word_list.len()) as i32,
+            params: StudyPlanAIParams {
+                intensity_level,
+                total_words: word_list.len() as i32,
+                period_days,
+                review_frequency,
+                start_date: calendar.first_available(first_date).format("%Y-%m-%d").to_string(),
+                word_list,
+            },
+            calendar,
+        })
     }
 
-    /// 重新开始学习计划
-    pub async fn restart_study_plan(&self, plan_id: Id) -> AppResult<()> {
-        // 检查状态
-        let (current_status, current_unified_status) = self
-            .repository
-            .find_status(plan_id)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", plan_id)))?;
+    /// 把追加的单词写入计划日程
+    ///
+    /// `ai_plans` 为 AI 规划的结果；为 None 时使用本地规划器安排
+    pub async fn add_words_to_plan(
+        &self,
+        addition: &PlanWordAddition,
+        ai_plans: Option<Vec<DailyStudyPlan>>,
+    ) -> AppResult<AddPlanWordsResult> {
+        use crate::repositories::study_schedule_repository::StudyScheduleRepository;
+        use crate::services::study_plan_scheduler::{align_additional_plans, place_additional_words};
 
-        // 验证状态转换
-        if current_status != "normal" {
-            return Err(AppError::ValidationError(
-                "只有正常状态的学习计划才能重新学习".to_string(),
-            ));
-        }
+        let daily_plans = match ai_plans {
+            Some(plans) => align_additional_plans(addition, plans)?,
+            None => place_additional_words(addition),
+        };
 
-        if current_unified_status != "Completed" && current_unified_status != "Terminated" {
+        let entries = daily_plans.iter().flat_map(|d| d.words.iter());
+        let review_entries = entries.clone().filter(|w| w.is_review).count() as i32;
+        let new_word_entries = entries.filter(|w| !w.is_review).count() as i32;
+        if new_word_entries == 0 {
             return Err(AppError::ValidationError(
-                "只有已完成或已终止的学习计划才能重新学习".to_string(),
+                "规划结果中没有安排任何新单词".to_string(),
             ));
         }
 
-        // 重置状态和数据
-        self.repository
-            .update_status_and_clear_dates(plan_id, "Pending")
+        let word_ids: Vec<Id> = addition
+            .params
+            .word_list
+            .iter()
+            .filter_map(|w| w.word_id.parse().ok())
+            .collect();
+        let schedule_repo = StudyScheduleRepository::new(self.pool.clone(), self.logger.clone());
+        let (added_days, end_date) = schedule_repo
+            .add_planned_words(addition.plan_id, &word_ids, &daily_plans)
             .await?;
 
-        // 清空所有相关数据
-        self.repository.reset_plan_data(plan_id).await?;
+        self.logger.info(
+            "STUDY_PLAN_SERVICE",
+            &format!(
+                "➕ Added {} words to plan {}: {} new entries, {} reviews, {} days added",
+                word_ids.len(),
+                addition.plan_id,
+                new_word_entries,
+                review_entries,
+                added_days
+            ),
+        );
 
-        // 记录状态变更历史
-        self.repository
-            .add_status_history(plan_id, &current_unified_status, "Pending", "用户重新开始学习，清空历史进度")
-            .await?;
+        Ok(AddPlanWordsResult {
+            added_words: word_ids.len() as i32,
+            skipped_words: addition.skipped_words,
+            new_word_entries,
+            review_entries,
+            added_days: added_days as i32,
+            end_date,
+        })
+    }
 
-        Ok(())
+    // ==================== 复制计划与计划模板 ====================
+
+    /// 读取计划的日程形状：从开始日期起每天的新词数
+    async fn plan_schedule_shape(&self, plan: &StudyPlan) -> AppResult<Vec<i32>> {
+        use crate::repositories::study_schedule_repository::StudyScheduleRepository;
+
+        let schedule_repo = StudyScheduleRepository::new(self.pool.clone(), self.logger.clone());
+        let load = schedule_repo.find_schedule_load(plan.id).await?;
+        let start_date = plan
+            .start_date
+            .as_deref()
+            .and_then(|date| chrono::NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+            .or_else(|| load.iter().map(|(date, _, _)| *date).min());
+        let Some(start_date) = start_date else {
+            return Ok(Vec::new());
+        };
+
+        let mut shape = Vec::new();
+        for (date, new_words, _) in load {
+            let day = (date - start_date).num_days();
+            if day < 0 {
+                continue;
+            }
+            let day = day as usize;
+            if shape.len() <= day {
+                shape.resize(day + 1, 0);
+            }
+            shape[day] += new_words;
+        }
+        Ok(shape)
     }
 
-    /// 编辑学习计划（转为草稿状态）
-    pub async fn edit_study_plan(&self, plan_id: Id) -> AppResult<()> {
-        // 检查状态
-        let (current_status, current_unified_status) = self
+    /// 查询计划并取出学习设置（强度, 周期, 复习频率）
+    async fn find_plan_settings(&self, plan_id: Id) -> AppResult<(StudyPlan, String, i32, i32)> {
+        let plan = self
             .repository
-            .find_status(plan_id)
+            .find_by_id(plan_id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", plan_id)))?;
+        match (
+            plan.intensity_level.clone(),
+            plan.study_period_days,
+            plan.review_frequency,
+        ) {
+            (Some(intensity_level), Some(period_days), Some(review_frequency)) => {
+                Ok((plan, intensity_level, period_days, review_frequency))
+            }
+            _ => Err(AppError::ValidationError(format!(
+                "学习计划「{}」缺少学习强度、周期或复习频率设置",
+                plan.name
+            ))),
+        }
+    }
 
-        // 验证状态转换
-        if current_status != "normal" {
-            return Err(AppError::ValidationError(
-                "只有正常状态的学习计划才能编辑".to_string(),
-            ));
+    /// 复制学习计划：沿用源计划的设置和日程形状，为新的单词本和开始日期生成日程
+    pub async fn clone_study_plan(&self, request: CloneStudyPlanRequest) -> AppResult<Id> {
+        use crate::repositories::word_repository::WordRepository;
+        use crate::services::study_plan_scheduler::generate_shaped_schedule;
+
+        let (plan, intensity_level, period_days, review_frequency) =
+            self.find_plan_settings(request.plan_id).await?;
+        if request.wordbook_ids.is_empty() {
+            return Err(AppError::ValidationError("请选择要学习的单词本".to_string()));
         }
 
-        // 更新状态并清空实际日期
-        self.repository
-            .update_status_and_clear_dates(plan_id, "Draft")
+        let word_repo = WordRepository::new(self.pool.clone(), self.logger.clone());
+        let word_list: Vec<StudyWordInfo> = word_repo
+            .find_words_by_wordbook_ids(&request.wordbook_ids)
+            .await?
+            .into_iter()
+            .map(|(id, word, wordbook_id)| StudyWordInfo {
+                word,
+                word_id: id.to_string(),
+                wordbook_id: wordbook_id.to_string(),
+            })
+            .collect();
+        if word_list.is_empty() {
+            return Err(AppError::ValidationError("所选单词本中没有单词".to_string()));
+        }
+
+        let shape = self.plan_schedule_shape(&plan).await?;
+        let params = StudyPlanAIParams {
+            intensity_level: intensity_level.clone(),
+            total_words: word_list.len() as i32,
+            period_days,
+            review_frequency,
+            start_date: request.start_date.clone(),
+            word_list,
+        };
+        let schedule = generate_shaped_schedule(&params, &shape)?;
+        let ai_plan_data = serde_json::to_string(&schedule)
+            .map_err(|e| AppError::InternalError(format!("Failed to serialize plan: {}", e)))?;
+
+        let name = request
+            .name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| format!("{} (副本)", plan.name));
+        let new_plan_id = self
+            .create_study_plan_with_schedule(CreateStudyPlanWithScheduleRequest {
+                name,
+                description: plan.description.clone(),
+                intensity_level,
+                study_period_days: period_days,
+                review_frequency,
+                start_date: schedule.plan_metadata.start_date.clone(),
+                end_date: schedule.plan_metadata.end_date.clone(),
+                ai_plan_data,
+                wordbook_ids: request.wordbook_ids,
+                status: request.status,
+                auto_repair: Some(true),
+            })
             .await?;
 
-        // 重置学习进度（保留单词关联）
-        self.repository.reset_plan_progress(plan_id).await?;
+        self.logger.info(
+            "STUDY_PLAN_SERVICE",
+            &format!(
+                "📋 Cloned plan {} into plan {} ({} words over {} days)",
+                plan.id,
+                new_plan_id,
+                schedule.plan_metadata.total_words,
+                shape.len().max(period_days as usize)
+            ),
+        );
 
-        // 记录状态变更历史
-        self.repository
-            .add_status_history(plan_id, &current_unified_status, "Draft", "用户编辑学习计划，重置学习进度")
-            .await?;
+        Ok(new_plan_id)
+    }
+
+    /// 创建学习计划模板
+    pub async fn create_study_plan_template(
+        &self,
+        request: CreateStudyPlanTemplateRequest,
+    ) -> AppResult<Id> {
+        use crate::repositories::study_plan_template_repository::StudyPlanTemplateRepository;
+        use crate::services::study_plan_scheduler::{daily_new_word_range, review_offsets};
+
+        if request.name.trim().is_empty() {
+            return Err(AppError::ValidationError("模板名称不能为空".to_string()));
+        }
+        if daily_new_word_range(&request.intensity_level).is_none() {
+            return Err(AppError::ValidationError(format!(
+                "无效的学习强度: {}",
+                request.intensity_level
+            )));
+        }
+        if review_offsets(request.study_period_days, request.review_frequency).is_none() {
+            return Err(AppError::ValidationError(format!(
+                "无效的学习周期: {}，必须为 1、3、7、14 或 28 天",
+                request.study_period_days
+            )));
+        }
+        if request.day_weights.iter().any(|w| *w < 0) {
+            return Err(AppError::ValidationError("日程形状不能包含负数".to_string()));
+        }
 
+        let repo = StudyPlanTemplateRepository::new(self.pool.clone(), self.logger.clone());
+        repo.create(&request, None).await
+    }
+
+    /// 把学习计划的设置和日程形状保存为模板
+    pub async fn save_study_plan_as_template(
+        &self,
+        plan_id: Id,
+        name: String,
+    ) -> AppResult<Id> {
+        use crate::repositories::study_plan_template_repository::StudyPlanTemplateRepository;
+
+        if name.trim().is_empty() {
+            return Err(AppError::ValidationError("模板名称不能为空".to_string()));
+        }
+        let (plan, intensity_level, study_period_days, review_frequency) =
+            self.find_plan_settings(plan_id).await?;
+        let day_weights = self.plan_schedule_shape(&plan).await?;
+
+        let repo = StudyPlanTemplateRepository::new(self.pool.clone(), self.logger.clone());
+        repo.create(
+            &CreateStudyPlanTemplateRequest {
+                name,
+                description: plan.description.clone(),
+                intensity_level,
+                study_period_days,
+                review_frequency,
+                day_weights,
+            },
+            Some(plan_id),
+        )
+        .await
+    }
+
+    /// 获取所有学习计划模板
+    pub async fn get_study_plan_templates(&self) -> AppResult<Vec<StudyPlanTemplate>> {
+        use crate::repositories::study_plan_template_repository::StudyPlanTemplateRepository;
+
+        StudyPlanTemplateRepository::new(self.pool.clone(), self.logger.clone())
+            .find_all()
+            .await
+    }
+
+    /// 获取学习计划模板
+    pub async fn get_study_plan_template(&self, template_id: Id) -> AppResult<StudyPlanTemplate> {
+        use crate::repositories::study_plan_template_repository::StudyPlanTemplateRepository;
+
+        StudyPlanTemplateRepository::new(self.pool.clone(), self.logger.clone())
+            .find_by_id(template_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("学习计划模板 {} 不存在", template_id)))
+    }
+
+    /// 删除学习计划模板
+    pub async fn delete_study_plan_template(&self, template_id: Id) -> AppResult<()> {
+        use crate::repositories::study_plan_template_repository::StudyPlanTemplateRepository;
+
+        let repo = StudyPlanTemplateRepository::new(self.pool.clone(), self.logger.clone());
+        if !repo.delete(template_id).await? {
+            return Err(AppError::NotFound(format!(
+                "学习计划模板 {} 不存在",
+                template_id
+            )));
+        }
         Ok(())
     }
 
-    /// 发布学习计划（从草稿转为待开始）
-    pub async fn publish_study_plan(&self, plan_id: Id) -> AppResult<()> {
-        // 检查状态
-        let (_, current_unified_status) = self
+    /// 删除学习计划（软删除，可撤销）
+    pub async fn delete_study_plan(&self, plan_id: Id) -> AppResult<()> {
+        let name = self
             .repository
-            .find_status(plan_id)
+            .find_by_id(plan_id)
             .await?
-            .ok_or_else(|| AppError::NotFound(format!("学习计划 {} 不存在", plan_id)))?;
+            .map(|plan| plan.name)
+            .unwrap_or_default();
+
+        let snapshots = SnapshotService::new(self.pool.clone(), self.logger.clone());
+        let snapshot_id = snapshots
+            .capture(
+                SnapshotOperation::DeleteStudyPlan,
+                Some(plan_id),
+                &format!("删除学习计划「{}」", name),
+                &[SnapshotScope::rows("study_plans", "id", &[plan_id]).soft_deleted()],
+            )
+            .await?;
 
-        // 验证状态转换
-        if current_unified_status != "Draft" {
-            return Err(AppError::ValidationError(
-                "只有草稿状态的学习计划才能发布".to_string(),
-            ));
-        }
+        // 删除（状态转换同时记录状态变更历史）
+        let result = self
+            .repository
+            .transition(plan_id, StudyPlanTransition::Delete, "用户删除学习计划")
+            .await;
+        snapshots.finish(snapshot_id, result.is_ok()).await;
+        result?;
+
+        Ok(())
+    }
 
-        // 更新状态
-        self.repository.update_status_for_publish(plan_id).await?;
+    /// 获取状态变更历史
+    pub async fn get_status_history(&self, plan_id: Id) -> AppResult<Vec<StudyPlanStatusHistory>> {
+        self.repository.find_status_history(plan_id).await
+    }
 
-        // 记录状态变更历史
+    /// 重新开始学习计划（清空历史进度）
+    pub async fn restart_study_plan(&self, plan_id: Id) -> AppResult<()> {
         self.repository
-            .add_status_history(plan_id, &current_unified_status, "Pending", "用户发布学习计划")
+            .transition(plan_id, StudyPlanTransition::Restart, "用户重新开始学习，清空历史进度")
             .await?;
+        Ok(())
+    }
 
+    /// 编辑学习计划（转为草稿状态，重置学习进度）
+    pub async fn edit_study_plan(&self, plan_id: Id) -> AppResult<()> {
+        self.repository
+            .transition(plan_id, StudyPlanTransition::Edit, "用户编辑学习计划，重置学习进度")
+            .await?;
+        Ok(())
+    }
+
+    /// 发布学习计划（从草稿转为待开始）
+    pub async fn publish_study_plan(&self, plan_id: Id) -> AppResult<()> {
+        self.repository
+            .transition(plan_id, StudyPlanTransition::Publish, "用户发布学习计划")
+            .await?;
         Ok(())
     }
 
@@ -283,19 +792,17 @@ impl StudyPlanService {
         let intensity_level = updates.get("intensity_level").and_then(|v| v.as_str());
         let review_frequency = updates.get("review_frequency").and_then(|v| v.as_i64()).map(|v| v as i32);
 
-        // 计算 unified_status
-        let unified_status = if let Some(status) = status {
-            Some(match status {
-                "draft" => "Draft",
-                "normal" => "Pending",
-                _ => "Draft",
+        // 状态变更按状态转换表执行
+        let status = status
+            .map(|status| match status {
+                "normal" => Ok(UnifiedStudyPlanStatus::Pending),
+                "draft" => Ok(UnifiedStudyPlanStatus::Draft),
+                other => Err(AppError::ValidationError(format!("未知的学习计划状态: {}", other))),
             })
-        } else {
-            None
-        };
+            .transpose()?;
 
         self.repository
-            .partial_update(plan_id, name, description, status, unified_status.as_deref(), intensity_level, review_frequency)
+            .partial_update(plan_id, status, name, description, intensity_level, review_frequency)
             .await
     }
 
@@ -394,8 +901,39 @@ impl StudyPlanService {
         use crate::types::study::{DailyStudyPlan, StudyPlanAIResult};
 
         // 解析 AI 规划数据
-        let ai_result: StudyPlanAIResult = serde_json::from_str(&request.ai_plan_data)
+        let mut ai_result: StudyPlanAIResult = serde_json::from_str(&request.ai_plan_data)
             .map_err(|e| AppError::ValidationError(format!("Invalid AI plan data: {}", e)))?;
+        let mut ai_plan_data = request.ai_plan_data;
+
+        // 校验规划数据，写入前自动修复
+        if !request.wordbook_ids.is_empty() {
+            let (source_words, start_date, end_date) = self
+                .load_plan_validation_context(&request.wordbook_ids, &request.start_date, &request.end_date)
+                .await?;
+            let violations =
+                study_plan_validator::validate_plan(&ai_result, &source_words, start_date, end_date);
+
+            if !violations.is_empty() {
+                self.logger.info(
+                    "STUDY_PLAN_SERVICE",
+                    &format!("AI plan has {} violation(s): {:?}", violations.len(), violations),
+                );
+
+                if !request.auto_repair.unwrap_or(true) {
+                    return Err(AppError::ValidationError(format!(
+                        "学习计划规划数据存在 {} 个问题: {}",
+                        violations.len(),
+                        violations.first().map(|v| v.message.as_str()).unwrap_or_default()
+                    )));
+                }
+
+                ai_result =
+                    study_plan_validator::repair_plan(ai_result, &source_words, start_date, end_date);
+                ai_plan_data = serde_json::to_string(&ai_result).map_err(|e| {
+                    AppError::InternalError(format!("Failed to serialize repaired plan: {}", e))
+                })?;
+            }
+        }
 
         // 开始数据库事务
         let mut tx = self.repository.begin_transaction().await?;
@@ -432,7 +970,7 @@ impl StudyPlanService {
             actual_start_date: None,
             actual_end_date: None,
             actual_terminated_date: None,
-            ai_plan_data: Some(request.ai_plan_data),
+            ai_plan_data: Some(ai_plan_data),
             deleted_at: None,
             total_schedules: None,
             completed_schedules: None,
@@ -499,6 +1037,64 @@ impl StudyPlanService {
         Ok(plan_id)
     }
 
+    /// 校验学习计划规划数据，可选返回修复后的规划
+    pub async fn validate_study_plan_schedule(
+        &self,
+        request: ValidateStudyPlanScheduleRequest,
+    ) -> AppResult<StudyPlanValidationResult> {
+        let plan: StudyPlanAIResult = serde_json::from_str(&request.ai_plan_data)
+            .map_err(|e| AppError::ValidationError(format!("Invalid AI plan data: {}", e)))?;
+
+        let (source_words, start_date, end_date) = self
+            .load_plan_validation_context(&request.wordbook_ids, &request.start_date, &request.end_date)
+            .await?;
+
+        let violations = study_plan_validator::validate_plan(&plan, &source_words, start_date, end_date);
+        let repaired_plan = if request.repair && !violations.is_empty() {
+            Some(study_plan_validator::repair_plan(plan, &source_words, start_date, end_date))
+        } else {
+            None
+        };
+
+        Ok(StudyPlanValidationResult {
+            valid: violations.is_empty(),
+            violations,
+            repaired_plan,
+        })
+    }
+
+    /// 加载规划校验所需的单词列表和日期范围
+    async fn load_plan_validation_context(
+        &self,
+        wordbook_ids: &[Id],
+        start_date: &str,
+        end_date: &str,
+    ) -> AppResult<(Vec<StudyWordInfo>, chrono::NaiveDate, chrono::NaiveDate)> {
+        use crate::repositories::word_repository::WordRepository;
+
+        let start = chrono::NaiveDate::parse_from_str(start_date, "%Y-%m-%d")
+            .map_err(|_| AppError::ValidationError(format!("无效的开始日期: {}", start_date)))?;
+        let end = chrono::NaiveDate::parse_from_str(end_date, "%Y-%m-%d")
+            .map_err(|_| AppError::ValidationError(format!("无效的结束日期: {}", end_date)))?;
+        if end < start {
+            return Err(AppError::ValidationError("结束日期不能早于开始日期".to_string()));
+        }
+
+        let word_repo = WordRepository::new(self.pool.clone(), self.logger.clone());
+        let source_words = word_repo
+            .find_words_by_wordbook_ids(wordbook_ids)
+            .await?
+            .into_iter()
+            .map(|(id, word, wordbook_id)| StudyWordInfo {
+                word,
+                word_id: id.to_string(),
+                wordbook_id: wordbook_id.to_string(),
+            })
+            .collect();
+
+        Ok((source_words, start, end))
+    }
+
     /// 从学习计划中移除单词
     pub async fn remove_word_from_plan(&self, plan_id: Id, word_id: Id) -> AppResult<()> {
         use crate::repositories::study_schedule_repository::StudyScheduleRepository;
@@ -526,24 +1122,46 @@ impl StudyPlanService {
     ) -> AppResult<usize> {
         use crate::repositories::study_schedule_repository::StudyScheduleRepository;
 
-        // 批量删除学习计划单词关联
-        let deleted_count = self
-            .repository
-            .batch_remove_words_from_plan(plan_id, word_ids)
+        // 保存计划单词和日程单词（连同练习记录）的快照
+        let snapshots = SnapshotService::new(self.pool.clone(), self.logger.clone());
+        let snapshot_id = snapshots
+            .capture(
+                SnapshotOperation::RemovePlanWords,
+                Some(plan_id),
+                &format!("从学习计划 {} 移除 {} 个单词", plan_id, word_ids.len()),
+                &[
+                    SnapshotScope::rows("study_plan_words", "plan_id", &[plan_id])
+                        .and("word_id", word_ids),
+                    SnapshotScope::rows("study_plan_schedule_words", "word_id", word_ids)
+                        .and_parent("schedule_id", "study_plan_schedules", "plan_id", &[plan_id])
+                        .with_cascade(),
+                ],
+            )
             .await?;
 
-        // 删除这些单词在学习计划中的所有日程安排
-        let schedule_repo = StudyScheduleRepository::new(
-            self.repository.get_pool(),
-            self.logger.clone(),
-        );
-        for word_id in word_ids {
-            schedule_repo
-                .delete_schedule_words_by_word_and_plan(*word_id, plan_id)
+        let result = async {
+            // 批量删除学习计划单词关联
+            let deleted_count = self
+                .repository
+                .batch_remove_words_from_plan(plan_id, word_ids)
                 .await?;
-        }
 
-        Ok(deleted_count)
+            // 删除这些单词在学习计划中的所有日程安排
+            let schedule_repo = StudyScheduleRepository::new(
+                self.repository.get_pool(),
+                self.logger.clone(),
+            );
+            for word_id in word_ids {
+                schedule_repo
+                    .delete_schedule_words_by_word_and_plan(*word_id, plan_id)
+                    .await?;
+            }
+
+            Ok(deleted_count)
+        }
+        .await;
+        snapshots.finish(snapshot_id, result.is_ok()).await;
+        result
     }
 
     /// 获取学习计划日历数据
@@ -603,6 +1221,14 @@ impl StudyPlanService {
         // 生成完整的日历数据
         let mut calendar_data = Vec::new();
         let today = chrono::Local::now().date_naive();
+        // 暂停期间冻结逾期：暂停当天及之后的日程不算逾期
+        let overdue_before = self
+            .repository
+            .find_paused_at(plan_id)
+            .await?
+            .as_deref()
+            .and_then(paused_on)
+            .map_or(today, |paused_on| paused_on.min(today));
         let mut current_date = calendar_start;
 
         while current_date <= calendar_end {
@@ -620,7 +1246,7 @@ impl StudyPlanService {
                 "completed"
             } else if completed_words > 0 {
                 "in-progress"
-            } else if current_date < today {
+            } else if current_date < overdue_before {
                 "overdue"
             } else {
                 "not-started"
@@ -655,3 +1281,15 @@ impl StudyPlanService {
         Ok(calendar_data)
     }
 }
+
+/// 暂停时间（UTC，datetime('now') 格式）对应的本地日期
+pub fn paused_on(paused_at: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDateTime::parse_from_str(paused_at, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|paused_at| {
+            paused_at
+                .and_utc()
+                .with_timezone(&chrono::Local)
+                .date_naive()
+        })
+}
@@ -0,0 +1,323 @@
+//! 逾期日程重新分配
+//!
+//! 把逾期和剩余日程中尚未完成的单词重新分配到今天及之后的日程:
+//! - 未学习的新词按原顺序平均分配到剩余日程，每天不超过学习强度的新词上限，放不下时延长计划
+//! - 随新词一起移动的复习保持与新词的间隔天数（同一天已有该单词的复习时顺延），超出计划范围的复习不再安排
+//! - 已学单词的逾期复习集中到第一个可用日程（同一天已有该单词的复习时合并）
+//! - 已完成、进行中或已有练习会话的日程保持不变
+//!
+//! 纯函数实现，不访问数据库，便于预览和单元测试
+
+use crate::types::common::Id;
+use chrono::{Duration, NaiveDate};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+/// 计划中的日程
+#[derive(Debug, Clone)]
+pub struct RebalanceSchedule {
+    pub id: Id,
+    pub date: NaiveDate,
+    pub movable: bool, // 未开始且没有练习会话的日程才可以调整
+}
+
+/// 可调整日程中的一条单词安排（study_plan_schedule_words 的一行）
+#[derive(Debug, Clone)]
+pub struct RebalanceEntry {
+    pub id: Id,
+    pub schedule_id: Id,
+    pub word_id: Id,
+    pub word: String,
+    pub is_review: bool,
+    pub review_count: Option<i32>,
+}
+
+/// 重新分配结果
+#[derive(Debug, Default)]
+pub struct RebalanceOutcome {
+    pub placements: HashMap<Id, NaiveDate>, // 单词安排 ID -> 新日期
+    pub dropped: Vec<Id>,                   // 不再安排的复习
+    pub added_dates: Vec<NaiveDate>,        // 延长计划新增的日期
+}
+
+/// 重新分配输入的指纹（SHA-256），用于确认应用时日程与预览时一致
+pub fn rebalance_fingerprint(
+    today: NaiveDate,
+    max_new_per_day: usize,
+    schedules: &[RebalanceSchedule],
+    entries: &[RebalanceEntry],
+) -> String {
+    let mut schedules: Vec<&RebalanceSchedule> = schedules.iter().collect();
+    schedules.sort_by_key(|s| s.id);
+    let mut entries: Vec<&RebalanceEntry> = entries.iter().collect();
+    entries.sort_by_key(|e| e.id);
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}|{}\n", today, max_new_per_day));
+    for s in schedules {
+        hasher.update(format!("s|{}|{}|{}\n", s.id, s.date, s.movable));
+    }
+    for e in entries {
+        hasher.update(format!(
+            "e|{}|{}|{}|{}|{:?}\n",
+            e.id, e.schedule_id, e.word_id, e.is_review, e.review_count
+        ));
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// 计算重新分配方案
+pub fn plan_rebalance(
+    today: NaiveDate,
+    max_new_per_day: usize,
+    schedules: &[RebalanceSchedule],
+    entries: &[RebalanceEntry],
+) -> RebalanceOutcome {
+    let dates: HashMap<Id, NaiveDate> = schedules.iter().map(|s| (s.id, s.date)).collect();
+    let movable: HashSet<Id> = schedules
+        .iter()
+        .filter(|s| s.movable)
+        .map(|s| s.id)
+        .collect();
+
+    let mut entries: Vec<(&RebalanceEntry, NaiveDate)> = entries
+        .iter()
+        .filter(|e| movable.contains(&e.schedule_id))
+        .filter_map(|e| dates.get(&e.schedule_id).map(|date| (e, *date)))
+        .collect();
+    entries.sort_by_key(|(e, date)| (*date, e.id));
+    if entries.is_empty() {
+        return RebalanceOutcome::default();
+    }
+
+    // 1. 可用日期：今天及之后的可调整日程，不够时在计划末尾之后追加
+    let mut targets: Vec<NaiveDate> = schedules
+        .iter()
+        .filter(|s| s.movable && s.date >= today)
+        .map(|s| s.date)
+        .collect();
+    targets.sort();
+
+    let new_entries: Vec<&(&RebalanceEntry, NaiveDate)> =
+        entries.iter().filter(|(e, _)| !e.is_review).collect();
+    let cap = max_new_per_day.max(1);
+    let needed = targets.len().max(new_entries.len().div_ceil(cap)).max(1);
+
+    let mut added_dates = Vec::new();
+    if needed > targets.len() {
+        let last_date = schedules.iter().map(|s| s.date).max().unwrap_or(today);
+        let mut next = today.max(last_date + Duration::days(1));
+        while targets.len() < needed {
+            targets.push(next);
+            added_dates.push(next);
+            next += Duration::days(1);
+        }
+    }
+
+    // 2. 新词按原顺序平均分配，前 remainder 天多分一个
+    let mut placements = HashMap::new();
+    let mut learned_on: HashMap<Id, (NaiveDate, NaiveDate)> = HashMap::new(); // 单词 -> (原日期, 新日期)
+    let base = new_entries.len() / targets.len();
+    let remainder = new_entries.len() % targets.len();
+    let mut queue = new_entries.iter();
+    for (day_index, target) in targets.iter().enumerate() {
+        let quota = base + usize::from(day_index < remainder);
+        for (entry, original) in queue.by_ref().take(quota) {
+            placements.insert(entry.id, *target);
+            learned_on
+                .entry(entry.word_id)
+                .or_insert((*original, *target));
+        }
+    }
+
+    // 3. 复习
+    let first_target = targets[0];
+    let mut dropped = Vec::new();
+    let mut reviews_on: HashSet<(Id, NaiveDate)> = HashSet::new();
+    let reviews: Vec<&(&RebalanceEntry, NaiveDate)> =
+        entries.iter().filter(|(e, _)| e.is_review).collect();
+
+    // 留在原日期和跟随新词移动的复习先占位，逾期复习再合并进来
+    let mut overdue = Vec::new();
+    for (entry, date) in reviews {
+        let desired = match learned_on.get(&entry.word_id) {
+            Some((original, learned)) => *learned + (*date - *original).max(Duration::zero()),
+            None if *date < today => {
+                overdue.push(entry);
+                continue;
+            }
+            None => *date,
+        };
+        // 同一单词每天只安排一次复习，目标日期已有复习时顺延到下一个可用日期
+        let target = targets
+            .iter()
+            .copied()
+            .find(|t| *t >= desired && !reviews_on.contains(&(entry.word_id, *t)));
+        match target {
+            Some(target) => {
+                placements.insert(entry.id, target);
+                reviews_on.insert((entry.word_id, target));
+            }
+            None => dropped.push(entry.id),
+        }
+    }
+    for entry in overdue {
+        if reviews_on.insert((entry.word_id, first_target)) {
+            placements.insert(entry.id, first_target);
+        } else {
+            dropped.push(entry.id);
+        }
+    }
+
+    RebalanceOutcome {
+        placements,
+        dropped,
+        added_dates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(offset: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 10).unwrap() + Duration::days(offset)
+    }
+
+    fn schedule(id: Id, offset: i64, movable: bool) -> RebalanceSchedule {
+        RebalanceSchedule {
+            id,
+            date: date(offset),
+            movable,
+        }
+    }
+
+    fn entry(id: Id, schedule_id: Id, word_id: Id, is_review: bool) -> RebalanceEntry {
+        RebalanceEntry {
+            id,
+            schedule_id,
+            word_id,
+            word: format!("word{}", word_id),
+            is_review,
+            review_count: None,
+        }
+    }
+
+    fn new_words_per_day(
+        outcome: &RebalanceOutcome,
+        entries: &[RebalanceEntry],
+    ) -> Vec<(NaiveDate, usize)> {
+        let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+        for e in entries.iter().filter(|e| !e.is_review) {
+            *counts.entry(outcome.placements[&e.id]).or_default() += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort();
+        counts
+    }
+
+    #[test]
+    fn test_overdue_new_words_spread_over_remaining_days() {
+        // 昨天逾期 4 个新词，今天和明天各 1 个
+        let schedules = vec![
+            schedule(1, -1, true),
+            schedule(2, 0, true),
+            schedule(3, 1, true),
+        ];
+        let entries: Vec<RebalanceEntry> = (1..=4)
+            .map(|i| entry(i, 1, i, false))
+            .chain([entry(5, 2, 5, false), entry(6, 3, 6, false)])
+            .collect();
+
+        let outcome = plan_rebalance(date(0), 10, &schedules, &entries);
+        assert!(outcome.added_dates.is_empty());
+        assert_eq!(
+            new_words_per_day(&outcome, &entries),
+            vec![(date(0), 3), (date(1), 3)]
+        );
+        // 原顺序保持：逾期的单词排在前面
+        assert_eq!(outcome.placements[&1], date(0));
+        assert_eq!(outcome.placements[&6], date(1));
+    }
+
+    #[test]
+    fn test_extends_plan_when_cap_is_exceeded() {
+        let schedules = vec![
+            schedule(1, -2, true),
+            schedule(2, 0, true),
+            schedule(3, 1, false),
+        ];
+        let entries: Vec<RebalanceEntry> = (1..=9).map(|i| entry(i, 1, i, false)).collect();
+
+        let outcome = plan_rebalance(date(0), 3, &schedules, &entries);
+        // 今天之后的第 1 天不可调整，新增日期从计划末尾之后开始
+        assert_eq!(outcome.added_dates, vec![date(2), date(3)]);
+        assert!(new_words_per_day(&outcome, &entries)
+            .iter()
+            .all(|(_, count)| *count <= 3));
+    }
+
+    #[test]
+    fn test_reviews_follow_moved_words_and_overdue_reviews_merge() {
+        let schedules = vec![
+            schedule(1, -3, false), // 已完成
+            schedule(2, -1, true),
+            schedule(3, 0, true),
+            schedule(4, 1, true),
+            schedule(5, 2, true),
+        ];
+        let entries = vec![
+            // 单词 1 昨天学习，间隔 2 天复习
+            entry(1, 2, 1, false),
+            entry(2, 4, 1, true),
+            // 单词 2 已在完成的日程中学过，逾期复习两次
+            entry(3, 2, 2, true),
+            entry(4, 3, 2, true),
+            // 单词 3 的复习超出计划范围
+            entry(5, 3, 3, false),
+            entry(6, 5, 3, true),
+        ];
+
+        let outcome = plan_rebalance(date(0), 10, &schedules, &entries);
+        assert_eq!(outcome.placements[&1], date(0));
+        assert_eq!(outcome.placements[&2], date(2));
+        // 逾期复习并入今天已有的复习
+        assert_eq!(outcome.placements[&4], date(0));
+        assert!(outcome.dropped.contains(&3));
+        // 单词 3 分到明天，复习应在第 4 天，超出计划
+        assert_eq!(outcome.placements[&5], date(1));
+        assert!(outcome.dropped.contains(&6));
+    }
+
+    #[test]
+    fn test_follow_on_reviews_never_share_a_day() {
+        let schedules = vec![
+            schedule(1, -2, true),
+            schedule(2, -1, true),
+            schedule(3, 0, true),
+            schedule(4, 2, true),
+            schedule(5, 3, true),
+        ];
+        // 单词 1 前天学习，间隔 1 天和 2 天复习；明天没有日程，两次复习都落在第 2 天
+        let entries = vec![
+            entry(1, 1, 1, false),
+            entry(2, 2, 1, true),
+            entry(3, 3, 1, true),
+        ];
+
+        let outcome = plan_rebalance(date(0), 10, &schedules, &entries);
+        assert_eq!(outcome.placements[&1], date(0));
+        assert_eq!(outcome.placements[&2], date(2));
+        assert_eq!(outcome.placements[&3], date(3));
+        assert!(outcome.dropped.is_empty());
+    }
+
+    #[test]
+    fn test_nothing_to_do_without_movable_entries() {
+        let schedules = vec![schedule(1, -1, false)];
+        let entries = vec![entry(1, 1, 1, false)];
+        let outcome = plan_rebalance(date(0), 10, &schedules, &entries);
+        assert!(outcome.placements.is_empty());
+        assert!(outcome.added_dates.is_empty());
+    }
+}
@@ -0,0 +1,548 @@
+//! 本地学习计划规划器
+//!
+//! 不依赖 AI 模型，按 `prompts/study_plan_agent.md` 中的规则生成学习计划:
+//! - 学习强度决定每日新词量范围（easy 5-15 / normal 15-30 / intensive 30-50）
+//! - 学习周期决定记忆曲线复习时间点
+//! - 单词按估算难度递增排列
+//!
+//! 复制计划或从模板生成时，可以按日程形状（每天新词数的相对比例）分配新词
+//!
+//! 相同输入始终得到相同输出，便于离线使用和单元测试
+//!
+//! 也负责向已有计划追加单词：新词填入今天之后未完成的日程（不超过每日新词上限），
+//! 复习按记忆曲线安排，已完成的日程保持不变
+
+use crate::error::{AppError, AppResult};
+use crate::services::spaced_repetition::DATE_FORMAT;
+use crate::types::study::*;
+use chrono::{Duration, NaiveDate};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 每日新词量绝对上限
+pub const MAX_NEW_WORDS_PER_DAY: i32 = 50;
+
+/// 学习强度对应的每日新词量范围（最小值, 最大值）
+pub fn daily_new_word_range(intensity_level: &str) -> Option<(i32, i32)> {
+    match intensity_level {
+        "easy" => Some((5, 15)),
+        "normal" => Some((15, 30)),
+        "intensive" => Some((30, MAX_NEW_WORDS_PER_DAY)),
+        _ => None,
+    }
+}
+
+/// 学习周期对应的复习时间点（学习后第 N 天）
+pub fn review_offsets(period_days: i32, review_frequency: i32) -> Option<Vec<i64>> {
+    let frequency = review_frequency.max(0) as usize;
+    let offsets: Vec<i64> = match period_days {
+        // 1天计划：当天内多次复习
+        1 => vec![0; frequency],
+        3 => vec![1, 2, 3],
+        7 => vec![1, 3, 6],
+        14 => vec![1, 3, 7, 12],
+        28 => vec![1, 3, 7, 14, 21],
+        _ => return None,
+    };
+
+    Some(offsets.into_iter().take(frequency).collect())
+}
+
+/// 计划类型描述
+pub fn plan_type_label(period_days: i32) -> String {
+    match period_days {
+        1 => "1天计划".to_string(),
+        3 => "3天计划".to_string(),
+        7 => "1周计划".to_string(),
+        14 => "2周计划".to_string(),
+        28 => "4周计划".to_string(),
+        days => format!("{}天计划", days),
+    }
+}
+
+/// 估算单词难度（1-5），以单词长度作为近似
+pub fn estimate_difficulty(word: &str) -> i32 {
+    match word.trim().chars().count() {
+        0..=4 => 1,
+        5..=6 => 2,
+        7..=8 => 3,
+        9..=10 => 4,
+        _ => 5,
+    }
+}
+
+/// 根据难度确定学习优先级
+pub fn priority_for_difficulty(difficulty_level: i32) -> &'static str {
+    match difficulty_level {
+        1..=2 => "high",
+        3 => "medium",
+        _ => "low",
+    }
+}
+
+/// 生成学习计划
+pub fn generate_schedule(params: &StudyPlanAIParams) -> AppResult<StudyPlanAIResult> {
+    generate_shaped_schedule(params, &[])
+}
+
+/// 按日程形状分配每天的新词数（最大余数法），形状的天数即计划天数
+pub fn shaped_daily_counts(total_words: usize, day_weights: &[i32]) -> Vec<usize> {
+    let weights: Vec<u64> = day_weights.iter().map(|w| (*w).max(0) as u64).collect();
+    let weight_sum: u64 = weights.iter().sum();
+    if weight_sum == 0 {
+        return vec![0; weights.len()];
+    }
+
+    let total = total_words as u64;
+    let mut counts: Vec<usize> = weights
+        .iter()
+        .map(|w| (total * w / weight_sum) as usize)
+        .collect();
+    let mut remainders: Vec<(u64, usize)> = weights
+        .iter()
+        .enumerate()
+        .map(|(day, w)| (total * w % weight_sum, day))
+        .collect();
+    // 余数大的优先，余数相同时靠前的日期优先
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    let assigned: usize = counts.iter().sum();
+    for (_, day) in remainders.into_iter().take(total_words - assigned) {
+        counts[day] += 1;
+    }
+    counts
+}
+
+/// 按日程形状生成学习计划
+///
+/// `day_weights` 为每天新词数的相对比例（来自已有计划或计划模板），新词按比例分配到各天，
+/// 计划天数为形状的天数，超出学习周期的部分不使用（来源计划可能被延长过）；为空时按学习周期平均分配
+pub fn generate_shaped_schedule(
+    params: &StudyPlanAIParams,
+    day_weights: &[i32],
+) -> AppResult<StudyPlanAIResult> {
+    let (min_per_day, max_per_day) = daily_new_word_range(&params.intensity_level)
+        .ok_or_else(|| AppError::ValidationError(format!("无效的学习强度: {}", params.intensity_level)))?;
+
+    let offsets = review_offsets(params.period_days, params.review_frequency).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "无效的学习周期: {}，必须为 1、3、7、14 或 28 天",
+            params.period_days
+        ))
+    })?;
+
+    let start_date = NaiveDate::parse_from_str(&params.start_date, DATE_FORMAT)
+        .map_err(|_| AppError::ValidationError(format!("无效的开始日期: {}", params.start_date)))?;
+
+    // 去重并按难度递增排序（难度相同时保持原顺序）
+    let mut seen = HashSet::new();
+    let mut words: Vec<(&StudyWordInfo, i32)> = params
+        .word_list
+        .iter()
+        .filter(|w| seen.insert(w.word_id.clone()))
+        .map(|w| (w, estimate_difficulty(&w.word)))
+        .collect();
+    words.sort_by_key(|(_, difficulty)| *difficulty);
+
+    if words.is_empty() {
+        return Err(AppError::ValidationError("单词列表不能为空".to_string()));
+    }
+
+    let total_words = words.len() as i32;
+    let period_days = params.period_days;
+    let day_weights = &day_weights[..day_weights.len().min(period_days as usize)];
+
+    let daily_counts = if day_weights.iter().any(|w| *w > 0) {
+        // 按日程形状分配，每天新词数不能超过学习强度上限
+        let counts = shaped_daily_counts(words.len(), day_weights);
+        let busiest = counts.iter().copied().max().unwrap_or(0) as i32;
+        if busiest > max_per_day {
+            return Err(AppError::ValidationError(format!(
+                "{} 个单词按日程形状需要某天学习 {} 个新词，超过 {} 强度上限 {} 个，请提高学习强度或减少单词",
+                total_words, busiest, params.intensity_level, max_per_day
+            )));
+        }
+        counts
+    } else {
+        // 每日新词量 = 总单词数 ÷ 学习周期天数，并限制在学习强度范围内
+        let even_share = (total_words + period_days - 1) / period_days;
+        if even_share > max_per_day {
+            return Err(AppError::ValidationError(format!(
+                "{} 个单词在 {} 天内需要每天学习 {} 个新词，超过 {} 强度上限 {} 个，请提高学习强度或延长学习周期",
+                total_words, period_days, even_share, params.intensity_level, max_per_day
+            )));
+        }
+        let new_per_day = even_share.max(min_per_day).min(total_words) as usize;
+        (0..period_days as usize)
+            .map(|day| new_per_day.min(words.len().saturating_sub(day * new_per_day)))
+            .collect()
+    };
+    let plan_days = daily_counts.len() as i32;
+    let day_indexes: Vec<usize> = daily_counts
+        .iter()
+        .enumerate()
+        .flat_map(|(day, count)| std::iter::repeat_n(day, *count))
+        .collect();
+
+    let end_date = start_date + Duration::days((plan_days - 1) as i64);
+    let mut daily_words: Vec<Vec<DailyStudyWord>> = (0..plan_days).map(|_| Vec::new()).collect();
+    let mut reviews: Vec<Vec<DailyStudyWord>> = (0..plan_days).map(|_| Vec::new()).collect();
+
+    for ((word, difficulty_level), day_index) in words.iter().zip(day_indexes) {
+        let day_index = day_index as i64;
+        let priority = priority_for_difficulty(*difficulty_level);
+
+        daily_words[day_index as usize].push(DailyStudyWord {
+            word_id: word.word_id.clone(),
+            word: word.word.clone(),
+            wordbook_id: word.wordbook_id.clone(),
+            is_review: false,
+            review_count: None,
+            priority: priority.to_string(),
+            difficulty_level: *difficulty_level,
+        });
+
+        for (review_index, offset) in offsets.iter().enumerate() {
+            let review_day = day_index + offset;
+            // 超出计划天数的复习不再安排
+            if review_day >= plan_days as i64 {
+                continue;
+            }
+
+            reviews[review_day as usize].push(DailyStudyWord {
+                word_id: word.word_id.clone(),
+                word: word.word.clone(),
+                wordbook_id: word.wordbook_id.clone(),
+                is_review: true,
+                review_count: Some(review_index as i32 + 1),
+                priority: priority.to_string(),
+                difficulty_level: *difficulty_level,
+            });
+        }
+    }
+
+    // 每天先学新词，再复习
+    let daily_plans: Vec<DailyStudyPlan> = daily_words
+        .into_iter()
+        .zip(reviews)
+        .enumerate()
+        .filter_map(|(day_index, (mut new_words, review_words))| {
+            new_words.extend(review_words);
+            if new_words.is_empty() {
+                return None;
+            }
+            Some(DailyStudyPlan {
+                day: day_index as i32 + 1,
+                date: (start_date + Duration::days(day_index as i64))
+                    .format(DATE_FORMAT)
+                    .to_string(),
+                words: new_words,
+            })
+        })
+        .collect();
+
+    Ok(StudyPlanAIResult {
+        plan_metadata: StudyPlanMetadata {
+            total_words,
+            study_period_days: period_days,
+            intensity_level: params.intensity_level.clone(),
+            review_frequency: params.review_frequency,
+            plan_type: plan_type_label(period_days),
+            start_date: start_date.format(DATE_FORMAT).to_string(),
+            end_date: end_date.format(DATE_FORMAT).to_string(),
+        },
+        daily_plans,
+        prompt_version: None,
+    })
+}
+
+// ==================== 追加单词 ====================
+
+/// 向已有计划追加单词时的日程情况
+#[derive(Debug, Clone, Default)]
+pub struct AdditionCalendar {
+    pub first_date: NaiveDate,                // 最早可以安排的日期（今天或计划开始日期）
+    pub open_days: HashMap<NaiveDate, i32>,   // 未完成的日程及其已有新词数
+    pub blocked_days: HashSet<NaiveDate>,     // 已完成的日程，保持不变
+    pub last_date: Option<NaiveDate>,         // 计划中最后一个日程的日期
+}
+
+impl AdditionCalendar {
+    /// 指定日期及之后第一个可以安排的日期
+    pub fn first_available(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date.max(self.first_date);
+        while self.blocked_days.contains(&date) {
+            date += Duration::days(1);
+        }
+        date
+    }
+}
+
+/// 追加单词的规划上下文
+#[derive(Debug, Clone)]
+pub struct PlanWordAddition {
+    pub plan_id: crate::types::common::Id,
+    pub params: StudyPlanAIParams, // 新单词的规划参数，本地规划和 AI 规划共用
+    pub calendar: AdditionCalendar,
+    pub max_new_words_per_day: i32,
+    pub review_offsets: Vec<i64>,
+    pub skipped_words: i32, // 已在计划中的单词数
+}
+
+fn study_word(word: &StudyWordInfo, difficulty_level: i32, review_count: Option<i32>) -> DailyStudyWord {
+    DailyStudyWord {
+        word_id: word.word_id.clone(),
+        word: word.word.clone(),
+        wordbook_id: word.wordbook_id.clone(),
+        is_review: review_count.is_some(),
+        review_count,
+        priority: priority_for_difficulty(difficulty_level).to_string(),
+        difficulty_level,
+    }
+}
+
+/// 按日期整理为每日计划，每天先学新词再复习
+fn into_daily_plans(days: BTreeMap<NaiveDate, Vec<DailyStudyWord>>) -> Vec<DailyStudyPlan> {
+    days.into_iter()
+        .enumerate()
+        .map(|(index, (date, mut words))| {
+            words.sort_by_key(|w| w.is_review);
+            DailyStudyPlan {
+                day: index as i32 + 1,
+                date: date.format(DATE_FORMAT).to_string(),
+                words,
+            }
+        })
+        .collect()
+}
+
+/// 本地规划追加的单词
+///
+/// 新词按难度递增依次填入可用日期，每天新词总数不超过上限，放不下时在计划末尾之后继续安排；
+/// 复习落在学习后第 N 天及之后第一个可用日期，超出计划范围的复习不再安排
+pub fn place_additional_words(addition: &PlanWordAddition) -> Vec<DailyStudyPlan> {
+    let calendar = &addition.calendar;
+    let cap = addition.max_new_words_per_day.max(1);
+
+    let mut seen = HashSet::new();
+    let mut words: Vec<(&StudyWordInfo, i32)> = addition
+        .params
+        .word_list
+        .iter()
+        .filter(|w| seen.insert(w.word_id.clone()))
+        .map(|w| (w, estimate_difficulty(&w.word)))
+        .collect();
+    words.sort_by_key(|(_, difficulty)| *difficulty);
+
+    let mut days: BTreeMap<NaiveDate, Vec<DailyStudyWord>> = BTreeMap::new();
+    let mut learned_on = Vec::new();
+    let mut date = calendar.first_available(calendar.first_date);
+    let mut queue = words.iter().peekable();
+    while queue.peek().is_some() {
+        let existing = calendar.open_days.get(&date).copied().unwrap_or(0);
+        let capacity = (cap - existing).max(0) as usize;
+        for (word, difficulty_level) in queue.by_ref().take(capacity) {
+            days.entry(date)
+                .or_default()
+                .push(study_word(word, *difficulty_level, None));
+            learned_on.push((*word, *difficulty_level, date));
+        }
+        date = calendar.first_available(date + Duration::days(1));
+    }
+
+    let last_date = days
+        .keys()
+        .next_back()
+        .copied()
+        .max(calendar.last_date)
+        .unwrap_or(calendar.first_date);
+    for (word, difficulty_level, date) in learned_on {
+        for (review_index, offset) in addition.review_offsets.iter().enumerate() {
+            let review_date = calendar.first_available(date + Duration::days(*offset));
+            if review_date > last_date {
+                continue;
+            }
+            days.entry(review_date).or_default().push(study_word(
+                word,
+                difficulty_level,
+                Some(review_index as i32 + 1),
+            ));
+        }
+    }
+
+    into_daily_plans(days)
+}
+
+/// 把 AI 规划的结果对齐到计划日程：日期挪到第一个可用日期，去掉不属于追加范围的单词
+pub fn align_additional_plans(
+    addition: &PlanWordAddition,
+    daily_plans: Vec<DailyStudyPlan>,
+) -> AppResult<Vec<DailyStudyPlan>> {
+    let allowed: HashSet<&str> = addition
+        .params
+        .word_list
+        .iter()
+        .map(|w| w.word_id.as_str())
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut days: BTreeMap<NaiveDate, Vec<DailyStudyWord>> = BTreeMap::new();
+    for daily_plan in daily_plans {
+        let date = NaiveDate::parse_from_str(&daily_plan.date, DATE_FORMAT)
+            .map_err(|_| AppError::ValidationError(format!("AI 规划的日期无效: {}", daily_plan.date)))?;
+        let date = addition.calendar.first_available(date);
+        for word in daily_plan.words {
+            if !allowed.contains(word.word_id.as_str())
+                || !seen.insert((word.word_id.clone(), word.is_review, word.review_count, date))
+            {
+                continue;
+            }
+            days.entry(date).or_default().push(word);
+        }
+    }
+
+    Ok(into_daily_plans(days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(intensity: &str, period_days: i32, review_frequency: i32, count: usize) -> StudyPlanAIParams {
+        let word_list: Vec<StudyWordInfo> = (1..=count)
+            .map(|i| StudyWordInfo {
+                word: format!("word{}", "x".repeat(i % 8)),
+                word_id: i.to_string(),
+                wordbook_id: "1".to_string(),
+            })
+            .collect();
+
+        StudyPlanAIParams {
+            intensity_level: intensity.to_string(),
+            total_words: word_list.len() as i32,
+            period_days,
+            review_frequency,
+            start_date: "2025-01-01".to_string(),
+            word_list,
+        }
+    }
+
+    #[test]
+    fn test_every_word_scheduled_once_as_new() {
+        let result = generate_schedule(&params("normal", 14, 4, 100)).unwrap();
+
+        let new_ids: Vec<&str> = result
+            .daily_plans
+            .iter()
+            .flat_map(|d| d.words.iter().filter(|w| !w.is_review))
+            .map(|w| w.word_id.as_str())
+            .collect();
+        let unique: HashSet<&str> = new_ids.iter().copied().collect();
+
+        assert_eq!(new_ids.len(), 100);
+        assert_eq!(unique.len(), 100);
+        assert_eq!(result.plan_metadata.end_date, "2025-01-14");
+    }
+
+    #[test]
+    fn test_daily_new_words_respect_intensity_cap() {
+        let result = generate_schedule(&params("easy", 7, 3, 100)).unwrap();
+        for day in &result.daily_plans {
+            let new_count = day.words.iter().filter(|w| !w.is_review).count();
+            assert!(new_count <= 15);
+        }
+
+        assert!(generate_schedule(&params("easy", 7, 3, 200)).is_err());
+    }
+
+    #[test]
+    fn test_reviews_follow_memory_curve() {
+        let result = generate_schedule(&params("easy", 7, 3, 5)).unwrap();
+        let days_with_review: Vec<i32> = result
+            .daily_plans
+            .iter()
+            .filter(|d| d.words.iter().any(|w| w.is_review))
+            .map(|d| d.day)
+            .collect();
+
+        // 第1天学习，第2、4、7天复习
+        assert_eq!(days_with_review, vec![2, 4, 7]);
+    }
+
+    #[test]
+    fn test_shaped_counts_follow_day_weights() {
+        assert_eq!(shaped_daily_counts(10, &[2, 0, 1, 1]), vec![5, 0, 3, 2]);
+        assert_eq!(shaped_daily_counts(3, &[1, 1, 1, 1]), vec![1, 1, 1, 0]);
+        assert_eq!(shaped_daily_counts(3, &[0, 0]), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_shaped_schedule_stays_within_period() {
+        let result = generate_shaped_schedule(&params("easy", 7, 3, 12), &[3, 3, 0, 2, 0, 0, 0, 0, 0, 0]).unwrap();
+        let new_counts: Vec<(i32, usize)> = result
+            .daily_plans
+            .iter()
+            .map(|d| (d.day, d.words.iter().filter(|w| !w.is_review).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        assert_eq!(new_counts, vec![(1, 5), (2, 4), (4, 3)]);
+        // 形状比 7 天学习周期长，结束日期仍与学习周期一致
+        assert_eq!(result.plan_metadata.end_date, "2025-01-07");
+        let shorter = generate_shaped_schedule(&params("easy", 7, 3, 12), &[3, 3, 2]).unwrap();
+        assert_eq!(shorter.plan_metadata.end_date, "2025-01-03");
+        assert!(generate_shaped_schedule(&params("easy", 7, 3, 40), &[1, 1]).is_err());
+    }
+
+    fn addition(count: usize, open_days: &[(i64, i32)], blocked_days: &[i64]) -> PlanWordAddition {
+        let day = |offset: i64| NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + Duration::days(offset);
+        let params = params("easy", 7, 3, count);
+        let open_days: HashMap<NaiveDate, i32> =
+            open_days.iter().map(|(offset, count)| (day(*offset), *count)).collect();
+        let blocked_days: HashSet<NaiveDate> = blocked_days.iter().map(|offset| day(*offset)).collect();
+        let last_date = open_days.keys().chain(blocked_days.iter()).max().copied();
+        PlanWordAddition {
+            plan_id: 1,
+            params,
+            calendar: AdditionCalendar {
+                first_date: day(0),
+                open_days,
+                blocked_days,
+                last_date,
+            },
+            max_new_words_per_day: 15,
+            review_offsets: vec![1, 3, 6],
+            skipped_words: 0,
+        }
+    }
+
+    #[test]
+    fn test_additional_words_fill_open_days_up_to_cap() {
+        // 第 1 天已完成，第 0 天已有 10 个新词
+        let plans = place_additional_words(&addition(20, &[(0, 10), (2, 0), (6, 0)], &[1]));
+        let new_counts: Vec<(&str, usize)> = plans
+            .iter()
+            .map(|d| (d.date.as_str(), d.words.iter().filter(|w| !w.is_review).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        assert_eq!(new_counts, vec![("2025-01-01", 5), ("2025-01-03", 15)]);
+        assert!(plans.iter().all(|d| d.date != "2025-01-02"));
+    }
+
+    #[test]
+    fn test_additional_reviews_stay_within_plan() {
+        let plans = place_additional_words(&addition(1, &[(0, 0), (4, 0)], &[1]));
+        let review_dates: Vec<&str> = plans
+            .iter()
+            .filter(|d| d.words.iter().any(|w| w.is_review))
+            .map(|d| d.date.as_str())
+            .collect();
+        // 第 1 天已完成，复习顺延到第 2 天；第 6 天超出计划
+        assert_eq!(review_dates, vec!["2025-01-03", "2025-01-04"]);
+    }
+
+    #[test]
+    fn test_output_is_deterministic() {
+        let a = serde_json::to_string(&generate_schedule(&params("intensive", 28, 5, 300)).unwrap()).unwrap();
+        let b = serde_json::to_string(&generate_schedule(&params("intensive", 28, 5, 300)).unwrap()).unwrap();
+        assert_eq!(a, b);
+    }
+}
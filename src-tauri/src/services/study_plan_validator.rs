@@ -0,0 +1,389 @@
+//! 学习计划规划校验与修复
+//!
+//! 对 AI 返回的 `StudyPlanAIResult` 进行结构校验:
+//! - 单词列表中的单词是否全部安排
+//! - 同一天是否重复安排同一单词
+//! - 日期是否在计划起止范围内
+//! - 单词是否属于所选单词本
+//!
+//! 并可在写入数据库前自动修复规划数据
+
+use crate::services::spaced_repetition::DATE_FORMAT;
+use crate::services::study_plan_scheduler::{estimate_difficulty, priority_for_difficulty};
+use crate::types::study::*;
+use chrono::{Duration, NaiveDate};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 创建校验问题
+fn violation(
+    kind: PlanViolationKind,
+    day: Option<i32>,
+    date: Option<&str>,
+    word_id: Option<&str>,
+    message: String,
+) -> PlanViolation {
+    PlanViolation {
+        kind,
+        day,
+        date: date.map(|d| d.to_string()),
+        word_id: word_id.map(|w| w.to_string()),
+        message,
+    }
+}
+
+/// 按 word_id 建立单词列表索引（保留首次出现）
+fn index_source_words(source_words: &[StudyWordInfo]) -> HashMap<&str, &StudyWordInfo> {
+    let mut index = HashMap::new();
+    for word in source_words {
+        index.entry(word.word_id.as_str()).or_insert(word);
+    }
+    index
+}
+
+/// 校验学习计划规划
+pub fn validate_plan(
+    plan: &StudyPlanAIResult,
+    source_words: &[StudyWordInfo],
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Vec<PlanViolation> {
+    let mut violations = Vec::new();
+    let source_index = index_source_words(source_words);
+    let mut seen_dates: HashMap<NaiveDate, i32> = HashMap::new();
+    let mut new_words: HashSet<&str> = HashSet::new();
+
+    for daily_plan in &plan.daily_plans {
+        let day = Some(daily_plan.day);
+        let date_str = Some(daily_plan.date.as_str());
+
+        match NaiveDate::parse_from_str(&daily_plan.date, DATE_FORMAT) {
+            Ok(date) => {
+                if date < start_date || date > end_date {
+                    violations.push(violation(
+                        PlanViolationKind::DateOutOfRange,
+                        day,
+                        date_str,
+                        None,
+                        format!(
+                            "第{}天日期 {} 超出计划范围 {} ~ {}",
+                            daily_plan.day,
+                            daily_plan.date,
+                            start_date.format(DATE_FORMAT),
+                            end_date.format(DATE_FORMAT)
+                        ),
+                    ));
+                }
+
+                let expected_day = (date - start_date).num_days() as i32 + 1;
+                if expected_day != daily_plan.day {
+                    violations.push(violation(
+                        PlanViolationKind::DayMismatch,
+                        day,
+                        date_str,
+                        None,
+                        format!(
+                            "日期 {} 应为第{}天，实际标注为第{}天",
+                            daily_plan.date, expected_day, daily_plan.day
+                        ),
+                    ));
+                }
+
+                if let Some(previous_day) = seen_dates.insert(date, daily_plan.day) {
+                    violations.push(violation(
+                        PlanViolationKind::DuplicateDate,
+                        day,
+                        date_str,
+                        None,
+                        format!(
+                            "第{}天与第{}天使用了相同日期 {}",
+                            daily_plan.day, previous_day, daily_plan.date
+                        ),
+                    ));
+                }
+            }
+            Err(_) => {
+                violations.push(violation(
+                    PlanViolationKind::InvalidDate,
+                    day,
+                    date_str,
+                    None,
+                    format!("第{}天日期格式无效: {}", daily_plan.day, daily_plan.date),
+                ));
+            }
+        }
+
+        let mut day_keys: HashSet<(&str, bool, Option<i32>)> = HashSet::new();
+        for word in &daily_plan.words {
+            let word_id = Some(word.word_id.as_str());
+
+            match source_index.get(word.word_id.as_str()) {
+                Some(source) if source.wordbook_id == word.wordbook_id => {}
+                Some(source) => {
+                    violations.push(violation(
+                        PlanViolationKind::ForeignWord,
+                        day,
+                        date_str,
+                        word_id,
+                        format!(
+                            "单词 {} 的单词本ID应为 {}，实际为 {}",
+                            word.word, source.wordbook_id, word.wordbook_id
+                        ),
+                    ));
+                }
+                None => {
+                    violations.push(violation(
+                        PlanViolationKind::ForeignWord,
+                        day,
+                        date_str,
+                        word_id,
+                        format!("单词 {} (ID {}) 不属于所选单词本", word.word, word.word_id),
+                    ));
+                }
+            }
+
+            if !day_keys.insert((word.word_id.as_str(), word.is_review, word.review_count)) {
+                violations.push(violation(
+                    PlanViolationKind::DuplicateWord,
+                    day,
+                    date_str,
+                    word_id,
+                    format!("第{}天重复安排了单词 {}", daily_plan.day, word.word),
+                ));
+            }
+
+            if !word.is_review && !new_words.insert(word.word_id.as_str()) {
+                violations.push(violation(
+                    PlanViolationKind::DuplicateNewWord,
+                    day,
+                    date_str,
+                    word_id,
+                    format!("单词 {} 被多次作为新词安排", word.word),
+                ));
+            }
+        }
+    }
+
+    // 未被安排为新词的单词
+    let mut reported = HashSet::new();
+    for source in source_words {
+        if !new_words.contains(source.word_id.as_str()) && reported.insert(source.word_id.as_str()) {
+            violations.push(violation(
+                PlanViolationKind::MissingWord,
+                None,
+                None,
+                Some(&source.word_id),
+                format!("单词 {} 未被安排学习", source.word),
+            ));
+        }
+    }
+
+    let metadata = &plan.plan_metadata;
+    if metadata.total_words != source_index.len() as i32 {
+        violations.push(violation(
+            PlanViolationKind::MetadataMismatch,
+            None,
+            None,
+            None,
+            format!(
+                "元数据单词总数为 {}，实际单词数为 {}",
+                metadata.total_words,
+                source_index.len()
+            ),
+        ));
+    }
+
+    if metadata.start_date != start_date.format(DATE_FORMAT).to_string()
+        || metadata.end_date != end_date.format(DATE_FORMAT).to_string()
+    {
+        violations.push(violation(
+            PlanViolationKind::MetadataMismatch,
+            None,
+            None,
+            None,
+            format!(
+                "元数据日期范围 {} ~ {} 与计划日期范围不一致",
+                metadata.start_date, metadata.end_date
+            ),
+        ));
+    }
+
+    violations
+}
+
+/// 修复学习计划规划
+///
+/// - 移除不属于所选单词本的单词，并按单词列表纠正单词文本和单词本ID
+/// - 无效日期按天数推算，超出范围的日期收敛到起止日期
+/// - 合并同一日期的日程，移除重复安排和重复新词
+/// - 未安排的单词补充到新词最少的一天
+/// - 重新计算天数和元数据
+pub fn repair_plan(
+    plan: StudyPlanAIResult,
+    source_words: &[StudyWordInfo],
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> StudyPlanAIResult {
+    let source_index = index_source_words(source_words);
+    let mut days: BTreeMap<NaiveDate, Vec<DailyStudyWord>> = BTreeMap::new();
+
+    for daily_plan in plan.daily_plans {
+        let date = NaiveDate::parse_from_str(&daily_plan.date, DATE_FORMAT)
+            .unwrap_or_else(|_| start_date + Duration::days((daily_plan.day - 1).max(0) as i64))
+            .clamp(start_date, end_date);
+
+        let day_words = days.entry(date).or_default();
+        for mut word in daily_plan.words {
+            let source = match source_index.get(word.word_id.as_str()) {
+                Some(source) => source,
+                None => continue,
+            };
+            word.word = source.word.clone();
+            word.wordbook_id = source.wordbook_id.clone();
+
+            let duplicated = day_words.iter().any(|w| {
+                w.word_id == word.word_id
+                    && w.is_review == word.is_review
+                    && w.review_count == word.review_count
+            });
+            if !duplicated {
+                day_words.push(word);
+            }
+        }
+    }
+
+    // 同一单词只保留最早的一次新词安排
+    let mut new_words: HashSet<String> = HashSet::new();
+    for day_words in days.values_mut() {
+        day_words.retain(|w| w.is_review || new_words.insert(w.word_id.clone()));
+    }
+
+    // 补充未安排的单词
+    for source in source_words {
+        if new_words.contains(&source.word_id) {
+            continue;
+        }
+        new_words.insert(source.word_id.clone());
+
+        let target_date = days
+            .iter()
+            .min_by_key(|(date, words)| (words.iter().filter(|w| !w.is_review).count(), **date))
+            .map(|(date, _)| *date)
+            .unwrap_or(start_date);
+
+        let difficulty_level = estimate_difficulty(&source.word);
+        days.entry(target_date).or_default().push(DailyStudyWord {
+            word_id: source.word_id.clone(),
+            word: source.word.clone(),
+            wordbook_id: source.wordbook_id.clone(),
+            is_review: false,
+            review_count: None,
+            priority: priority_for_difficulty(difficulty_level).to_string(),
+            difficulty_level,
+        });
+    }
+
+    let daily_plans = days
+        .into_iter()
+        .filter(|(_, words)| !words.is_empty())
+        .map(|(date, mut words)| {
+            // 新词在前，复习在后
+            words.sort_by_key(|w| w.is_review);
+            DailyStudyPlan {
+                day: (date - start_date).num_days() as i32 + 1,
+                date: date.format(DATE_FORMAT).to_string(),
+                words,
+            }
+        })
+        .collect();
+
+    StudyPlanAIResult {
+        plan_metadata: StudyPlanMetadata {
+            total_words: source_index.len() as i32,
+            start_date: start_date.format(DATE_FORMAT).to_string(),
+            end_date: end_date.format(DATE_FORMAT).to_string(),
+            ..plan.plan_metadata
+        },
+        daily_plans,
+        prompt_version: plan.prompt_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    fn source() -> Vec<StudyWordInfo> {
+        ["apple", "book", "cat"]
+            .iter()
+            .enumerate()
+            .map(|(i, w)| StudyWordInfo {
+                word: w.to_string(),
+                word_id: (i + 1).to_string(),
+                wordbook_id: "1".to_string(),
+            })
+            .collect()
+    }
+
+    fn word(word_id: &str, is_review: bool) -> DailyStudyWord {
+        DailyStudyWord {
+            word_id: word_id.to_string(),
+            word: format!("w{}", word_id),
+            wordbook_id: "1".to_string(),
+            is_review,
+            review_count: if is_review { Some(1) } else { None },
+            priority: "medium".to_string(),
+            difficulty_level: 1,
+        }
+    }
+
+    fn broken_plan() -> StudyPlanAIResult {
+        StudyPlanAIResult {
+            plan_metadata: StudyPlanMetadata {
+                total_words: 3,
+                study_period_days: 3,
+                intensity_level: "easy".to_string(),
+                review_frequency: 3,
+                plan_type: "3天计划".to_string(),
+                start_date: "2025-01-01".to_string(),
+                end_date: "2025-01-03".to_string(),
+            },
+            daily_plans: vec![
+                DailyStudyPlan {
+                    day: 1,
+                    date: "2025-01-01".to_string(),
+                    words: vec![word("1", false), word("1", false), word("99", false)],
+                },
+                DailyStudyPlan {
+                    day: 2,
+                    date: "2025-01-09".to_string(),
+                    words: vec![word("2", false), word("1", true)],
+                },
+            ],
+            prompt_version: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_violations() {
+        let violations = validate_plan(&broken_plan(), &source(), date("2025-01-01"), date("2025-01-03"));
+        let kinds: Vec<PlanViolationKind> = violations.iter().map(|v| v.kind).collect();
+
+        assert!(kinds.contains(&PlanViolationKind::DuplicateWord));
+        assert!(kinds.contains(&PlanViolationKind::ForeignWord));
+        assert!(kinds.contains(&PlanViolationKind::DateOutOfRange));
+        assert!(kinds.contains(&PlanViolationKind::MissingWord));
+    }
+
+    #[test]
+    fn test_repaired_plan_is_valid() {
+        let repaired = repair_plan(broken_plan(), &source(), date("2025-01-01"), date("2025-01-03"));
+        let violations = validate_plan(&repaired, &source(), date("2025-01-01"), date("2025-01-03"));
+
+        assert!(violations.is_empty(), "{:?}", violations);
+        assert_eq!(repaired.daily_plans.last().unwrap().date, "2025-01-03");
+    }
+}
@@ -9,7 +9,9 @@
 
 use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
+use crate::phonics_rules;
 use crate::repositories::word_repository::WordRepository;
+use crate::services::pronunciation::PronunciationService;
 use crate::types::{common::{Id, PaginatedResponse}, wordbook::*};
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -19,13 +21,17 @@ use std::sync::Arc;
 /// 负责单词的业务逻辑处理
 pub struct WordService {
     repository: WordRepository,
+    pronunciation: PronunciationService,
+    logger: Arc<Logger>,
 }
 
 impl WordService {
     /// 创建新的服务实例
     pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
         Self {
-            repository: WordRepository::new(pool, logger),
+            repository: WordRepository::new(pool.clone(), logger.clone()),
+            pronunciation: PronunciationService::new(pool, logger.clone()),
+            logger,
         }
     }
 
@@ -34,8 +40,51 @@ impl WordService {
     pub async fn add_word_to_book(
         &self,
         book_id: Id,
-        word_data: CreateWordRequest,
+        mut word_data: CreateWordRequest,
     ) -> AppResult<Id> {
+        // 已填写的音节和规则与拼读规则不一致时只记录日志，不修改用户输入
+        for disagreement in phonics_rules::check(
+            &word_data.word,
+            word_data.syllables.as_deref(),
+            word_data.phonics_rule.as_deref(),
+        ) {
+            self.logger.info(
+                "WORD_SERVICE",
+                &format!(
+                    "⚠️  {} 的 {} 与拼读规则不一致: {}（规则: {}）",
+                    disagreement.word,
+                    disagreement.field,
+                    disagreement.message,
+                    disagreement.rule_value
+                ),
+            );
+        }
+
+        // 空缺的音标由发音词典补全，已填写的音标与词典不一致时记录日志
+        if let Some(mismatch) = self
+            .pronunciation
+            .fill_or_check(&word_data.word, &mut word_data.ipa)
+            .await?
+        {
+            self.logger.info(
+                "WORD_SERVICE",
+                &format!(
+                    "⚠️  {} 的音标 {} 与发音词典不一致（词典: {}）",
+                    mismatch.word,
+                    mismatch.ai_ipa,
+                    mismatch.dictionary_ipa.join(", ")
+                ),
+            );
+        }
+
+        // 空缺的音节、拼读片段和规则由拼读规则补全
+        phonics_rules::fill_missing(
+            &word_data.word,
+            &mut word_data.syllables,
+            &mut word_data.phonics_segments,
+            &mut word_data.phonics_rule,
+        );
+
         // 将 CreateWordRequest 转换为 Word
         let word = Word {
             id: 0, // 新单词，ID 由数据库生成
@@ -57,6 +106,7 @@ impl WordService {
             analysis_explanation: word_data.analysis_explanation,
             created_at: String::new(),
             updated_at: String::new(),
+            highlight: None,
         };
 
         // 调用 repository 创建
@@ -151,6 +201,21 @@ impl WordService {
             )
             .await?;
 
+        Ok(PaginatedResponse::new(words, total, page, page_size))
+    }
+    /// 全文搜索所有单词本中的单词
+    pub async fn search_words(
+        &self,
+        query: WordSearchQuery,
+    ) -> AppResult<PaginatedResponse<Word>> {
+        if query.query.trim().is_empty() {
+            return Err(AppError::ValidationError("搜索关键词不能为空".to_string()));
+        }
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+        let (words, total) = self.repository.search(&query, page, page_size).await?;
+
         Ok(PaginatedResponse::new(words, total, page, page_size))
     }
 }
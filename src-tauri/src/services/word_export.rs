@@ -0,0 +1,301 @@
+//! 单词本导出业务逻辑服务
+//!
+//! 将单词本及其单词导出为：
+//! - CSV：表头与导入列名一致，可直接重新导入
+//! - JSON 导出包：包含单词本信息、主题标签和全部单词字段，可完整还原
+//! - Anki 牌组（.apkg）：可选打包已缓存的发音音频
+
+use crate::anki_package::{self, AnkiDeck, AnkiMedia, AnkiNote};
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::repositories::word_repository::WordRepository;
+use crate::repositories::wordbook_repository::WordBookRepository;
+use crate::types::common::Id;
+use crate::types::wordbook::*;
+use sqlx::{Row, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// CSV 列名（与导入时识别的列名一致）
+const CSV_HEADERS: [&str; 13] = [
+    "word",
+    "meaning",
+    "ipa",
+    "syllables",
+    "phonics_segments",
+    "part_of_speech",
+    "pos_abbreviation",
+    "pos_english",
+    "pos_chinese",
+    "phonics_rule",
+    "analysis_explanation",
+    "description",
+    "theme_tags",
+];
+
+/// 导出的文件内容
+struct ExportedFile {
+    content: Vec<u8>,
+    word_count: usize,
+    audio_count: usize,
+}
+
+/// 单词本导出服务
+pub struct WordExportService {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl WordExportService {
+    /// 创建新的服务实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    /// 导出单词本到文件，未指定路径时保存到 `default_dir`
+    pub async fn export_to_file(
+        &self,
+        book_id: Id,
+        request: &WordBookExportRequest,
+        default_dir: &Path,
+    ) -> AppResult<WordBookExportResult> {
+        let bundle = self.build_bundle(book_id).await?;
+        let exported = match request.format {
+            WordBookExportFormat::Csv => Self::to_csv(&bundle)?,
+            WordBookExportFormat::Json => ExportedFile {
+                content: serde_json::to_vec_pretty(&bundle)
+                    .map_err(|e| AppError::InternalError(e.to_string()))?,
+                word_count: bundle.words.len(),
+                audio_count: 0,
+            },
+            WordBookExportFormat::Apkg => {
+                self.to_apkg(book_id, &bundle, request.include_audio)
+                    .await?
+            }
+        };
+
+        let file_path = match &request.output_path {
+            Some(path) => PathBuf::from(path),
+            None => default_dir.join(format!(
+                "{}-{}.{}",
+                file_stem(&bundle.book.title),
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                request.format.extension()
+            )),
+        };
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::InternalError(format!("创建导出目录失败: {}", e)))?;
+        }
+        std::fs::write(&file_path, &exported.content)
+            .map_err(|e| AppError::InternalError(format!("写入导出文件失败: {}", e)))?;
+
+        self.logger.info(
+            "WORD_EXPORT",
+            &format!(
+                "📤 Exported book {} as {:?}: {} words, {} audio files -> {}",
+                book_id,
+                request.format,
+                exported.word_count,
+                exported.audio_count,
+                file_path.display()
+            ),
+        );
+
+        Ok(WordBookExportResult {
+            file_path: file_path.to_string_lossy().to_string(),
+            format: request.format,
+            word_count: exported.word_count,
+            audio_count: exported.audio_count,
+            file_size: exported.content.len() as u64,
+        })
+    }
+
+    /// 读取单词本、主题标签和全部单词
+    pub async fn build_bundle(&self, book_id: Id) -> AppResult<WordBookBundle> {
+        let book = WordBookRepository::new(self.pool.clone(), self.logger.clone())
+            .find_by_id(book_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("单词本 {} 不存在", book_id)))?;
+        let words = WordRepository::new(self.pool.clone(), self.logger.clone())
+            .find_all_by_book(book_id)
+            .await?;
+
+        Ok(WordBookBundle {
+            version: WORD_BOOK_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            theme_tags: book
+                .theme_tags
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tag| WordBookBundleTag {
+                    name: tag.name,
+                    icon: tag.icon,
+                    color: tag.color,
+                })
+                .collect(),
+            book: WordBookBundleInfo {
+                title: book.title,
+                description: book.description,
+                icon: book.icon,
+                icon_color: book.icon_color,
+            },
+            words: words
+                .into_iter()
+                .map(|w| CreateWordRequest {
+                    word: w.word,
+                    meaning: w.meaning,
+                    description: w.description,
+                    ipa: w.ipa,
+                    syllables: w.syllables,
+                    phonics_segments: w.phonics_segments,
+                    part_of_speech: w.part_of_speech,
+                    category_id: None, // 分类 ID 只在本机有效
+                    pos_abbreviation: w.pos_abbreviation,
+                    pos_english: w.pos_english,
+                    pos_chinese: w.pos_chinese,
+                    phonics_rule: w.phonics_rule,
+                    analysis_explanation: w.analysis_explanation,
+                })
+                .collect(),
+        })
+    }
+
+    fn to_csv(bundle: &WordBookBundle) -> AppResult<ExportedFile> {
+        let csv_error = |e: csv::Error| AppError::InternalError(format!("生成 CSV 失败: {}", e));
+        let theme_tags = bundle
+            .theme_tags
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(CSV_HEADERS).map_err(csv_error)?;
+        for word in &bundle.words {
+            let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+            writer
+                .write_record([
+                    word.word.clone(),
+                    word.meaning.clone(),
+                    optional(&word.ipa),
+                    optional(&word.syllables),
+                    optional(&word.phonics_segments),
+                    optional(&word.part_of_speech),
+                    optional(&word.pos_abbreviation),
+                    optional(&word.pos_english),
+                    optional(&word.pos_chinese),
+                    optional(&word.phonics_rule),
+                    optional(&word.analysis_explanation),
+                    optional(&word.description),
+                    theme_tags.clone(),
+                ])
+                .map_err(csv_error)?;
+        }
+
+        let content = writer
+            .into_inner()
+            .map_err(|e| AppError::InternalError(format!("生成 CSV 失败: {}", e)))?;
+        // 带 BOM，Excel 打开时能正确识别 UTF-8
+        let mut bytes = "\u{feff}".as_bytes().to_vec();
+        bytes.extend(content);
+
+        Ok(ExportedFile {
+            content: bytes,
+            word_count: bundle.words.len(),
+            audio_count: 0,
+        })
+    }
+
+    async fn to_apkg(
+        &self,
+        book_id: Id,
+        bundle: &WordBookBundle,
+        include_audio: bool,
+    ) -> AppResult<ExportedFile> {
+        let tags: Vec<String> = bundle
+            .theme_tags
+            .iter()
+            .map(|t| anki_package::tag_name(&t.name))
+            .collect();
+
+        let mut deck = AnkiDeck {
+            id: anki_package::deck_id_for_book(book_id),
+            name: bundle.book.title.clone(),
+            description: bundle.book.description.clone(),
+            notes: Vec::with_capacity(bundle.words.len()),
+            media: Vec::new(),
+        };
+        for word in &bundle.words {
+            let audio = if include_audio {
+                self.find_cached_audio(&word.word).await?
+            } else {
+                None
+            };
+            let audio_file = audio.map(|media| {
+                let file_name = media.file_name.clone();
+                if !deck.media.iter().any(|m| m.file_name == file_name) {
+                    deck.media.push(media);
+                }
+                file_name
+            });
+
+            deck.notes.push(AnkiNote {
+                word: word.word.clone(),
+                meaning: word.meaning.clone(),
+                ipa: word.ipa.clone(),
+                syllables: word.syllables.clone(),
+                phonics_rule: word.phonics_rule.clone(),
+                audio_file,
+                tags: tags.clone(),
+            });
+        }
+
+        Ok(ExportedFile {
+            content: anki_package::build_apkg(&deck).await?,
+            word_count: deck.notes.len(),
+            audio_count: deck.media.len(),
+        })
+    }
+
+    /// 查找单词最近使用的发音缓存（缓存文件已丢失的跳过）
+    async fn find_cached_audio(&self, word: &str) -> AppResult<Option<AnkiMedia>> {
+        let rows = sqlx::query(
+            "SELECT text_hash, file_path FROM tts_cache WHERE original_text = ? COLLATE NOCASE ORDER BY last_used DESC",
+        )
+        .bind(word.trim())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        for row in rows {
+            let text_hash: String = row.get("text_hash");
+            let file_path: String = row.get("file_path");
+            if let Ok(data) = std::fs::read(&file_path) {
+                return Ok(Some(AnkiMedia {
+                    file_name: format!("redlark-{}.mp3", text_hash),
+                    data,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// 由单词本标题生成文件名（去掉文件系统不允许的字符）
+fn file_stem(title: &str) -> String {
+    let stem: String = title
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if stem.is_empty() {
+        "wordbook".to_string()
+    } else {
+        stem
+    }
+}
@@ -0,0 +1,420 @@
+//! 单词导入业务逻辑服务
+//!
+//! 从 CSV、TSV 和 Anki 纯文本文件批量导入单词，不需要 AI 分析：
+//! - 预览列映射、文件内重复行和与单词本已有单词的冲突
+//! - 新单词由拼读规则和发音词典补全空缺字段
+//! - 在同一事务中创建单词本、插入新单词、更新冲突单词
+//! - 导入单词本 JSON 导出包，完整还原单词本、主题标签和单词
+
+use crate::error::{AppError, AppResult};
+use crate::logger::Logger;
+use crate::repositories::word_repository::WordRepository;
+use crate::repositories::wordbook_repository::WordBookRepository;
+use crate::services::pronunciation::PronunciationService;
+use crate::types::common::{Id, WordSaveResult};
+use crate::types::wordbook::*;
+use crate::word_import::{map_rows, parse_table, suggest_mapping, ParsedTable};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 预览中返回的样例单词数量
+const SAMPLE_SIZE: usize = 20;
+
+/// 解析和查重后的导入计划
+struct ImportPlan {
+    table: ParsedTable,
+    mapping: WordImportMapping,
+    new_words: Vec<CreateWordRequest>,
+    conflicts: Vec<(WordImportConflict, CreateWordRequest)>,
+    duplicate_lines: Vec<usize>,
+    errors: Vec<WordImportRowError>,
+}
+
+/// 单词导入服务
+pub struct WordImportService {
+    pool: Arc<SqlitePool>,
+    logger: Arc<Logger>,
+}
+
+impl WordImportService {
+    /// 创建新的服务实例
+    pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
+        Self { pool, logger }
+    }
+
+    /// 预览导入结果，不修改数据库
+    pub async fn preview(&self, request: &WordImportRequest) -> AppResult<WordImportPreview> {
+        let plan = self.plan(request).await?;
+
+        Ok(WordImportPreview {
+            format: plan.table.format,
+            has_header: plan.table.has_header,
+            headers: plan.table.headers,
+            mapping: plan.mapping,
+            total_rows: plan.table.rows.len(),
+            new_count: plan.new_words.len(),
+            sample: plan.new_words.into_iter().take(SAMPLE_SIZE).collect(),
+            conflicts: plan.conflicts.into_iter().map(|(c, _)| c).collect(),
+            duplicate_lines: plan.duplicate_lines,
+            errors: plan.errors,
+        })
+    }
+
+    /// 导入单词，冲突单词按请求中的策略跳过或更新
+    pub async fn import(&self, request: WordImportRequest) -> AppResult<WordSaveResult> {
+        let plan = self.plan(&request).await?;
+        if plan.new_words.is_empty() && plan.conflicts.is_empty() {
+            return Err(AppError::ValidationError(
+                "文件中没有可导入的单词".to_string(),
+            ));
+        }
+
+        let mut skipped_count = plan.duplicate_lines.len() + plan.errors.len();
+        let mut words_to_add = plan.new_words;
+        let mut words_to_update = Vec::new();
+        match request.conflict_strategy {
+            ImportConflictStrategy::Skip => skipped_count += plan.conflicts.len(),
+            ImportConflictStrategy::Update => words_to_update = plan.conflicts,
+        }
+
+        // 新单词补全拼读字段和音标；更新的单词只校验文件中给出的音标
+        for word in words_to_add.iter_mut() {
+            crate::phonics_rules::fill_missing(
+                &word.word,
+                &mut word.syllables,
+                &mut word.phonics_segments,
+                &mut word.phonics_rule,
+            );
+        }
+        let pronunciation = PronunciationService::new(self.pool.clone(), self.logger.clone());
+        let mut ipa_mismatches = pronunciation.fill_or_check_words(&mut words_to_add).await?;
+        for (_, word) in words_to_update.iter_mut() {
+            if word.ipa.is_some() {
+                if let Some(mismatch) = pronunciation
+                    .fill_or_check(&word.word, &mut word.ipa)
+                    .await?
+                {
+                    ipa_mismatches.push(mismatch);
+                }
+            }
+        }
+
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        let book_id = match (request.book_id, &request.new_book) {
+            (Some(book_id), _) => book_id,
+            (None, Some(new_book)) => create_book(&mut tx, new_book).await?,
+            (None, None) => unreachable!("plan 已校验目标单词本"),
+        };
+        insert_words(&mut tx, book_id, &words_to_add).await?;
+
+        // 只覆盖文件中有值的字段，未映射或为空的列保留原值
+        let update_query = r#"
+            UPDATE words SET
+                meaning = COALESCE(NULLIF(?, ''), meaning),
+                description = COALESCE(?, description),
+                ipa = COALESCE(?, ipa),
+                syllables = COALESCE(?, syllables),
+                phonics_segments = COALESCE(?, phonics_segments),
+                part_of_speech = COALESCE(?, part_of_speech),
+                pos_abbreviation = COALESCE(?, pos_abbreviation),
+                pos_english = COALESCE(?, pos_english),
+                pos_chinese = COALESCE(?, pos_chinese),
+                phonics_rule = COALESCE(?, phonics_rule),
+                analysis_explanation = COALESCE(?, analysis_explanation),
+                updated_at = datetime('now')
+            WHERE id = ?
+        "#;
+        for (conflict, word) in &words_to_update {
+            sqlx::query(update_query)
+                .bind(&word.meaning)
+                .bind(&word.description)
+                .bind(&word.ipa)
+                .bind(&word.syllables)
+                .bind(&word.phonics_segments)
+                .bind(&word.part_of_speech)
+                .bind(&word.pos_abbreviation)
+                .bind(&word.pos_english)
+                .bind(&word.pos_chinese)
+                .bind(&word.phonics_rule)
+                .bind(&word.analysis_explanation)
+                .bind(conflict.existing_word_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to update word '{}': {}", word.word, e))
+                })?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+        // 更新单词本统计（在事务外，避免长时间锁定）
+        WordBookRepository::new(self.pool.clone(), self.logger.clone())
+            .update_statistics(book_id)
+            .await?;
+
+        let result = WordSaveResult {
+            book_id,
+            added_count: words_to_add.len() as i32,
+            updated_count: words_to_update.len() as i32,
+            skipped_count: skipped_count as i32,
+            ipa_mismatches,
+        };
+        self.logger.info(
+            "WORD_IMPORT",
+            &format!(
+                "📥 Imported words into book {}: {} added, {} updated, {} skipped",
+                book_id, result.added_count, result.updated_count, result.skipped_count
+            ),
+        );
+        Ok(result)
+    }
+
+    /// 导入单词本 JSON 导出包：创建新单词本，主题标签按名称匹配
+    pub async fn import_bundle(&self, content: &str) -> AppResult<WordSaveResult> {
+        let bundle: WordBookBundle =
+            serde_json::from_str(content.trim_start_matches('\u{feff}'))
+                .map_err(|e| AppError::ValidationError(format!("单词本导出包格式错误: {}", e)))?;
+        if bundle.version > WORD_BOOK_BUNDLE_VERSION {
+            return Err(AppError::ValidationError(format!(
+                "不支持的导出包版本: {}",
+                bundle.version
+            )));
+        }
+        if bundle.book.title.trim().is_empty() {
+            return Err(AppError::ValidationError("单词本标题不能为空".to_string()));
+        }
+
+        // 导出包中的单词原样保存，只跳过空单词和重复单词
+        let mut seen = HashSet::new();
+        let mut skipped_count = 0;
+        let words: Vec<CreateWordRequest> = bundle
+            .words
+            .into_iter()
+            .filter(|w| {
+                let keep = !w.word.trim().is_empty() && seen.insert(w.word.to_lowercase());
+                if !keep {
+                    skipped_count += 1;
+                }
+                keep
+            })
+            .collect();
+
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        // 不存在的主题标签按导出包中的图标和颜色创建
+        let mut theme_tag_ids = Vec::new();
+        for tag in &bundle.theme_tags {
+            sqlx::query("INSERT OR IGNORE INTO theme_tags (name, icon, color) VALUES (?, ?, ?)")
+                .bind(&tag.name)
+                .bind(&tag.icon)
+                .bind(&tag.color)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let tag_id: Id = sqlx::query_scalar("SELECT id FROM theme_tags WHERE name = ?")
+                .bind(&tag.name)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            theme_tag_ids.push(tag_id);
+        }
+
+        let book = CreateWordBookRequest {
+            title: bundle.book.title,
+            description: bundle.book.description,
+            icon: bundle.book.icon,
+            icon_color: bundle.book.icon_color,
+            theme_tag_ids: Some(theme_tag_ids),
+        };
+        let book_id = create_book(&mut tx, &book).await?;
+        insert_words(&mut tx, book_id, &words).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+        WordBookRepository::new(self.pool.clone(), self.logger.clone())
+            .update_statistics(book_id)
+            .await?;
+
+        self.logger.info(
+            "WORD_IMPORT",
+            &format!(
+                "📥 Imported bundle '{}' as book {}: {} words, {} skipped",
+                book.title,
+                book_id,
+                words.len(),
+                skipped_count
+            ),
+        );
+        Ok(WordSaveResult {
+            book_id,
+            added_count: words.len() as i32,
+            updated_count: 0,
+            skipped_count,
+            ipa_mismatches: Vec::new(),
+        })
+    }
+
+    /// 解析文件、应用列映射，并与目标单词本查重
+    async fn plan(&self, request: &WordImportRequest) -> AppResult<ImportPlan> {
+        match (request.book_id, &request.new_book) {
+            (Some(book_id), _) => {
+                let exists: Option<Id> = sqlx::query_scalar(
+                    "SELECT id FROM word_books WHERE id = ? AND deleted_at IS NULL",
+                )
+                .bind(book_id)
+                .fetch_optional(self.pool.as_ref())
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                if exists.is_none() {
+                    return Err(AppError::NotFound(format!("单词本 {} 不存在", book_id)));
+                }
+            }
+            (None, Some(new_book)) if new_book.title.trim().is_empty() => {
+                return Err(AppError::ValidationError("单词本标题不能为空".to_string()));
+            }
+            (None, Some(_)) => {}
+            (None, None) => {
+                return Err(AppError::ValidationError(
+                    "请指定要导入的单词本或新单词本信息".to_string(),
+                ));
+            }
+        }
+
+        let table = parse_table(&request.content, request.format, request.has_header)?;
+        let mapping = request
+            .mapping
+            .clone()
+            .unwrap_or_else(|| suggest_mapping(&table));
+        let (rows, errors) = map_rows(&table, &mapping);
+
+        // 文件内去重，只保留第一次出现的单词
+        let mut seen = HashSet::new();
+        let mut unique_rows = Vec::new();
+        let mut duplicate_lines = Vec::new();
+        for (line, word) in rows {
+            if seen.insert(word.word.to_lowercase()) {
+                unique_rows.push((line, word));
+            } else {
+                duplicate_lines.push(line);
+            }
+        }
+
+        let existing = match request.book_id {
+            Some(book_id) => {
+                let word_list: Vec<String> = seen.into_iter().collect();
+                WordRepository::new(self.pool.clone(), self.logger.clone())
+                    .find_existing_words_by_book(book_id, &word_list)
+                    .await?
+            }
+            None => Default::default(),
+        };
+
+        let mut new_words = Vec::new();
+        let mut conflicts = Vec::new();
+        for (line, word) in unique_rows {
+            match existing.get(&word.word.to_lowercase()) {
+                Some(&existing_word_id) => conflicts.push((
+                    WordImportConflict {
+                        line,
+                        word: word.word.clone(),
+                        existing_word_id,
+                    },
+                    word,
+                )),
+                None => new_words.push(word),
+            }
+        }
+
+        Ok(ImportPlan {
+            table,
+            mapping,
+            new_words,
+            conflicts,
+            duplicate_lines,
+            errors,
+        })
+    }
+}
+
+/// 在事务中创建单词本并关联主题标签
+async fn create_book(conn: &mut SqliteConnection, book: &CreateWordBookRequest) -> AppResult<Id> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO word_books (title, description, icon, icon_color, status)
+        VALUES (?, ?, ?, ?, 'normal')
+        "#,
+    )
+    .bind(book.title.trim())
+    .bind(&book.description)
+    .bind(&book.icon)
+    .bind(&book.icon_color)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::DatabaseError(format!("Failed to create word book: {}", e)))?;
+    let book_id = result.last_insert_rowid();
+
+    for tag_id in book.theme_tag_ids.iter().flatten() {
+        sqlx::query(
+            "INSERT OR IGNORE INTO word_book_theme_tags (word_book_id, theme_tag_id) VALUES (?, ?)",
+        )
+        .bind(book_id)
+        .bind(tag_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to add theme tag {}: {}", tag_id, e))
+        })?;
+    }
+
+    Ok(book_id)
+}
+
+/// 在事务中插入单词
+async fn insert_words(
+    conn: &mut SqliteConnection,
+    book_id: Id,
+    words: &[CreateWordRequest],
+) -> AppResult<()> {
+    let insert_query = r#"
+        INSERT INTO words (
+            word, meaning, description, ipa, syllables, phonics_segments,
+            part_of_speech, pos_abbreviation, pos_english, pos_chinese,
+            phonics_rule, analysis_explanation, word_book_id,
+            created_at, updated_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+    "#;
+    for word in words {
+        sqlx::query(insert_query)
+            .bind(&word.word)
+            .bind(&word.meaning)
+            .bind(&word.description)
+            .bind(&word.ipa)
+            .bind(&word.syllables)
+            .bind(&word.phonics_segments)
+            .bind(&word.part_of_speech)
+            .bind(&word.pos_abbreviation)
+            .bind(&word.pos_english)
+            .bind(&word.pos_chinese)
+            .bind(&word.phonics_rule)
+            .bind(&word.analysis_explanation)
+            .bind(book_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to insert word '{}': {}", word.word, e))
+            })?;
+    }
+    Ok(())
+}
@@ -10,6 +10,10 @@
 use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
 use crate::repositories::wordbook_repository::{WordBookRepository, WordBookFilters};
+use crate::repositories::operation_snapshot_repository::SnapshotScope;
+use crate::services::pronunciation::PronunciationService;
+use crate::services::snapshot::SnapshotService;
+use crate::types::snapshot::SnapshotOperation;
 use crate::types::{common::{Id, WordSaveResult}, wordbook::*};
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -19,13 +23,15 @@ use std::sync::Arc;
 /// 负责单词本的业务逻辑处理
 pub struct WordBookService {
     repository: WordBookRepository,
+    snapshots: SnapshotService,
 }
 
 impl WordBookService {
     /// 创建新的服务实例
     pub fn new(pool: Arc<SqlitePool>, logger: Arc<Logger>) -> Self {
         Self {
-            repository: WordBookRepository::new(pool, logger),
+            repository: WordBookRepository::new(pool.clone(), logger.clone()),
+            snapshots: SnapshotService::new(pool, logger),
         }
     }
 
@@ -92,17 +98,29 @@ impl WordBookService {
         self.repository.update(id, request).await
     }
 
-    /// 删除单词本(软删除)
+    /// 删除单词本(软删除，可撤销)
     pub async fn delete_word_book(&self, id: Id) -> AppResult<()> {
         // 验证单词本是否存在
-        let _existing = self
+        let existing = self
             .repository
             .find_by_id(id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("单词本 {} 不存在", id)))?;
 
+        let snapshot_id = self
+            .snapshots
+            .capture(
+                SnapshotOperation::DeleteWordBook,
+                Some(id),
+                &format!("删除单词本「{}」", existing.title),
+                &[SnapshotScope::rows("word_books", "id", &[id]).soft_deleted()],
+            )
+            .await?;
+
         // 调用 repository 删除
-        self.repository.delete(id).await
+        let result = self.repository.delete(id).await;
+        self.snapshots.finish(snapshot_id, result.is_ok()).await;
+        result
     }
 
     /// 获取单词本统计信息
@@ -196,6 +214,20 @@ impl WordBookService {
             return Err(AppError::ValidationError("去重后没有单词需要处理".to_string()));
         }
 
+        // 用发音词典补全空缺的音标，并记录与词典不一致的 AI 音标
+        let pronunciation = PronunciationService::new(
+            self.repository.get_pool(),
+            self.repository.get_logger(),
+        );
+        let mut ipa_mismatches = pronunciation
+            .fill_or_check_analyzed(&mut words_to_add)
+            .await?;
+        for (_, word) in words_to_update.iter_mut() {
+            if let Some(mismatch) = pronunciation.fill_or_check(&word.word, &mut word.ipa).await? {
+                ipa_mismatches.push(mismatch);
+            }
+        }
+
         // 3. 开始事务，确保原子性
         let mut tx = self.repository.get_pool().begin().await.map_err(|e| {
             AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
@@ -278,26 +310,37 @@ impl WordBookService {
         if !words_to_add.is_empty() {
             let words: Vec<Word> = words_to_add
                 .into_iter()
-                .map(|aw| Word {
-                    id: 0,
-                    word: aw.word,
-                    meaning: aw.meaning,
-                    description: None,
-                    ipa: aw.ipa,
-                    syllables: aw.syllables,
-                    phonics_segments: None,
-                    image_path: None,
-                    audio_path: None,
-                    part_of_speech: aw.part_of_speech,
-                    category_id: None,
-                    word_book_id: Some(book_id),
-                    pos_abbreviation: aw.pos_abbreviation,
-                    pos_english: aw.pos_english,
-                    pos_chinese: aw.pos_chinese,
-                    phonics_rule: aw.phonics_rule,
-                    analysis_explanation: aw.analysis_explanation,
-                    created_at: String::new(),
-                    updated_at: String::new(),
+                .map(|mut aw| {
+                    // AI 结果不包含拼读片段，由拼读规则补全
+                    let mut phonics_segments = None;
+                    crate::phonics_rules::fill_missing(
+                        &aw.word,
+                        &mut aw.syllables,
+                        &mut phonics_segments,
+                        &mut aw.phonics_rule,
+                    );
+                    Word {
+                        id: 0,
+                        word: aw.word,
+                        meaning: aw.meaning,
+                        description: None,
+                        ipa: aw.ipa,
+                        syllables: aw.syllables,
+                        phonics_segments,
+                        image_path: None,
+                        audio_path: None,
+                        part_of_speech: aw.part_of_speech,
+                        category_id: None,
+                        word_book_id: Some(book_id),
+                        pos_abbreviation: aw.pos_abbreviation,
+                        pos_english: aw.pos_english,
+                        pos_chinese: aw.pos_chinese,
+                        phonics_rule: aw.phonics_rule,
+                        analysis_explanation: aw.analysis_explanation,
+                        created_at: String::new(),
+                        updated_at: String::new(),
+                        highlight: None,
+                    }
                 })
                 .collect();
 
@@ -396,6 +439,7 @@ impl WordBookService {
             added_count: added_count as i32,
             updated_count: updated_count as i32,
             skipped_count: 0,
+            ipa_mismatches,
         })
     }
 }
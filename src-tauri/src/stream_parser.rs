@@ -0,0 +1,242 @@
+//! 流式响应增量解析
+//!
+//! LLM 流式返回的文本按任意位置切分，这里把增量文本拼接后尽早取出完整的数据:
+//! - `CsvLineStream`: 逐行取出 CSV 数据行（跳过 markdown 代码块标记和标题行）
+//! - `JsonArrayStream`: 逐个取出 JSON 中指定数组字段的对象元素
+
+use std::mem;
+
+/// 增量 CSV 行解析器
+#[derive(Debug, Default)]
+pub struct CsvLineStream {
+    buffer: String,
+    header: Option<String>,
+}
+
+impl CsvLineStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 标题行（收到第一行有效内容后可用）
+    pub fn header(&self) -> Option<&str> {
+        self.header.as_deref()
+    }
+
+    /// 追加增量文本，返回新完成的数据行
+    pub fn push(&mut self, delta: &str) -> Vec<String> {
+        self.buffer.push_str(delta);
+
+        let mut rows = Vec::new();
+        while let Some(line) = self.take_line() {
+            if let Some(row) = self.accept(&line) {
+                rows.push(row);
+            }
+        }
+        rows
+    }
+
+    /// 流结束，返回缓冲区中剩余的最后一行
+    pub fn finish(&mut self) -> Vec<String> {
+        let rest = mem::take(&mut self.buffer);
+        self.accept(&rest).into_iter().collect()
+    }
+
+    /// 取出一行（引号内的换行属于字段内容）
+    fn take_line(&mut self) -> Option<String> {
+        let mut in_quotes = false;
+        let end = self.buffer.bytes().position(|b| match b {
+            b'"' => {
+                in_quotes = !in_quotes;
+                false
+            }
+            b'\n' => !in_quotes,
+            _ => false,
+        })?;
+
+        let line = self.buffer[..end].to_string();
+        self.buffer.drain(..=end);
+        Some(line)
+    }
+
+    fn accept(&mut self, line: &str) -> Option<String> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("```") {
+            return None;
+        }
+        if self.header.is_none() {
+            self.header = Some(trimmed.to_string());
+            return None;
+        }
+        Some(trimmed.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsonScanState {
+    /// 查找字段名
+    SeekingKey,
+    /// 查找数组开始
+    SeekingArray,
+    /// 在数组内
+    InArray,
+    /// 数组已结束
+    Done,
+}
+
+/// 增量 JSON 数组元素解析器
+///
+/// 只做结构扫描（字符串、转义、括号深度），元素内容由调用方反序列化
+#[derive(Debug)]
+pub struct JsonArrayStream {
+    key_pattern: String,
+    buffer: String,
+    position: usize,
+    state: JsonScanState,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    element_start: usize,
+}
+
+impl JsonArrayStream {
+    /// 解析字段 `key` 对应数组中的对象
+    pub fn new(key: &str) -> Self {
+        Self {
+            key_pattern: format!("\"{}\"", key),
+            buffer: String::new(),
+            position: 0,
+            state: JsonScanState::SeekingKey,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            element_start: 0,
+        }
+    }
+
+    /// 追加增量文本，返回新完成的数组元素（原始 JSON 文本）
+    pub fn push(&mut self, delta: &str) -> Vec<String> {
+        self.buffer.push_str(delta);
+
+        let mut elements = Vec::new();
+        if self.state == JsonScanState::SeekingKey && !self.seek_key() {
+            return elements;
+        }
+
+        let bytes = self.buffer.as_bytes();
+        while self.position < bytes.len() && self.state != JsonScanState::Done {
+            let b = bytes[self.position];
+            match self.state {
+                JsonScanState::SeekingArray => {
+                    if b == b'[' {
+                        self.state = JsonScanState::InArray;
+                    }
+                }
+                JsonScanState::InArray if self.in_string => {
+                    if self.escaped {
+                        self.escaped = false;
+                    } else if b == b'\\' {
+                        self.escaped = true;
+                    } else if b == b'"' {
+                        self.in_string = false;
+                    }
+                }
+                JsonScanState::InArray => match b {
+                    b'"' => self.in_string = true,
+                    b'{' => {
+                        if self.depth == 0 {
+                            self.element_start = self.position;
+                        }
+                        self.depth += 1;
+                    }
+                    b'}' if self.depth > 0 => {
+                        self.depth -= 1;
+                        if self.depth == 0 {
+                            elements
+                                .push(self.buffer[self.element_start..=self.position].to_string());
+                        }
+                    }
+                    b']' if self.depth == 0 => self.state = JsonScanState::Done,
+                    _ => {}
+                },
+                JsonScanState::SeekingKey | JsonScanState::Done => {}
+            }
+            self.position += 1;
+        }
+
+        elements
+    }
+
+    /// 查找字段名，未找到时保留可能被切断的结尾
+    fn seek_key(&mut self) -> bool {
+        match self.buffer[self.position..].find(&self.key_pattern) {
+            Some(offset) => {
+                self.position += offset + self.key_pattern.len();
+                self.state = JsonScanState::SeekingArray;
+                true
+            }
+            None => {
+                let mut keep_from = self.buffer.len().saturating_sub(self.key_pattern.len());
+                while !self.buffer.is_char_boundary(keep_from) {
+                    keep_from -= 1;
+                }
+                self.position = keep_from.max(self.position);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按固定长度切分文本，模拟流式增量
+    fn chunks(text: &str, size: usize) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        chars.chunks(size).map(|c| c.iter().collect()).collect()
+    }
+
+    #[test]
+    fn test_csv_rows_arrive_incrementally() {
+        let content = "```csv\nword,frequency,note\ncat,2,\"a, b\"\ndog,1,\"multi\nline\"\nsun,1,x";
+
+        for size in [1, 3, 7, 100] {
+            let mut parser = CsvLineStream::new();
+            let mut rows = Vec::new();
+            for chunk in chunks(content, size) {
+                rows.extend(parser.push(&chunk));
+            }
+            // 最后一行没有换行，只能在结束时取出
+            assert_eq!(rows.len(), 2);
+            rows.extend(parser.finish());
+
+            assert_eq!(parser.header(), Some("word,frequency,note"));
+            assert_eq!(rows, vec!["cat,2,\"a, b\"", "dog,1,\"multi\nline\"", "sun,1,x"]);
+        }
+    }
+
+    #[test]
+    fn test_json_array_elements_arrive_incrementally() {
+        let content = r#"说明文字 {"plan_metadata": {"daily_plans_hint": "[{"},
+            "daily_plans": [
+                {"day": 1, "words": [{"word": "a}b"}]},
+                {"day": 2, "note": "引号 \" 和 {括号}"}
+            ],
+            "tail": [{"day": 3}]}"#;
+
+        for size in [1, 4, 11, 1000] {
+            let mut parser = JsonArrayStream::new("daily_plans");
+            let mut elements = Vec::new();
+            for chunk in chunks(content, size) {
+                elements.extend(parser.push(&chunk));
+            }
+
+            assert_eq!(elements.len(), 2);
+            let first: serde_json::Value = serde_json::from_str(&elements[0]).unwrap();
+            assert_eq!(first["words"][0]["word"], "a}b");
+            let second: serde_json::Value = serde_json::from_str(&elements[1]).unwrap();
+            assert_eq!(second["note"], "引号 \" 和 {括号}");
+        }
+    }
+}
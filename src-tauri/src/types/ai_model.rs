@@ -10,12 +10,32 @@ pub struct AIProvider {
     pub display_name: String,
     pub base_url: String,
     pub api_key: String,
+    /// 接口类型：openai / anthropic / ollama / mock（mock 的 base_url 为脚本文件路径）
+    #[serde(default = "default_provider_type")]
+    pub provider_type: String,
     pub description: Option<String>,
     pub is_active: bool,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
 
+/// 更新AI提供商请求（只更新提供的字段）
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAIProviderRequest {
+    pub display_name: Option<String>,
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    pub provider_type: Option<String>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// 默认接口类型（OpenAI 兼容）
+fn default_provider_type() -> String {
+    "openai".to_string()
+}
+
 /// AI模型
 /// AI模型配置（包含提供商信息）
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,6 +48,12 @@ pub struct AIModelConfig {
     pub description: Option<String>,
     pub max_tokens: Option<i32>,
     pub temperature: Option<f64>,
+    /// 每百万输入 token 价格（美元）
+    #[serde(default)]
+    pub input_price_per_million: Option<f64>,
+    /// 每百万输出 token 价格（美元）
+    #[serde(default)]
+    pub output_price_per_million: Option<f64>,
     pub is_active: bool,
     pub is_default: bool,
     pub created_at: Timestamp,
@@ -61,6 +87,8 @@ pub struct AIProviderSafe {
     pub name: String,
     pub display_name: String,
     pub base_url: String,
+    /// 接口类型：openai / anthropic / ollama / mock
+    pub provider_type: String,
     pub description: Option<String>,
     pub is_active: bool,
     pub created_at: Timestamp,
@@ -85,6 +113,7 @@ impl From<AIProvider> for AIProviderSafe {
             name: provider.name,
             display_name: provider.display_name,
             base_url: provider.base_url,
+            provider_type: provider.provider_type,
             description: provider.description,
             is_active: provider.is_active,
             created_at: provider.created_at,
@@ -121,6 +150,12 @@ pub struct AIModelConfigSafe {
     pub description: Option<String>,
     pub max_tokens: Option<i32>,
     pub temperature: Option<f64>,
+    /// 每百万输入 token 价格（美元）
+    #[serde(default)]
+    pub input_price_per_million: Option<f64>,
+    /// 每百万输出 token 价格（美元）
+    #[serde(default)]
+    pub output_price_per_million: Option<f64>,
     pub is_active: bool,
     pub is_default: bool,
     pub created_at: Timestamp,
@@ -138,6 +173,8 @@ impl From<AIModelConfig> for AIModelConfigSafe {
             description: config.description,
             max_tokens: config.max_tokens,
             temperature: config.temperature,
+            input_price_per_million: config.input_price_per_million,
+            output_price_per_million: config.output_price_per_million,
             is_active: config.is_active,
             is_default: config.is_default,
             created_at: config.created_at,
@@ -146,3 +183,100 @@ impl From<AIModelConfig> for AIModelConfigSafe {
         }
     }
 }
+
+/// AI 调用用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AIUsagePurpose {
+    /// 单词提取
+    Extraction,
+    /// 批量自然拼读分析
+    BatchPhonics,
+    /// 自然拼读分析
+    Phonics,
+    /// 学习计划生成
+    PlanGeneration,
+    /// 模型测试
+    Test,
+}
+
+impl AIUsagePurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AIUsagePurpose::Extraction => "extraction",
+            AIUsagePurpose::BatchPhonics => "batch_phonics",
+            AIUsagePurpose::Phonics => "phonics",
+            AIUsagePurpose::PlanGeneration => "plan_generation",
+            AIUsagePurpose::Test => "test",
+        }
+    }
+}
+
+/// AI 调用用量记录
+#[derive(Debug, Clone)]
+pub struct AIUsageRecord {
+    pub model_id: Option<Id>,
+    pub provider_id: Option<Id>,
+    pub model_name: String,
+    pub provider_name: String,
+    pub purpose: AIUsagePurpose,
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    pub tokens_estimated: bool,
+    pub latency_ms: u64,
+    pub success: bool,
+    pub estimated_cost: Option<f64>,
+}
+
+/// 用量统计分组
+#[derive(Debug, Clone)]
+pub struct AIUsageBucket {
+    pub key: String,
+    pub calls: i64,
+    pub failed_calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub estimated_cost: f64,
+}
+
+/// 用量统计项
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AIUsageSummary {
+    /// 分组键：日期 / 月份 / 提供商名称 / 用途
+    pub key: String,
+    pub calls: i64,
+    pub failed_calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub estimated_cost: f64,
+}
+
+impl From<AIUsageBucket> for AIUsageSummary {
+    fn from(bucket: AIUsageBucket) -> Self {
+        Self {
+            key: bucket.key,
+            calls: bucket.calls,
+            failed_calls: bucket.failed_calls,
+            prompt_tokens: bucket.prompt_tokens,
+            completion_tokens: bucket.completion_tokens,
+            total_tokens: bucket.prompt_tokens + bucket.completion_tokens,
+            estimated_cost: bucket.estimated_cost,
+        }
+    }
+}
+
+/// AI 用量报告
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AIUsageReport {
+    /// 统计粒度：daily / monthly
+    pub granularity: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub total: AIUsageSummary,
+    pub by_period: Vec<AIUsageSummary>,
+    pub by_provider: Vec<AIUsageSummary>,
+    pub by_feature: Vec<AIUsageSummary>,
+}
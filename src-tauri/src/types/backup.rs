@@ -0,0 +1,73 @@
+//! 数据库备份相关类型定义
+
+use serde::{Deserialize, Serialize};
+
+/// 备份清单文件格式版本
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// 备份类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    /// 用户手动创建
+    Manual,
+    /// 按设置的间隔自动创建（超出保留数量的会被清理）
+    Scheduled,
+    /// 恢复备份前自动保存的当前数据
+    PreRestore,
+}
+
+impl BackupKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupKind::Manual => "manual",
+            BackupKind::Scheduled => "scheduled",
+            BackupKind::PreRestore => "pre_restore",
+        }
+    }
+}
+
+/// 备份清单（备份压缩包中的 manifest.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub kind: BackupKind,
+    pub created_at: String,     // RFC 3339
+    pub migration_version: i64, // 备份时数据库已执行的最新迁移版本
+    pub app_version: String,
+    pub tts_file_count: usize,
+}
+
+/// 备份文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub file_path: String,
+    pub file_size: u64,
+    pub kind: BackupKind,
+    pub created_at: String,
+    pub migration_version: i64,
+    pub app_version: String,
+    pub tts_file_count: usize,
+}
+
+/// 自动备份设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_hours: i64,
+    pub retention_count: i64, // 保留的自动备份数量
+    pub last_backup_at: Option<String>,
+}
+
+/// 更新自动备份设置请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBackupSettingsRequest {
+    pub enabled: Option<bool>,
+    pub interval_hours: Option<i64>,
+    pub retention_count: Option<i64>,
+}
@@ -1,3 +1,4 @@
+use crate::types::pronunciation::IpaMismatch;
 use serde::{Deserialize, Serialize};
 
 /// 通用 ID 类型
@@ -13,6 +14,8 @@ pub struct WordSaveResult {
     pub added_count: i32,
     pub updated_count: i32,
     pub skipped_count: i32,
+    #[serde(default)]
+    pub ipa_mismatches: Vec<IpaMismatch>, // 与发音词典不一致的音标（保留原音标）
 }
 
 /// 分页响应
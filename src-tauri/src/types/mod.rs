@@ -8,7 +8,11 @@
 
 
 pub mod ai_model;
+pub mod backup;
 pub mod common;
+pub mod prompt;
+pub mod pronunciation;
+pub mod snapshot;
 pub mod study;
 pub mod tts;
 pub mod word_analysis;
@@ -16,7 +20,11 @@ pub mod wordbook;
 
 // Re-export commonly used types
 pub use ai_model::*;
+pub use backup::*;
 pub use common::*;
+pub use prompt::*;
+pub use pronunciation::*;
+pub use snapshot::*;
 pub use study::*;
 pub use wordbook::*;
 // pub use tts::*; // 暂未使用，注释掉
@@ -0,0 +1,93 @@
+use crate::types::common::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// 提示词模板名称
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptName {
+    /// 单词提取
+    WordExtraction,
+    /// 自然拼读分析
+    Phonics,
+    /// 批量自然拼读分析
+    BatchPhonics,
+    /// 学习计划生成
+    StudyPlan,
+}
+
+impl PromptName {
+    /// 所有提示词
+    pub const ALL: [PromptName; 4] = [
+        PromptName::WordExtraction,
+        PromptName::Phonics,
+        PromptName::BatchPhonics,
+        PromptName::StudyPlan,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptName::WordExtraction => "word_extraction",
+            PromptName::Phonics => "phonics",
+            PromptName::BatchPhonics => "batch_phonics",
+            PromptName::StudyPlan => "study_plan",
+        }
+    }
+
+    /// 从名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|name| name.as_str() == value)
+    }
+}
+
+/// 提示词模板来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptSource {
+    /// 内置模板
+    Default,
+    /// 应用数据目录中的自定义模板
+    Custom,
+}
+
+impl PromptSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptSource::Default => "default",
+            PromptSource::Custom => "custom",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        if value == "custom" {
+            PromptSource::Custom
+        } else {
+            PromptSource::Default
+        }
+    }
+}
+
+/// 当前生效的提示词模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    pub name: PromptName,
+    pub content: String,
+    /// 版本号（内容 SHA-256 前 12 位）
+    pub version: String,
+    pub source: PromptSource,
+    /// 必须包含的占位符
+    pub required_placeholders: Vec<String>,
+    /// 自定义模板无效（缺少占位符）时的原因，此时使用内置模板
+    pub custom_error: Option<String>,
+}
+
+/// 提示词历史版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptVersion {
+    pub name: PromptName,
+    pub version: String,
+    pub content: String,
+    pub source: PromptSource,
+    pub created_at: Timestamp,
+}
@@ -0,0 +1,88 @@
+//! 发音词典相关类型定义
+
+use serde::{Deserialize, Serialize};
+
+/// 音标体系
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpaConvention {
+    /// 美式音标（保留 r 音，oʊ）
+    #[default]
+    Us,
+    /// 英式音标（非元音前的 r 不发音，əʊ）
+    Uk,
+}
+
+impl IpaConvention {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IpaConvention::Us => "us",
+            IpaConvention::Uk => "uk",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "us" | "en-us" => Some(IpaConvention::Us),
+            "uk" | "gb" | "en-gb" => Some(IpaConvention::Uk),
+            _ => None,
+        }
+    }
+}
+
+/// 发音词典条目（一个单词可以有多个读音）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PronunciationEntry {
+    pub word: String,    // 小写单词
+    pub variant: i64,    // 读音序号，CMUdict 中 READ(1) 为 1，默认读音为 0
+    pub arpabet: String, // ARPABET 音素，以空格分隔
+}
+
+/// 发音词典导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PronunciationImportResult {
+    pub imported: usize,      // 写入的读音条目数
+    pub skipped_lines: usize, // 无法解析而跳过的行数
+    pub total_entries: i64,   // 导入后词典中的条目总数
+}
+
+/// 单词的词典读音
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PronunciationLookup {
+    pub word: String,
+    pub variant: i64,
+    pub arpabet: String,
+    pub us_ipa: String,
+    pub uk_ipa: String,
+}
+
+/// 待校验音标的单词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpaCheckItem {
+    pub word: String,
+    pub ipa: Option<String>,
+}
+
+/// 与词典读音不一致的音标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpaMismatch {
+    pub word: String,
+    pub ai_ipa: String,              // AI（或用户）给出的音标
+    pub dictionary_ipa: Vec<String>, // 词典中的全部读音
+}
+
+/// 音标校验报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpaCheckReport {
+    pub checked: usize,            // 词典中存在且给出了音标的单词数
+    pub matched: usize,            // 与词典一致的单词数
+    pub filled: Vec<IpaCheckItem>, // 没有音标、可由词典补全的单词
+    pub not_found: Vec<String>,    // 词典中不存在的单词
+    pub mismatches: Vec<IpaMismatch>,
+}
@@ -0,0 +1,59 @@
+//! 破坏性操作快照相关类型定义
+
+use serde::{Deserialize, Serialize};
+
+/// 会先保存快照的破坏性操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotOperation {
+    ResetUserData,
+    ResetSelectedTables,
+    DeleteWordBook,
+    DeleteStudyPlan,
+    RemovePlanWords,
+}
+
+impl SnapshotOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotOperation::ResetUserData => "reset_user_data",
+            SnapshotOperation::ResetSelectedTables => "reset_selected_tables",
+            SnapshotOperation::DeleteWordBook => "delete_word_book",
+            SnapshotOperation::DeleteStudyPlan => "delete_study_plan",
+            SnapshotOperation::RemovePlanWords => "remove_plan_words",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "reset_user_data" => Some(SnapshotOperation::ResetUserData),
+            "reset_selected_tables" => Some(SnapshotOperation::ResetSelectedTables),
+            "delete_word_book" => Some(SnapshotOperation::DeleteWordBook),
+            "delete_study_plan" => Some(SnapshotOperation::DeleteStudyPlan),
+            "remove_plan_words" => Some(SnapshotOperation::RemovePlanWords),
+            _ => None,
+        }
+    }
+}
+
+/// 操作快照（不含行数据）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSnapshot {
+    pub id: i64,
+    pub operation: SnapshotOperation,
+    pub target_id: Option<i64>, // 操作对象 ID（单词本或学习计划）
+    pub description: String,
+    pub row_count: i64,
+    pub tables: Vec<String>, // 快照涉及的表
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+/// 撤销结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoOperationResult {
+    pub snapshot: OperationSnapshot,
+    pub restored_rows: i64,
+}
@@ -1,4 +1,5 @@
 use super::{Id, Timestamp};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// 学习计划
@@ -245,6 +246,77 @@ pub struct PracticeStatistics {
     pub words_learned: i32, // 已学会的单词数
 }
 
+// ==================== 间隔重复相关类型 ====================
+
+/// 复习算法
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ReviewAlgorithm {
+    #[serde(rename = "sm2")]
+    Sm2,
+    #[serde(rename = "fsrs")]
+    Fsrs,
+}
+
+impl ReviewAlgorithm {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewAlgorithm::Sm2 => "sm2",
+            ReviewAlgorithm::Fsrs => "fsrs",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值回退为 SM-2
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "fsrs" => ReviewAlgorithm::Fsrs,
+            _ => ReviewAlgorithm::Sm2,
+        }
+    }
+}
+
+/// 单词记忆状态
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WordReviewState {
+    #[serde(rename = "planId")]
+    pub plan_id: i64,
+    #[serde(rename = "wordId")]
+    pub word_id: i64,
+    #[serde(rename = "easeFactor")]
+    pub ease_factor: f64, // SM-2 难度因子
+    #[serde(rename = "intervalDays")]
+    pub interval_days: i32, // 当前复习间隔（天）
+    pub repetitions: i32, // 连续答对次数
+    pub stability: f64, // FSRS 记忆稳定性（天）
+    pub difficulty: f64, // FSRS 难度（1-10）
+    pub lapses: i32, // 遗忘次数
+    #[serde(rename = "lastGrade")]
+    pub last_grade: Option<i32>, // 最近一次评分 0-5
+    #[serde(rename = "lastReviewedAt")]
+    pub last_reviewed_at: Option<String>, // YYYY-MM-DD
+    #[serde(rename = "dueDate")]
+    pub due_date: Option<String>, // YYYY-MM-DD
+}
+
+impl WordReviewState {
+    /// 创建尚未复习过的初始状态
+    pub fn new(plan_id: i64, word_id: i64) -> Self {
+        Self {
+            plan_id,
+            word_id,
+            ease_factor: 2.5,
+            interval_days: 0,
+            repetitions: 0,
+            stability: 0.0,
+            difficulty: 0.0,
+            lapses: 0,
+            last_grade: None,
+            last_reviewed_at: None,
+            due_date: None,
+        }
+    }
+}
+
 // ==================== AI规划相关类型 ====================
 
 /// 学习计划规划请求
@@ -258,10 +330,14 @@ pub struct StudyPlanScheduleRequest {
     pub start_date: String,      // YYYY-MM-DD
     pub wordbook_ids: Vec<Id>,   // 选择的单词本ID列表
     pub model_id: Option<i64>,   // AI模型ID
+    #[serde(default)]
+    pub use_local_planner: bool, // 使用本地规划器（无需AI模型）
+    #[serde(default)]
+    pub template_id: Option<Id>, // 计划模板ID，指定时使用模板的强度、周期、复习频率和日程形状（日程形状只用于本地规划器）
 }
 
 /// 学习计划规划参数（传递给AI的参数）
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StudyPlanAIParams {
     pub intensity_level: String,
     pub total_words: i32,
@@ -272,7 +348,7 @@ pub struct StudyPlanAIParams {
 }
 
 /// 传递给AI的单词信息
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StudyWordInfo {
     pub word: String,
     pub word_id: String,
@@ -280,7 +356,7 @@ pub struct StudyWordInfo {
 }
 
 /// AI规划结果的元数据
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct StudyPlanMetadata {
     #[serde(rename = "totalWords")]
     pub total_words: i32,
@@ -299,7 +375,7 @@ pub struct StudyPlanMetadata {
 }
 
 /// 每日学习计划
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct DailyStudyPlan {
     pub day: i32,
     pub date: String,
@@ -307,7 +383,7 @@ pub struct DailyStudyPlan {
 }
 
 /// 每日学习单词
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct DailyStudyWord {
     #[serde(rename = "wordId")]
     pub word_id: String,
@@ -323,13 +399,25 @@ pub struct DailyStudyWord {
     pub difficulty_level: i32,
 }
 
+/// AI规划流式接收到一天计划的事件
+#[derive(Debug, Serialize)]
+pub struct StudyPlanDailyPlanEvent<'a> {
+    pub index: usize, // 第几个每日计划（从 0 开始）
+    #[serde(rename = "dailyPlan")]
+    pub daily_plan: &'a DailyStudyPlan,
+}
+
 /// AI规划完整结果
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct StudyPlanAIResult {
     #[serde(rename = "planMetadata")]
     pub plan_metadata: StudyPlanMetadata,
     #[serde(rename = "dailyPlans")]
     pub daily_plans: Vec<DailyStudyPlan>,
+    /// 生成该规划的提示词版本（本地排程或旧数据为空）
+    #[serde(rename = "promptVersion", default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub prompt_version: Option<String>,
 }
 
 /// 创建带AI规划的学习计划请求
@@ -345,6 +433,54 @@ pub struct CreateStudyPlanWithScheduleRequest {
     pub ai_plan_data: String, // JSON字符串
     pub wordbook_ids: Vec<Id>,
     pub status: Option<String>, // "draft" 或 "active"
+    #[serde(default)]
+    pub auto_repair: Option<bool>, // 是否自动修复规划数据，默认修复
+}
+
+/// 学习计划规划校验问题类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PlanViolationKind {
+    MissingWord,      // 单词列表中的单词未被安排学习
+    DuplicateWord,    // 同一天重复安排同一单词
+    DuplicateNewWord, // 同一单词被多次作为新词安排
+    ForeignWord,      // 单词不属于所选单词本
+    InvalidDate,      // 日期格式无效
+    DateOutOfRange,   // 日期超出计划起止范围
+    DuplicateDate,    // 多个日程使用同一日期
+    DayMismatch,      // 天数与日期不一致
+    MetadataMismatch, // 元数据与实际内容不一致
+}
+
+/// 学习计划规划校验问题
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanViolation {
+    pub kind: PlanViolationKind,
+    pub day: Option<i32>,
+    pub date: Option<String>,
+    #[serde(rename = "wordId")]
+    pub word_id: Option<String>,
+    pub message: String,
+}
+
+/// 校验学习计划规划请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidateStudyPlanScheduleRequest {
+    pub ai_plan_data: String, // JSON字符串
+    pub wordbook_ids: Vec<Id>,
+    pub start_date: String,
+    pub end_date: String,
+    #[serde(default)]
+    pub repair: bool,
+}
+
+/// 学习计划规划校验结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StudyPlanValidationResult {
+    pub valid: bool,
+    pub violations: Vec<PlanViolation>,
+    #[serde(rename = "repairedPlan")]
+    pub repaired_plan: Option<StudyPlanAIResult>,
 }
 
 /// 学习计划日程
@@ -501,10 +637,120 @@ pub struct StudyPlanStatusHistory {
     pub reason: Option<String>,
 }
 
+/// 恢复学习计划结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResumeStudyPlanResult {
+    pub paused_days: i64,        // 暂停的天数（未完成日程后移的天数）
+    pub shifted_schedules: i32,  // 后移的日程数
+    pub end_date: Option<String>, // 新的结束日期
+}
+
+/// 逾期日程重新分配：某一天的单词数变化
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RebalanceDayDiff {
+    pub date: String,
+    pub schedule_id: Option<Id>, // 延长计划新增的日期为空
+    pub before_new_words: i32,
+    pub before_review_words: i32,
+    pub after_new_words: i32,
+    pub after_review_words: i32,
+}
+
+/// 逾期日程重新分配：单词安排的移动
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RebalanceWordMove {
+    pub word_id: Id,
+    pub word: String,
+    pub is_review: bool,
+    pub review_count: Option<i32>,
+    pub from_date: String,
+    pub to_date: Option<String>, // 为空表示复习超出计划范围，不再安排
+}
+
+/// 逾期日程重新分配预览（应用后返回实际执行的方案）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StudyPlanRebalancePreview {
+    pub plan_id: Id,
+    pub max_new_words_per_day: i32, // 学习强度的每日新词上限
+    pub overdue_schedules: i32,     // 被清空的逾期日程数
+    pub moved_words: i32,
+    pub dropped_reviews: i32,
+    pub added_days: i32, // 延长计划新增的天数
+    pub old_end_date: Option<String>,
+    pub new_end_date: Option<String>,
+    pub days: Vec<RebalanceDayDiff>,
+    pub moves: Vec<RebalanceWordMove>,
+    pub fingerprint: String, // 预览时日程的指纹，应用时用于确认日程未变化
+}
+
+/// 向学习计划追加单词的请求（单词和单词本可以同时指定）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddPlanWordsRequest {
+    pub plan_id: Id,
+    #[serde(default)]
+    pub word_ids: Vec<Id>,
+    #[serde(default)]
+    pub wordbook_ids: Vec<Id>,
+    pub model_id: Option<i64>, // AI模型ID
+    #[serde(default)]
+    pub use_local_planner: bool, // 使用本地规划器（无需AI模型）
+}
+
+/// 向学习计划追加单词的结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddPlanWordsResult {
+    pub added_words: i32,
+    pub skipped_words: i32, // 已在计划中的单词
+    pub new_word_entries: i32,
+    pub review_entries: i32,
+    pub added_days: i32, // 新建的日程数
+    pub end_date: Option<String>,
+}
+
+/// 学习计划模板：计划设置和日程形状
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StudyPlanTemplate {
+    pub id: Id,
+    pub name: String,
+    pub description: String,
+    pub intensity_level: String,
+    pub study_period_days: i32,
+    pub review_frequency: i32,
+    pub day_weights: Vec<i32>, // 每天新词数的相对比例，为空表示平均分配
+    pub source_plan_id: Option<Id>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+/// 创建学习计划模板请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateStudyPlanTemplateRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub intensity_level: String,
+    pub study_period_days: i32,
+    pub review_frequency: i32,
+    #[serde(default)]
+    pub day_weights: Vec<i32>,
+}
+
+/// 复制学习计划请求：沿用源计划的设置和日程形状，学习新的单词本
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloneStudyPlanRequest {
+    pub plan_id: Id,
+    pub name: Option<String>, // 默认为「源计划名称 (副本)」
+    pub start_date: String,   // YYYY-MM-DD
+    pub wordbook_ids: Vec<Id>,
+    pub status: Option<String>, // "draft" 或 "active"
+}
+
 // ==================== 统一状态管理 ====================
 
 /// 学习计划统一状态（新版本）
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+///
+/// 以文本形式存储在 study_plans.unified_status 列中
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
 pub enum UnifiedStudyPlanStatus {
     /// 草稿状态 - 刚创建，还未完成配置
     #[serde(rename = "Draft")]
@@ -529,6 +775,106 @@ pub enum UnifiedStudyPlanStatus {
     Deleted,
 }
 
+impl UnifiedStudyPlanStatus {
+    /// 数据库中存储的值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "Draft",
+            Self::Pending => "Pending",
+            Self::Active => "Active",
+            Self::Paused => "Paused",
+            Self::Completed => "Completed",
+            Self::Terminated => "Terminated",
+            Self::Deleted => "Deleted",
+        }
+    }
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Draft => "草稿",
+            Self::Pending => "待开始",
+            Self::Active => "进行中",
+            Self::Paused => "已暂停",
+            Self::Completed => "已完成",
+            Self::Terminated => "已终止",
+            Self::Deleted => "已删除",
+        }
+    }
+
+    /// 对应的管理状态（study_plans.status 列），由统一状态推导
+    pub fn management_status(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Deleted => "deleted",
+            _ => "normal",
+        }
+    }
+}
+
+/// 学习计划状态转换操作
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StudyPlanTransition {
+    Publish,
+    Start,
+    Pause,
+    Resume,
+    Complete,
+    Terminate,
+    Restart,
+    Edit,
+    Delete,
+}
+
+impl StudyPlanTransition {
+    /// 状态转换表：(操作, 允许的起始状态, 目标状态)
+    pub const TABLE: &'static [(Self, &'static [UnifiedStudyPlanStatus], UnifiedStudyPlanStatus)] = {
+        use UnifiedStudyPlanStatus::*;
+        &[
+            (Self::Publish, &[Draft], Pending),
+            (Self::Start, &[Pending], Active),
+            (Self::Pause, &[Active], Paused),
+            (Self::Resume, &[Paused], Active),
+            (Self::Complete, &[Active], Completed),
+            (Self::Terminate, &[Active, Paused], Terminated),
+            (Self::Restart, &[Completed, Terminated], Pending),
+            (Self::Edit, &[Pending, Active, Paused, Completed, Terminated], Draft),
+            (Self::Delete, &[Draft, Pending, Active, Paused, Completed, Terminated], Deleted),
+        ]
+    };
+
+    /// 从指定状态执行该操作后的目标状态，不允许时返回 None
+    pub fn target(self, from: UnifiedStudyPlanStatus) -> Option<UnifiedStudyPlanStatus> {
+        Self::TABLE
+            .iter()
+            .find(|(transition, sources, _)| *transition == self && sources.contains(&from))
+            .map(|(_, _, to)| *to)
+    }
+
+    /// 查找从一个状态到另一个状态的操作
+    pub fn between(from: UnifiedStudyPlanStatus, to: UnifiedStudyPlanStatus) -> Option<Self> {
+        Self::TABLE
+            .iter()
+            .find(|(_, sources, target)| *target == to && sources.contains(&from))
+            .map(|(transition, _, _)| *transition)
+    }
+
+    /// 操作名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Publish => "发布",
+            Self::Start => "开始学习",
+            Self::Pause => "暂停",
+            Self::Resume => "恢复学习",
+            Self::Complete => "完成",
+            Self::Terminate => "终止",
+            Self::Restart => "重新学习",
+            Self::Edit => "编辑",
+            Self::Delete => "删除",
+        }
+    }
+}
+
 
 
 /// 今日学习日程
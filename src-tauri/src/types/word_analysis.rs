@@ -20,6 +20,70 @@ pub struct WordExtractionResult {
     pub unique_count: usize,
 }
 
+/// 本地单词提取的用户词表类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionWordListType {
+    Stop,  // 重点模式下额外排除的停用词
+    Known, // 已掌握的单词，任何模式都排除
+}
+
+impl ExtractionWordListType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtractionWordListType::Stop => "stop",
+            ExtractionWordListType::Known => "known",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stop" => Some(ExtractionWordListType::Stop),
+            "known" => Some(ExtractionWordListType::Known),
+            _ => None,
+        }
+    }
+}
+
+/// 本地单词提取的用户词表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionWordLists {
+    pub stop_words: Vec<String>,
+    pub known_words: Vec<String>,
+}
+
+/// 基于规则的自然拼读分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulePhonicsAnalysis {
+    pub word: String,
+    pub syllables: String,           // 音节划分，以 - 连接
+    pub syllable_count: usize,       // 音节数量
+    pub phonics_segments: String,    // 拼读片段，以 ", " 连接（魔法 e 记为 a_e）
+    pub phonics_rule: String,        // 主要规则
+    pub detected_rules: Vec<String>, // 适用的全部规则
+}
+
+/// AI 分析结果与拼读规则不一致的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhonicsDisagreement {
+    pub word: String,
+    pub field: String,      // "syllables" 或 "phonics_rule"
+    pub ai_value: String,   // AI 给出的值
+    pub rule_value: String, // 规则分析的值
+    pub message: String,
+}
+
+/// 单词拼读规则检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhonicsRuleCheck {
+    pub analysis: Option<RulePhonicsAnalysis>,
+    pub disagreements: Vec<PhonicsDisagreement>,
+}
+
 /// 批量分析进度
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -81,6 +145,14 @@ pub struct BatchAnalysisResult {
     pub completed_words: usize,
     pub failed_words: usize,
     pub elapsed_seconds: f64,
+    #[serde(default)]
+    pub job_id: Option<i64>, // 对应的批量分析任务ID（用于恢复）
+    #[serde(default)]
+    pub cache_hits: usize, // 命中分析缓存的单词数
+    #[serde(default)]
+    pub cache_misses: usize, // 需要请求 AI 分析的单词数
+    #[serde(default)]
+    pub phonics_warnings: Vec<PhonicsDisagreement>, // 与拼读规则不一致的分析结果
 }
 
 /// 批量分析配置
@@ -122,6 +194,8 @@ pub struct WordStatusUpdateEvent {
     pub word: String,
     pub status: String,
     pub error: Option<String>,
+    #[serde(default)]
+    pub result: Option<PhonicsWord>, // 分析结果（流式解析到该单词时即发送）
 }
 
 /// 批次完成事件
@@ -142,3 +216,32 @@ pub struct AnalysisCompleteEvent {
     pub failed_words: usize,
     pub elapsed_seconds: f64,
 }
+
+/// 批量分析任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchAnalysisJob {
+    pub id: i64,
+    pub input_preview: Option<String>, // 输入文本开头部分
+    pub extraction_mode: String,       // 单词提取模式
+    pub local_extraction: bool,        // 是否在本地提取单词（否则由 AI 模型提取）
+    pub model_id: Option<i64>,         // 使用的 AI 模型ID
+    pub prompt_version: Option<String>, // 批量分析提示词版本
+    pub status: String, // "extracting", "analyzing", "completed", "partial", "cancelled", "failed"
+    pub total_words: usize,
+    pub completed_words: usize,
+    pub failed_words: usize,
+    pub error_message: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 批量分析任务中的单词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisJobWord {
+    pub word: String,
+    pub status: String, // "pending", "completed", "failed"
+    pub result: Option<PhonicsWord>,
+    pub error: Option<String>,
+}
@@ -73,10 +73,31 @@ pub struct Word {
     pub analysis_explanation: Option<String>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<WordHighlight>, // 仅全文搜索结果包含
+}
+
+/// 全文搜索命中的高亮内容（匹配部分用 <mark></mark> 包裹）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WordHighlight {
+    pub word: String,
+    pub meaning: String,
+    pub snippet: Option<String>, // 释义说明、拼读规则或分析说明中的匹配片段
+}
+
+/// 全局单词搜索请求
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WordSearchQuery {
+    pub query: String,
+    pub book_id: Option<Id>,
+    pub theme_tag_id: Option<Id>,
+    pub part_of_speech: Option<String>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
 }
 
 /// 创建单词请求
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateWordRequest {
     pub word: String,
     pub meaning: String,
@@ -160,3 +181,150 @@ pub struct CreateWordBookFromAnalysisRequest {
     pub book_id: Option<Id>, // 如果提供，则向现有单词本添加单词；否则创建新单词本
     pub theme_tag_ids: Option<Vec<Id>>, // 主题标签ID列表
 }
+
+/// 单词导入文件格式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WordImportFormat {
+    Csv,
+    Tsv,
+    AnkiText, // Anki 导出的纯文本（Notes in Plain Text）
+}
+
+/// 导入的单词与单词本中已有单词重复时的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictStrategy {
+    #[default]
+    Skip,
+    Update, // 用文件中非空的字段更新已有单词
+}
+
+/// 导入列映射（列序号从 0 开始）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WordImportMapping {
+    pub word: usize,
+    pub meaning: Option<usize>,
+    pub description: Option<usize>,
+    pub ipa: Option<usize>,
+    pub syllables: Option<usize>,
+    pub phonics_segments: Option<usize>,
+    pub part_of_speech: Option<usize>,
+    pub pos_abbreviation: Option<usize>,
+    pub pos_english: Option<usize>,
+    pub pos_chinese: Option<usize>,
+    pub phonics_rule: Option<usize>,
+    pub analysis_explanation: Option<usize>,
+}
+
+/// 导入单词请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordImportRequest {
+    pub content: String,
+    pub format: Option<WordImportFormat>, // 未指定时自动识别
+    pub has_header: Option<bool>,         // 未指定时根据第一行自动判断
+    pub mapping: Option<WordImportMapping>, // 未指定时根据表头推断
+    pub book_id: Option<Id>,              // 导入到现有单词本
+    pub new_book: Option<CreateWordBookRequest>, // 或创建新单词本
+    #[serde(default)]
+    pub conflict_strategy: ImportConflictStrategy,
+}
+
+/// 与单词本中已有单词重复的导入行
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WordImportConflict {
+    pub line: usize,
+    pub word: String,
+    pub existing_word_id: Id,
+}
+
+/// 无法导入的行
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WordImportRowError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// 导入预览
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordImportPreview {
+    pub format: WordImportFormat,
+    pub has_header: bool,
+    pub headers: Vec<String>,
+    pub mapping: WordImportMapping,
+    pub total_rows: usize,
+    pub new_count: usize,
+    pub conflicts: Vec<WordImportConflict>,
+    pub duplicate_lines: Vec<usize>, // 文件内重复的单词（只导入第一次出现的）
+    pub errors: Vec<WordImportRowError>,
+    pub sample: Vec<CreateWordRequest>,
+}
+
+/// 单词本导出格式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WordBookExportFormat {
+    Csv,
+    Json, // 可通过导入完整还原的 JSON 包
+    Apkg, // Anki 牌组
+}
+
+impl WordBookExportFormat {
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Apkg => "apkg",
+        }
+    }
+}
+
+/// 导出单词本请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordBookExportRequest {
+    pub format: WordBookExportFormat,
+    #[serde(default)]
+    pub include_audio: bool, // 仅 Anki 牌组：打包已缓存的发音
+    pub output_path: Option<String>, // 未指定时保存到下载目录
+}
+
+/// 导出结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordBookExportResult {
+    pub file_path: String,
+    pub format: WordBookExportFormat,
+    pub word_count: usize,
+    pub audio_count: usize,
+    pub file_size: u64,
+}
+
+/// JSON 导出包的格式版本
+pub const WORD_BOOK_BUNDLE_VERSION: u32 = 1;
+
+/// 单词本 JSON 导出包
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordBookBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub book: WordBookBundleInfo,
+    pub theme_tags: Vec<WordBookBundleTag>,
+    pub words: Vec<CreateWordRequest>,
+}
+
+/// 导出包中的单词本信息
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordBookBundleInfo {
+    pub title: String,
+    pub description: String,
+    pub icon: String,
+    pub icon_color: String,
+}
+
+/// 导出包中的主题标签（导入时按名称匹配）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordBookBundleTag {
+    pub name: String,
+    pub icon: String,
+    pub color: String,
+}
@@ -1,40 +1,73 @@
-use crate::ai_service::AIService;
-use crate::error::AppResult;
+use crate::ai_service::{batch_phonics_prompt_version, AIService, PhonicsWord};
+use crate::batch_executor::{BatchEvent, BatchExecutor};
+use crate::error::{AppError, AppResult};
 use crate::logger::Logger;
+use crate::phonics_rules;
 use crate::progress_manager::{get_enhanced_progress_manager, EnhancedProgressManager};
+use crate::repositories::ai_usage_repository::AIUsageRepository;
+use crate::repositories::analysis_job_repository::AnalysisJobRepository;
+use crate::repositories::extraction_word_list_repository::ExtractionWordListRepository;
+use crate::repositories::phonics_cache_repository::{normalize_word, PhonicsCacheRepository};
 use crate::types::word_analysis::{
-    BatchAnalysisConfig, BatchAnalysisProgress, BatchAnalysisResult,
+    BatchAnalysisConfig, BatchAnalysisJob, BatchAnalysisProgress, BatchAnalysisResult,
+    ExtractionWordListType, ExtractionWordLists, PhonicsRuleCheck,
 };
 use crate::types::AIModelConfig;
-use futures::stream::{self, StreamExt};
+use crate::word_extractor::{ExtractionMode, WordExtractor};
 use sqlx::{Row, SqlitePool};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
 
+/// 任务列表默认返回数量
+const DEFAULT_JOB_LIST_LIMIT: i64 = 50;
+
 /// 提取单词（第一步）
+///
+/// 默认由 AI 模型提取；`use_local_extractor` 为 true 时在本地完成，不请求 AI 模型
 #[tauri::command]
 pub async fn extract_words_from_text(
     app: AppHandle,
     text: String,
     model_id: Option<i64>,
+    extraction_mode: Option<String>,
+    use_local_extractor: Option<bool>,
 ) -> AppResult<crate::types::word_analysis::WordExtractionResult> {
     let pool = app.state::<SqlitePool>();
     let logger = app.state::<Logger>();
 
-    // 获取 AI 服务
-    let model_config = get_model_config(model_id, &pool, &logger).await?;
-    let ai_service = Arc::new(AIService::from_model_config(&model_config)?);
-
-    logger.info(
-        "WORD_ANALYSIS",
-        &format!(
-            "🚀 Starting word extraction from text (length: {})",
-            text.len()
-        ),
+    let extraction_mode = extraction_mode.as_deref().unwrap_or("focus");
+    let use_local_extractor = use_local_extractor.unwrap_or(false);
+    logger.api_request(
+        "extract_words_from_text",
+        Some(&format!(
+            "text length: {}, mode: {}, local: {}",
+            text.len(),
+            extraction_mode,
+            use_local_extractor
+        )),
     );
 
-    // 执行单词提取（默认使用 focus 模式）
-    let result = ai_service.extract_words(&text, "focus", &logger).await?;
+    let result = if use_local_extractor {
+        local_word_extractor(&pool, &logger, extraction_mode)
+            .await?
+            .extract(&text)
+    } else {
+        let model_config = get_model_config(model_id, &pool, &logger).await?;
+        let ai_service = AIService::from_model_config(&model_config)?.with_usage_log(
+            AIUsageRepository::new(
+                Arc::new(pool.inner().clone()),
+                Arc::new(logger.inner().clone()),
+            ),
+        );
+        match ai_service.extract_words(&text, extraction_mode, &logger).await {
+            Ok(result) => result,
+            Err(e) => {
+                logger.api_response("extract_words_from_text", false, Some(&e.to_string()));
+                return Err(AppError::InternalError(e.to_string()));
+            }
+        }
+    };
 
     logger.api_response(
         "extract_words_from_text",
@@ -48,6 +81,122 @@ pub async fn extract_words_from_text(
     Ok(result)
 }
 
+/// 获取本地单词提取的用户词表
+#[tauri::command]
+pub async fn get_extraction_word_lists(app: AppHandle) -> AppResult<ExtractionWordLists> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("get_extraction_word_lists", None);
+
+    let result = extraction_word_list_repository(&pool, &logger).find_all().await;
+    match &result {
+        Ok(lists) => logger.api_response(
+            "get_extraction_word_lists",
+            true,
+            Some(&format!(
+                "{} stop words, {} known words",
+                lists.stop_words.len(),
+                lists.known_words.len()
+            )),
+        ),
+        Err(e) => logger.api_response("get_extraction_word_lists", false, Some(&e.to_string())),
+    }
+    result
+}
+
+/// 替换本地单词提取的用户词表（list_type: "stop" 或 "known"）
+#[tauri::command]
+pub async fn set_extraction_word_list(
+    app: AppHandle,
+    list_type: String,
+    words: Vec<String>,
+) -> AppResult<usize> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "set_extraction_word_list",
+        Some(&format!("list_type: {}, words: {}", list_type, words.len())),
+    );
+
+    let result = match parse_word_list_type(&list_type) {
+        Ok(list_type) => {
+            extraction_word_list_repository(&pool, &logger)
+                .replace(list_type, &words)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    match &result {
+        Ok(count) => logger.api_response(
+            "set_extraction_word_list",
+            true,
+            Some(&format!("Saved {} words", count)),
+        ),
+        Err(e) => logger.api_response("set_extraction_word_list", false, Some(&e.to_string())),
+    }
+    result
+}
+
+/// 向本地单词提取的用户词表追加单词（如把单词标记为已掌握）
+#[tauri::command]
+pub async fn add_extraction_words(
+    app: AppHandle,
+    list_type: String,
+    words: Vec<String>,
+) -> AppResult<usize> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request(
+        "add_extraction_words",
+        Some(&format!("list_type: {}, words: {}", list_type, words.len())),
+    );
+
+    let result = match parse_word_list_type(&list_type) {
+        Ok(list_type) => {
+            extraction_word_list_repository(&pool, &logger)
+                .add(list_type, &words)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    match &result {
+        Ok(count) => logger.api_response(
+            "add_extraction_words",
+            true,
+            Some(&format!("Added {} words", count)),
+        ),
+        Err(e) => logger.api_response("add_extraction_words", false, Some(&e.to_string())),
+    }
+    result
+}
+
+/// 按拼读规则分析单词，并检查给定的音节划分和拼读规则是否与规则一致
+#[tauri::command]
+pub async fn check_word_phonics(
+    app: AppHandle,
+    word: String,
+    syllables: Option<String>,
+    phonics_rule: Option<String>,
+) -> AppResult<PhonicsRuleCheck> {
+    let logger = app.state::<Logger>();
+    logger.api_request("check_word_phonics", Some(&format!("word: {}", word)));
+
+    let result = PhonicsRuleCheck {
+        analysis: phonics_rules::analyze(&word),
+        disagreements: phonics_rules::check(&word, syllables.as_deref(), phonics_rule.as_deref()),
+    };
+
+    logger.api_response(
+        "check_word_phonics",
+        true,
+        Some(&format!("{} disagreements", result.disagreements.len())),
+    );
+    Ok(result)
+}
+
 /// 批量分析已提取的单词（第二步）
 #[tauri::command]
 pub async fn analyze_extracted_words(
@@ -61,7 +210,12 @@ pub async fn analyze_extracted_words(
 
     // 1. 获取 AI 服务
     let model_config = get_model_config(model_id, &pool, &logger).await?;
-    let ai_service = Arc::new(AIService::from_model_config(&model_config)?);
+    let ai_service = Arc::new(
+        AIService::from_model_config(&model_config)?.with_usage_log(AIUsageRepository::new(
+            Arc::new(pool.inner().clone()),
+            Arc::new(logger.inner().clone()),
+        )),
+    );
 
     // 2. 获取进度管理器
     let progress_manager = get_enhanced_progress_manager();
@@ -80,15 +234,37 @@ pub async fn analyze_extracted_words(
         ),
     );
 
-    // 4. 执行批量分析
+    // 4. 创建可恢复的分析任务
+    let repository = AnalysisJobRepository::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+    let prompt_version = batch_phonics_prompt_version();
+    let job_id = repository
+        .create_job(None, "focus", false, Some(model_config.id), &prompt_version)
+        .await?;
+    repository.save_words(job_id, &words).await?;
+    let cache = PhonicsCacheRepository::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+    let context = AnalysisContext {
+        repository: &repository,
+        job_id,
+        cache: &cache,
+        model_id: model_config.id,
+        prompt_version,
+    };
+
+    // 5. 执行批量分析
     let result = analyze_words_parallel(
         Arc::clone(&ai_service),
         words,
-        &model_config,
         &logger,
-        &progress_manager,
+        progress_manager,
         &config,
-        app.clone(),
+        &context,
+        Some(app.clone()),
     )
     .await?;
 
@@ -111,6 +287,7 @@ pub async fn analyze_text_with_batching(
     text: String,
     model_id: Option<i64>,
     extraction_mode: Option<String>,
+    use_local_extractor: Option<bool>,
     config: Option<BatchAnalysisConfig>,
 ) -> AppResult<BatchAnalysisResult> {
     let pool = app.state::<SqlitePool>();
@@ -118,7 +295,12 @@ pub async fn analyze_text_with_batching(
 
     // 1. 获取 AI 服务
     let model_config = get_model_config(model_id, &pool, &logger).await?;
-    let ai_service = Arc::new(AIService::from_model_config(&model_config)?);
+    let ai_service = Arc::new(
+        AIService::from_model_config(&model_config)?.with_usage_log(AIUsageRepository::new(
+            Arc::new(pool.inner().clone()),
+            Arc::new(logger.inner().clone()),
+        )),
+    );
 
     // 2. 获取进度管理器
     let progress_manager = get_enhanced_progress_manager();
@@ -126,6 +308,8 @@ pub async fn analyze_text_with_batching(
 
     // 3. 获取配置
     let config = config.unwrap_or_default();
+    let extraction_mode = extraction_mode.as_deref().unwrap_or("focus");
+    let use_local_extractor = use_local_extractor.unwrap_or(false);
 
     logger.info(
         "WORD_ANALYSIS",
@@ -135,15 +319,49 @@ pub async fn analyze_text_with_batching(
         ),
     );
 
-    // 4. 执行批量分析
+    // 4. 创建可恢复的分析任务
+    let repository = AnalysisJobRepository::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+    let prompt_version = batch_phonics_prompt_version();
+    let job_id = repository
+        .create_job(
+            Some(&text),
+            extraction_mode,
+            use_local_extractor,
+            Some(model_config.id),
+            &prompt_version,
+        )
+        .await?;
+    let cache = PhonicsCacheRepository::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+    let context = AnalysisContext {
+        repository: &repository,
+        job_id,
+        cache: &cache,
+        model_id: model_config.id,
+        prompt_version,
+    };
+
+    // 5. 执行批量分析
+    let extractor = if use_local_extractor {
+        Some(local_word_extractor(&pool, &logger, extraction_mode).await?)
+    } else {
+        None
+    };
     let result = analyze_text_with_batching_impl(
         Arc::clone(&ai_service),
         &text,
-        &model_config,
-        extraction_mode.as_deref().unwrap_or("focus"),
+        extraction_mode,
+        extractor.as_ref(),
         &logger,
-        &progress_manager,
+        progress_manager,
         &config,
+        &context,
+        Some(app.clone()),
     )
     .await?;
 
@@ -159,6 +377,165 @@ pub async fn analyze_text_with_batching(
     Ok(result)
 }
 
+/// 获取批量分析任务列表
+#[tauri::command]
+pub async fn get_batch_analysis_jobs(
+    app: AppHandle,
+    limit: Option<i64>,
+) -> AppResult<Vec<BatchAnalysisJob>> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("get_batch_analysis_jobs", None);
+
+    let repository = AnalysisJobRepository::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+
+    match repository.find_all(limit.unwrap_or(DEFAULT_JOB_LIST_LIMIT)).await {
+        Ok(jobs) => {
+            logger.api_response(
+                "get_batch_analysis_jobs",
+                true,
+                Some(&format!("Retrieved {} analysis jobs", jobs.len())),
+            );
+            Ok(jobs)
+        }
+        Err(e) => {
+            logger.api_response("get_batch_analysis_jobs", false, Some(&e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// 恢复批量分析任务（只分析尚未完成的单词）
+#[tauri::command]
+pub async fn resume_batch_analysis(
+    app: AppHandle,
+    job_id: i64,
+    config: Option<BatchAnalysisConfig>,
+) -> AppResult<BatchAnalysisResult> {
+    let pool = app.state::<SqlitePool>();
+    let logger = app.state::<Logger>();
+
+    logger.api_request("resume_batch_analysis", Some(&format!("job_id: {}", job_id)));
+
+    let repository = AnalysisJobRepository::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+    let analysis_job = repository
+        .find_by_id(job_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("批量分析任务 {} 不存在", job_id)))?;
+
+    // 模型已被删除时使用默认模型
+    let model_config = get_model_config(analysis_job.model_id, &pool, &logger).await?;
+    let ai_service = Arc::new(
+        AIService::from_model_config(&model_config)?.with_usage_log(AIUsageRepository::new(
+            Arc::new(pool.inner().clone()),
+            Arc::new(logger.inner().clone()),
+        )),
+    );
+
+    let progress_manager = get_enhanced_progress_manager();
+    progress_manager.start_batch_analysis();
+
+    let config = config.unwrap_or_default();
+
+    // 模板在任务中断后被修改时，剩余单词使用新版本分析
+    let prompt_version = batch_phonics_prompt_version();
+    if analysis_job.prompt_version.as_deref() != Some(prompt_version.as_str()) {
+        logger.info(
+            "WORD_ANALYSIS",
+            &format!(
+                "Batch phonics prompt changed since job {} was created ({:?} -> {})",
+                job_id, analysis_job.prompt_version, prompt_version
+            ),
+        );
+        repository.update_prompt_version(job_id, &prompt_version).await?;
+    }
+
+    let cache = PhonicsCacheRepository::new(
+        Arc::new(pool.inner().clone()),
+        Arc::new(logger.inner().clone()),
+    );
+    let context = AnalysisContext {
+        repository: &repository,
+        job_id,
+        cache: &cache,
+        model_id: model_config.id,
+        prompt_version,
+    };
+
+    let result = if analysis_job.total_words == 0 {
+        // 单词尚未提取，从输入文本重新开始
+        let text = repository
+            .find_input_text(job_id)
+            .await?
+            .ok_or_else(|| AppError::ValidationError("该任务没有可用的输入文本".to_string()))?;
+
+        let extractor = if analysis_job.local_extraction {
+            Some(local_word_extractor(&pool, &logger, &analysis_job.extraction_mode).await?)
+        } else {
+            None
+        };
+        analyze_text_with_batching_impl(
+            Arc::clone(&ai_service),
+            &text,
+            &analysis_job.extraction_mode,
+            extractor.as_ref(),
+            &logger,
+            progress_manager,
+            &config,
+            &context,
+            Some(app.clone()),
+        )
+        .await?
+    } else {
+        let pending_words: Vec<String> = repository
+            .find_words(job_id)
+            .await?
+            .into_iter()
+            .filter(|w| w.status != "completed")
+            .map(|w| w.word)
+            .collect();
+
+        logger.info(
+            "WORD_ANALYSIS",
+            &format!(
+                "🔄 恢复批量分析任务 {}：剩余 {}/{} 个单词",
+                job_id,
+                pending_words.len(),
+                analysis_job.total_words
+            ),
+        );
+
+        analyze_words_parallel(
+            Arc::clone(&ai_service),
+            pending_words,
+            &logger,
+            progress_manager,
+            &config,
+            &context,
+            Some(app.clone()),
+        )
+        .await?
+    };
+
+    logger.api_response(
+        "resume_batch_analysis",
+        true,
+        Some(&format!(
+            "Job {} now has {}/{} words analyzed",
+            job_id, result.completed_words, result.total_words
+        )),
+    );
+
+    Ok(result)
+}
+
 /// 获取批量分析进度（新命令）
 #[tauri::command]
 pub async fn get_batch_analysis_progress(_app: AppHandle) -> AppResult<BatchAnalysisProgress> {
@@ -174,15 +551,29 @@ pub async fn cancel_batch_analysis(_app: AppHandle) -> AppResult<()> {
     Ok(())
 }
 
+/// 批量分析上下文（任务持久化和分析缓存）
+struct AnalysisContext<'a> {
+    repository: &'a AnalysisJobRepository,
+    job_id: i64,
+    cache: &'a PhonicsCacheRepository,
+    model_id: i64,
+    prompt_version: String,
+}
+
 /// 批量分析实现
+///
+/// 传入 `extractor` 时在本地提取单词，否则由 AI 模型流式提取
+#[allow(clippy::too_many_arguments)]
 async fn analyze_text_with_batching_impl(
     ai_service: Arc<AIService>,
     text: &str,
-    _model_config: &AIModelConfig,
     extraction_mode: &str,
+    extractor: Option<&WordExtractor>,
     logger: &Logger,
     progress_manager: &EnhancedProgressManager,
     config: &BatchAnalysisConfig,
+    context: &AnalysisContext<'_>,
+    app_handle: Option<AppHandle>,
 ) -> Result<BatchAnalysisResult, Box<dyn std::error::Error>> {
     let start_time = std::time::Instant::now();
 
@@ -190,11 +581,78 @@ async fn analyze_text_with_batching_impl(
     logger.info(
         "WORD_ANALYSIS",
         &format!(
-            "🚀 步骤 1：开始提取单词（模式: {}）...",
+            "🚀 步骤 1：开始{}提取单词（模式: {}）...",
+            if extractor.is_some() { "本地" } else { "" },
             extraction_mode
         ),
     );
-    let extraction_result = ai_service.extract_words(text, extraction_mode, logger).await?;
+    // 每提取到一个单词立即显示为待分析
+    let mark_pending = |word: &str| {
+        progress_manager.update_word_status(&crate::types::word_analysis::WordAnalysisStatus {
+            word: word.to_string(),
+            status: "pending".to_string(),
+            error: None,
+            result: None,
+        });
+        emit_word_status(
+            &app_handle,
+            logger,
+            crate::types::word_analysis::WordStatusUpdateEvent {
+                word: word.to_string(),
+                status: "pending".to_string(),
+                error: None,
+                result: None,
+            },
+        );
+    };
+    let extraction_result = match extractor {
+        Some(extractor) => {
+            let result = extractor.extract(text);
+            for word in &result.words {
+                mark_pending(&word.word);
+            }
+            result
+        }
+        None => {
+            let extracted_count = Mutex::new(0usize);
+            match ai_service
+                .extract_words_streaming(
+                    text,
+                    extraction_mode,
+                    logger,
+                    &|| progress_manager.is_cancelled(),
+                    &|word| {
+                        let mut count = extracted_count.lock().unwrap();
+                        *count += 1;
+                        progress_manager.update_extraction_progress(
+                            &crate::types::word_analysis::ExtractionProgress {
+                                total_words: *count,
+                                extracted_words: *count,
+                                elapsed_seconds: start_time.elapsed().as_secs_f64(),
+                            },
+                        );
+                        mark_pending(&word.word);
+                    },
+                )
+                .await
+                .map_err(|e| e.to_string())
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    let status = if progress_manager.is_cancelled() {
+                        "cancelled"
+                    } else {
+                        "failed"
+                    };
+                    context
+                        .repository
+                        .update_status(context.job_id, status, Some(&e))
+                        .await?;
+                    return Err(e.into());
+                }
+            }
+        }
+    };
 
     // 更新提取进度
     progress_manager.update_extraction_progress(&crate::types::word_analysis::ExtractionProgress {
@@ -225,90 +683,52 @@ async fn analyze_text_with_batching_impl(
         .into_iter()
         .map(|w| w.word)
         .collect();
+    context.repository.save_words(context.job_id, &words).await?;
 
-    let total_batches = (words.len() + config.batch_size - 1) / config.batch_size;
-
-    // 使用 tokio 的并发工具
-    let mut analysis_results: Vec<crate::ai_service::PhonicsWord> = Vec::new();
-    let mut failed_words: Vec<String> = Vec::new();
-
-    // 分批处理
-    for (batch_index, batch) in words.chunks(config.batch_size).enumerate() {
-        // 检查是否已取消
-        if progress_manager.is_cancelled() {
-            logger.info("WORD_ANALYSIS", "🚫 批量分析已取消");
-            break;
-        }
-
-        let batch_words: Vec<String> = batch.iter().cloned().collect();
-
-        // 更新分析进度
-        progress_manager.update_analysis_progress(&crate::types::word_analysis::AnalysisProgress {
-            total_words: words.len(),
-            completed_words: analysis_results.len(),
-            failed_words: failed_words.len(),
-            current_word: Some(batch_words.first().cloned().unwrap_or_default()),
-            batch_info: crate::types::word_analysis::BatchInfo {
-                total_batches,
-                completed_batches: batch_index,
-                current_batch: batch_index,
-                batch_size: config.batch_size,
-            },
-            elapsed_seconds: start_time.elapsed().as_secs_f64(),
-        });
-
-        // 并发处理批次
-        match ai_service
-            .analyze_words_batch(batch_words.clone(), batch_index, total_batches, logger)
-            .await
-        {
-            Ok(batch_words) => {
-                analysis_results.extend(batch_words.clone());
-                // 更新每个单词的状态
-                for word in &batch_words {
-                    progress_manager.update_word_status(
-                        &crate::types::word_analysis::WordAnalysisStatus {
-                            word: word.word.clone(),
-                            status: "completed".to_string(),
-                            error: None,
-                            result: Some(word.clone()),
-                        },
-                    );
-                }
-            }
-            Err(e) => {
-                // 批次失败，标记所有单词为失败
-                for word in &batch_words {
-                    failed_words.push(word.clone() as String);
-                    progress_manager.update_word_status(
-                        &crate::types::word_analysis::WordAnalysisStatus {
-                            word: word.clone(),
-                            status: "failed".to_string(),
-                            error: Some(e.to_string()),
-                            result: None,
-                        },
-                    );
-                }
-            }
-        }
-    }
+    let result = analyze_words_parallel(
+        ai_service,
+        words,
+        logger,
+        progress_manager,
+        config,
+        context,
+        app_handle,
+    )
+    .await?;
 
     // 步骤 3：合并结果
     logger.info("WORD_ANALYSIS", "✅ 步骤 3：批量分析完成");
 
-    let total_words_count = words.len();
-    let completed_words_count = analysis_results.len();
-    let failed_words_count = failed_words.len();
-
     Ok(BatchAnalysisResult {
-        words: analysis_results,
-        total_words: total_words_count,
-        completed_words: completed_words_count,
-        failed_words: failed_words_count,
         elapsed_seconds: start_time.elapsed().as_secs_f64(),
+        ..result
     })
 }
 
+fn extraction_word_list_repository(
+    pool: &SqlitePool,
+    logger: &Logger,
+) -> ExtractionWordListRepository {
+    ExtractionWordListRepository::new(Arc::new(pool.clone()), Arc::new(logger.clone()))
+}
+
+fn parse_word_list_type(list_type: &str) -> AppResult<ExtractionWordListType> {
+    ExtractionWordListType::parse(list_type)
+        .ok_or_else(|| AppError::ValidationError(format!("未知的词表类型: {}", list_type)))
+}
+
+/// 创建本地单词提取器（内置停用词 + 用户词表）
+async fn local_word_extractor(
+    pool: &SqlitePool,
+    logger: &Logger,
+    extraction_mode: &str,
+) -> AppResult<WordExtractor> {
+    let lists = extraction_word_list_repository(pool, logger).find_all().await?;
+    Ok(WordExtractor::new(ExtractionMode::parse(extraction_mode))
+        .with_stop_words(&lists.stop_words)
+        .with_known_words(&lists.known_words))
+}
+
 /// 获取模型配置
 async fn get_model_config(
     model_id: Option<i64>,
@@ -338,6 +758,8 @@ async fn get_model_config(
             description: row.get("description"),
             max_tokens: row.get("max_tokens"),
             temperature: row.get("temperature"),
+            input_price_per_million: row.get("input_price_per_million"),
+            output_price_per_million: row.get("output_price_per_million"),
             is_active: row.get("is_active"),
             is_default: row.get("is_default"),
             created_at: row.get("created_at"),
@@ -348,6 +770,7 @@ async fn get_model_config(
                 display_name: provider_row.get("display_name"),
                 base_url: provider_row.get("base_url"),
                 api_key: provider_row.get("api_key"),
+                provider_type: provider_row.get("provider_type"),
                 description: provider_row.get("description"),
                 is_active: provider_row.get("is_active"),
                 created_at: provider_row.get("created_at"),
@@ -376,6 +799,8 @@ async fn get_model_config(
             description: row.get("description"),
             max_tokens: row.get("max_tokens"),
             temperature: row.get("temperature"),
+            input_price_per_million: row.get("input_price_per_million"),
+            output_price_per_million: row.get("output_price_per_million"),
             is_active: row.get("is_active"),
             is_default: row.get("is_default"),
             created_at: row.get("created_at"),
@@ -386,6 +811,7 @@ async fn get_model_config(
                 display_name: provider_row.get("display_name"),
                 base_url: provider_row.get("base_url"),
                 api_key: provider_row.get("api_key"),
+                provider_type: provider_row.get("provider_type"),
                 description: provider_row.get("description"),
                 is_active: provider_row.get("is_active"),
                 created_at: provider_row.get("created_at"),
@@ -402,15 +828,56 @@ async fn get_model_config(
     Ok(config)
 }
 
+/// 发送单词状态更新事件
+fn emit_word_status(
+    app_handle: &Option<AppHandle>,
+    logger: &Logger,
+    event: crate::types::word_analysis::WordStatusUpdateEvent,
+) {
+    if let Some(app_handle) = app_handle {
+        if let Err(e) = app_handle.emit_to("main", "word-status-update", &event) {
+            logger.error(
+                "WORD_ANALYSIS",
+                &format!(
+                    "Failed to emit word-status-update event for word '{}': {}",
+                    event.word, e
+                ),
+                None,
+            );
+        }
+    }
+}
+
+/// 将批次结果与请求的单词对应
+///
+/// 只接受规范化后（大小写、首尾空白）完全一致的结果；AI 改写了词形的结果不猜测对应关系，
+/// 对应的单词视为未返回，由批量执行器重试或标记失败，避免错误的结果写入任务和分析缓存
+fn pair_results(words: &[String], results: &[PhonicsWord]) -> Vec<(String, PhonicsWord)> {
+    let mut unmatched = words.to_vec();
+    results
+        .iter()
+        .filter_map(|result| take_requested_word(&mut unmatched, result).map(|w| (w, result.clone())))
+        .collect()
+}
+
+/// 从尚未对应的请求单词中取出与结果对应的单词
+fn take_requested_word(unmatched: &mut Vec<String>, result: &PhonicsWord) -> Option<String> {
+    let key = result.word.trim().to_lowercase();
+    let index = unmatched.iter().position(|w| w.trim().to_lowercase() == key)?;
+    Some(unmatched.remove(index))
+}
+
 /// 只批量分析单词（不包含提取步骤）- 并行版本
+///
+/// 每个批次的结果立即写入任务，最终结果以任务中保存的全部单词为准
 async fn analyze_words_parallel(
     ai_service: Arc<AIService>,
     words: Vec<String>,
-    _model_config: &AIModelConfig,
     logger: &Logger,
     progress_manager: &EnhancedProgressManager,
     config: &BatchAnalysisConfig,
-    app_handle: AppHandle,
+    context: &AnalysisContext<'_>,
+    app_handle: Option<AppHandle>,
 ) -> Result<BatchAnalysisResult, Box<dyn std::error::Error>> {
     let start_time = std::time::Instant::now();
 
@@ -424,310 +891,265 @@ async fn analyze_words_parallel(
         ),
     );
 
-    let total_batches = (words.len() + config.batch_size - 1) / config.batch_size;
-
-    // 将单词分成批次
-    let batches: Vec<Vec<String>> = words
-        .chunks(config.batch_size)
-        .map(|chunk| chunk.to_vec())
+    // 先查询分析缓存，命中的单词直接写入任务
+    let cached = context
+        .cache
+        .find_many(context.model_id, &context.prompt_version, &words)
+        .await
+        .unwrap_or_else(|e| {
+            logger.error("WORD_ANALYSIS", "Failed to read phonics cache", Some(&e.to_string()));
+            HashMap::new()
+        });
+    let (cached_words, uncached_words): (Vec<String>, Vec<String>) = words
+        .into_iter()
+        .partition(|w| cached.contains_key(&normalize_word(w)));
+    let cached_results: Vec<(String, PhonicsWord)> = cached_words
+        .into_iter()
+        .map(|w| {
+            let result = cached[&normalize_word(&w)].clone();
+            (w, result)
+        })
         .collect();
 
     logger.info(
         "WORD_ANALYSIS",
         &format!(
-            "📊 共分为 {} 个批次，每批最多 {} 个单词",
-            batches.len(),
-            config.batch_size
+            "💾 分析缓存命中 {} 个单词，{} 个单词需要 AI 分析",
+            cached_results.len(),
+            uncached_words.len()
         ),
     );
 
-    // 并行处理批次，限制并发数
-    let mut analysis_results: Vec<crate::ai_service::PhonicsWord> = Vec::new();
-    let mut failed_words: Vec<String> = Vec::new();
-    let mut completed_batches = 0;
-
-    // 初始化分析进度
-    let total_words_count = words.len();
-
-    progress_manager.update_analysis_progress(&crate::types::word_analysis::AnalysisProgress {
-        total_words: total_words_count,
-        completed_words: 0,
-        failed_words: 0,
-        current_word: None,
-        batch_info: crate::types::word_analysis::BatchInfo {
-            total_batches,
-            completed_batches: 0,
-            current_batch: 0,
-            batch_size: config.batch_size,
-        },
-        elapsed_seconds: start_time.elapsed().as_secs_f64(),
-    });
-
-    // 克隆 total_words_count 以便在闭包中使用
-    let total_words_count_for_closure = total_words_count;
-
-    // 使用 futures stream 来限制并发数
-    let batches_stream = stream::iter(batches.into_iter().enumerate())
-        .map(|(batch_index, batch_words)| {
-            let ai_service = Arc::clone(&ai_service);
-            let logger = logger.clone();
-            let batch_index_clone = batch_index;
-            let batch_words_clone = batch_words.clone();
-            let total_words_count = total_words_count_for_closure;
-            let app_handle = app_handle.clone();
-
-            async move {
-                // 检查是否已取消
-                if progress_manager.is_cancelled() {
-                    logger.info("WORD_ANALYSIS", "🚫 批量分析已取消");
-                    return (batch_index, None, batch_words_clone);
-                }
+    if !cached_results.is_empty() {
+        context
+            .repository
+            .save_results(context.job_id, &cached_results)
+            .await?;
+
+        for (word, result) in &cached_results {
+            progress_manager.update_word_status(&crate::types::word_analysis::WordAnalysisStatus {
+                word: word.clone(),
+                status: "completed".to_string(),
+                error: None,
+                result: Some(result.clone()),
+            });
+            emit_word_status(
+                &app_handle,
+                logger,
+                crate::types::word_analysis::WordStatusUpdateEvent {
+                    word: word.clone(),
+                    status: "completed".to_string(),
+                    error: None,
+                    result: Some(result.clone()),
+                },
+            );
+        }
+    }
 
-                // 发送批次开始事件
-                if let Err(e) = app_handle.emit_to(
-                    "main",
-                    "batch-start",
-                    crate::types::word_analysis::BatchStartEvent {
-                        batch_index: batch_index_clone,
-                        total_batches,
-                        words: batch_words_clone.clone(),
-                    },
-                ) {
-                    logger.error(
-                        "WORD_ANALYSIS",
-                        &format!("Failed to emit batch-start event: {}", e),
-                        None,
-                    );
-                }
+    let cache_hits = cached_results.len();
+    let cache_misses = uncached_words.len();
+    let failures: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+    BatchExecutor::new(config, progress_manager, logger)
+        .run(
+            uncached_words,
+            |batch_words, batch_index, total_batches| {
+                let ai_service = Arc::clone(&ai_service);
+                let app_handle = &app_handle;
+                async move {
+                    // 流式解析，每个单词的结果到达后立即显示；按 `pair_results` 的规则逐条对应，
+                    // 只为本批次请求的单词发送状态，未对应的结果随后也不会写入任务
+                    let streamed = Mutex::new(batch_words.clone());
+                    let results = ai_service
+                        .analyze_words_batch_streaming(
+                            batch_words.clone(),
+                            batch_index,
+                            total_batches,
+                            logger,
+                            &|| progress_manager.is_cancelled(),
+                            &|result| {
+                                let Some(word) =
+                                    take_requested_word(&mut streamed.lock().unwrap(), result)
+                                else {
+                                    return;
+                                };
+                                progress_manager.update_word_status(
+                                    &crate::types::word_analysis::WordAnalysisStatus {
+                                        word: word.clone(),
+                                        status: "completed".to_string(),
+                                        error: None,
+                                        result: Some(result.clone()),
+                                    },
+                                );
+                                emit_word_status(
+                                    app_handle,
+                                    logger,
+                                    crate::types::word_analysis::WordStatusUpdateEvent {
+                                        word,
+                                        status: "completed".to_string(),
+                                        error: None,
+                                        result: Some(result.clone()),
+                                    },
+                                );
+                            },
+                        )
+                        .await
+                        .map_err(|e| e.to_string())?;
 
-                // 更新批次开始状态 - 立即更新单词状态为"analyzing"
-                for word in &batch_words_clone {
-                    progress_manager.update_word_status(
-                        &crate::types::word_analysis::WordAnalysisStatus {
-                            word: word.clone(),
-                            status: "analyzing".to_string(),
-                            error: None,
-                            result: None,
-                        },
-                    );
-
-                    // 发送单词状态更新事件
-                    if let Err(e) = app_handle.emit_to(
-                        "main",
-                        "word-status-update",
-                        crate::types::word_analysis::WordStatusUpdateEvent {
-                            word: word.clone(),
-                            status: "analyzing".to_string(),
-                            error: None,
-                        },
-                    ) {
+                    // 立即保存，应用关闭后恢复任务时无需重新分析
+                    let pairs = pair_results(&batch_words, &results);
+                    if let Err(e) = context.repository.save_results(context.job_id, &pairs).await {
                         logger.error(
                             "WORD_ANALYSIS",
-                            &format!(
-                                "Failed to emit word-status-update event for word '{}': {}",
-                                word, e
-                            ),
-                            None,
+                            &format!("Failed to save results of batch {}", batch_index + 1),
+                            Some(&e.to_string()),
+                        );
+                    }
+                    if let Err(e) = context
+                        .cache
+                        .save_many(context.model_id, &context.prompt_version, &pairs)
+                        .await
+                    {
+                        logger.error(
+                            "WORD_ANALYSIS",
+                            &format!("Failed to cache results of batch {}", batch_index + 1),
+                            Some(&e.to_string()),
                         );
                     }
-                }
 
-                // 在批次开始时更新进度，让进度条能够移动
-                progress_manager.update_analysis_progress(
-                    &crate::types::word_analysis::AnalysisProgress {
-                        total_words: total_words_count,
-                        completed_words: batch_index_clone * config.batch_size,
-                        failed_words: 0,
-                        current_word: None,
-                        batch_info: crate::types::word_analysis::BatchInfo {
-                            total_batches,
-                            completed_batches: batch_index_clone,
-                            current_batch: batch_index_clone,
-                            batch_size: config.batch_size,
-                        },
-                        elapsed_seconds: start_time.elapsed().as_secs_f64(),
-                    },
-                );
-
-                // 处理批次
-                match ai_service
-                    .analyze_words_batch(
-                        batch_words_clone.clone(),
-                        batch_index_clone,
-                        total_batches,
-                        &logger,
-                    )
-                    .await
-                {
-                    Ok(batch_results) => {
-                        // 更新每个单词的状态为完成
-                        for word in &batch_results {
-                            progress_manager.update_word_status(
-                                &crate::types::word_analysis::WordAnalysisStatus {
-                                    word: word.word.clone(),
-                                    status: "completed".to_string(),
-                                    error: None,
-                                    result: Some(word.clone()),
-                                },
-                            );
-
-                            // 发送单词状态更新事件
-                            if let Err(e) = app_handle.emit_to(
-                                "main",
-                                "word-status-update",
-                                crate::types::word_analysis::WordStatusUpdateEvent {
-                                    word: word.word.clone(),
-                                    status: "completed".to_string(),
-                                    error: None,
-                                },
-                            ) {
-                                logger.error(
-                                    "WORD_ANALYSIS",
-                                    &format!(
-                                        "Failed to emit word-status-update event for word '{}': {}",
-                                        word.word, e
-                                    ),
-                                    None,
-                                );
-                            }
-                        }
-
-                        // 发送批次完成事件
-                        if let Err(e) = app_handle.emit_to(
-                            "main",
-                            "batch-complete",
-                            crate::types::word_analysis::BatchCompleteEvent {
-                                batch_index: batch_index_clone,
-                                completed_words: batch_results.len(),
-                                failed_words: 0,
-                            },
-                        ) {
-                            logger.error(
-                                "WORD_ANALYSIS",
-                                &format!(
-                                    "Failed to emit batch-complete event for batch {}: {}",
-                                    batch_index_clone, e
-                                ),
-                                None,
-                            );
-                        }
-
-                        (batch_index, Some(batch_results), Vec::new())
+                    Ok(pairs.into_iter().map(|(_, result)| result).collect())
+                }
+            },
+            |event| {
+                if let BatchEvent::WordStatus(status) = &event {
+                    if status.status == "failed" {
+                        failures.lock().unwrap().push((
+                            status.word.clone(),
+                            status.error.clone().unwrap_or_default(),
+                        ));
                     }
-                    Err(e) => {
-                        // 批次失败，标记所有单词为失败
-                        for word in &batch_words_clone {
-                            progress_manager.update_word_status(
-                                &crate::types::word_analysis::WordAnalysisStatus {
-                                    word: word.clone(),
-                                    status: "failed".to_string(),
-                                    error: Some(e.to_string()),
-                                    result: None,
-                                },
-                            );
-
-                            // 发送单词状态更新事件
-                            if let Err(e) = app_handle.emit_to(
-                                "main",
-                                "word-status-update",
-                                crate::types::word_analysis::WordStatusUpdateEvent {
-                                    word: word.clone(),
-                                    status: "failed".to_string(),
-                                    error: Some(e.to_string()),
-                                },
-                            ) {
-                                logger.error(
-                                    "WORD_ANALYSIS",
-                                    &format!(
-                                        "Failed to emit word-status-update event for word '{}': {}",
-                                        word, e
-                                    ),
-                                    None,
-                                );
-                            }
-                        }
+                }
 
-                        (batch_index, None, batch_words_clone)
+                if let Some(app_handle) = &app_handle {
+                    if let Err(e) = app_handle.emit_to("main", event.name(), &event) {
+                        logger.error(
+                            "WORD_ANALYSIS",
+                            &format!("Failed to emit {} event: {}", event.name(), e),
+                            None,
+                        );
                     }
                 }
-            }
-        })
-        .buffer_unordered(config.max_concurrent_batches as usize);
-
-    // 处理所有批次
-    let mut batch_results_stream = Box::pin(batches_stream);
-
-    while let Some((batch_index, batch_result, failed_batch_words)) =
-        batch_results_stream.next().await
-    {
-        completed_batches += 1;
-
-        // 立即更新分析进度 - 在批次完成时更新
-        progress_manager.update_analysis_progress(&crate::types::word_analysis::AnalysisProgress {
-            total_words: total_words_count,
-            completed_words: analysis_results.len(),
-            failed_words: failed_words.len(),
-            current_word: None,
-            batch_info: crate::types::word_analysis::BatchInfo {
-                total_batches,
-                completed_batches,
-                current_batch: batch_index,
-                batch_size: config.batch_size,
             },
-            elapsed_seconds: start_time.elapsed().as_secs_f64(),
-        });
+        )
+        .await;
 
-        if let Some(results) = batch_result {
-            let result_len = results.len();
-            analysis_results.extend(results);
-            logger.info(
-                "WORD_ANALYSIS",
-                &format!(
-                    "✅ 批次 {}/{} 完成，分析了 {} 个单词",
-                    batch_index + 1,
-                    total_batches,
-                    result_len
-                ),
-            );
-        } else {
-            failed_words.extend(failed_batch_words);
+    // 保存任务最终状态
+    let failures = failures.into_inner().unwrap_or_default();
+    context.repository.mark_words_failed(context.job_id, &failures).await?;
+
+    let status = if progress_manager.is_cancelled() {
+        "cancelled"
+    } else if failures.is_empty() {
+        "completed"
+    } else {
+        "partial"
+    };
+    context.repository.update_status(context.job_id, status, None).await?;
+
+    // 汇总任务中的全部单词（包括之前已完成的单词）
+    let job_words = context.repository.find_words(context.job_id).await?;
+    let failed_words = job_words.iter().filter(|w| w.status == "failed").count();
+    let total_words = job_words.len();
+    let mut analyzed: Vec<PhonicsWord> = job_words.into_iter().filter_map(|w| w.result).collect();
+
+    // 用拼读规则补全空缺的音节和规则，并标记与规则不一致的 AI 输出
+    let phonics_warnings: Vec<_> = analyzed
+        .iter_mut()
+        .flat_map(phonics_rules::check_phonics_word)
+        .collect();
+    if !phonics_warnings.is_empty() {
+        logger.info(
+            "WORD_ANALYSIS",
+            &format!("⚠️  {} 处分析结果与拼读规则不一致", phonics_warnings.len()),
+        );
+    }
+
+    let result = BatchAnalysisResult {
+        total_words,
+        completed_words: analyzed.len(),
+        failed_words,
+        words: analyzed,
+        elapsed_seconds: start_time.elapsed().as_secs_f64(),
+        job_id: Some(context.job_id),
+        cache_hits,
+        cache_misses,
+        phonics_warnings,
+    };
+
+    logger.info("WORD_ANALYSIS", "✅ 批量分析完成");
+
+    // 发送分析完成事件
+    if let Some(app_handle) = &app_handle {
+        if let Err(e) = app_handle.emit_to(
+            "main",
+            "analysis-complete",
+            crate::types::word_analysis::AnalysisCompleteEvent {
+                total_words: result.total_words,
+                completed_words: result.completed_words,
+                failed_words: result.failed_words,
+                elapsed_seconds: start_time.elapsed().as_secs_f64(),
+            },
+        ) {
             logger.error(
                 "WORD_ANALYSIS",
-                &format!("❌ 批次 {}/{} 失败", batch_index + 1, total_batches),
+                &format!("Failed to emit analysis-complete event: {}", e),
                 None,
             );
         }
     }
 
-    logger.info("WORD_ANALYSIS", "✅ 批量分析完成");
+    Ok(result)
+}
 
-    let total_words_count = words.len();
-    let completed_words_count = analysis_results.len();
-    let failed_words_count = failed_words.len();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phonics_word(word: &str, syllables: &str) -> PhonicsWord {
+        PhonicsWord {
+            word: word.to_string(),
+            frequency: 1,
+            chinese_translation: String::new(),
+            pos_abbreviation: "n.".to_string(),
+            pos_english: "noun".to_string(),
+            pos_chinese: "名词".to_string(),
+            ipa: String::new(),
+            syllables: syllables.to_string(),
+            phonics_rule: String::new(),
+            analysis_explanation: String::new(),
+        }
+    }
 
-    // 发送分析完成事件
-    if let Err(e) = app_handle.emit_to(
-        "main",
-        "analysis-complete",
-        crate::types::word_analysis::AnalysisCompleteEvent {
-            total_words: total_words_count,
-            completed_words: completed_words_count,
-            failed_words: failed_words_count,
-            elapsed_seconds: start_time.elapsed().as_secs_f64(),
-        },
-    ) {
-        logger.error(
-            "WORD_ANALYSIS",
-            &format!("Failed to emit analysis-complete event: {}", e),
-            None,
+    #[test]
+    fn test_pair_results_only_accepts_exact_matches() {
+        let words = vec!["Cat".to_string(), "mice".to_string(), "dog".to_string()];
+        // AI 把 mice 改写为 mouse，不能按位置对应到其他单词
+        let results = vec![
+            phonics_word("mouse", "mouse"),
+            phonics_word(" cat ", "cat"),
+            phonics_word("dog", "dog"),
+        ];
+
+        let pairs: Vec<(String, String)> = pair_results(&words, &results)
+            .into_iter()
+            .map(|(word, result)| (word, result.syllables))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("Cat".to_string(), "cat".to_string()),
+                ("dog".to_string(), "dog".to_string()),
+            ]
         );
     }
-
-    Ok(BatchAnalysisResult {
-        words: analysis_results,
-        total_words: total_words_count,
-        completed_words: completed_words_count,
-        failed_words: failed_words_count,
-        elapsed_seconds: start_time.elapsed().as_secs_f64(),
-    })
 }
@@ -0,0 +1,592 @@
+//! 本地单词提取
+//!
+//! 作为 AI 提取之外的选项，批量分析的第一步可以不请求 AI 模型，在本地完成分词、词形还原、频率统计和停用词过滤，
+//! 输出与 AI 提取相同的 `WordExtractionResult`，结果确定且不消耗 token:
+//! - 分词：按字母序列切分，处理缩写（don't、it's）和所有格，排除含数字或非英文字母的片段
+//! - 词形还原：不规则变化查表（mice→mouse、went→go），规则变化按后缀还原（running→run）
+//! - 过滤：重点模式排除内置停用词和用户停用词，任何模式都排除用户标记的已掌握单词
+
+use crate::types::word_analysis::{ExtractedWord, WordExtractionResult};
+use std::collections::{HashMap, HashSet};
+
+/// 单词最小长度
+const MIN_WORD_LENGTH: usize = 2;
+/// 单词最大长度
+const MAX_WORD_LENGTH: usize = 20;
+
+/// 重点模式内置停用词（按类别分组，AI 提取提示词也使用同一份列表）
+pub const STOP_WORD_GROUPS: &[(&str, &[&str])] = &[
+    ("冠词", &["a", "an", "the"]),
+    (
+        "基础代词",
+        &[
+            "i", "you", "he", "she", "it", "we", "they", "me", "him", "her", "us", "them",
+        ],
+    ),
+    (
+        "基础be动词",
+        &["am", "is", "are", "was", "were", "be", "been", "being"],
+    ),
+    (
+        "基础助动词",
+        &[
+            "do", "does", "did", "have", "has", "had", "will", "would", "can", "could", "should",
+            "shall", "may", "might", "must",
+        ],
+    ),
+    (
+        "基础介词",
+        &[
+            "in", "on", "at", "to", "for", "of", "with", "by", "from", "up", "out", "off", "over",
+            "under",
+        ],
+    ),
+    (
+        "基础连词",
+        &["and", "or", "but", "so", "if", "when", "then", "than", "as"],
+    ),
+    (
+        "基础副词",
+        &[
+            "not", "no", "yes", "very", "too", "also", "only", "just", "now", "here", "there",
+        ],
+    ),
+    (
+        "过于简单的数字词",
+        &[
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        ],
+    ),
+];
+
+/// 不规则变化（变化形式, 原形）
+const IRREGULAR_FORMS: &[(&str, &str)] = &[
+    // be / have / do / go
+    ("am", "be"),
+    ("is", "be"),
+    ("are", "be"),
+    ("was", "be"),
+    ("were", "be"),
+    ("been", "be"),
+    ("being", "be"),
+    ("has", "have"),
+    ("had", "have"),
+    ("does", "do"),
+    ("did", "do"),
+    ("done", "do"),
+    ("went", "go"),
+    ("gone", "go"),
+    // 不规则动词
+    ("ate", "eat"),
+    ("eaten", "eat"),
+    ("became", "become"),
+    ("began", "begin"),
+    ("begun", "begin"),
+    ("bought", "buy"),
+    ("broke", "break"),
+    ("broken", "break"),
+    ("brought", "bring"),
+    ("built", "build"),
+    ("came", "come"),
+    ("caught", "catch"),
+    ("chose", "choose"),
+    ("chosen", "choose"),
+    ("drank", "drink"),
+    ("drew", "draw"),
+    ("drawn", "draw"),
+    ("drove", "drive"),
+    ("driven", "drive"),
+    ("fed", "feed"),
+    ("fell", "fall"),
+    ("fallen", "fall"),
+    ("felt", "feel"),
+    ("flew", "fly"),
+    ("flown", "fly"),
+    ("forgot", "forget"),
+    ("forgotten", "forget"),
+    ("fought", "fight"),
+    ("found", "find"),
+    ("froze", "freeze"),
+    ("frozen", "freeze"),
+    ("gave", "give"),
+    ("given", "give"),
+    ("got", "get"),
+    ("gotten", "get"),
+    ("grew", "grow"),
+    ("grown", "grow"),
+    ("heard", "hear"),
+    ("held", "hold"),
+    ("hid", "hide"),
+    ("hidden", "hide"),
+    ("kept", "keep"),
+    ("knew", "know"),
+    ("known", "know"),
+    ("led", "lead"),
+    ("lost", "lose"),
+    ("made", "make"),
+    ("meant", "mean"),
+    ("met", "meet"),
+    ("paid", "pay"),
+    ("ran", "run"),
+    ("rode", "ride"),
+    ("ridden", "ride"),
+    ("rose", "rise"),
+    ("risen", "rise"),
+    ("said", "say"),
+    ("sang", "sing"),
+    ("sung", "sing"),
+    ("sat", "sit"),
+    ("saw", "see"),
+    ("seen", "see"),
+    ("sent", "send"),
+    ("shook", "shake"),
+    ("shaken", "shake"),
+    ("slept", "sleep"),
+    ("sold", "sell"),
+    ("sought", "seek"),
+    ("spent", "spend"),
+    ("spoke", "speak"),
+    ("spoken", "speak"),
+    ("stole", "steal"),
+    ("stolen", "steal"),
+    ("stood", "stand"),
+    ("swam", "swim"),
+    ("swum", "swim"),
+    ("taught", "teach"),
+    ("thought", "think"),
+    ("threw", "throw"),
+    ("thrown", "throw"),
+    ("told", "tell"),
+    ("took", "take"),
+    ("taken", "take"),
+    ("understood", "understand"),
+    ("woke", "wake"),
+    ("woken", "wake"),
+    ("won", "win"),
+    ("wore", "wear"),
+    ("worn", "wear"),
+    ("wrote", "write"),
+    ("written", "write"),
+    // 规则后缀无法正确还原的动词形式
+    ("agreed", "agree"),
+    ("disagreed", "disagree"),
+    ("freed", "free"),
+    ("guaranteed", "guarantee"),
+    ("dying", "die"),
+    ("lying", "lie"),
+    ("tying", "tie"),
+    ("used", "use"),
+    ("using", "use"),
+    // 不规则复数
+    ("children", "child"),
+    ("men", "man"),
+    ("women", "woman"),
+    ("mice", "mouse"),
+    ("lice", "louse"),
+    ("feet", "foot"),
+    ("teeth", "tooth"),
+    ("geese", "goose"),
+    ("oxen", "ox"),
+    ("wolves", "wolf"),
+    ("leaves", "leaf"),
+    ("knives", "knife"),
+    ("lives", "life"),
+    ("wives", "wife"),
+    ("halves", "half"),
+    ("shelves", "shelf"),
+    ("thieves", "thief"),
+    ("calves", "calf"),
+    ("loaves", "loaf"),
+    ("shoes", "shoe"),
+    ("toes", "toe"),
+    ("canoes", "canoe"),
+    ("movies", "movie"),
+    ("cookies", "cookie"),
+    ("buses", "bus"),
+    ("quizzes", "quiz"),
+    ("analyses", "analysis"),
+    ("crises", "crisis"),
+    ("criteria", "criterion"),
+    ("phenomena", "phenomenon"),
+    // 不规则比较级
+    ("better", "good"),
+    ("best", "good"),
+    ("worse", "bad"),
+    ("worst", "bad"),
+];
+
+/// 看起来带有屈折后缀、实际是原形的单词
+const UNINFLECTED_WORDS: &[&str] = &[
+    "always",
+    "perhaps",
+    "news",
+    "series",
+    "species",
+    "physics",
+    "mathematics",
+    "politics",
+    "economics",
+    "sometimes",
+    "towards",
+    "afterwards",
+    "besides",
+    "during",
+    "morning",
+    "evening",
+    "nothing",
+    "something",
+    "anything",
+    "everything",
+    "ceiling",
+    "hundred",
+    "naked",
+    "wicked",
+    "sacred",
+];
+
+/// 单词提取模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionMode {
+    /// 重点模式：排除停用词
+    Focus,
+    /// 全部模式：保留所有单词
+    All,
+}
+
+impl ExtractionMode {
+    /// 解析提取模式，未知值按重点模式处理
+    pub fn parse(mode: &str) -> Self {
+        match mode {
+            "all" => ExtractionMode::All,
+            _ => ExtractionMode::Focus,
+        }
+    }
+}
+
+/// 内置停用词
+pub fn default_stop_words() -> impl Iterator<Item = &'static str> {
+    STOP_WORD_GROUPS
+        .iter()
+        .flat_map(|(_, words)| words.iter().copied())
+}
+
+/// 本地单词提取器
+#[derive(Debug, Clone)]
+pub struct WordExtractor {
+    mode: ExtractionMode,
+    stop_words: HashSet<String>,
+    known_words: HashSet<String>,
+}
+
+impl WordExtractor {
+    /// 创建提取器（重点模式使用内置停用词）
+    pub fn new(mode: ExtractionMode) -> Self {
+        let stop_words = match mode {
+            ExtractionMode::Focus => default_stop_words().map(str::to_string).collect(),
+            ExtractionMode::All => HashSet::new(),
+        };
+        Self {
+            mode,
+            stop_words,
+            known_words: HashSet::new(),
+        }
+    }
+
+    /// 提取模式
+    pub fn mode(&self) -> ExtractionMode {
+        self.mode
+    }
+
+    /// 追加用户停用词（仅重点模式生效）
+    pub fn with_stop_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if self.mode == ExtractionMode::Focus {
+            self.stop_words
+                .extend(words.into_iter().map(|w| w.as_ref().trim().to_lowercase()));
+        }
+        self
+    }
+
+    /// 追加用户已掌握的单词（按原形匹配，任何模式都会排除）
+    pub fn with_known_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.known_words.extend(
+            words
+                .into_iter()
+                .map(|w| lemmatize(&w.as_ref().trim().to_lowercase())),
+        );
+        self
+    }
+
+    /// 提取单词，按频率从高到低排序（频率相同时按首次出现的顺序）
+    pub fn extract(&self, text: &str) -> WordExtractionResult {
+        let mut order: Vec<String> = Vec::new();
+        let mut frequencies: HashMap<String, i32> = HashMap::new();
+
+        for token in tokenize(text) {
+            let lemma = lemmatize(&token);
+            if self.is_excluded(&token, &lemma) {
+                continue;
+            }
+            let count = frequencies.entry(lemma.clone()).or_insert(0);
+            if *count == 0 {
+                order.push(lemma);
+            }
+            *count += 1;
+        }
+
+        let mut words: Vec<ExtractedWord> = order
+            .into_iter()
+            .map(|word| ExtractedWord {
+                frequency: frequencies[&word],
+                word,
+                part_of_speech: None,
+                meaning: None,
+            })
+            .collect();
+        // 稳定排序保留首次出现的顺序
+        words.sort_by_key(|w| std::cmp::Reverse(w.frequency));
+
+        WordExtractionResult {
+            total_count: words.iter().map(|w| w.frequency as usize).sum(),
+            unique_count: words.len(),
+            words,
+        }
+    }
+
+    fn is_excluded(&self, token: &str, lemma: &str) -> bool {
+        let listed = |set: &HashSet<String>| set.contains(token) || set.contains(lemma);
+        listed(&self.stop_words) || listed(&self.known_words)
+    }
+}
+
+/// 分词：返回小写单词，缩写和所有格还原为词干，排除长度不符或包含非英文字母的片段
+pub fn tokenize(text: &str) -> Vec<String> {
+    let normalized = text.replace(['\u{2019}', '\u{2018}'], "'");
+
+    normalized
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter_map(|raw| {
+            let token = strip_clitic(&raw.trim_matches('\'').to_lowercase());
+            let valid = token.chars().all(|c| c.is_ascii_lowercase())
+                && (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&token.len());
+            valid.then_some(token)
+        })
+        .collect()
+}
+
+/// 去掉缩写和所有格后缀（don't→do、we're→we、tom's→tom）
+fn strip_clitic(token: &str) -> String {
+    if let Some(stem) = token.strip_suffix("n't") {
+        return match token {
+            "can't" => "can".to_string(),
+            "won't" => "will".to_string(),
+            "shan't" => "shall".to_string(),
+            "ain't" => "be".to_string(),
+            _ => stem.to_string(),
+        };
+    }
+
+    match token.split_once('\'') {
+        Some((stem, "s" | "re" | "ve" | "ll" | "d" | "m")) => stem.to_string(),
+        // o'clock 等单词内部的撇号
+        Some(_) => token.replace('\'', ""),
+        None => token.to_string(),
+    }
+}
+
+/// 词形还原：小写单词还原为原形（名词复数、动词时态、不规则比较级）
+pub fn lemmatize(word: &str) -> String {
+    if let Some((_, base)) = IRREGULAR_FORMS.iter().find(|(form, _)| *form == word) {
+        return base.to_string();
+    }
+    if word.len() <= 3 || UNINFLECTED_WORDS.contains(&word) {
+        return word.to_string();
+    }
+
+    if let Some(stem) = word.strip_suffix("ies") {
+        // dies、ties 等短词只去掉 s
+        return if stem.len() == 1 {
+            format!("{}ie", stem)
+        } else {
+            format!("{}y", stem)
+        };
+    }
+    if let Some(stem) = word.strip_suffix("ied") {
+        return format!("{}y", stem);
+    }
+    if word.ends_with("sses")
+        || word.ends_with("shes")
+        || word.ends_with("ches")
+        || word.ends_with("xes")
+        || word.ends_with("zzes")
+        || word.ends_with("oes")
+    {
+        return word[..word.len() - 2].to_string();
+    }
+    if word.ends_with('s')
+        && !(word.ends_with("ss") || word.ends_with("us") || word.ends_with("is"))
+    {
+        return word[..word.len() - 1].to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ing") {
+        return restore_stem(word, stem);
+    }
+    if word.ends_with("eed") {
+        // need、speed、succeed 等本身就是原形
+        return word.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ed") {
+        return restore_stem(word, stem);
+    }
+
+    word.to_string()
+}
+
+fn is_vowel(c: u8) -> bool {
+    matches!(c, b'a' | b'e' | b'i' | b'o' | b'u')
+}
+
+/// 元音组数量（近似音节数，y 在辅音后视为元音）
+fn vowel_groups(word: &[u8]) -> usize {
+    let mut groups = 0;
+    let mut previous_vowel = false;
+    for (i, &c) in word.iter().enumerate() {
+        let vowel = is_vowel(c) || (c == b'y' && i > 0 && !is_vowel(word[i - 1]));
+        if vowel && !previous_vowel {
+            groups += 1;
+        }
+        previous_vowel = vowel;
+    }
+    groups
+}
+
+/// 去掉 -ing / -ed 后还原词干：双写辅音去重（running→run），
+/// 单音节“辅音-元音-辅音”结尾补回 e（making→make）
+fn restore_stem(word: &str, stem: &str) -> String {
+    let bytes = stem.as_bytes();
+    // 词干没有元音说明后缀是单词本身的一部分（thing、bed）
+    if bytes.len() < 2 || vowel_groups(bytes) == 0 {
+        return word.to_string();
+    }
+
+    let n = bytes.len();
+    let last = bytes[n - 1];
+    let before_last = bytes[n - 2];
+
+    if last == before_last && !is_vowel(last) {
+        // add、egg 等以元音开头的三字母词保留双写
+        if matches!(last, b'l' | b's' | b'z' | b'f') || (n == 3 && is_vowel(bytes[0])) {
+            return stem.to_string();
+        }
+        return stem[..n - 1].to_string();
+    }
+
+    let consonant_vowel_consonant = n >= 3
+        && !is_vowel(last)
+        && !matches!(last, b'w' | b'x' | b'y')
+        && is_vowel(before_last)
+        && !is_vowel(bytes[n - 3]);
+    if consonant_vowel_consonant && vowel_groups(bytes) == 1 {
+        return format!("{}e", stem);
+    }
+
+    stem.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lemmatize_inflections() {
+        let cases = [
+            ("running", "run"),
+            ("mice", "mouse"),
+            ("went", "go"),
+            ("studies", "study"),
+            ("studied", "study"),
+            ("boxes", "box"),
+            ("watches", "watch"),
+            ("horses", "horse"),
+            ("making", "make"),
+            ("hoping", "hope"),
+            ("hopping", "hop"),
+            ("jumped", "jump"),
+            ("visited", "visit"),
+            ("falling", "fall"),
+            ("adding", "add"),
+            ("cats", "cat"),
+            ("wolves", "wolf"),
+            ("better", "good"),
+            // 原形保持不变
+            ("thing", "thing"),
+            ("bus", "bus"),
+            ("class", "class"),
+            ("need", "need"),
+            ("morning", "morning"),
+            ("red", "red"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(lemmatize(word), expected, "lemmatize({})", word);
+        }
+    }
+
+    #[test]
+    fn test_tokenize_contractions_and_noise() {
+        let tokens = tokenize("Don't stop! Tom’s 3 cats can't see mp3 files at o'clock, café a");
+        assert_eq!(
+            tokens,
+            vec!["do", "stop", "tom", "cats", "can", "see", "files", "at", "oclock"]
+        );
+    }
+
+    #[test]
+    fn test_extract_counts_lemmas_and_filters_stop_words() {
+        let text = "The mice were running. A mouse runs; the children run and a child ran.";
+
+        let focus = WordExtractor::new(ExtractionMode::Focus).extract(text);
+        let words: Vec<(&str, i32)> = focus
+            .words
+            .iter()
+            .map(|w| (w.word.as_str(), w.frequency))
+            .collect();
+        assert_eq!(words, vec![("run", 4), ("mouse", 2), ("child", 2)]);
+        assert_eq!(focus.unique_count, 3);
+        assert_eq!(focus.total_count, 8);
+
+        // 全部模式保留停用词
+        let all = WordExtractor::new(ExtractionMode::All).extract(text);
+        assert!(all
+            .words
+            .iter()
+            .any(|w| w.word == "the" && w.frequency == 2));
+        assert!(all.words.iter().any(|w| w.word == "be"));
+    }
+
+    #[test]
+    fn test_custom_stop_and_known_words() {
+        let text = "Apples and bananas. An apple, a banana, a cherry.";
+
+        let result = WordExtractor::new(ExtractionMode::Focus)
+            .with_stop_words(["Cherry"])
+            .with_known_words(["apple"])
+            .extract(text);
+        let words: Vec<&str> = result.words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["banana"]);
+
+        // 用户停用词只在重点模式生效，已掌握单词在任何模式都排除
+        let result = WordExtractor::new(ExtractionMode::All)
+            .with_stop_words(["cherry"])
+            .with_known_words(["apples"])
+            .extract(text);
+        let words: Vec<&str> = result.words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["banana", "and", "an", "cherry"]);
+    }
+}
@@ -0,0 +1,393 @@
+//! 单词表格导入解析
+//!
+//! 解析 CSV、TSV 和 Anki 导出的纯文本文件，按列映射转换为单词：
+//! - 自动识别文件格式和表头
+//! - Anki 文件头（`#separator:`、`#html:`、`#columns:`）和 HTML 字段
+//! - 根据表头名称推断列映射，没有表头时默认第一列为单词、第二列为释义
+
+use crate::error::{AppError, AppResult};
+use crate::types::wordbook::{
+    CreateWordRequest, WordImportFormat, WordImportMapping, WordImportRowError,
+};
+
+/// 表头名称与单词字段的对应关系（名称已规范化为小写、下划线分隔）
+const FIELD_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "word",
+        &[
+            "word",
+            "单词",
+            "英文",
+            "english",
+            "front",
+            "term",
+            "vocabulary",
+        ],
+    ),
+    (
+        "meaning",
+        &[
+            "meaning",
+            "释义",
+            "中文",
+            "意思",
+            "翻译",
+            "back",
+            "translation",
+            "chinese",
+            "definition",
+        ],
+    ),
+    ("ipa", &["ipa", "音标", "phonetic", "pronunciation"]),
+    ("syllables", &["syllables", "音节"]),
+    (
+        "phonics_segments",
+        &["phonics_segments", "segments", "拼读片段"],
+    ),
+    ("part_of_speech", &["part_of_speech", "pos", "词性"]),
+    ("pos_abbreviation", &["pos_abbreviation"]),
+    ("pos_english", &["pos_english"]),
+    ("pos_chinese", &["pos_chinese"]),
+    ("phonics_rule", &["phonics_rule", "rule", "拼读规则"]),
+    (
+        "analysis_explanation",
+        &["analysis_explanation", "explanation", "解析"],
+    ),
+    (
+        "description",
+        &["description", "example", "例句", "notes", "备注"],
+    ),
+];
+
+/// 解析后的表格
+#[derive(Debug, Clone)]
+pub struct ParsedTable {
+    pub format: WordImportFormat,
+    pub has_header: bool,
+    pub headers: Vec<String>,
+    pub rows: Vec<TableRow>,
+}
+
+/// 表格中的一行数据
+#[derive(Debug, Clone)]
+pub struct TableRow {
+    pub line: usize, // 在文件中的行号（从 1 开始）
+    pub fields: Vec<String>,
+}
+
+/// Anki 纯文本导出的文件头
+struct AnkiHeaders {
+    separator: u8,
+    html: bool,
+    columns: Option<Vec<String>>,
+}
+
+fn parse_anki_headers(content: &str) -> AnkiHeaders {
+    let mut headers = AnkiHeaders {
+        separator: b'\t',
+        html: false,
+        columns: None,
+    };
+
+    for line in content.lines().take_while(|l| l.starts_with('#')) {
+        let Some((key, value)) = line[1..].split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "separator" => {
+                headers.separator = match value.to_lowercase().as_str() {
+                    "tab" => b'\t',
+                    "comma" => b',',
+                    "semicolon" => b';',
+                    "pipe" => b'|',
+                    "space" => b' ',
+                    "colon" => b':',
+                    other if other.len() == 1 => other.as_bytes()[0],
+                    _ => b'\t',
+                }
+            }
+            "html" => headers.html = value.eq_ignore_ascii_case("true"),
+            // 列名使用与数据相同的分隔符（Anki 总是先写 #separator:）
+            "columns" => {
+                headers.columns = Some(
+                    value
+                        .split(headers.separator as char)
+                        .map(|c| c.trim().to_string())
+                        .collect(),
+                )
+            }
+            _ => {}
+        }
+    }
+
+    headers
+}
+
+/// 识别文件格式：带 Anki 文件头的为 Anki 文本，第一行制表符多于逗号的为 TSV
+pub fn detect_format(content: &str) -> WordImportFormat {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let Some(first) = lines.next() else {
+        return WordImportFormat::Csv;
+    };
+    if first.starts_with("#separator:") || first.starts_with("#html:") {
+        return WordImportFormat::AnkiText;
+    }
+
+    let tabs = first.matches('\t').count();
+    let commas = first.matches(',').count();
+    if tabs > 0 && tabs >= commas {
+        WordImportFormat::Tsv
+    } else {
+        WordImportFormat::Csv
+    }
+}
+
+/// 去掉 Anki 字段中的 HTML 标签并还原常见实体
+fn strip_html(value: &str) -> String {
+    let mut text = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_lowercase();
+        if tag.starts_with("br") || tag == "/div" || tag == "/p" {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 解析表格，未指定格式或是否有表头时自动识别
+pub fn parse_table(
+    content: &str,
+    format: Option<WordImportFormat>,
+    has_header: Option<bool>,
+) -> AppResult<ParsedTable> {
+    let content = content.trim_start_matches('\u{feff}');
+    let format = format.unwrap_or_else(|| detect_format(content));
+    let anki = (format == WordImportFormat::AnkiText).then(|| parse_anki_headers(content));
+    let delimiter = match (&anki, format) {
+        (Some(headers), _) => headers.separator,
+        (None, WordImportFormat::Tsv) => b'\t',
+        _ => b',',
+    };
+
+    // Anki 文件头只出现在文件开头，跳过后按偏移量修正行号
+    let header_lines = match anki {
+        Some(_) => content.lines().take_while(|l| l.starts_with('#')).count(),
+        None => 0,
+    };
+    let body = content
+        .split_inclusive('\n')
+        .skip(header_lines)
+        .collect::<String>();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+
+    let html = anki.as_ref().is_some_and(|h| h.html);
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| {
+            let line = e.position().map_or(0, |p| p.line() as usize) + header_lines;
+            AppError::ValidationError(format!("第 {} 行解析失败: {}", line, e))
+        })?;
+        let fields: Vec<String> = record
+            .iter()
+            .map(|f| {
+                let f = if html { strip_html(f) } else { f.to_string() };
+                f.trim().to_string()
+            })
+            .collect();
+        if fields.iter().all(|f| f.is_empty()) {
+            continue;
+        }
+        rows.push(TableRow {
+            line: record.position().map_or(0, |p| p.line() as usize) + header_lines,
+            fields,
+        });
+    }
+
+    // Anki 文件的列名写在文件头中；CSV/TSV 第一行能识别出单词列时视为表头
+    let mut headers = Vec::new();
+    let has_header = match anki.and_then(|h| h.columns) {
+        Some(columns) => {
+            headers = columns;
+            false
+        }
+        None => has_header.unwrap_or_else(|| {
+            rows.first()
+                .is_some_and(|row| find_column(&row.fields, "word").is_some())
+        }),
+    };
+    if has_header && !rows.is_empty() {
+        headers = rows.remove(0).fields;
+    }
+
+    Ok(ParsedTable {
+        format,
+        has_header,
+        headers,
+        rows,
+    })
+}
+
+fn normalize_header(header: &str) -> String {
+    header.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+fn find_column(headers: &[String], field: &str) -> Option<usize> {
+    let aliases = FIELD_ALIASES
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, aliases)| *aliases)?;
+    headers
+        .iter()
+        .position(|h| aliases.contains(&normalize_header(h).as_str()))
+}
+
+/// 根据表头推断列映射；没有可识别的表头时第一列为单词、第二列为释义
+pub fn suggest_mapping(table: &ParsedTable) -> WordImportMapping {
+    let headers = &table.headers;
+    let Some(word) = find_column(headers, "word") else {
+        let columns = table.rows.iter().map(|r| r.fields.len()).max().unwrap_or(0);
+        return WordImportMapping {
+            word: 0,
+            meaning: (columns > 1).then_some(1),
+            ..Default::default()
+        };
+    };
+
+    WordImportMapping {
+        word,
+        meaning: find_column(headers, "meaning"),
+        description: find_column(headers, "description"),
+        ipa: find_column(headers, "ipa"),
+        syllables: find_column(headers, "syllables"),
+        phonics_segments: find_column(headers, "phonics_segments"),
+        part_of_speech: find_column(headers, "part_of_speech"),
+        pos_abbreviation: find_column(headers, "pos_abbreviation"),
+        pos_english: find_column(headers, "pos_english"),
+        pos_chinese: find_column(headers, "pos_chinese"),
+        phonics_rule: find_column(headers, "phonics_rule"),
+        analysis_explanation: find_column(headers, "analysis_explanation"),
+    }
+}
+
+/// 按列映射把表格行转换为单词，缺少单词的行作为错误返回
+pub fn map_rows(
+    table: &ParsedTable,
+    mapping: &WordImportMapping,
+) -> (Vec<(usize, CreateWordRequest)>, Vec<WordImportRowError>) {
+    let mut words = Vec::new();
+    let mut errors = Vec::new();
+
+    for row in &table.rows {
+        let cell = |column: Option<usize>| {
+            column
+                .and_then(|c| row.fields.get(c))
+                .filter(|v| !v.is_empty())
+                .cloned()
+        };
+
+        let Some(word) = cell(Some(mapping.word)) else {
+            errors.push(WordImportRowError {
+                line: row.line,
+                message: "缺少单词".to_string(),
+            });
+            continue;
+        };
+
+        words.push((
+            row.line,
+            CreateWordRequest {
+                word,
+                meaning: cell(mapping.meaning).unwrap_or_default(),
+                description: cell(mapping.description),
+                ipa: cell(mapping.ipa),
+                syllables: cell(mapping.syllables),
+                phonics_segments: cell(mapping.phonics_segments),
+                part_of_speech: cell(mapping.part_of_speech),
+                category_id: None,
+                pos_abbreviation: cell(mapping.pos_abbreviation),
+                pos_english: cell(mapping.pos_english),
+                pos_chinese: cell(mapping.pos_chinese),
+                phonics_rule: cell(mapping.phonics_rule),
+                analysis_explanation: cell(mapping.analysis_explanation),
+            },
+        ));
+    }
+
+    (words, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_with_header_and_quoted_fields() {
+        let content =
+            "\u{feff}Word,释义,IPA,Example\ncat,猫,/kæt/,\"A cat, sleeping.\"\n,,,\ndog,狗,,\n";
+        let table = parse_table(content, None, None).unwrap();
+        assert_eq!(table.format, WordImportFormat::Csv);
+        assert!(table.has_header);
+
+        let mapping = suggest_mapping(&table);
+        assert_eq!(mapping.word, 0);
+        assert_eq!(mapping.meaning, Some(1));
+        assert_eq!(mapping.ipa, Some(2));
+        assert_eq!(mapping.description, Some(3));
+
+        let (words, errors) = map_rows(&table, &mapping);
+        assert!(errors.is_empty());
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].0, 2);
+        assert_eq!(words[0].1.description.as_deref(), Some("A cat, sleeping."));
+        assert_eq!(words[1].1.ipa, None);
+    }
+
+    #[test]
+    fn test_tsv_without_header() {
+        let table = parse_table("apple\t苹果\n\t缺少单词\n", None, None).unwrap();
+        assert_eq!(table.format, WordImportFormat::Tsv);
+        assert!(!table.has_header);
+
+        let mapping = suggest_mapping(&table);
+        assert_eq!((mapping.word, mapping.meaning), (0, Some(1)));
+        let (words, errors) = map_rows(&table, &mapping);
+        assert_eq!(words[0].1.meaning, "苹果");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+    }
+
+    #[test]
+    fn test_anki_text_export() {
+        let content = "#separator:tab\n#html:true\n#columns:Front\tBack\tTags\nbanana\t香蕉<br>一种水果\tfruit\n\"a&amp;b\"\t<b>测试</b>&nbsp;\t\n";
+        let table = parse_table(content, None, None).unwrap();
+        assert_eq!(table.format, WordImportFormat::AnkiText);
+        assert_eq!(table.headers, vec!["Front", "Back", "Tags"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].line, 4);
+
+        let (words, _) = map_rows(&table, &suggest_mapping(&table));
+        assert_eq!(words[0].1.meaning, "香蕉\n一种水果");
+        assert_eq!(words[1].1.word, "a&b");
+        assert_eq!(words[1].1.meaning, "测试");
+    }
+}
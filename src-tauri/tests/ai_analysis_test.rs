@@ -1,4 +1,5 @@
 use redlark_app::ai_service::{AIProvider, AIService};
+use redlark_app::llm_provider::LlmProviderType;
 use redlark_app::logger::Logger;
 use redlark_app::types::AIModelConfig;
 use std::env;
@@ -9,6 +10,7 @@ fn create_test_provider() -> AIProvider {
         name: "test-provider".to_string(),
         base_url: "https://api.openai.com/v1".to_string(),
         api_key: env::var("OPENAI_API_KEY").unwrap_or("test-key".to_string()),
+        provider_type: LlmProviderType::OpenAiCompatible,
         default_model: "gpt-3.5-turbo".to_string(),
     }
 }
@@ -23,6 +25,8 @@ fn create_test_model_config() -> AIModelConfig {
         description: "Test model for unit testing".to_string(),
         max_tokens: Some(1000),
         temperature: Some(0.7),
+        input_price_per_million: None,
+        output_price_per_million: None,
         is_default: true,
         provider: redlark_app::types::AIProvider {
             id: 1,
@@ -30,6 +34,7 @@ fn create_test_model_config() -> AIModelConfig {
             display_name: "Test Provider".to_string(),
             base_url: "https://api.openai.com/v1".to_string(),
             api_key: env::var("OPENAI_API_KEY").unwrap_or("test-key".to_string()),
+            provider_type: "openai".to_string(),
             description: "Test provider".to_string(),
             is_active: true,
             created_at: "2024-01-01T00:00:00Z".to_string(),
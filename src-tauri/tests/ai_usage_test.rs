@@ -0,0 +1,112 @@
+// AI 调用用量与费用统计测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::ai_service::AIService;
+use redlark_app_lib::repositories::ai_model_repository::AIModelRepository;
+use redlark_app_lib::repositories::ai_usage_repository::AIUsageRepository;
+use redlark_app_lib::services::ai_model::AIModelService;
+use redlark_app_lib::services::ai_usage::AIUsageService;
+use sqlx::Row;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_llm_calls_are_recorded_with_cost() {
+    let pool = setup_test_db().await;
+    let server = MockLlmServer::start().await;
+    let (_, model_id) = insert_mock_llm_provider(&pool, &server).await;
+    let logger = test_logger();
+    let pool_arc = Arc::new(pool.clone());
+
+    // 输入 $2 / 输出 $10 每百万 token
+    AIModelService::new(pool_arc.clone(), logger.clone())
+        .update_model_pricing(model_id, Some(2.0), Some(10.0))
+        .await
+        .unwrap();
+
+    let model_config = AIModelRepository::new(pool_arc.clone(), logger.clone())
+        .find_model_config_by_id(model_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(model_config.input_price_per_million, Some(2.0));
+
+    let ai_service = AIService::from_model_config(&model_config)
+        .unwrap()
+        .with_usage_log(AIUsageRepository::new(pool_arc.clone(), logger.clone()));
+
+    ai_service
+        .extract_words("The cat is jumping in the garden.", "focus", &logger)
+        .await
+        .unwrap();
+    ai_service
+        .analyze_words_batch(vec!["cat".to_string()], 0, 1, &logger)
+        .await
+        .unwrap();
+
+    let rows = sqlx::query(
+        "SELECT purpose, model_id, prompt_tokens, completion_tokens, tokens_estimated, estimated_cost, success
+         FROM ai_usage_log ORDER BY id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<String, _>("purpose"), "extraction");
+    assert_eq!(rows[1].get::<String, _>("purpose"), "batch_phonics");
+
+    for row in &rows {
+        assert_eq!(row.get::<Option<i64>, _>("model_id"), Some(model_id));
+        assert!(row.get::<bool, _>("success"));
+        // 流式请求同样记录提供商返回的用量
+        assert!(!row.get::<bool, _>("tokens_estimated"));
+        let prompt: i64 = row.get("prompt_tokens");
+        let completion: i64 = row.get("completion_tokens");
+        let cost: f64 = row.get("estimated_cost");
+        let expected = (prompt as f64 * 2.0 + completion as f64 * 10.0) / 1_000_000.0;
+        assert!((cost - expected).abs() < 1e-12);
+    }
+
+    let report = AIUsageService::new(pool_arc, logger)
+        .get_usage_report(Some("monthly"), None, None)
+        .await
+        .unwrap();
+    assert_eq!(report.total.calls, 2);
+    assert_eq!(report.by_period.len(), 1);
+    assert_eq!(report.by_feature.len(), 2);
+    assert_eq!(report.by_provider.len(), 1);
+    assert_eq!(
+        report.total.total_tokens,
+        report.total.prompt_tokens + report.total.completion_tokens
+    );
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_usage_report_validation() {
+    let pool = setup_test_db().await;
+    let logger = test_logger();
+    let service = AIUsageService::new(Arc::new(pool.clone()), logger.clone());
+
+    // 无调用记录时返回空报表
+    let report = service
+        .get_usage_report(None, Some("2024-01-01"), Some("2024-01-31"))
+        .await
+        .unwrap();
+    assert_eq!(report.granularity, "daily");
+    assert_eq!(report.total.calls, 0);
+    assert!(report.by_period.is_empty());
+
+    assert!(service.get_usage_report(Some("weekly"), None, None).await.is_err());
+    assert!(service
+        .get_usage_report(None, Some("2024-02-01"), Some("2024-01-01"))
+        .await
+        .is_err());
+
+    let model_service = AIModelService::new(Arc::new(pool.clone()), logger);
+    assert!(model_service.update_model_pricing(1, Some(-1.0), None).await.is_err());
+    assert!(model_service.update_model_pricing(9999, Some(1.0), None).await.is_err());
+
+    teardown_test_db(&pool).await;
+}
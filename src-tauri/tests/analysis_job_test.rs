@@ -0,0 +1,72 @@
+// 可恢复批量分析任务的持久化测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::ai_service::PhonicsWord;
+use redlark_app_lib::repositories::analysis_job_repository::AnalysisJobRepository;
+use std::sync::Arc;
+
+fn phonics_word(word: &str) -> PhonicsWord {
+    PhonicsWord {
+        word: word.to_string(),
+        frequency: 1,
+        chinese_translation: "测试".to_string(),
+        pos_abbreviation: "n.".to_string(),
+        pos_english: "noun".to_string(),
+        pos_chinese: "名词".to_string(),
+        ipa: format!("/{}/", word),
+        syllables: word.to_string(),
+        phonics_rule: String::new(),
+        analysis_explanation: String::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_job_keeps_results_for_resume() {
+    let pool = setup_test_db().await;
+    let logger = test_logger();
+    let repository = AnalysisJobRepository::new(Arc::new(pool.clone()), logger);
+
+    let job_id = repository
+        .create_job(Some("The cat sat in the garden."), "focus", true, None, "v1")
+        .await
+        .unwrap();
+    let words: Vec<String> = ["cat", "sat", "garden"].iter().map(|w| w.to_string()).collect();
+    repository.save_words(job_id, &words).await.unwrap();
+
+    // 第一批完成，第二批失败后应用关闭
+    repository
+        .save_results(job_id, &[("cat".to_string(), phonics_word("cat"))])
+        .await
+        .unwrap();
+    repository
+        .mark_words_failed(job_id, &[("sat".to_string(), "timeout".to_string())])
+        .await
+        .unwrap();
+
+    let job = repository.find_by_id(job_id).await.unwrap().unwrap();
+    assert_eq!(job.status, "analyzing");
+    assert_eq!(job.total_words, 3);
+    assert_eq!(job.completed_words, 1);
+    assert_eq!(job.failed_words, 1);
+    assert_eq!(job.prompt_version.as_deref(), Some("v1"));
+    // 恢复时沿用创建任务时的提取方式
+    assert!(job.local_extraction);
+
+    // 恢复时只需分析未完成的单词
+    let job_words = repository.find_words(job_id).await.unwrap();
+    let pending: Vec<&str> = job_words
+        .iter()
+        .filter(|w| w.status != "completed")
+        .map(|w| w.word.as_str())
+        .collect();
+    assert_eq!(pending, vec!["sat", "garden"]);
+    assert_eq!(job_words[0].result.as_ref().unwrap().ipa, "/cat/");
+
+    repository.update_status(job_id, "partial", None).await.unwrap();
+    let jobs = repository.find_all(10).await.unwrap();
+    assert_eq!(jobs[0].id, job_id);
+    assert_eq!(jobs[0].status, "partial");
+
+    teardown_test_db(&pool).await;
+}
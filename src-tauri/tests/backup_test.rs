@@ -0,0 +1,288 @@
+// 数据库备份与恢复测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::services::backup::{BackupPaths, BackupService};
+use redlark_app_lib::types::backup::{BackupKind, UpdateBackupSettingsRequest};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 每个测试使用独立的数据目录和缓存目录，缓存目录中放一个发音文件
+fn test_paths(name: &str) -> BackupPaths {
+    let root = std::env::temp_dir()
+        .join("redlark-backup-test")
+        .join(format!("{}-{}", name, uuid::Uuid::new_v4()));
+    let paths = BackupPaths::new(&root.join("data"), &root.join("cache"));
+    std::fs::create_dir_all(&paths.tts_cache_dir).unwrap();
+    std::fs::write(paths.tts_cache_dir.join("applehash.mp3"), b"ID3 test audio").unwrap();
+    paths
+}
+
+/// 与应用一样使用 WAL 模式的文件数据库（内存数据库不支持 `VACUUM INTO`）
+async fn setup_file_db(paths: &BackupPaths) -> SqlitePool {
+    std::fs::create_dir_all(paths.database.parent().unwrap()).unwrap();
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(
+            SqliteConnectOptions::new()
+                .filename(&paths.database)
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal),
+        )
+        .await
+        .unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
+
+async fn insert_cached_audio(pool: &sqlx::SqlitePool, paths: &BackupPaths) {
+    sqlx::query(
+        "INSERT INTO tts_cache (text_hash, original_text, voice_id, model_id, file_path, file_size)
+         VALUES ('applehash', 'apple', 'voice', 'model', ?, 14)",
+    )
+    .bind(
+        paths
+            .tts_cache_dir
+            .join("applehash.mp3")
+            .to_string_lossy()
+            .to_string(),
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_create_list_and_prune_backups() {
+    let paths = test_paths("create");
+    let pool = setup_file_db(&paths).await;
+    insert_cached_audio(&pool, &paths).await;
+    let service = BackupService::new(Arc::new(pool.clone()), test_logger(), paths.clone());
+
+    let manual = service.create_backup(BackupKind::Manual).await.unwrap();
+    assert_eq!(manual.tts_file_count, 1);
+    assert!(manual.migration_version >= 41);
+    assert!(manual.file_name.ends_with("-manual.zip"));
+
+    let mut archive =
+        zip::ZipArchive::new(std::fs::File::open(&manual.file_path).unwrap()).unwrap();
+    let mut audio = Vec::new();
+    archive
+        .by_name("tts/applehash.mp3")
+        .unwrap()
+        .read_to_end(&mut audio)
+        .unwrap();
+    assert_eq!(audio, b"ID3 test audio");
+    assert!(archive.by_name("vocabulary.db").unwrap().size() > 0);
+
+    for _ in 0..3 {
+        service.create_backup(BackupKind::Scheduled).await.unwrap();
+    }
+    let backups = service.list_backups().unwrap();
+    assert_eq!(backups.len(), 4);
+    assert_eq!(backups[0].kind, BackupKind::Scheduled);
+
+    // 只清理自动备份，手动备份保留
+    assert_eq!(service.prune(1).unwrap(), 2);
+    let kinds: Vec<BackupKind> = service
+        .list_backups()
+        .unwrap()
+        .into_iter()
+        .map(|b| b.kind)
+        .collect();
+    assert_eq!(kinds, vec![BackupKind::Scheduled, BackupKind::Manual]);
+
+    let settings = service.get_settings().await.unwrap();
+    assert!(settings.last_backup_at.is_some());
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_scheduled_backup_settings() {
+    let paths = test_paths("schedule");
+    let pool = setup_file_db(&paths).await;
+    let service = BackupService::new(Arc::new(pool.clone()), test_logger(), paths);
+
+    let settings = service.get_settings().await.unwrap();
+    assert!(settings.enabled);
+    assert_eq!(settings.interval_hours, 24);
+    assert_eq!(settings.retention_count, 7);
+    assert!(settings.last_backup_at.is_none());
+
+    assert!(service
+        .update_settings(&UpdateBackupSettingsRequest {
+            retention_count: Some(0),
+            ..Default::default()
+        })
+        .await
+        .is_err());
+
+    // 从未备份过时立即备份，之后在间隔内不再重复
+    assert!(service.run_scheduled_if_due().await.unwrap().is_some());
+    assert!(service.run_scheduled_if_due().await.unwrap().is_none());
+
+    let settings = service
+        .update_settings(&UpdateBackupSettingsRequest {
+            enabled: Some(false),
+            retention_count: Some(3),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(!settings.enabled);
+    assert_eq!(settings.interval_hours, 24);
+    assert_eq!(settings.retention_count, 3);
+
+    sqlx::query("UPDATE backup_settings SET last_backup_at = NULL")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(service.run_scheduled_if_due().await.unwrap().is_none());
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_restore_backup() {
+    let paths = test_paths("restore");
+    let pool = setup_file_db(&paths).await;
+    insert_cached_audio(&pool, &paths).await;
+    sqlx::query("INSERT INTO words (word, meaning, word_book_id) VALUES ('lemon', '柠檬', 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let service = BackupService::new(Arc::new(pool.clone()), test_logger(), paths.clone());
+    let backup = service.create_backup(BackupKind::Manual).await.unwrap();
+
+    // 恢复到新的数据目录和缓存目录
+    let restored_paths = test_paths("restore-target");
+    std::fs::write(restored_paths.tts_cache_dir.join("stale.mp3"), b"stale").unwrap();
+    std::fs::create_dir_all(restored_paths.database.parent().unwrap()).unwrap();
+    std::fs::write(&restored_paths.database, b"old database").unwrap();
+    let restore_service = BackupService::new(
+        Arc::new(pool.clone()),
+        test_logger(),
+        restored_paths.clone(),
+    );
+
+    let staged = restore_service
+        .stage_restore(&PathBuf::from(&backup.file_path))
+        .await
+        .unwrap();
+    assert_eq!(staged.manifest.migration_version, backup.migration_version);
+    restore_service.apply_restore(staged).unwrap();
+
+    let mut files: Vec<String> = std::fs::read_dir(&restored_paths.tts_cache_dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    assert_eq!(files, vec!["applehash.mp3".to_string()]);
+
+    let mut conn = SqliteConnection::connect_with(
+        &SqliteConnectOptions::new().filename(&restored_paths.database),
+    )
+    .await
+    .unwrap();
+    let lemon: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM words WHERE word = 'lemon'")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(lemon, 1);
+    let audio_path: String = sqlx::query_scalar("SELECT file_path FROM tts_cache")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(
+        PathBuf::from(audio_path),
+        restored_paths.tts_cache_dir.join("applehash.mp3")
+    );
+    conn.close().await.unwrap();
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_failed_restore_puts_original_files_back() {
+    let paths = test_paths("rollback");
+    let pool = setup_file_db(&paths).await;
+    let service = BackupService::new(Arc::new(pool.clone()), test_logger(), paths.clone());
+    let backup = service.create_backup(BackupKind::Manual).await.unwrap();
+
+    // 缓存目录中与备份发音文件同名的非空目录使移入失败
+    let restored_paths = test_paths("rollback-target");
+    std::fs::remove_file(restored_paths.tts_cache_dir.join("applehash.mp3")).unwrap();
+    std::fs::create_dir_all(restored_paths.tts_cache_dir.join("applehash.mp3")).unwrap();
+    std::fs::write(
+        restored_paths.tts_cache_dir.join("applehash.mp3").join("keep"),
+        b"keep",
+    )
+    .unwrap();
+    std::fs::write(restored_paths.tts_cache_dir.join("stale.mp3"), b"stale").unwrap();
+    std::fs::create_dir_all(restored_paths.database.parent().unwrap()).unwrap();
+    std::fs::write(&restored_paths.database, b"old database").unwrap();
+    let mut wal = restored_paths.database.clone().into_os_string();
+    wal.push("-wal");
+    std::fs::write(&wal, b"old wal").unwrap();
+    let restore_service = BackupService::new(
+        Arc::new(pool.clone()),
+        test_logger(),
+        restored_paths.clone(),
+    );
+
+    let staged = restore_service
+        .stage_restore(&PathBuf::from(&backup.file_path))
+        .await
+        .unwrap();
+    assert!(restore_service.apply_restore(staged).is_err());
+
+    assert_eq!(std::fs::read(&restored_paths.database).unwrap(), b"old database");
+    assert_eq!(std::fs::read(&wal).unwrap(), b"old wal");
+    assert_eq!(
+        std::fs::read(restored_paths.tts_cache_dir.join("stale.mp3")).unwrap(),
+        b"stale"
+    );
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_restore_rejects_newer_migration() {
+    let paths = test_paths("newer");
+    let pool = setup_file_db(&paths).await;
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+         VALUES (9999, 'from the future', 1, x'00', 0)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let service = BackupService::new(Arc::new(pool.clone()), test_logger(), paths.clone());
+    let backup = service.create_backup(BackupKind::Manual).await.unwrap();
+    assert_eq!(backup.migration_version, 9999);
+
+    let error = service
+        .stage_restore(&PathBuf::from(&backup.file_path))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("9999"));
+
+    // 校验失败后不留下临时目录
+    let leftovers = std::fs::read_dir(&paths.backup_dir)
+        .unwrap()
+        .filter(|e| {
+            e.as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with(".restore-")
+        })
+        .count();
+    assert_eq!(leftovers, 0);
+
+    teardown_test_db(&pool).await;
+}
@@ -1,10 +1,24 @@
 // 测试工具模块
+#![allow(dead_code)]
+
+use chrono::{Duration, Local};
+use redlark_app_lib::logger::Logger;
+use redlark_app_lib::services::study_plan::StudyPlanService;
+use serde_json::{json, Value};
+use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::{Row, SqlitePool};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 
 /// 创建内存测试数据库
+///
+/// 内存数据库按连接隔离，限制为单连接保证迁移和查询使用同一数据库
 pub async fn setup_test_db() -> SqlitePool {
-    let pool = SqlitePool::connect(":memory:")
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(":memory:")
         .await
         .expect("Failed to create test database");
 
@@ -84,6 +98,452 @@ pub async fn insert_test_word_book(pool: &SqlitePool) -> i64 {
     row.get("id")
 }
 
+/// 插入测试数据 - 指向 Mock LLM 服务的 AI 提供商和默认模型
+///
+/// 返回 (provider_id, model_id)，model_id 为 ai_models 表主键
+pub async fn insert_mock_llm_provider(pool: &SqlitePool, server: &MockLlmServer) -> (i64, i64) {
+    let provider_id = sqlx::query(
+        r#"
+        INSERT INTO ai_providers (name, display_name, base_url, api_key, provider_type, description, is_active)
+        VALUES ('mock_llm', 'Mock LLM', ?, 'mock_key', 'openai', 'In-process mock chat-completions server', 1)
+        "#,
+    )
+    .bind(server.base_url())
+    .execute(pool)
+    .await
+    .expect("Failed to insert mock AI provider")
+    .last_insert_rowid();
+
+    // 替换默认模型，保证按默认模型查询时命中 Mock
+    sqlx::query("UPDATE ai_models SET is_default = 0")
+        .execute(pool)
+        .await
+        .expect("Failed to reset default AI model");
+
+    let model_id = sqlx::query(
+        r#"
+        INSERT INTO ai_models (provider_id, name, display_name, model_id, description, max_tokens, temperature, is_active, is_default)
+        VALUES (?, 'mock_model', 'Mock Model', 'mock-model', 'Mock model for offline testing', 4000, 0.1, 1, 1)
+        "#,
+    )
+    .bind(provider_id)
+    .execute(pool)
+    .await
+    .expect("Failed to insert mock AI model")
+    .last_insert_rowid();
+
+    (provider_id, model_id)
+}
+
+// ==================== Mock LLM 服务 ====================
+
+/// 提示词类型（按提示词模板标题识别）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PromptKind {
+    WordExtraction,
+    BatchPhonics,
+    Phonics,
+    StudyPlan,
+    Chat,
+}
+
+impl PromptKind {
+    /// 根据系统提示词识别类型
+    pub fn classify(prompt: &str) -> Self {
+        let title = prompt.trim_start().lines().next().unwrap_or_default();
+        if title.starts_with("# 单词提取专家") {
+            PromptKind::WordExtraction
+        } else if title.starts_with("# 英语自然拼读批量分析专家") {
+            PromptKind::BatchPhonics
+        } else if title.starts_with("# 英语自然拼读分析专家") {
+            PromptKind::Phonics
+        } else if title.starts_with("# 英语学习计划制定专家") {
+            PromptKind::StudyPlan
+        } else {
+            PromptKind::Chat
+        }
+    }
+
+    /// 默认响应（结构化输出请求按 Schema 返回驼峰字段名的学习计划）
+    fn default_fixture(&self, structured: bool) -> &'static str {
+        match self {
+            PromptKind::WordExtraction => include_str!("fixtures/mock_llm/word_extraction.csv"),
+            PromptKind::BatchPhonics => include_str!("fixtures/mock_llm/batch_phonics.csv"),
+            PromptKind::Phonics => include_str!("fixtures/mock_llm/phonics.json"),
+            PromptKind::StudyPlan if structured => {
+                include_str!("fixtures/mock_llm/study_plan_structured.json")
+            }
+            PromptKind::StudyPlan => include_str!("fixtures/mock_llm/study_plan.json"),
+            PromptKind::Chat => include_str!("fixtures/mock_llm/chat.txt"),
+        }
+    }
+}
+
+/// 进程内 Mock LLM 服务
+///
+/// 监听本地随机端口，实现 OpenAI chat-completions 协议（含 SSE 流式响应和强制工具调用），
+/// 按提示词类型返回 `tests/fixtures/mock_llm` 下的固定响应
+pub struct MockLlmServer {
+    base_url: String,
+    fixtures: Arc<Mutex<HashMap<PromptKind, String>>>,
+    requests: Arc<Mutex<Vec<(PromptKind, bool)>>>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl MockLlmServer {
+    /// 启动服务
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind mock LLM server");
+        let address = listener
+            .local_addr()
+            .expect("Failed to get mock LLM address");
+
+        let fixtures = Arc::new(Mutex::new(HashMap::new()));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let handle = {
+            let fixtures = Arc::clone(&fixtures);
+            let requests = Arc::clone(&requests);
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let fixtures = Arc::clone(&fixtures);
+                    let requests = Arc::clone(&requests);
+                    tokio::spawn(async move {
+                        let _ = handle_connection(stream, fixtures, requests).await;
+                    });
+                }
+            })
+        };
+
+        Self {
+            base_url: format!("http://{}/v1", address),
+            fixtures,
+            requests,
+            handle,
+        }
+    }
+
+    /// OpenAI 兼容的 API 地址
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 覆盖某类提示词的响应
+    pub fn set_fixture(&self, kind: PromptKind, content: impl Into<String>) {
+        self.fixtures.lock().unwrap().insert(kind, content.into());
+    }
+
+    /// 某类提示词收到的请求数
+    pub fn request_count(&self, kind: PromptKind) -> usize {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(k, _)| *k == kind)
+            .count()
+    }
+
+    /// 某类提示词收到的结构化输出（带 tools）请求数
+    pub fn structured_request_count(&self, kind: PromptKind) -> usize {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(k, structured)| *k == kind && *structured)
+            .count()
+    }
+}
+
+impl Drop for MockLlmServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// 处理单个 HTTP 连接（每个连接一个请求，响应后关闭）
+async fn handle_connection(
+    mut stream: TcpStream,
+    fixtures: Arc<Mutex<HashMap<PromptKind, String>>>,
+    requests: Arc<Mutex<Vec<(PromptKind, bool)>>>,
+) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+
+    // 读取请求头
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&buffer[..header_end]).to_lowercase();
+    let content_length = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    // 读取请求体
+    while buffer.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+
+    let body: Value = serde_json::from_slice(&buffer[header_end..]).unwrap_or(Value::Null);
+    let prompt = body["messages"][0]["content"].as_str().unwrap_or_default();
+    let kind = PromptKind::classify(prompt);
+    // 强制调用的工具名，存在时通过工具调用参数返回内容
+    let tool_name = body["tools"][0]["function"]["name"]
+        .as_str()
+        .map(str::to_string);
+    requests.lock().unwrap().push((kind, tool_name.is_some()));
+
+    let fixture = fixtures
+        .lock()
+        .unwrap()
+        .get(&kind)
+        .cloned()
+        .unwrap_or_else(|| kind.default_fixture(tool_name.is_some()).to_string());
+    let content = match kind {
+        PromptKind::BatchPhonics => filter_batch_rows(&fixture, prompt),
+        _ => fixture,
+    };
+
+    let model = body["model"].as_str().unwrap_or("mock-model");
+    let response = if body["stream"].as_bool().unwrap_or(false) {
+        let mut events = String::new();
+        let chars: Vec<char> = content.chars().collect();
+        for piece in chars.chunks(32) {
+            let piece: String = piece.iter().collect();
+            let delta = match &tool_name {
+                Some(name) => json!({
+                    "tool_calls": [{
+                        "index": 0,
+                        "id": "call-mock",
+                        "type": "function",
+                        "function": { "name": name, "arguments": piece }
+                    }]
+                }),
+                None => json!({ "content": piece }),
+            };
+            let chunk = json!({
+                "id": "chatcmpl-mock",
+                "object": "chat.completion.chunk",
+                "created": 0,
+                "model": model,
+                "choices": [{
+                    "index": 0,
+                    "delta": delta,
+                    "finish_reason": null
+                }]
+            });
+            events.push_str(&format!("data: {}\n\n", chunk));
+        }
+        // 请求 include_usage 时最后一个分块返回用量
+        if body["stream_options"]["include_usage"].as_bool().unwrap_or(false) {
+            let chunk = json!({
+                "id": "chatcmpl-mock",
+                "object": "chat.completion.chunk",
+                "created": 0,
+                "model": model,
+                "choices": [],
+                "usage": {
+                    "prompt_tokens": prompt.chars().count() / 4,
+                    "completion_tokens": content.chars().count() / 4,
+                    "total_tokens": (prompt.chars().count() + content.chars().count()) / 4
+                }
+            });
+            events.push_str(&format!("data: {}\n\n", chunk));
+        }
+        events.push_str("data: [DONE]\n\n");
+        http_response("text/event-stream", &events)
+    } else {
+        let message = match &tool_name {
+            Some(name) => json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call-mock",
+                    "type": "function",
+                    "function": { "name": name, "arguments": content }
+                }]
+            }),
+            None => json!({ "role": "assistant", "content": content }),
+        };
+        let completion = json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion",
+            "created": 0,
+            "model": model,
+            "choices": [{
+                "index": 0,
+                "message": message,
+                "finish_reason": if tool_name.is_some() { "tool_calls" } else { "stop" }
+            }],
+            "usage": {
+                "prompt_tokens": prompt.chars().count() / 4,
+                "completion_tokens": content.chars().count() / 4,
+                "total_tokens": (prompt.chars().count() + content.chars().count()) / 4
+            }
+        });
+        http_response("application/json", &completion.to_string())
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// 构建 HTTP 响应
+fn http_response(content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// 批量分析只返回本批次请求的单词（单词列表位于提示词最后一行）
+fn filter_batch_rows(fixture: &str, prompt: &str) -> String {
+    let requested: Vec<String> = prompt
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .split(',')
+        .map(|w| w.trim().to_lowercase())
+        .collect();
+
+    let mut lines = fixture.lines();
+    let mut output = vec![lines.next().unwrap_or_default().to_string()];
+    output.extend(
+        lines
+            .filter(|line| {
+                let word = line.split(',').next().unwrap_or_default().trim_matches('"');
+                requested.contains(&word.to_lowercase())
+            })
+            .map(|line| line.to_string()),
+    );
+    output.join("\n")
+}
+
+/// 测试日志（写入临时目录）
+pub fn test_logger() -> Arc<Logger> {
+    Arc::new(Logger::new(&std::env::temp_dir().join("redlark-test")).unwrap())
+}
+
+/// 创建学习计划服务
+pub fn plan_service(pool: &SqlitePool) -> StudyPlanService {
+    StudyPlanService::new(Arc::new(pool.clone()), test_logger())
+}
+
+/// 相对今天的日期（YYYY-MM-DD）
+pub fn day(offset: i64) -> String {
+    (Local::now().date_naive() + Duration::days(offset))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// 一天的日程：(相对今天的天数, 状态, [(单词, 是否复习)])
+pub type ScheduleSpec<'a> = (i64, &'a str, &'a [(&'a str, bool)]);
+
+/// 创建进行中的学习计划（1周计划，轻松强度，复习 3 次）
+pub async fn insert_plan(pool: &SqlitePool, schedules: &[ScheduleSpec<'_>]) -> i64 {
+    let plan_id = sqlx::query(
+        "INSERT INTO study_plans
+         (name, status, unified_status, intensity_level, study_period_days, review_frequency, start_date, end_date)
+         VALUES ('测试学习计划', 'normal', 'Active', 'easy', 7, 3, ?, ?)",
+    )
+    .bind(day(schedules[0].0))
+    .bind(day(schedules[schedules.len() - 1].0))
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    for (i, (offset, status, words)) in schedules.iter().enumerate() {
+        let new_words = words.iter().filter(|(_, is_review)| !is_review).count() as i64;
+        let completed = if *status == "completed" {
+            words.len() as i64
+        } else {
+            0
+        };
+        let schedule_id = sqlx::query(
+            "INSERT INTO study_plan_schedules
+             (plan_id, day_number, schedule_date, new_words_count, review_words_count,
+              total_words_count, completed_words_count, status)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(plan_id)
+        .bind(i as i64 + 1)
+        .bind(day(*offset))
+        .bind(new_words)
+        .bind(words.len() as i64 - new_words)
+        .bind(words.len() as i64)
+        .bind(completed)
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+        for (word, is_review) in words.iter() {
+            sqlx::query(
+                "INSERT INTO study_plan_schedule_words (schedule_id, word_id, wordbook_id, is_review, review_count)
+                 SELECT ?, id, word_book_id, ?, ? FROM words WHERE word = ?",
+            )
+            .bind(schedule_id)
+            .bind(is_review)
+            .bind(if *is_review { Some(1) } else { None })
+            .bind(word)
+            .execute(pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT OR IGNORE INTO study_plan_words (plan_id, word_id)
+                 SELECT ?, id FROM words WHERE word = ?",
+            )
+            .bind(plan_id)
+            .bind(word)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+    plan_id
+}
+
+/// 每天的安排：(日期, 单词, 是否复习)，按日期、新词在前、单词排序
+pub async fn placements(pool: &SqlitePool, plan_id: i64) -> Vec<(String, String, bool)> {
+    sqlx::query_as(
+        "SELECT s.schedule_date, w.word, sw.is_review
+         FROM study_plan_schedule_words sw
+         JOIN study_plan_schedules s ON s.id = sw.schedule_id
+         JOIN words w ON w.id = sw.word_id
+         WHERE s.plan_id = ?
+         ORDER BY s.schedule_date, sw.is_review, w.word",
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+/// 期望的单词安排，与 `placements` 的格式一致
+pub fn placed(date: i64, word: &str, is_review: bool) -> (String, String, bool) {
+    (day(date), word.to_string(), is_review)
+}
+
 /// 清理所有测试数据
 pub async fn cleanup_test_data(pool: &SqlitePool) {
     let tables = vec![
@@ -138,6 +598,36 @@ mod tests {
         teardown_test_db(&pool).await;
     }
 
+    #[tokio::test]
+    async fn test_insert_mock_llm_provider() {
+        let pool = setup_test_db().await;
+        let server = MockLlmServer::start().await;
+        let (provider_id, model_id) = insert_mock_llm_provider(&pool, &server).await;
+
+        let row = sqlx::query(
+            "SELECT p.base_url, m.is_default FROM ai_models m JOIN ai_providers p ON m.provider_id = p.id WHERE m.id = ?",
+        )
+        .bind(model_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        assert!(provider_id > 0);
+        assert_eq!(row.get::<String, _>("base_url"), server.base_url());
+        assert!(row.get::<bool, _>("is_default"));
+
+        teardown_test_db(&pool).await;
+    }
+
+    #[test]
+    fn test_filter_batch_rows() {
+        let fixture = PromptKind::BatchPhonics.default_fixture(false);
+        let filtered = filter_batch_rows(fixture, "分析以下单词：\n\ncat, garden\n");
+
+        assert_eq!(filtered.lines().count(), 3);
+        assert!(!filtered.contains("jumping"));
+    }
+
     #[tokio::test]
     async fn test_insert_test_word_book() {
         let pool = setup_test_db().await;
@@ -97,6 +97,8 @@ async fn test_ai_service_model_usage() {
         description: "Moonshot AI Kimi K2 free model".to_string(),
         max_tokens: Some(4000),
         temperature: Some(0.3),
+        input_price_per_million: None,
+        output_price_per_million: None,
         is_default: false,
         provider: redlark_app::types::AIProvider {
             id: 3,
@@ -104,6 +106,7 @@ async fn test_ai_service_model_usage() {
             display_name: "Moonshot AI".to_string(),
             base_url: "https://api.moonshot.cn/v1".to_string(),
             api_key: "test-key".to_string(),
+            provider_type: "openai".to_string(),
             description: "Moonshot AI provider".to_string(),
             is_active: true,
             created_at: "2024-01-01T00:00:00Z".to_string(),
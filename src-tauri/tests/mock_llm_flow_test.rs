@@ -0,0 +1,311 @@
+// 基于 Mock LLM 服务的端到端测试（无需真实 API Key）
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::ai_service::{AIProvider, AIService, PhonicsWord, STREAM_CANCELLED_MESSAGE};
+use redlark_app_lib::llm_provider::{LlmProviderType, MockLlmProvider};
+use redlark_app_lib::logger::Logger;
+use redlark_app_lib::repositories::ai_model_repository::AIModelRepository;
+use redlark_app_lib::repositories::word_repository::WordRepository;
+use redlark_app_lib::services::study_plan::StudyPlanService;
+use redlark_app_lib::services::wordbook::WordBookService;
+use redlark_app_lib::types::{
+    AIModelConfig, AnalyzedWord, CreateStudyPlanWithScheduleRequest,
+    CreateWordBookFromAnalysisRequest, StudyPlanAIParams, StudyWordInfo,
+};
+use sqlx::{Row, SqlitePool};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 准备数据库、Mock 服务和模型配置
+async fn setup() -> (SqlitePool, MockLlmServer, AIModelConfig, Arc<Logger>) {
+    let pool = setup_test_db().await;
+    let server = MockLlmServer::start().await;
+    let (_, model_id) = insert_mock_llm_provider(&pool, &server).await;
+    let logger = test_logger();
+
+    let model_config = AIModelRepository::new(Arc::new(pool.clone()), Arc::clone(&logger))
+        .find_model_config_by_id(model_id)
+        .await
+        .unwrap()
+        .expect("Mock model should be registered");
+
+    (pool, server, model_config, logger)
+}
+
+fn to_analyzed_word(word: PhonicsWord) -> AnalyzedWord {
+    AnalyzedWord {
+        word: word.word,
+        meaning: word.chinese_translation,
+        part_of_speech: Some(word.pos_abbreviation.clone()),
+        example_sentence: None,
+        ipa: Some(word.ipa),
+        syllables: Some(word.syllables),
+        pos_abbreviation: Some(word.pos_abbreviation),
+        pos_english: Some(word.pos_english),
+        pos_chinese: Some(word.pos_chinese),
+        phonics_rule: Some(word.phonics_rule),
+        analysis_explanation: Some(word.analysis_explanation),
+        word_frequency: Some(word.frequency),
+    }
+}
+
+#[tokio::test]
+async fn test_extraction_analysis_wordbook_flow() {
+    let (pool, server, model_config, logger) = setup().await;
+    let ai_service = AIService::from_model_config(&model_config).unwrap();
+
+    // 1. 提取单词
+    let extraction = ai_service
+        .extract_words("The cat is jumping in the garden.", "focus", &logger)
+        .await
+        .unwrap();
+    assert_eq!(extraction.unique_count, 3);
+
+    // 2. 分两批分析
+    let words: Vec<String> = extraction.words.into_iter().map(|w| w.word).collect();
+    let mut analyzed = Vec::new();
+    for (batch_index, batch) in words.chunks(2).enumerate() {
+        let result = ai_service
+            .analyze_words_batch(batch.to_vec(), batch_index, 2, &logger)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), batch.len());
+        analyzed.extend(result);
+    }
+
+    // 3. 创建单词本
+    let save_result = WordBookService::new(Arc::new(pool.clone()), Arc::clone(&logger))
+        .create_word_book_from_analysis(CreateWordBookFromAnalysisRequest {
+            title: "Mock Book".to_string(),
+            description: "Created from mock analysis".to_string(),
+            icon: None,
+            icon_color: None,
+            words: analyzed.into_iter().map(to_analyzed_word).collect(),
+            status: None,
+            book_id: None,
+            theme_tag_ids: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(save_result.added_count, 3);
+
+    let ipa: String = sqlx::query("SELECT ipa FROM words WHERE word_book_id = ? AND word = 'cat'")
+        .bind(save_result.book_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("ipa");
+    assert_eq!(ipa, "/kæt/");
+
+    assert_eq!(server.request_count(PromptKind::WordExtraction), 1);
+    assert_eq!(server.request_count(PromptKind::BatchPhonics), 2);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_study_plan_generation_flow() {
+    let (pool, server, model_config, logger) = setup().await;
+    let ai_service = AIService::from_model_config(&model_config).unwrap();
+
+    // 使用迁移预置的单词本 1
+    let word_list: Vec<StudyWordInfo> = WordRepository::new(Arc::new(pool.clone()), Arc::clone(&logger))
+        .find_words_by_wordbook_ids(&[1])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(word_id, word, wordbook_id)| StudyWordInfo {
+            word,
+            word_id: word_id.to_string(),
+            wordbook_id: wordbook_id.to_string(),
+        })
+        .collect();
+
+    let params = StudyPlanAIParams {
+        intensity_level: "easy".to_string(),
+        total_words: word_list.len() as i32,
+        period_days: 3,
+        review_frequency: 3,
+        start_date: "2025-01-01".to_string(),
+        word_list,
+    };
+
+    // 流式生成，每天的计划在完整结果之前到达
+    let streamed_days = Mutex::new(Vec::new());
+    let plan = ai_service
+        .generate_study_plan_schedule_streaming(params, &model_config, &logger, &|index, day| {
+            streamed_days.lock().unwrap().push((index, day.date.clone()));
+        })
+        .await
+        .unwrap();
+    assert_eq!(plan.daily_plans.len(), 3);
+    let streamed_days = streamed_days.into_inner().unwrap();
+    assert_eq!(
+        streamed_days,
+        plan.daily_plans
+            .iter()
+            .enumerate()
+            .map(|(index, day)| (index, day.date.clone()))
+            .collect::<Vec<_>>()
+    );
+
+    // 不允许自动修复，Mock 规划必须通过校验
+    let plan_id = StudyPlanService::new(Arc::new(pool.clone()), Arc::clone(&logger))
+        .create_study_plan_with_schedule(CreateStudyPlanWithScheduleRequest {
+            name: "Mock Plan".to_string(),
+            description: "Generated by mock LLM".to_string(),
+            intensity_level: "easy".to_string(),
+            study_period_days: 3,
+            review_frequency: 3,
+            start_date: "2025-01-01".to_string(),
+            end_date: "2025-01-03".to_string(),
+            ai_plan_data: serde_json::to_string(&plan).unwrap(),
+            wordbook_ids: vec![1],
+            status: Some("draft".to_string()),
+            auto_repair: Some(false),
+        })
+        .await
+        .unwrap();
+
+    let schedules: i64 = sqlx::query("SELECT COUNT(*) as count FROM study_plan_schedules WHERE plan_id = ?")
+        .bind(plan_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(schedules, 3);
+    assert_eq!(server.request_count(PromptKind::StudyPlan), 1);
+    assert_eq!(server.structured_request_count(PromptKind::StudyPlan), 1);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_chat_completion_and_phonics_analysis() {
+    let (pool, server, model_config, logger) = setup().await;
+    let ai_service = AIService::from_model_config(&model_config).unwrap();
+
+    let reply = ai_service
+        .chat_completion("Hello", None, None, &logger)
+        .await
+        .unwrap();
+    assert!(reply.contains("mock model"));
+
+    let phonics = ai_service
+        .analyze_phonics("The cat.", None, None, None, "focus", &logger)
+        .await
+        .unwrap();
+    assert_eq!(phonics.words[0].word, "cat");
+
+    assert_eq!(server.request_count(PromptKind::Chat), 1);
+    assert_eq!(server.request_count(PromptKind::Phonics), 1);
+    // 自然拼读分析使用结构化输出，普通对话不使用
+    assert_eq!(server.structured_request_count(PromptKind::Phonics), 1);
+    assert_eq!(server.structured_request_count(PromptKind::Chat), 0);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_structured_output_falls_back_to_heuristic_parsing() {
+    let (pool, server, model_config, logger) = setup().await;
+
+    // 工具参数不符合 Schema（下划线字段名）时退回启发式解析
+    server.set_fixture(
+        PromptKind::StudyPlan,
+        include_str!("fixtures/mock_llm/study_plan.json"),
+    );
+    let params = StudyPlanAIParams {
+        intensity_level: "easy".to_string(),
+        total_words: 3,
+        period_days: 3,
+        review_frequency: 3,
+        start_date: "2025-01-01".to_string(),
+        word_list: Vec::new(),
+    };
+    let plan = AIService::from_model_config(&model_config)
+        .unwrap()
+        .generate_study_plan_schedule(params, &model_config, &logger)
+        .await
+        .unwrap();
+    assert_eq!(plan.daily_plans.len(), 3);
+    assert_eq!(plan.plan_metadata.start_date, "2025-01-01");
+    assert_eq!(server.structured_request_count(PromptKind::StudyPlan), 1);
+
+    // 不支持结构化输出的提供商直接使用启发式解析（带说明文字的自由文本）
+    let provider = AIProvider {
+        name: "mock".to_string(),
+        base_url: String::new(),
+        api_key: String::new(),
+        provider_type: LlmProviderType::Mock,
+        default_model: "mock-model".to_string(),
+    };
+    let script = format!(
+        "分析结果如下：\n```json\n{}\n```",
+        include_str!("fixtures/mock_llm/phonics.json")
+    );
+    let phonics = AIService::with_llm_provider(provider, Arc::new(MockLlmProvider::new(vec![script])))
+        .analyze_phonics("The cat.", None, None, None, "focus", &logger)
+        .await
+        .unwrap();
+    assert_eq!(phonics.words[0].word, "cat");
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_streaming_results_and_cancellation() {
+    let (pool, server, model_config, logger) = setup().await;
+    let ai_service = AIService::from_model_config(&model_config).unwrap();
+    let text = "The cat is jumping in the garden.";
+
+    // 提取结果逐个回调
+    let streamed = Mutex::new(Vec::new());
+    let extraction = ai_service
+        .extract_words_streaming(text, "focus", &logger, &|| false, &|word| {
+            streamed.lock().unwrap().push(word.word.clone());
+        })
+        .await
+        .unwrap();
+    let extracted: Vec<String> = extraction.words.into_iter().map(|w| w.word).collect();
+    assert_eq!(streamed.into_inner().unwrap(), extracted);
+
+    // 批量分析结果逐个回调
+    let streamed = Mutex::new(Vec::new());
+    let analyzed = ai_service
+        .analyze_words_batch_streaming(extracted.clone(), 0, 1, &logger, &|| false, &|word| {
+            streamed.lock().unwrap().push(word.word.clone());
+        })
+        .await
+        .unwrap();
+    assert_eq!(analyzed.len(), extracted.len());
+    assert_eq!(
+        streamed.into_inner().unwrap(),
+        analyzed.iter().map(|w| w.word.clone()).collect::<Vec<_>>()
+    );
+
+    // 收到第一个单词后取消，中断流并返回错误而不是空结果
+    let cancelled = AtomicBool::new(false);
+    let streamed = Mutex::new(Vec::new());
+    let error = ai_service
+        .extract_words_streaming(
+            text,
+            "focus",
+            &logger,
+            &|| cancelled.load(Ordering::SeqCst),
+            &|word| {
+                streamed.lock().unwrap().push(word.word.clone());
+                cancelled.store(true, Ordering::SeqCst);
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains(STREAM_CANCELLED_MESSAGE));
+    // 同一段增量中已到达的行仍会回调，之后的内容不再接收
+    assert!(streamed.into_inner().unwrap().len() < extracted.len());
+
+    assert_eq!(server.request_count(PromptKind::WordExtraction), 2);
+
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,340 @@
+// 破坏性操作快照与撤销测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::error::AppError;
+use redlark_app_lib::repositories::operation_snapshot_repository::{
+    SnapshotScope, SNAPSHOT_HISTORY_LIMIT,
+};
+use redlark_app_lib::services::snapshot::SnapshotService;
+use redlark_app_lib::services::study_plan::StudyPlanService;
+use redlark_app_lib::services::wordbook::WordBookService;
+use redlark_app_lib::services::StatisticsService;
+use redlark_app_lib::types::snapshot::SnapshotOperation;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+async fn count(pool: &SqlitePool, sql: &str) -> i64 {
+    sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+}
+
+/// 创建一个进行中的学习计划：单词本 1 的两个单词，其中第一个单词已有练习记录
+async fn insert_plan(pool: &SqlitePool) -> (i64, Vec<i64>) {
+    let word_ids: Vec<i64> =
+        sqlx::query_scalar("SELECT id FROM words WHERE word_book_id = 1 ORDER BY id LIMIT 2")
+            .fetch_all(pool)
+            .await
+            .unwrap();
+    let plan_id = sqlx::query(
+        "INSERT INTO study_plans (name, status, unified_status, start_date, end_date)
+         VALUES ('快照测试计划', 'normal', 'Active', '2026-10-01', '2026-10-10')",
+    )
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    let schedule_id = sqlx::query(
+        "INSERT INTO study_plan_schedules (plan_id, day_number, schedule_date) VALUES (?, 1, '2026-10-01')",
+    )
+    .bind(plan_id)
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let mut plan_word_ids = Vec::new();
+    for word_id in &word_ids {
+        sqlx::query("INSERT INTO study_plan_words (plan_id, word_id) VALUES (?, ?)")
+            .bind(plan_id)
+            .bind(word_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        let plan_word_id = sqlx::query(
+            "INSERT INTO study_plan_schedule_words (schedule_id, word_id, wordbook_id) VALUES (?, ?, 1)",
+        )
+        .bind(schedule_id)
+        .bind(word_id)
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        plan_word_ids.push(plan_word_id);
+    }
+
+    sqlx::query(
+        "INSERT INTO practice_sessions (id, plan_id, schedule_id, schedule_date, start_time)
+         VALUES ('session-1', ?, ?, '2026-10-01', '2026-10-01T08:00:00Z')",
+    )
+    .bind(plan_id)
+    .bind(schedule_id)
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO word_practice_records (session_id, word_id, plan_word_id, step, user_input, is_correct, time_spent)
+         VALUES ('session-1', ?, ?, 1, 'answer', 1, 1200)",
+    )
+    .bind(word_ids[0])
+    .bind(plan_word_ids[0])
+    .execute(pool)
+    .await
+    .unwrap();
+
+    (plan_id, word_ids)
+}
+
+const COUNTS: [&str; 7] = [
+    "SELECT COUNT(*) FROM word_books",
+    "SELECT COUNT(*) FROM words",
+    "SELECT COUNT(*) FROM word_book_theme_tags",
+    "SELECT COUNT(*) FROM study_plans",
+    "SELECT COUNT(*) FROM study_plan_schedule_words",
+    "SELECT COUNT(*) FROM word_practice_records",
+    "SELECT COUNT(*) FROM practice_sessions",
+];
+
+async fn all_counts(pool: &SqlitePool) -> Vec<i64> {
+    let mut counts = Vec::new();
+    for sql in COUNTS {
+        counts.push(count(pool, sql).await);
+    }
+    counts
+}
+
+#[tokio::test]
+async fn test_undo_reset_user_data() {
+    let pool = setup_test_db().await;
+    insert_plan(&pool).await;
+    sqlx::query(
+        "INSERT INTO word_book_theme_tags (word_book_id, theme_tag_id)
+         SELECT 1, id FROM theme_tags WHERE name = '学习'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let before = all_counts(&pool).await;
+    assert!(before.iter().all(|c| *c > 0));
+
+    let logger = test_logger();
+    let result = StatisticsService::new(Arc::new(pool.clone()), logger.clone())
+        .reset_user_data()
+        .await
+        .unwrap();
+    assert!(result.success);
+    // 单词本主题标签不在重置列表中，但会被级联删除
+    assert!(all_counts(&pool).await.iter().all(|c| *c == 0));
+
+    let snapshots = SnapshotService::new(Arc::new(pool.clone()), logger);
+    let history = snapshots.get_snapshots().await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].operation, SnapshotOperation::ResetUserData);
+    assert!(history[0]
+        .tables
+        .contains(&"word_book_theme_tags".to_string()));
+
+    let undo = snapshots.undo_last().await.unwrap();
+    assert_eq!(undo.restored_rows, history[0].row_count);
+    assert!(undo.snapshot.undone_at.is_some());
+    assert_eq!(all_counts(&pool).await, before);
+    let foreign_key_errors = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert!(foreign_key_errors.is_empty());
+
+    // 已撤销的操作不能再次撤销
+    assert!(matches!(
+        snapshots.undo_last().await,
+        Err(AppError::NotFound(_))
+    ));
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_undo_soft_deletes_in_order() {
+    let pool = setup_test_db().await;
+    let (plan_id, _) = insert_plan(&pool).await;
+    let logger = test_logger();
+
+    WordBookService::new(Arc::new(pool.clone()), logger.clone())
+        .delete_word_book(1)
+        .await
+        .unwrap();
+    StudyPlanService::new(Arc::new(pool.clone()), logger.clone())
+        .delete_study_plan(plan_id)
+        .await
+        .unwrap();
+
+    let snapshots = SnapshotService::new(Arc::new(pool.clone()), logger);
+    let undo = snapshots.undo_last().await.unwrap();
+    assert_eq!(undo.snapshot.operation, SnapshotOperation::DeleteStudyPlan);
+    assert_eq!(undo.snapshot.description, "删除学习计划「快照测试计划」");
+
+    let (status, unified_status, deleted_at): (String, String, Option<String>) =
+        sqlx::query_as("SELECT status, unified_status, deleted_at FROM study_plans WHERE id = ?")
+            .bind(plan_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        (status.as_str(), unified_status.as_str()),
+        ("normal", "Active")
+    );
+    assert!(deleted_at.is_none());
+    let history: Vec<(String, String)> = sqlx::query_as(
+        "SELECT from_status, to_status FROM study_plan_status_history WHERE plan_id = ? ORDER BY id",
+    )
+    .bind(plan_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        history,
+        vec![
+            ("Active".to_string(), "Deleted".to_string()),
+            ("Deleted".to_string(), "Active".to_string()),
+        ]
+    );
+    assert_eq!(
+        count(
+            &pool,
+            "SELECT COUNT(*) FROM word_books WHERE id = 1 AND deleted_at IS NOT NULL"
+        )
+        .await,
+        1
+    );
+
+    let undo = snapshots.undo_last().await.unwrap();
+    assert_eq!(undo.snapshot.operation, SnapshotOperation::DeleteWordBook);
+    assert_eq!(undo.restored_rows, 1);
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM word_books WHERE id = 1 AND deleted_at IS NULL AND status = 'normal'").await,
+        1
+    );
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_undo_batch_remove_words_from_plan() {
+    let pool = setup_test_db().await;
+    let (plan_id, word_ids) = insert_plan(&pool).await;
+    let logger = test_logger();
+    let plan_words = "SELECT COUNT(*) FROM study_plan_words";
+    let schedule_words = "SELECT COUNT(*) FROM study_plan_schedule_words";
+    let records = "SELECT COUNT(*) FROM word_practice_records";
+
+    StudyPlanService::new(Arc::new(pool.clone()), logger.clone())
+        .batch_remove_words_from_plan(plan_id, &word_ids[..1])
+        .await
+        .unwrap();
+    assert_eq!(count(&pool, plan_words).await, 1);
+    assert_eq!(count(&pool, schedule_words).await, 1);
+    assert_eq!(count(&pool, records).await, 0);
+
+    let undo = SnapshotService::new(Arc::new(pool.clone()), logger)
+        .undo_last()
+        .await
+        .unwrap();
+    assert_eq!(undo.snapshot.target_id, Some(plan_id));
+    assert_eq!(undo.restored_rows, 3);
+    assert_eq!(count(&pool, plan_words).await, 2);
+    assert_eq!(count(&pool, schedule_words).await, 2);
+    assert_eq!(count(&pool, records).await, 1);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_undo_refuses_to_overwrite_new_rows() {
+    let pool = setup_test_db().await;
+    let (plan_id, word_ids) = insert_plan(&pool).await;
+    let logger = test_logger();
+
+    let removed_id: i64 =
+        sqlx::query_scalar("SELECT id FROM study_plan_words WHERE plan_id = ? AND word_id = ?")
+            .bind(plan_id)
+            .bind(word_ids[0])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    StudyPlanService::new(Arc::new(pool.clone()), logger.clone())
+        .batch_remove_words_from_plan(plan_id, &word_ids[..1])
+        .await
+        .unwrap();
+    // 移除后新加入的单词占用了被移除的行的主键
+    sqlx::query(
+        "INSERT INTO study_plan_words (id, plan_id, word_id)
+         SELECT ?, ?, id FROM words WHERE word_book_id = 2 LIMIT 1",
+    )
+    .bind(removed_id)
+    .bind(plan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let snapshots = SnapshotService::new(Arc::new(pool.clone()), logger);
+    assert!(matches!(
+        snapshots.undo_last().await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    // 整个撤销回滚，新数据保留，快照仍未撤销
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM study_plan_words").await, 2);
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM study_plan_words WHERE word_id IN (SELECT id FROM words WHERE word_book_id = 2)").await,
+        1
+    );
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM study_plan_schedule_words").await, 1);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM word_practice_records").await, 0);
+    assert!(snapshots.get_snapshots().await.unwrap()[0].undone_at.is_none());
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_snapshot_history_is_limited() {
+    let pool = setup_test_db().await;
+    let snapshots = SnapshotService::new(Arc::new(pool.clone()), test_logger());
+    let scope = [SnapshotScope::rows("word_books", "id", &[1])];
+
+    for i in 0..SNAPSHOT_HISTORY_LIMIT + 2 {
+        snapshots
+            .capture(
+                SnapshotOperation::DeleteWordBook,
+                Some(1),
+                &format!("快照 {}", i),
+                &scope,
+            )
+            .await
+            .unwrap();
+    }
+    let history = snapshots.get_snapshots().await.unwrap();
+    assert_eq!(history.len() as i64, SNAPSHOT_HISTORY_LIMIT);
+    assert_eq!(
+        history[0].description,
+        format!("快照 {}", SNAPSHOT_HISTORY_LIMIT + 1)
+    );
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM operation_snapshot_tables").await,
+        SNAPSHOT_HISTORY_LIMIT
+    );
+
+    // 操作失败时快照被丢弃
+    let snapshot_id = snapshots
+        .capture(
+            SnapshotOperation::DeleteWordBook,
+            Some(1),
+            "失败的操作",
+            &scope,
+        )
+        .await
+        .unwrap();
+    snapshots.finish(snapshot_id, false).await;
+    let history = snapshots.get_snapshots().await.unwrap();
+    assert!(history.iter().all(|s| s.id != snapshot_id));
+
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,62 @@
+// 自然拼读分析缓存测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::ai_service::PhonicsWord;
+use redlark_app_lib::repositories::phonics_cache_repository::PhonicsCacheRepository;
+use sqlx::Row;
+use std::sync::Arc;
+
+fn phonics_word(word: &str, ipa: &str) -> PhonicsWord {
+    PhonicsWord {
+        word: word.to_string(),
+        frequency: 3,
+        chinese_translation: "测试".to_string(),
+        pos_abbreviation: "n.".to_string(),
+        pos_english: "noun".to_string(),
+        pos_chinese: "名词".to_string(),
+        ipa: ipa.to_string(),
+        syllables: word.to_string(),
+        phonics_rule: String::new(),
+        analysis_explanation: String::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_cache_keyed_by_word_model_and_prompt_version() {
+    let pool = setup_test_db().await;
+    let logger = test_logger();
+    let cache = PhonicsCacheRepository::new(Arc::new(pool.clone()), logger);
+
+    cache
+        .save_many(1, "v1", &[("Cat".to_string(), phonics_word("cat", "/kæt/"))])
+        .await
+        .unwrap();
+
+    // 单词大小写和空白不影响命中
+    let words = vec![" CAT ".to_string(), "dog".to_string()];
+    let hits = cache.find_many(1, "v1", &words).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits["cat"].ipa, "/kæt/");
+
+    // 不同模型或提示词版本不命中
+    assert!(cache.find_many(2, "v1", &words).await.unwrap().is_empty());
+    assert!(cache.find_many(1, "v2", &words).await.unwrap().is_empty());
+
+    // 覆盖写入
+    cache
+        .save_many(1, "v1", &[("cat".to_string(), phonics_word("cat", "/kat/"))])
+        .await
+        .unwrap();
+    let hits = cache.find_many(1, "v1", &words).await.unwrap();
+    assert_eq!(hits["cat"].ipa, "/kat/");
+
+    let hit_count: i64 = sqlx::query("SELECT hit_count FROM phonics_analysis_cache WHERE normalized_word = 'cat'")
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("hit_count");
+    assert_eq!(hit_count, 2);
+
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,69 @@
+// 拼读规则补全手动添加单词测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::phonics_rules::{self, PhonicsRule};
+use redlark_app_lib::services::word::WordService;
+use redlark_app_lib::types::wordbook::CreateWordRequest;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+fn word_request(word: &str, syllables: Option<&str>) -> CreateWordRequest {
+    CreateWordRequest {
+        word: word.to_string(),
+        meaning: "测试".to_string(),
+        description: None,
+        ipa: None,
+        syllables: syllables.map(str::to_string),
+        phonics_segments: None,
+        part_of_speech: None,
+        category_id: None,
+        pos_abbreviation: None,
+        pos_english: None,
+        pos_chinese: None,
+        phonics_rule: None,
+        analysis_explanation: None,
+    }
+}
+
+/// 查询单词的 (音节, 拼读片段, 拼读规则)
+async fn phonics_fields(
+    pool: &SqlitePool,
+    id: i64,
+) -> (Option<String>, Option<String>, Option<String>) {
+    sqlx::query_as("SELECT syllables, phonics_segments, phonics_rule FROM words WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_add_word_fills_missing_phonics_fields() {
+    let pool = setup_test_db().await;
+    let logger = test_logger();
+    let service = WordService::new(Arc::new(pool.clone()), logger);
+
+    let id = service
+        .add_word_to_book(1, word_request("cupcake", None))
+        .await
+        .unwrap();
+    let (syllables, segments, rule) = phonics_fields(&pool, id).await;
+    assert_eq!(syllables.as_deref(), Some("cup-cake"));
+    assert_eq!(segments.as_deref(), Some("c, u, p, c, a_e, k"));
+    assert_eq!(rule.as_deref(), Some(PhonicsRule::Vce.label()));
+
+    // 用户填写的内容即使与规则不一致也保持不变
+    let id = service
+        .add_word_to_book(1, word_request("tiger", Some("tig-er")))
+        .await
+        .unwrap();
+    let (syllables, segments, _) = phonics_fields(&pool, id).await;
+    assert_eq!(syllables.as_deref(), Some("tig-er"));
+    assert_eq!(segments.as_deref(), Some("t, i, g, er"));
+    assert!(phonics_rules::check("tiger", Some("ti-g-er"), None)
+        .iter()
+        .any(|d| d.field == "syllables"));
+
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,121 @@
+// 练习结果驱动的复习安排测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::repositories::review_state_repository::ReviewStateRepository;
+use redlark_app_lib::repositories::study_schedule_repository::StudyScheduleRepository;
+use redlark_app_lib::types::study::WordReviewState;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+async fn word_id(pool: &SqlitePool, word: &str) -> i64 {
+    sqlx::query_scalar("SELECT id FROM words WHERE word = ?")
+        .bind(word)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_replan_moves_reviews_and_keeps_one_per_day() {
+    let pool = setup_test_db().await;
+    let repo = ReviewStateRepository::new(Arc::new(pool.clone()), test_logger());
+    let plan_id = insert_plan(
+        &pool,
+        &[
+            (0, "in-progress", &[("Apple", false)]),
+            (1, "not-started", &[("Book", false), ("Apple", true)]),
+            (3, "not-started", &[("Cat", false)]),
+            (4, "not-started", &[]),
+        ],
+    )
+    .await;
+
+    let mut state = WordReviewState::new(plan_id, word_id(&pool, "Apple").await);
+    state.repetitions = 1;
+    state.due_date = Some(day(2));
+
+    // 第 2、3 天都落到第 3 天的日程，后一次顺延到第 4 天；第 9 天超出计划范围
+    let review_dates = vec![day(2), day(3), day(9)];
+    let placed_count = repo
+        .save_state_and_replan(&state, true, 0.6, &day(0), &review_dates)
+        .await
+        .unwrap();
+    assert_eq!(placed_count, 2);
+
+    assert_eq!(
+        placements(&pool, plan_id).await,
+        vec![
+            placed(0, "Apple", false),
+            placed(1, "Book", false),
+            placed(3, "Cat", false),
+            placed(3, "Apple", true),
+            placed(4, "Apple", true),
+        ]
+    );
+
+    let counts: Vec<(String, i64, i64, i64)> = sqlx::query_as(
+        "SELECT schedule_date, new_words_count, review_words_count, total_words_count
+         FROM study_plan_schedules WHERE plan_id = ? ORDER BY schedule_date",
+    )
+    .bind(plan_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        counts,
+        vec![
+            (day(0), 1, 0, 1),
+            (day(1), 1, 0, 1),
+            (day(3), 1, 1, 2),
+            (day(4), 0, 1, 1),
+        ]
+    );
+
+    let saved = repo.find_state(plan_id, state.word_id).await.unwrap().unwrap();
+    assert_eq!(saved.due_date, Some(day(2)));
+    let (learned, mastery): (bool, f64) = sqlx::query_as(
+        "SELECT learned, mastery_score FROM study_plan_words WHERE plan_id = ? AND word_id = ?",
+    )
+    .bind(plan_id)
+    .bind(state.word_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(learned);
+    assert_eq!(mastery, 0.6);
+}
+
+#[tokio::test]
+async fn test_schedule_progress_accumulates_across_sessions() {
+    let pool = setup_test_db().await;
+    let repo = StudyScheduleRepository::new(Arc::new(pool.clone()), test_logger());
+    let plan_id = insert_plan(
+        &pool,
+        &[(0, "not-started", &[("Apple", false), ("Book", false), ("Cat", true)])],
+    )
+    .await;
+    let schedule_id: i64 =
+        sqlx::query_scalar("SELECT id FROM study_plan_schedules WHERE plan_id = ?")
+            .bind(plan_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let progress = || async {
+        sqlx::query_as::<_, (i64, String)>(
+            "SELECT completed_words_count, status FROM study_plan_schedules WHERE id = ?",
+        )
+        .bind(schedule_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+    };
+
+    // 一次练习只完成部分单词，日程仍在进行中
+    repo.add_progress(schedule_id, 2).await.unwrap();
+    assert_eq!(progress().await, (2, "in-progress".to_string()));
+
+    // 累计达到日程单词总数后完成，且不超过总数
+    repo.add_progress(schedule_id, 2).await.unwrap();
+    assert_eq!(progress().await, (3, "completed".to_string()));
+}
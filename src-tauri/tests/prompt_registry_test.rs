@@ -0,0 +1,141 @@
+// 提示词模板注册表与版本历史测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::ai_service::AIService;
+use redlark_app_lib::prompt_registry::{
+    default_template, init_prompt_registry, prompt_version, PromptRegistry,
+};
+use redlark_app_lib::repositories::ai_model_repository::AIModelRepository;
+use redlark_app_lib::services::prompt::PromptService;
+use redlark_app_lib::types::{PromptName, PromptSource, StudyPlanAIParams};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 每个测试使用独立的应用数据目录
+fn temp_app_data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("redlark-prompt-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn test_custom_prompt_validation_and_history() {
+    let pool = setup_test_db().await;
+    let dir = temp_app_data_dir("history");
+    let registry = Arc::new(PromptRegistry::new(&dir));
+    let service =
+        PromptService::with_registry(Arc::new(pool.clone()), test_logger(), registry.clone());
+
+    let builtin = service.current(PromptName::BatchPhonics).await.unwrap();
+    assert_eq!(builtin.source, PromptSource::Default);
+    assert_eq!(
+        builtin.version,
+        prompt_version(default_template(PromptName::BatchPhonics))
+    );
+
+    // 缺少必需占位符时拒绝保存
+    assert!(service
+        .save_template(PromptName::BatchPhonics, "Analyze these words.")
+        .await
+        .is_err());
+
+    let custom_content = format!(
+        "{}\n额外要求：音节用 - 分隔。",
+        default_template(PromptName::BatchPhonics)
+    );
+    let custom = service
+        .save_template(PromptName::BatchPhonics, &custom_content)
+        .await
+        .unwrap();
+    assert_eq!(custom.source, PromptSource::Custom);
+    assert_ne!(custom.version, builtin.version);
+    assert_eq!(
+        registry.load(PromptName::BatchPhonics).content,
+        custom_content
+    );
+
+    let versions = service
+        .get_versions(PromptName::BatchPhonics)
+        .await
+        .unwrap();
+    assert_eq!(versions.len(), 2);
+    assert!(versions
+        .iter()
+        .any(|v| v.version == builtin.version && v.source == PromptSource::Default));
+
+    // 恢复内置版本会删除自定义模板
+    let restored = service
+        .restore_version(PromptName::BatchPhonics, &builtin.version)
+        .await
+        .unwrap();
+    assert_eq!(restored.source, PromptSource::Default);
+    assert!(!dir.join("prompts/batch_phonics.md").exists());
+
+    // 手动修改的模板缺少占位符时回退到内置模板
+    std::fs::write(dir.join("prompts/study_plan.md"), "只有 {word_list}").unwrap();
+    let fallback = registry.load(PromptName::StudyPlan);
+    assert_eq!(fallback.source, PromptSource::Default);
+    assert!(fallback.custom_error.unwrap().contains("{period_days}"));
+
+    assert!(service
+        .restore_version(PromptName::Phonics, "missing")
+        .await
+        .is_err());
+    assert!(PromptService::parse_name("unknown").is_err());
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_study_plan_records_prompt_version() {
+    let pool = setup_test_db().await;
+    let server = MockLlmServer::start().await;
+    let (_, model_id) = insert_mock_llm_provider(&pool, &server).await;
+    let logger = test_logger();
+
+    // 全局注册表指向临时应用数据目录，AI 服务使用其中的自定义模板
+    let registry = init_prompt_registry(&temp_app_data_dir("global"));
+    let service = PromptService::new(Arc::new(pool.clone()), logger.clone());
+    let custom = service
+        .save_template(
+            PromptName::StudyPlan,
+            &format!(
+                "{}\n请优先安排名词。",
+                default_template(PromptName::StudyPlan)
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(registry.load(PromptName::StudyPlan).version, custom.version);
+
+    let model_config = AIModelRepository::new(Arc::new(pool.clone()), logger.clone())
+        .find_model_config_by_id(model_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let params = StudyPlanAIParams {
+        intensity_level: "easy".to_string(),
+        total_words: 3,
+        period_days: 3,
+        review_frequency: 3,
+        start_date: "2025-01-01".to_string(),
+        word_list: Vec::new(),
+    };
+    let plan = AIService::from_model_config(&model_config)
+        .unwrap()
+        .generate_study_plan_schedule(params, &model_config, &logger)
+        .await
+        .unwrap();
+    assert_eq!(
+        plan.prompt_version.as_deref(),
+        Some(custom.version.as_str())
+    );
+
+    // 版本号随 ai_plan_data 一起保存
+    let json = serde_json::to_value(&plan).unwrap();
+    assert_eq!(json["promptVersion"], custom.version);
+
+    teardown_test_db(&pool).await;
+}
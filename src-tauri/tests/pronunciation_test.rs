@@ -0,0 +1,176 @@
+// 离线发音词典导入与音标校验测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::services::pronunciation::PronunciationService;
+use redlark_app_lib::services::word::WordService;
+use redlark_app_lib::services::wordbook::WordBookService;
+use redlark_app_lib::types::pronunciation::{IpaCheckItem, IpaConvention};
+use redlark_app_lib::types::wordbook::{
+    AnalyzedWord, CreateWordBookFromAnalysisRequest, CreateWordRequest,
+};
+use std::sync::Arc;
+
+const DICTIONARY: &str = ";;; test dictionary
+CAT  K AE1 T
+GARDEN  G AA1 R D AH0 N
+READ  R IY1 D
+READ(1)  R EH1 D
+TOMATO  T AH0 M EY1 T OW2
+TOMATO(1)  T AH0 M AA1 T OW2
+BROKEN  B R OW1 K XX0 N
+";
+
+fn analyzed_word(word: &str, ipa: Option<&str>) -> AnalyzedWord {
+    AnalyzedWord {
+        word: word.to_string(),
+        meaning: "测试".to_string(),
+        part_of_speech: None,
+        example_sentence: None,
+        ipa: ipa.map(str::to_string),
+        syllables: None,
+        pos_abbreviation: None,
+        pos_english: None,
+        pos_chinese: None,
+        phonics_rule: None,
+        analysis_explanation: None,
+        word_frequency: None,
+    }
+}
+
+#[tokio::test]
+async fn test_import_lookup_and_check() {
+    let pool = setup_test_db().await;
+    let service = PronunciationService::new(Arc::new(pool.clone()), test_logger());
+
+    let result = service.import_cmudict(DICTIONARY).await.unwrap();
+    assert_eq!(result.imported, 6);
+    assert_eq!(result.skipped_lines, 1);
+    assert_eq!(result.total_entries, 6);
+    // 重复导入覆盖已有读音
+    let result = service.import_cmudict("CAT  K AE1 T\n").await.unwrap();
+    assert_eq!(result.total_entries, 6);
+    assert!(service.import_cmudict(";;; empty\n").await.is_err());
+
+    let tomato = service.lookup("Tomato").await.unwrap();
+    assert_eq!(tomato.len(), 2);
+    assert_eq!(tomato[0].us_ipa, "/təˈmeɪtoʊ/");
+    assert_eq!(tomato[1].uk_ipa, "/təˈmɑːtəʊ/");
+
+    let report = service
+        .with_convention(IpaConvention::Uk)
+        .check(&[
+            IpaCheckItem {
+                word: "garden".to_string(),
+                ipa: Some("/ˈɡɑːrdn/".to_string()),
+            },
+            IpaCheckItem {
+                word: "read".to_string(),
+                ipa: Some("/red/".to_string()),
+            },
+            IpaCheckItem {
+                word: "cat".to_string(),
+                ipa: Some("/kɑːt/".to_string()),
+            },
+            IpaCheckItem {
+                word: "garden".to_string(),
+                ipa: None,
+            },
+            IpaCheckItem {
+                word: "zebra".to_string(),
+                ipa: Some("/ˈziːbrə/".to_string()),
+            },
+        ])
+        .await
+        .unwrap();
+    assert_eq!(report.checked, 3);
+    assert_eq!(report.matched, 2);
+    assert_eq!(report.mismatches.len(), 1);
+    assert_eq!(report.mismatches[0].word, "cat");
+    assert_eq!(
+        report.mismatches[0].dictionary_ipa,
+        vec!["/kæt/".to_string()]
+    );
+    assert_eq!(report.filled[0].ipa.as_deref(), Some("/ˈɡɑːdən/"));
+    assert_eq!(report.not_found, vec!["zebra".to_string()]);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_fill_ipa_before_saving_words() {
+    let pool = setup_test_db().await;
+    let logger = test_logger();
+    PronunciationService::new(Arc::new(pool.clone()), logger.clone())
+        .import_cmudict(DICTIONARY)
+        .await
+        .unwrap();
+
+    // 手动添加单词时补全空缺的音标
+    let word_id = WordService::new(Arc::new(pool.clone()), logger.clone())
+        .add_word_to_book(
+            1,
+            CreateWordRequest {
+                word: "garden".to_string(),
+                meaning: "花园".to_string(),
+                description: None,
+                ipa: None,
+                syllables: None,
+                phonics_segments: None,
+                part_of_speech: None,
+                category_id: None,
+                pos_abbreviation: None,
+                pos_english: None,
+                pos_chinese: None,
+                phonics_rule: None,
+                analysis_explanation: None,
+            },
+        )
+        .await
+        .unwrap();
+    let ipa: Option<String> = sqlx::query_scalar("SELECT ipa FROM words WHERE id = ?")
+        .bind(word_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(ipa.as_deref(), Some("/ˈɡɑːrdən/"));
+
+    // 从分析结果保存时补全空缺音标，不一致的 AI 音标保留并报告
+    let result = WordBookService::new(Arc::new(pool.clone()), logger)
+        .create_word_book_from_analysis(CreateWordBookFromAnalysisRequest {
+            title: "发音词典测试".to_string(),
+            description: String::new(),
+            icon: None,
+            icon_color: None,
+            words: vec![
+                analyzed_word("cat", Some("/kʌt/")),
+                analyzed_word("read", None),
+                analyzed_word("tomato", Some("/təˈmɑːtəʊ/")),
+            ],
+            status: None,
+            book_id: None,
+            theme_tag_ids: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(result.added_count, 3);
+    assert_eq!(result.ipa_mismatches.len(), 1);
+    assert_eq!(result.ipa_mismatches[0].ai_ipa, "/kʌt/");
+
+    let saved: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT word, ipa FROM words WHERE word_book_id = ? ORDER BY word")
+            .bind(result.book_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        saved,
+        vec![
+            ("cat".to_string(), Some("/kʌt/".to_string())),
+            ("read".to_string(), Some("/riːd/".to_string())),
+            ("tomato".to_string(), Some("/təˈmɑːtəʊ/".to_string())),
+        ]
+    );
+
+    teardown_test_db(&pool).await;
+}
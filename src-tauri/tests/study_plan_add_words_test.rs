@@ -0,0 +1,164 @@
+// 向学习计划追加单词测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::error::AppError;
+use redlark_app_lib::types::study::AddPlanWordsRequest;
+use sqlx::SqlitePool;
+
+fn request(plan_id: i64, word_ids: Vec<i64>, wordbook_ids: Vec<i64>) -> AddPlanWordsRequest {
+    AddPlanWordsRequest {
+        plan_id,
+        word_ids,
+        wordbook_ids,
+        model_id: None,
+        use_local_planner: true,
+    }
+}
+
+async fn word_id(pool: &SqlitePool, word: &str) -> i64 {
+    sqlx::query_scalar("SELECT id FROM words WHERE word = ?")
+        .bind(word)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_add_words_fills_future_days_with_reviews() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_plan(
+        &pool,
+        &[
+            (-1, "completed", &[("Apple", false)]),
+            (0, "not-started", &[("Water", false), ("Apple", true)]),
+            (1, "not-started", &[("Book", false)]),
+        ],
+    )
+    .await;
+
+    // Apple 已在计划中，跳过；单词本 2 整本加入
+    let red = word_id(&pool, "Red").await;
+    let apple = word_id(&pool, "Apple").await;
+    let addition = service
+        .prepare_plan_word_addition(&request(plan_id, vec![red, apple], vec![2]))
+        .await
+        .unwrap();
+    assert_eq!(addition.skipped_words, 1);
+    assert_eq!(addition.params.start_date, day(0));
+
+    let result = service.add_words_to_plan(&addition, None).await.unwrap();
+    assert_eq!(result.added_words, 4);
+    assert_eq!(result.skipped_words, 1);
+    assert_eq!(result.new_word_entries, 4);
+    // 第 3、6 天的复习超出计划范围
+    assert_eq!(result.review_entries, 4);
+    assert_eq!(result.added_days, 0);
+    assert_eq!(result.end_date, Some(day(1)));
+
+    assert_eq!(
+        placements(&pool, plan_id).await,
+        vec![
+            placed(-1, "Apple", false),
+            placed(0, "Cat", false),
+            placed(0, "Dog", false),
+            placed(0, "Elephant", false),
+            placed(0, "Red", false),
+            placed(0, "Water", false),
+            placed(0, "Apple", true),
+            placed(1, "Book", false),
+            placed(1, "Cat", true),
+            placed(1, "Dog", true),
+            placed(1, "Elephant", true),
+            placed(1, "Red", true),
+        ]
+    );
+
+    // 已完成的日程保持不变，其余日程重新统计
+    let schedules: Vec<(String, i64, i64, i64, String)> = sqlx::query_as(
+        "SELECT schedule_date, new_words_count, review_words_count, total_words_count, status
+         FROM study_plan_schedules WHERE plan_id = ? ORDER BY schedule_date",
+    )
+    .bind(plan_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        schedules,
+        vec![
+            (day(-1), 1, 0, 1, "completed".to_string()),
+            (day(0), 5, 1, 6, "not-started".to_string()),
+            (day(1), 1, 4, 5, "not-started".to_string()),
+        ]
+    );
+    let total_words: i64 = sqlx::query_scalar("SELECT total_words FROM study_plans WHERE id = ?")
+        .bind(plan_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(total_words, 7);
+
+    // 再次添加同一单词本时没有新单词
+    assert!(matches!(
+        service
+            .prepare_plan_word_addition(&request(plan_id, vec![], vec![2]))
+            .await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_add_words_extends_plan_past_completed_days() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_plan(
+        &pool,
+        &[
+            (-1, "completed", &[("Apple", false)]),
+            (0, "completed", &[("Water", false)]),
+        ],
+    )
+    .await;
+
+    let addition = service
+        .prepare_plan_word_addition(&request(plan_id, vec![], vec![4]))
+        .await
+        .unwrap();
+    let result = service.add_words_to_plan(&addition, None).await.unwrap();
+    assert_eq!(result.added_days, 1);
+    assert_eq!(result.review_entries, 0);
+    assert_eq!(result.end_date, Some(day(1)));
+    assert_eq!(
+        placements(&pool, plan_id).await,
+        vec![
+            placed(-1, "Apple", false),
+            placed(0, "Water", false),
+            placed(1, "One", false),
+            placed(1, "Three", false),
+            placed(1, "Two", false),
+        ]
+    );
+    let day_number: i64 = sqlx::query_scalar(
+        "SELECT day_number FROM study_plan_schedules WHERE plan_id = ? AND schedule_date = ?",
+    )
+    .bind(plan_id)
+    .bind(day(1))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(day_number, 3);
+
+    // 只有草稿或进行中的计划可以添加单词
+    service.pause_study_plan(plan_id).await.unwrap();
+    assert!(matches!(
+        service
+            .prepare_plan_word_addition(&request(plan_id, vec![], vec![3]))
+            .await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    teardown_test_db(&pool).await;
+}
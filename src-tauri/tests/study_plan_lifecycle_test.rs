@@ -0,0 +1,388 @@
+// 学习计划生命周期（状态转换、暂停、恢复）测试
+mod common_test_utils;
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use common_test_utils::*;
+use redlark_app_lib::error::AppError;
+use redlark_app_lib::services::StatisticsService;
+use redlark_app_lib::types::study::{StudyPlanTransition, UnifiedStudyPlanStatus};
+use sqlx::SqlitePool;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// 创建进行中的学习计划，日程按 (相对今天的天数, 是否已完成) 生成
+async fn insert_active_plan(pool: &SqlitePool, schedules: &[(i64, bool)]) -> i64 {
+    let plan_id = sqlx::query(
+        "INSERT INTO study_plans (name, status, unified_status, start_date, end_date)
+         VALUES ('暂停测试计划', 'normal', 'Active', ?, ?)",
+    )
+    .bind(day(schedules[0].0))
+    .bind(day(schedules[schedules.len() - 1].0))
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    for (i, (offset, completed)) in schedules.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO study_plan_schedules
+             (plan_id, day_number, schedule_date, total_words_count, completed_words_count, status)
+             VALUES (?, ?, ?, 10, ?, ?)",
+        )
+        .bind(plan_id)
+        .bind(i as i64 + 1)
+        .bind(day(*offset))
+        .bind(if *completed { 10 } else { 0 })
+        .bind(if *completed {
+            "completed"
+        } else {
+            "not-started"
+        })
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    plan_id
+}
+
+/// 把暂停时间改到若干天前
+async fn set_paused_days_ago(pool: &SqlitePool, plan_id: i64, days: i64) {
+    sqlx::query("UPDATE study_plans SET paused_at = datetime('now', ?) WHERE id = ?")
+        .bind(format!("-{} days", days))
+        .bind(plan_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn schedule_dates(pool: &SqlitePool, plan_id: i64) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT schedule_date FROM study_plan_schedules WHERE plan_id = ? ORDER BY day_number",
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_status_history() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_active_plan(&pool, &[(0, false), (1, false)]).await;
+
+    assert!(matches!(
+        service.resume_study_plan(plan_id).await,
+        Err(AppError::InvalidStatusTransition(_))
+    ));
+    service.pause_study_plan(plan_id).await.unwrap();
+    assert!(matches!(
+        service.pause_study_plan(plan_id).await,
+        Err(AppError::InvalidStatusTransition(_))
+    ));
+    let plan = service.get_study_plan(plan_id).await.unwrap();
+    assert_eq!(plan.unified_status, "Paused");
+
+    // 当天恢复：日程不移动
+    let result = service.resume_study_plan(plan_id).await.unwrap();
+    assert_eq!(result.paused_days, 0);
+    assert_eq!(result.shifted_schedules, 0);
+    assert_eq!(schedule_dates(&pool, plan_id).await, vec![day(0), day(1)]);
+
+    let (unified_status, paused_at): (String, Option<String>) =
+        sqlx::query_as("SELECT unified_status, paused_at FROM study_plans WHERE id = ?")
+            .bind(plan_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(unified_status, "Active");
+    assert!(paused_at.is_none());
+
+    let history: Vec<(String, String)> = sqlx::query_as(
+        "SELECT from_status, to_status FROM study_plan_status_history WHERE plan_id = ? ORDER BY id",
+    )
+    .bind(plan_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        history,
+        vec![
+            ("Active".to_string(), "Paused".to_string()),
+            ("Paused".to_string(), "Active".to_string()),
+        ]
+    );
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_resume_shifts_incomplete_schedules() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    // 第 4 天已经提前完成，第 3 天后移时要跳过它占用的日期
+    let plan_id =
+        insert_active_plan(&pool, &[(-5, true), (-4, false), (1, false), (4, true)]).await;
+    let in_progress_schedule: i64 = sqlx::query_scalar(
+        "SELECT id FROM study_plan_schedules WHERE plan_id = ? AND day_number = 2",
+    )
+    .bind(plan_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO practice_sessions (id, plan_id, schedule_id, schedule_date, start_time)
+         VALUES ('session-1', ?, ?, ?, '2026-01-01T08:00:00Z')",
+    )
+    .bind(plan_id)
+    .bind(in_progress_schedule)
+    .bind(day(-4))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    service.pause_study_plan(plan_id).await.unwrap();
+    set_paused_days_ago(&pool, plan_id, 3).await;
+
+    let result = service.resume_study_plan(plan_id).await.unwrap();
+    assert_eq!(result.paused_days, 3);
+    assert_eq!(result.shifted_schedules, 2);
+    assert_eq!(result.end_date, Some(day(5)));
+    assert_eq!(
+        schedule_dates(&pool, plan_id).await,
+        vec![day(-5), day(-1), day(5), day(4)]
+    );
+
+    let session_date: String =
+        sqlx::query_scalar("SELECT schedule_date FROM practice_sessions WHERE id = 'session-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(session_date, day(-1));
+    let end_date: String = sqlx::query_scalar("SELECT end_date FROM study_plans WHERE id = ?")
+        .bind(plan_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(end_date, day(5));
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_overdue_is_frozen_while_paused() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_active_plan(&pool, &[(-5, false), (-2, false), (1, false)]).await;
+    let statistics = StatisticsService::new(Arc::new(pool.clone()), test_logger());
+    assert_eq!(
+        statistics
+            .get_study_plan_statistics(plan_id)
+            .await
+            .unwrap()
+            .overdue_days,
+        2
+    );
+
+    service.pause_study_plan(plan_id).await.unwrap();
+    set_paused_days_ago(&pool, plan_id, 3).await;
+
+    // 暂停之后的日程不再计入逾期
+    assert_eq!(
+        statistics
+            .get_study_plan_statistics(plan_id)
+            .await
+            .unwrap()
+            .overdue_days,
+        1
+    );
+    let today = Local::now().date_naive();
+    let mut months = vec![month_of(today - Duration::days(5)), month_of(today)];
+    months.dedup();
+    let mut overdue_dates = BTreeSet::new();
+    for (year, month) in months {
+        for calendar_day in service
+            .get_plan_calendar_data(plan_id, year, month)
+            .await
+            .unwrap()
+        {
+            if calendar_day.status == "overdue" {
+                overdue_dates.insert(calendar_day.date);
+            }
+        }
+    }
+    assert_eq!(overdue_dates.into_iter().collect::<Vec<_>>(), vec![day(-5)]);
+
+    teardown_test_db(&pool).await;
+}
+
+/// 状态历史：(原状态, 新状态)
+async fn status_history(pool: &SqlitePool, plan_id: i64) -> Vec<(String, String)> {
+    sqlx::query_as(
+        "SELECT from_status, to_status FROM study_plan_status_history WHERE plan_id = ? ORDER BY id",
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+async fn plan_status(pool: &SqlitePool, plan_id: i64) -> (String, String) {
+    sqlx::query_as("SELECT status, unified_status FROM study_plans WHERE id = ?")
+        .bind(plan_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_transitions_follow_table_and_record_history() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_active_plan(&pool, &[(0, false), (1, false)]).await;
+    sqlx::query("UPDATE study_plans SET status = 'draft', unified_status = 'Draft' WHERE id = ?")
+        .bind(plan_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    service.publish_study_plan(plan_id).await.unwrap();
+    service.start_study_plan(plan_id).await.unwrap();
+    service.pause_study_plan(plan_id).await.unwrap();
+    service.terminate_study_plan(plan_id).await.unwrap();
+    let (paused_at, terminated_at): (Option<String>, Option<String>) =
+        sqlx::query_as("SELECT paused_at, actual_terminated_date FROM study_plans WHERE id = ?")
+            .bind(plan_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(paused_at.is_none());
+    assert!(terminated_at.is_some());
+
+    service.restart_study_plan(plan_id).await.unwrap();
+    assert_eq!(schedule_dates(&pool, plan_id).await, Vec::<String>::new());
+    service.edit_study_plan(plan_id).await.unwrap();
+
+    // 管理状态随统一状态一起更新
+    assert_eq!(
+        plan_status(&pool, plan_id).await,
+        ("draft".to_string(), "Draft".to_string())
+    );
+    let pairs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect()
+    };
+    assert_eq!(
+        status_history(&pool, plan_id).await,
+        pairs(&[
+            ("Draft", "Pending"),
+            ("Pending", "Active"),
+            ("Active", "Paused"),
+            ("Paused", "Terminated"),
+            ("Terminated", "Pending"),
+            ("Pending", "Draft"),
+        ])
+    );
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_illegal_transitions_are_rejected_without_side_effects() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_active_plan(&pool, &[(0, false)]).await;
+
+    for result in [
+        service.start_study_plan(plan_id).await,
+        service.publish_study_plan(plan_id).await,
+        service.restart_study_plan(plan_id).await,
+    ] {
+        assert!(matches!(result, Err(AppError::InvalidStatusTransition(_))));
+    }
+    assert!(matches!(
+        service.start_study_plan(-1).await,
+        Err(AppError::NotFound(_))
+    ));
+    // 非法转换不修改状态、不清空日程、不写历史
+    assert_eq!(
+        plan_status(&pool, plan_id).await,
+        ("normal".to_string(), "Active".to_string())
+    );
+    assert_eq!(schedule_dates(&pool, plan_id).await, vec![day(0)]);
+    assert!(status_history(&pool, plan_id).await.is_empty());
+
+    // 部分更新中的状态同样走状态转换表，转换失败时其他字段也不更新
+    assert!(matches!(
+        service
+            .partial_update(plan_id, &serde_json::json!({ "status": "normal", "name": "未保存的名称" }))
+            .await,
+        Err(AppError::InvalidStatusTransition(_))
+    ));
+    assert!(matches!(
+        service
+            .partial_update(plan_id, &serde_json::json!({ "status": "archived" }))
+            .await,
+        Err(AppError::ValidationError(_))
+    ));
+    let name: String = sqlx::query_scalar("SELECT name FROM study_plans WHERE id = ?")
+        .bind(plan_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_ne!(name, "未保存的名称");
+    // 其他字段的更新不改动状态
+    service
+        .partial_update(plan_id, &serde_json::json!({ "name": "改名后的计划" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        plan_status(&pool, plan_id).await,
+        ("normal".to_string(), "Active".to_string())
+    );
+    assert!(status_history(&pool, plan_id).await.is_empty());
+
+    service.complete_study_plan(plan_id).await.unwrap();
+    service.delete_study_plan(plan_id).await.unwrap();
+    assert_eq!(
+        plan_status(&pool, plan_id).await,
+        ("deleted".to_string(), "Deleted".to_string())
+    );
+    assert!(matches!(
+        service.edit_study_plan(plan_id).await,
+        Err(AppError::InvalidStatusTransition(_))
+    ));
+    assert_eq!(status_history(&pool, plan_id).await.len(), 2);
+
+    teardown_test_db(&pool).await;
+}
+
+#[test]
+fn test_transition_table() {
+    use UnifiedStudyPlanStatus::*;
+    let all = [
+        Draft, Pending, Active, Paused, Completed, Terminated, Deleted,
+    ];
+
+    // 除已删除外的状态都可以删除，已删除的计划不能再转换
+    for status in all {
+        assert_eq!(
+            StudyPlanTransition::Delete.target(status).is_some(),
+            status != Deleted
+        );
+    }
+    assert!(StudyPlanTransition::TABLE
+        .iter()
+        .all(|(transition, _, _)| transition.target(Deleted).is_none()));
+    assert_eq!(
+        StudyPlanTransition::between(Paused, Active),
+        Some(StudyPlanTransition::Resume)
+    );
+    assert_eq!(StudyPlanTransition::between(Draft, Active), None);
+}
+
+fn month_of(date: NaiveDate) -> (i32, i32) {
+    (date.year(), date.month() as i32)
+}
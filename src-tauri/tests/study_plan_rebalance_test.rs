@@ -0,0 +1,248 @@
+// 逾期日程重新分配测试
+mod common_test_utils;
+
+use chrono::Local;
+use common_test_utils::*;
+use redlark_app_lib::error::AppError;
+use redlark_app_lib::repositories::study_schedule_repository::StudyScheduleRepository;
+use redlark_app_lib::services::study_plan_rebalancer::{plan_rebalance, rebalance_fingerprint};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_preview_and_apply_rebalance() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_plan(
+        &pool,
+        &[
+            (-3, "completed", &[("Apple", false)]),
+            (
+                -2,
+                "overdue",
+                &[
+                    ("Cat", false),
+                    ("Dog", false),
+                    ("Elephant", false),
+                    ("Apple", true),
+                ],
+            ),
+            (0, "not-started", &[("Red", false), ("Blue", false)]),
+            (1, "not-started", &[("Green", false), ("Cat", true)]),
+        ],
+    )
+    .await;
+    let before = placements(&pool, plan_id).await;
+
+    // 预览不修改数据
+    let preview = service.preview_rebalance_study_plan(plan_id).await.unwrap();
+    assert_eq!(placements(&pool, plan_id).await, before);
+    assert_eq!(preview.max_new_words_per_day, 15);
+    assert_eq!(preview.overdue_schedules, 1);
+    assert_eq!(preview.moved_words, 6);
+    assert_eq!(preview.dropped_reviews, 1);
+    assert_eq!(preview.added_days, 0);
+    assert_eq!(preview.new_end_date, Some(day(1)));
+
+    let days: Vec<(String, i32, i32, i32, i32)> = preview
+        .days
+        .iter()
+        .map(|d| {
+            (
+                d.date.clone(),
+                d.before_new_words,
+                d.before_review_words,
+                d.after_new_words,
+                d.after_review_words,
+            )
+        })
+        .collect();
+    assert_eq!(
+        days,
+        vec![
+            (day(-2), 3, 1, 0, 0),
+            (day(0), 2, 0, 3, 1),
+            (day(1), 1, 1, 3, 0),
+        ]
+    );
+    // Cat 挪到今天学习，原定 3 天后的复习超出计划范围
+    let cat_review = preview
+        .moves
+        .iter()
+        .find(|m| m.word == "Cat" && m.is_review)
+        .unwrap();
+    assert_eq!(cat_review.from_date, day(1));
+    assert!(cat_review.to_date.is_none());
+
+    let result = service
+        .rebalance_study_plan(plan_id, &preview.fingerprint)
+        .await
+        .unwrap();
+    assert_eq!(result.moved_words, preview.moved_words);
+    assert_eq!(result.new_end_date, Some(day(1)));
+    assert_eq!(
+        placements(&pool, plan_id).await,
+        vec![
+            placed(-3, "Apple", false),
+            placed(0, "Cat", false),
+            placed(0, "Dog", false),
+            placed(0, "Elephant", false),
+            placed(0, "Apple", true),
+            placed(1, "Blue", false),
+            placed(1, "Green", false),
+            placed(1, "Red", false),
+        ]
+    );
+
+    // 逾期日程被清空后删除，其余日程重新统计
+    let schedules: Vec<(String, i64, i64, i64, String)> = sqlx::query_as(
+        "SELECT schedule_date, new_words_count, review_words_count, total_words_count, status
+         FROM study_plan_schedules WHERE plan_id = ? ORDER BY schedule_date",
+    )
+    .bind(plan_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        schedules,
+        vec![
+            (day(-3), 1, 0, 1, "completed".to_string()),
+            (day(0), 3, 1, 4, "not-started".to_string()),
+            (day(1), 3, 0, 3, "not-started".to_string()),
+        ]
+    );
+
+    // 再次预览时没有需要调整的内容
+    let preview = service.preview_rebalance_study_plan(plan_id).await.unwrap();
+    assert!(preview.moves.is_empty());
+    assert!(preview.days.is_empty());
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_rebalance_extends_plan_and_keeps_started_schedules() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let plan_id = insert_plan(
+        &pool,
+        &[
+            (-2, "overdue", &[("Red", false), ("Blue", false)]),
+            (-1, "overdue", &[("Green", false)]),
+        ],
+    )
+    .await;
+
+    // 昨天的日程已经开始练习，保持不变
+    sqlx::query(
+        "INSERT INTO practice_sessions (id, plan_id, schedule_id, schedule_date, start_time)
+         SELECT 'session-1', plan_id, id, schedule_date, datetime('now')
+         FROM study_plan_schedules WHERE plan_id = ? AND day_number = 2",
+    )
+    .bind(plan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // 预览后日程变化时拒绝应用
+    let preview = service.preview_rebalance_study_plan(plan_id).await.unwrap();
+    sqlx::query(
+        "DELETE FROM study_plan_schedule_words WHERE schedule_id IN
+         (SELECT id FROM study_plan_schedules WHERE plan_id = ? AND day_number = 1)
+         AND word_id = (SELECT id FROM words WHERE word = 'Red')",
+    )
+    .bind(plan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert!(matches!(
+        service
+            .rebalance_study_plan(plan_id, &preview.fingerprint)
+            .await,
+        Err(AppError::ValidationError(_))
+    ));
+    sqlx::query(
+        "INSERT INTO study_plan_schedule_words (schedule_id, word_id, wordbook_id)
+         SELECT s.id, w.id, w.word_book_id FROM study_plan_schedules s, words w
+         WHERE s.plan_id = ? AND s.day_number = 1 AND w.word = 'Red'",
+    )
+    .bind(plan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // 今天及之后没有日程，延长计划
+    let preview = service.preview_rebalance_study_plan(plan_id).await.unwrap();
+    let result = service
+        .rebalance_study_plan(plan_id, &preview.fingerprint)
+        .await
+        .unwrap();
+    assert_eq!(result.added_days, 1);
+    assert_eq!(result.old_end_date, Some(day(-1)));
+    assert_eq!(result.new_end_date, Some(day(0)));
+    assert_eq!(result.days[1].schedule_id, None);
+    assert_eq!(
+        placements(&pool, plan_id).await,
+        vec![
+            placed(-1, "Green", false),
+            placed(0, "Blue", false),
+            placed(0, "Red", false),
+        ]
+    );
+    let day_numbers: Vec<i64> = sqlx::query_scalar(
+        "SELECT day_number FROM study_plan_schedules WHERE plan_id = ? ORDER BY schedule_date",
+    )
+    .bind(plan_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(day_numbers, vec![2, 3]);
+
+    // 只有进行中的计划可以重新分配
+    service.pause_study_plan(plan_id).await.unwrap();
+    assert!(matches!(
+        service.preview_rebalance_study_plan(plan_id).await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_apply_rebalance_rechecks_fingerprint_in_transaction() {
+    let pool = setup_test_db().await;
+    let repo = StudyScheduleRepository::new(Arc::new(pool.clone()), test_logger());
+    let plan_id = insert_plan(
+        &pool,
+        &[
+            (-1, "overdue", &[("Red", false), ("Blue", false)]),
+            (0, "not-started", &[("Green", false)]),
+        ],
+    )
+    .await;
+
+    // 计算方案之后、应用之前日程被修改
+    let today = Local::now().date_naive();
+    let schedules = repo.find_rebalance_schedules(plan_id).await.unwrap();
+    let movable_ids: Vec<i64> = schedules.iter().filter(|s| s.movable).map(|s| s.id).collect();
+    let entries = repo.find_rebalance_entries(&movable_ids).await.unwrap();
+    let fingerprint = rebalance_fingerprint(today, 5, &schedules, &entries);
+    let outcome = plan_rebalance(today, 5, &schedules, &entries);
+    sqlx::query(
+        "DELETE FROM study_plan_schedule_words
+         WHERE word_id = (SELECT id FROM words WHERE word = 'Blue')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert!(matches!(
+        repo.apply_rebalance(plan_id, today, 5, &fingerprint, &outcome).await,
+        Err(AppError::ValidationError(_))
+    ));
+    assert_eq!(
+        placements(&pool, plan_id).await,
+        vec![placed(-1, "Red", false), placed(0, "Green", false)]
+    );
+
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,227 @@
+// 复制学习计划与计划模板测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::error::AppError;
+use redlark_app_lib::types::study::{
+    CloneStudyPlanRequest, CreateStudyPlanTemplateRequest, UnifiedStudyPlanStatus,
+};
+use sqlx::SqlitePool;
+
+/// 创建已完成的源计划（1周计划，轻松强度，复习 3 次），日程为 (日期, 新词数)
+async fn insert_source_plan(pool: &SqlitePool, schedules: &[(&str, i64)]) -> i64 {
+    let plan_id = sqlx::query(
+        "INSERT INTO study_plans
+         (name, description, status, unified_status, intensity_level, study_period_days, review_frequency,
+          start_date, end_date)
+         VALUES ('源计划', '每周一本', 'normal', 'Completed', 'easy', 7, 3, ?, ?)",
+    )
+    .bind(schedules[0].0)
+    .bind(schedules[schedules.len() - 1].0)
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    for (i, (date, new_words)) in schedules.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO study_plan_schedules
+             (plan_id, day_number, schedule_date, new_words_count, total_words_count, status)
+             VALUES (?, ?, ?, ?, ?, 'completed')",
+        )
+        .bind(plan_id)
+        .bind(i as i64 + 1)
+        .bind(date)
+        .bind(new_words)
+        .bind(new_words)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    plan_id
+}
+
+#[tokio::test]
+async fn test_clone_plan_keeps_settings_and_schedule_shape() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let source_id = insert_source_plan(
+        &pool,
+        &[("2025-03-01", 2), ("2025-03-02", 0), ("2025-03-03", 1)],
+    )
+    .await;
+
+    let plan_id = service
+        .clone_study_plan(CloneStudyPlanRequest {
+            plan_id: source_id,
+            name: None,
+            start_date: "2025-06-01".to_string(),
+            wordbook_ids: vec![2],
+            status: None,
+        })
+        .await
+        .unwrap();
+
+    let plan = service.get_study_plan(plan_id).await.unwrap();
+    assert_eq!(plan.name, "源计划 (副本)");
+    assert_eq!(plan.description, "每周一本");
+    assert_eq!(plan.unified_status, UnifiedStudyPlanStatus::Draft.as_str());
+    assert_eq!(plan.intensity_level.as_deref(), Some("easy"));
+    assert_eq!(plan.study_period_days, Some(7));
+    assert_eq!(plan.review_frequency, Some(3));
+    assert_eq!(plan.total_words, 3);
+    assert_eq!(plan.start_date.as_deref(), Some("2025-06-01"));
+    assert_eq!(plan.end_date.as_deref(), Some("2025-06-03"));
+
+    // 新词按源计划的形状分配（2, 0, 1），第 1 天的单词次日复习
+    let schedules: Vec<(String, i64, i64)> = sqlx::query_as(
+        "SELECT schedule_date, new_words_count, review_words_count
+         FROM study_plan_schedules WHERE plan_id = ? ORDER BY schedule_date",
+    )
+    .bind(plan_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        schedules,
+        vec![
+            ("2025-06-01".to_string(), 2, 0),
+            ("2025-06-02".to_string(), 0, 2),
+            ("2025-06-03".to_string(), 1, 0),
+        ]
+    );
+
+    // 单词本为空时不能复制
+    assert!(matches!(
+        service
+            .clone_study_plan(CloneStudyPlanRequest {
+                plan_id: source_id,
+                name: Some("空计划".to_string()),
+                start_date: "2025-06-01".to_string(),
+                wordbook_ids: vec![],
+                status: None,
+            })
+            .await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_clone_extended_plan_stays_within_period() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    // 1周计划被延长到第 9 天
+    let source_id = insert_source_plan(&pool, &[("2025-03-01", 2), ("2025-03-09", 1)]).await;
+
+    let plan_id = service
+        .clone_study_plan(CloneStudyPlanRequest {
+            plan_id: source_id,
+            name: None,
+            start_date: "2025-06-01".to_string(),
+            wordbook_ids: vec![2],
+            status: None,
+        })
+        .await
+        .unwrap();
+
+    let plan = service.get_study_plan(plan_id).await.unwrap();
+    assert_eq!(plan.study_period_days, Some(7));
+    assert_eq!(plan.end_date.as_deref(), Some("2025-06-07"));
+    let last_date: String = sqlx::query_scalar(
+        "SELECT MAX(schedule_date) FROM study_plan_schedules WHERE plan_id = ?",
+    )
+    .bind(plan_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(last_date.as_str() <= "2025-06-07");
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_plan_templates() {
+    let pool = setup_test_db().await;
+    let service = plan_service(&pool);
+    let source_id = insert_source_plan(
+        &pool,
+        &[("2025-03-01", 2), ("2025-03-03", 1), ("2025-03-04", 0)],
+    )
+    .await;
+
+    // 从计划保存模板：设置和日程形状
+    let saved_id = service
+        .save_study_plan_as_template(source_id, "每周一本".to_string())
+        .await
+        .unwrap();
+    let saved = service.get_study_plan_template(saved_id).await.unwrap();
+    assert_eq!(saved.intensity_level, "easy");
+    assert_eq!(saved.study_period_days, 7);
+    assert_eq!(saved.review_frequency, 3);
+    assert_eq!(saved.day_weights, vec![2, 0, 1, 0]);
+    assert_eq!(saved.source_plan_id, Some(source_id));
+
+    // 模板名称唯一
+    assert!(matches!(
+        service
+            .save_study_plan_as_template(source_id, "每周一本".to_string())
+            .await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    let created_id = service
+        .create_study_plan_template(CreateStudyPlanTemplateRequest {
+            name: "两周常规".to_string(),
+            description: String::new(),
+            intensity_level: "normal".to_string(),
+            study_period_days: 14,
+            review_frequency: 4,
+            day_weights: vec![],
+        })
+        .await
+        .unwrap();
+    assert!(matches!(
+        service
+            .create_study_plan_template(CreateStudyPlanTemplateRequest {
+                name: "无效周期".to_string(),
+                description: String::new(),
+                intensity_level: "normal".to_string(),
+                study_period_days: 10,
+                review_frequency: 4,
+                day_weights: vec![],
+            })
+            .await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    let names: Vec<String> = service
+        .get_study_plan_templates()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    assert_eq!(names, vec!["两周常规", "每周一本"]);
+
+    // 源计划删除后模板保留
+    sqlx::query("DELETE FROM study_plans WHERE id = ?")
+        .bind(source_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let saved = service.get_study_plan_template(saved_id).await.unwrap();
+    assert_eq!(saved.source_plan_id, None);
+
+    service
+        .delete_study_plan_template(created_id)
+        .await
+        .unwrap();
+    assert!(matches!(
+        service.delete_study_plan_template(created_id).await,
+        Err(AppError::NotFound(_))
+    ));
+
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,222 @@
+// 单词本导出（CSV、JSON 导出包、Anki 牌组）测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::services::word_export::WordExportService;
+use redlark_app_lib::services::word_import::WordImportService;
+use redlark_app_lib::types::wordbook::{
+    CreateWordBookRequest, WordBookExportFormat, WordBookExportRequest, WordImportRequest,
+};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+type WordRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+fn export_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("redlark-word-export-test")
+        .join(format!("{}-{}", name, uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn export_request(format: WordBookExportFormat, include_audio: bool) -> WordBookExportRequest {
+    WordBookExportRequest {
+        format,
+        include_audio,
+        output_path: None,
+    }
+}
+
+async fn book_words(pool: &sqlx::SqlitePool, book_id: i64) -> Vec<WordRow> {
+    sqlx::query_as(
+        "SELECT word, meaning, ipa, syllables, phonics_segments, phonics_rule
+         FROM words WHERE word_book_id = ? ORDER BY word",
+    )
+    .bind(book_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+/// 给单词本 1 添加主题标签和一个单词的发音缓存
+async fn prepare_book(pool: &sqlx::SqlitePool, dir: &std::path::Path) {
+    sqlx::query(
+        "INSERT INTO word_book_theme_tags (word_book_id, theme_tag_id)
+         SELECT 1, id FROM theme_tags WHERE name = '学习'",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query("UPDATE words SET phonics_rule = 'Closed Syllable | 闭音节' WHERE word = 'Apple'")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let audio_path = dir.join("apple.mp3");
+    std::fs::write(&audio_path, b"ID3 test audio").unwrap();
+    sqlx::query(
+        "INSERT INTO tts_cache (text_hash, original_text, voice_id, model_id, file_path, file_size)
+         VALUES ('applehash', 'apple', 'voice', 'model', ?, 14)",
+    )
+    .bind(audio_path.to_string_lossy().to_string())
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_json_bundle_round_trip() {
+    let pool = setup_test_db().await;
+    let dir = export_dir("json");
+    prepare_book(&pool, &dir).await;
+    let logger = test_logger();
+
+    let result = WordExportService::new(Arc::new(pool.clone()), logger.clone())
+        .export_to_file(1, &export_request(WordBookExportFormat::Json, false), &dir)
+        .await
+        .unwrap();
+    let original = book_words(&pool, 1).await;
+    assert!(!original.is_empty());
+    assert_eq!(result.word_count, original.len());
+    assert!(result.file_path.ends_with(".json"));
+
+    let content = std::fs::read_to_string(&result.file_path).unwrap();
+    let imported = WordImportService::new(Arc::new(pool.clone()), logger)
+        .import_bundle(&content)
+        .await
+        .unwrap();
+    assert_ne!(imported.book_id, 1);
+    assert_eq!(imported.added_count as usize, original.len());
+    assert_eq!(book_words(&pool, imported.book_id).await, original);
+
+    let (title, tag): (String, String) = sqlx::query_as(
+        "SELECT wb.title, tt.name FROM word_books wb
+         JOIN word_book_theme_tags wbt ON wbt.word_book_id = wb.id
+         JOIN theme_tags tt ON tt.id = wbt.theme_tag_id
+         WHERE wb.id = ?",
+    )
+    .bind(imported.book_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(title, "基础英语词汇");
+    assert_eq!(tag, "学习");
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_csv_export_can_be_imported() {
+    let pool = setup_test_db().await;
+    let dir = export_dir("csv");
+    prepare_book(&pool, &dir).await;
+    let logger = test_logger();
+
+    let result = WordExportService::new(Arc::new(pool.clone()), logger.clone())
+        .export_to_file(1, &export_request(WordBookExportFormat::Csv, false), &dir)
+        .await
+        .unwrap();
+    let content = std::fs::read_to_string(&result.file_path).unwrap();
+    assert!(content.contains("学习"));
+
+    let preview = WordImportService::new(Arc::new(pool.clone()), logger)
+        .preview(&WordImportRequest {
+            content,
+            format: None,
+            has_header: None,
+            mapping: None,
+            book_id: None,
+            new_book: Some(CreateWordBookRequest {
+                title: "CSV 导入".to_string(),
+                description: String::new(),
+                icon: "📚".to_string(),
+                icon_color: "#3B82F6".to_string(),
+                theme_tag_ids: None,
+            }),
+            conflict_strategy: Default::default(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(preview.new_count, result.word_count);
+    assert!(preview.errors.is_empty());
+    assert_eq!(preview.mapping.phonics_segments, Some(4));
+    assert_eq!(preview.mapping.phonics_rule, Some(9));
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_anki_package_with_audio() {
+    let pool = setup_test_db().await;
+    let dir = export_dir("apkg");
+    prepare_book(&pool, &dir).await;
+
+    let result = WordExportService::new(Arc::new(pool.clone()), test_logger())
+        .export_to_file(1, &export_request(WordBookExportFormat::Apkg, true), &dir)
+        .await
+        .unwrap();
+    assert_eq!(result.audio_count, 1);
+
+    let file = std::fs::File::open(&result.file_path).unwrap();
+    let mut archive = zip::ZipArchive::new(file).unwrap();
+    let mut media = String::new();
+    archive
+        .by_name("media")
+        .unwrap()
+        .read_to_string(&mut media)
+        .unwrap();
+    assert_eq!(media, r#"{"0":"redlark-applehash.mp3"}"#);
+    let mut audio = Vec::new();
+    archive
+        .by_name("0")
+        .unwrap()
+        .read_to_end(&mut audio)
+        .unwrap();
+    assert_eq!(audio, b"ID3 test audio");
+
+    let collection_path = dir.join("collection.anki2");
+    let mut collection = Vec::new();
+    archive
+        .by_name("collection.anki2")
+        .unwrap()
+        .read_to_end(&mut collection)
+        .unwrap();
+    std::fs::write(&collection_path, collection).unwrap();
+
+    let mut conn =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&collection_path))
+            .await
+            .unwrap();
+    let notes: Vec<(String, String, String)> =
+        sqlx::query_as("SELECT sfld, flds, tags FROM notes ORDER BY id")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+    assert_eq!(notes.len(), result.word_count);
+    let cards: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cards")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(cards as usize, notes.len());
+
+    let (_, apple_fields, apple_tags) = notes.iter().find(|(w, _, _)| w == "Apple").unwrap();
+    let fields: Vec<&str> = apple_fields.split('\u{1f}').collect();
+    assert_eq!(fields[1], "苹果");
+    assert_eq!(fields[4], "Closed Syllable | 闭音节");
+    assert_eq!(fields[5], "[sound:redlark-applehash.mp3]");
+    assert_eq!(apple_tags.trim(), "学习");
+
+    conn.close().await.unwrap();
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,65 @@
+// 本地单词提取与用户词表测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::repositories::extraction_word_list_repository::ExtractionWordListRepository;
+use redlark_app_lib::types::word_analysis::ExtractionWordListType;
+use redlark_app_lib::word_extractor::{ExtractionMode, WordExtractor};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_word_lists_filter_local_extraction() {
+    let pool = setup_test_db().await;
+    let logger = test_logger();
+    let repository = ExtractionWordListRepository::new(Arc::new(pool.clone()), logger);
+
+    let saved = repository
+        .replace(
+            ExtractionWordListType::Stop,
+            &[" Really ".to_string(), "really".to_string(), String::new()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(saved, 1);
+
+    let added = repository
+        .add(
+            ExtractionWordListType::Known,
+            &["cats".to_string(), "dog".to_string()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(added, 2);
+    // 已存在的单词不重复添加
+    let added = repository
+        .add(ExtractionWordListType::Known, &["DOG".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(added, 0);
+
+    let lists = repository.find_all().await.unwrap();
+    assert_eq!(lists.stop_words, vec!["really"]);
+    assert_eq!(lists.known_words, vec!["cats", "dog"]);
+
+    let text = "The cat really liked the dogs. Cats chase mice, and mice were hiding.";
+    let result = WordExtractor::new(ExtractionMode::Focus)
+        .with_stop_words(&lists.stop_words)
+        .with_known_words(&lists.known_words)
+        .extract(text);
+    let words: Vec<(&str, i32)> = result
+        .words
+        .iter()
+        .map(|w| (w.word.as_str(), w.frequency))
+        .collect();
+    assert_eq!(words, vec![("mouse", 2), ("like", 1), ("chase", 1), ("hide", 1)]);
+    assert_eq!(result.total_count, 5);
+
+    // 替换词表会清除旧单词
+    repository
+        .replace(ExtractionWordListType::Stop, &[])
+        .await
+        .unwrap();
+    assert!(repository.find_all().await.unwrap().stop_words.is_empty());
+
+    teardown_test_db(&pool).await;
+}
@@ -0,0 +1,172 @@
+// CSV/TSV/Anki 文本单词导入测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::services::word_import::WordImportService;
+use redlark_app_lib::types::wordbook::{
+    CreateWordBookRequest, ImportConflictStrategy, WordImportFormat, WordImportRequest,
+};
+use std::sync::Arc;
+
+const CSV: &str = "Word,Meaning,IPA,Notes
+lemon,柠檬,/ˈlemən/,A slice of lemon.
+cupcake,纸杯蛋糕,,
+Lemon,重复的柠檬,,
+,缺少单词,,
+kitten,小猫咪,,
+";
+
+fn import_request(content: &str, strategy: ImportConflictStrategy) -> WordImportRequest {
+    WordImportRequest {
+        content: content.to_string(),
+        format: None,
+        has_header: None,
+        mapping: None,
+        book_id: Some(1),
+        new_book: None,
+        conflict_strategy: strategy,
+    }
+}
+
+async fn insert_existing_word(pool: &sqlx::SqlitePool) -> i64 {
+    sqlx::query(
+        "INSERT INTO words (word, meaning, description, word_book_id) VALUES ('Kitten', '小猫', '原有备注', 1)",
+    )
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid()
+}
+
+#[tokio::test]
+async fn test_preview_and_import_csv() {
+    let pool = setup_test_db().await;
+    let kitten_id = insert_existing_word(&pool).await;
+    let service = WordImportService::new(Arc::new(pool.clone()), test_logger());
+
+    let preview = service
+        .preview(&import_request(CSV, ImportConflictStrategy::Skip))
+        .await
+        .unwrap();
+    assert_eq!(preview.format, WordImportFormat::Csv);
+    assert!(preview.has_header);
+    assert_eq!(preview.mapping.ipa, Some(2));
+    assert_eq!(preview.mapping.description, Some(3));
+    assert_eq!(preview.total_rows, 5);
+    assert_eq!(preview.new_count, 2);
+    assert_eq!(preview.duplicate_lines, vec![4]);
+    assert_eq!(preview.errors.len(), 1);
+    assert_eq!(preview.errors[0].line, 5);
+    assert_eq!(preview.conflicts.len(), 1);
+    assert_eq!(preview.conflicts[0].existing_word_id, kitten_id);
+
+    // 冲突单词按更新策略只覆盖文件中有值的字段
+    let result = service
+        .import(import_request(CSV, ImportConflictStrategy::Update))
+        .await
+        .unwrap();
+    assert_eq!(result.book_id, 1);
+    assert_eq!(result.added_count, 2);
+    assert_eq!(result.updated_count, 1);
+    assert_eq!(result.skipped_count, 2);
+
+    let kitten: (String, Option<String>) =
+        sqlx::query_as("SELECT meaning, description FROM words WHERE id = ?")
+            .bind(kitten_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(kitten, ("小猫咪".to_string(), Some("原有备注".to_string())));
+
+    // 新单词由拼读规则补全空缺字段
+    let cupcake: (Option<String>, Option<String>) = sqlx::query_as(
+        "SELECT syllables, phonics_segments FROM words WHERE word = 'cupcake' AND word_book_id = 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(cupcake.0.as_deref(), Some("cup-cake"));
+    assert!(cupcake.1.is_some());
+
+    let (total_words, word_count): (i64, i64) = sqlx::query_as(
+        "SELECT total_words, (SELECT COUNT(*) FROM words WHERE word_book_id = 1) FROM word_books WHERE id = 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(total_words, word_count);
+
+    // 再次导入时已有单词全部跳过
+    let result = service
+        .import(import_request(CSV, ImportConflictStrategy::Skip))
+        .await
+        .unwrap();
+    assert_eq!(result.added_count, 0);
+    assert_eq!(result.updated_count, 0);
+    assert_eq!(result.skipped_count, 5);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_preview_large_file_against_existing_book() {
+    let pool = setup_test_db().await;
+    let kitten_id = insert_existing_word(&pool).await;
+    let service = WordImportService::new(Arc::new(pool.clone()), test_logger());
+
+    // 单词数超过 SQLite 单条语句的绑定参数上限，已有单词在最后一行
+    let mut content = String::from("Word,Meaning\n");
+    for i in 0..33_000 {
+        content.push_str(&format!("word{},含义\n", i));
+    }
+    content.push_str("kitten,小猫咪\n");
+
+    let preview = service
+        .preview(&import_request(&content, ImportConflictStrategy::Skip))
+        .await
+        .unwrap();
+    assert_eq!(preview.total_rows, 33_001);
+    assert_eq!(preview.new_count, 33_000);
+    assert_eq!(preview.conflicts.len(), 1);
+    assert_eq!(preview.conflicts[0].existing_word_id, kitten_id);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_import_anki_text_into_new_book() {
+    let pool = setup_test_db().await;
+    let service = WordImportService::new(Arc::new(pool.clone()), test_logger());
+
+    let content = "#separator:tab\n#html:true\n#columns:Front\tBack\nbanana\t香蕉<br>一种水果\norange\t<b>橙子</b>\n";
+    let mut request = import_request(content, ImportConflictStrategy::Skip);
+    request.book_id = None;
+    assert!(service.preview(&request).await.is_err());
+
+    request.new_book = Some(CreateWordBookRequest {
+        title: "Anki 导入".to_string(),
+        description: String::new(),
+        icon: "📚".to_string(),
+        icon_color: "#3B82F6".to_string(),
+        theme_tag_ids: None,
+    });
+    let result = service.import(request).await.unwrap();
+    assert_ne!(result.book_id, 1);
+    assert_eq!(result.added_count, 2);
+
+    let words: Vec<(String, String)> =
+        sqlx::query_as("SELECT word, meaning FROM words WHERE word_book_id = ? ORDER BY word")
+            .bind(result.book_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        words,
+        vec![
+            ("banana".to_string(), "香蕉\n一种水果".to_string()),
+            ("orange".to_string(), "橙子".to_string()),
+        ]
+    );
+
+    teardown_test_db(&pool).await;
+}
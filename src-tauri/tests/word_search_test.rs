@@ -0,0 +1,226 @@
+// 单词全文搜索测试
+mod common_test_utils;
+
+use common_test_utils::*;
+use redlark_app_lib::error::AppError;
+use redlark_app_lib::services::word::WordService;
+use redlark_app_lib::types::wordbook::WordSearchQuery;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+fn word_service(pool: &SqlitePool) -> WordService {
+    WordService::new(Arc::new(pool.clone()), test_logger())
+}
+
+fn search(query: &str) -> WordSearchQuery {
+    WordSearchQuery {
+        query: query.to_string(),
+        ..Default::default()
+    }
+}
+
+async fn insert_word(
+    pool: &SqlitePool,
+    book_id: i64,
+    word: &str,
+    meaning: &str,
+    part_of_speech: &str,
+    analysis_explanation: Option<&str>,
+) -> i64 {
+    sqlx::query(
+        "INSERT INTO words (word, meaning, part_of_speech, analysis_explanation, word_book_id)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(word)
+    .bind(meaning)
+    .bind(part_of_speech)
+    .bind(analysis_explanation)
+    .bind(book_id)
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid()
+}
+
+#[tokio::test]
+async fn test_search_ranks_and_highlights_across_books() {
+    let pool = setup_test_db().await;
+    insert_word(&pool, 2, "Pineapple", "菠萝", "noun", None).await;
+    insert_word(
+        &pool,
+        3,
+        "Orchard",
+        "果园",
+        "noun",
+        Some("An orchard is where apple trees grow"),
+    )
+    .await;
+
+    let result = word_service(&pool)
+        .search_words(search("apple"))
+        .await
+        .unwrap();
+    let words: Vec<&str> = result.data.iter().map(|w| w.word.as_str()).collect();
+    assert_eq!(words, vec!["Apple", "Pineapple", "Orchard"]);
+    assert_eq!(result.total, 3);
+
+    let apple = result.data[0].highlight.as_ref().unwrap();
+    assert_eq!(apple.word, "<mark>Apple</mark>");
+    assert_eq!(apple.meaning, "苹果");
+    assert!(apple.snippet.is_none());
+    let pineapple = result.data[1].highlight.as_ref().unwrap();
+    assert_eq!(pineapple.word, "Pine<mark>apple</mark>");
+    let orchard = result.data[2].highlight.as_ref().unwrap();
+    assert_eq!(orchard.word, "Orchard");
+    assert!(orchard
+        .snippet
+        .as_deref()
+        .unwrap()
+        .contains("<mark>apple</mark>"));
+
+    // 分页
+    let mut paged = search("apple");
+    paged.page = Some(2);
+    paged.page_size = Some(2);
+    let result = word_service(&pool).search_words(paged).await.unwrap();
+    assert_eq!(result.data.len(), 1);
+    assert_eq!(result.total_pages, 2);
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_search_index_follows_word_changes() {
+    let pool = setup_test_db().await;
+    let service = word_service(&pool);
+    let word_id = insert_word(&pool, 1, "Bicycle", "自行车", "noun", None).await;
+    assert_eq!(
+        service.search_words(search("自行车")).await.unwrap().total,
+        1
+    );
+
+    sqlx::query("UPDATE words SET meaning = '脚踏车' WHERE id = ?")
+        .bind(word_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        service.search_words(search("自行车")).await.unwrap().total,
+        0
+    );
+    let result = service.search_words(search("脚踏车")).await.unwrap();
+    assert_eq!(
+        result.data[0].highlight.as_ref().unwrap().meaning,
+        "<mark>脚踏车</mark>"
+    );
+
+    sqlx::query("DELETE FROM words WHERE id = ?")
+        .bind(word_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        service.search_words(search("bicycle")).await.unwrap().total,
+        0
+    );
+    let integrity: Vec<sqlx::sqlite::SqliteRow> =
+        sqlx::query("INSERT INTO words_fts (words_fts) VALUES ('integrity-check')")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert!(integrity.is_empty());
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_search_short_terms_use_substring_match() {
+    let pool = setup_test_db().await;
+    insert_word(&pool, 2, "Applesauce", "苹果酱", "noun", None).await;
+
+    // 两个字的中文关键词无法使用 trigram 索引
+    let result = word_service(&pool)
+        .search_words(search("苹果"))
+        .await
+        .unwrap();
+    let words: Vec<&str> = result.data.iter().map(|w| w.word.as_str()).collect();
+    assert_eq!(words, vec!["Apple", "Applesauce"]);
+    assert_eq!(
+        result.data[1].highlight.as_ref().unwrap().meaning,
+        "<mark>苹果</mark>酱"
+    );
+
+    // 长短关键词组合：长关键词走全文索引，短关键词作为附加条件
+    let result = word_service(&pool)
+        .search_words(search("apple 酱"))
+        .await
+        .unwrap();
+    assert_eq!(result.total, 1);
+    assert_eq!(result.data[0].word, "Applesauce");
+
+    // LIKE 通配符按字面匹配
+    assert_eq!(
+        word_service(&pool)
+            .search_words(search("%"))
+            .await
+            .unwrap()
+            .total,
+        0
+    );
+
+    teardown_test_db(&pool).await;
+}
+
+#[tokio::test]
+async fn test_search_filters() {
+    let pool = setup_test_db().await;
+    let service = word_service(&pool);
+    insert_word(&pool, 2, "Sunlight", "阳光", "noun", None).await;
+    insert_word(&pool, 3, "Sunny", "晴朗的", "adjective", None).await;
+    sqlx::query(
+        "INSERT INTO word_book_theme_tags (word_book_id, theme_tag_id)
+         SELECT 3, id FROM theme_tags WHERE name = '日常'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let tag_id: i64 = sqlx::query_scalar("SELECT id FROM theme_tags WHERE name = '日常'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(service.search_words(search("sun")).await.unwrap().total, 2);
+
+    let mut by_book = search("sun");
+    by_book.book_id = Some(2);
+    let result = service.search_words(by_book).await.unwrap();
+    assert_eq!(result.data[0].word, "Sunlight");
+    assert_eq!(result.total, 1);
+
+    let mut by_tag = search("sun");
+    by_tag.theme_tag_id = Some(tag_id);
+    let result = service.search_words(by_tag).await.unwrap();
+    assert_eq!(result.data[0].word, "Sunny");
+    assert_eq!(result.total, 1);
+
+    let mut by_pos = search("sun");
+    by_pos.part_of_speech = Some("noun".to_string());
+    let result = service.search_words(by_pos).await.unwrap();
+    assert_eq!(result.data[0].word, "Sunlight");
+    assert_eq!(result.total, 1);
+
+    // 已删除单词本中的单词不出现在结果中
+    sqlx::query("UPDATE word_books SET deleted_at = CURRENT_TIMESTAMP WHERE id = 2")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let result = service.search_words(search("sun")).await.unwrap();
+    assert_eq!(result.total, 1);
+    assert_eq!(result.data[0].word, "Sunny");
+
+    assert!(matches!(
+        service.search_words(search("  ")).await,
+        Err(AppError::ValidationError(_))
+    ));
+
+    teardown_test_db(&pool).await;
+}
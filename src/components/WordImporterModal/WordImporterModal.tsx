@@ -349,7 +349,8 @@ export const WordImporterModal: React.FC<WordImporterModalProps> = ({
     try {
       const result = await wordAnalysisService.extractWordsFromText(
         textContent,
-        parseInt(selectedModel)
+        extractionMode,
+        { modelId: parseInt(selectedModel) }
       );
 
       setExtractedWordList(result);
@@ -11,7 +11,12 @@ import type {
   Id,
   LoadingState,
   ApiResult,
-  WordExtractionMode
+  WordExtractionMode,
+  AIUsageGranularity,
+  AIUsageReport,
+  PromptName,
+  PromptTemplate,
+  PromptVersion
 } from '../types';
 
 /**
@@ -99,6 +104,7 @@ export class AIModelService extends BaseService {
         displayName: request.displayName,
         baseUrl: request.baseUrl,
         apiKey: request.apiKey,
+        providerType: request.providerType,
         description: request.description
       });
     }, setLoading);
@@ -116,16 +122,7 @@ export class AIModelService extends BaseService {
       console.log('updateAIProvider called with:', { providerId, request });
       this.validateRequired({ providerId }, ['providerId']);
 
-      // 扁平化参数，将 providerId 和 request 的所有字段作为单独的参数
-      // 注意：前端使用驼峰命名，后端期望驼峰命名（Tauri会自动转换为下划线）
-      const params = {
-        providerId: providerId,
-        displayName: request.displayName,
-        baseUrl: request.baseUrl,
-        apiKey: request.apiKey,
-        description: request.description,
-        isActive: request.isActive
-      };
+      const params = { providerId, request };
 
       console.log('Calling update_ai_provider with params:', params);
       const result = await this.client.invoke<void>('update_ai_provider', params);
@@ -342,4 +339,99 @@ export class AIModelService extends BaseService {
       }
     }, setLoading);
   }
+
+  /**
+   * 配置模型价格（美元 / 百万 token），传空值表示不估算费用
+   */
+  async updateAIModelPricing(
+    modelId: Id,
+    inputPricePerMillion?: number,
+    outputPricePerMillion?: number,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<void>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<void>('update_ai_model_pricing', {
+        modelId,
+        inputPricePerMillion: inputPricePerMillion ?? null,
+        outputPricePerMillion: outputPricePerMillion ?? null
+      });
+    }, setLoading);
+  }
+
+  /**
+   * 获取AI用量报表（按日/按月，并按提供商和功能拆分）
+   */
+  async getAIUsageReport(
+    granularity: AIUsageGranularity = 'daily',
+    startDate?: string,
+    endDate?: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<AIUsageReport>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<AIUsageReport>('get_ai_usage_report', {
+        granularity,
+        startDate: startDate || null,
+        endDate: endDate || null
+      });
+    }, setLoading);
+  }
+
+  /**
+   * 获取所有提示词模板（当前生效的版本）
+   */
+  async getPromptTemplates(setLoading?: (state: LoadingState) => void): Promise<ApiResult<PromptTemplate[]>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<PromptTemplate[]>('get_prompt_templates');
+    }, setLoading);
+  }
+
+  /**
+   * 保存自定义提示词模板（必须包含所有必需占位符）
+   */
+  async savePromptTemplate(
+    name: PromptName,
+    content: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<PromptTemplate>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<PromptTemplate>('save_prompt_template', { name, content });
+    }, setLoading);
+  }
+
+  /**
+   * 恢复为内置提示词模板
+   */
+  async resetPromptTemplate(
+    name: PromptName,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<PromptTemplate>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<PromptTemplate>('reset_prompt_template', { name });
+    }, setLoading);
+  }
+
+  /**
+   * 获取提示词历史版本
+   */
+  async getPromptVersions(
+    name: PromptName,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<PromptVersion[]>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<PromptVersion[]>('get_prompt_versions', { name });
+    }, setLoading);
+  }
+
+  /**
+   * 恢复提示词到历史版本
+   */
+  async restorePromptVersion(
+    name: PromptName,
+    version: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<PromptTemplate>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<PromptTemplate>('restore_prompt_version', { name, version });
+    }, setLoading);
+  }
 }
@@ -2,13 +2,18 @@ import { BaseService } from './baseService';
 import type { 
   DatabaseOverview,
   ResetResult,
+  BackupInfo,
+  BackupSettings,
+  UpdateBackupSettingsRequest,
+  OperationSnapshot,
+  UndoOperationResult,
   LoadingState,
   ApiResult 
 } from '../types';
 
 /**
  * 数据管理服务
- * 处理数据库统计和重置相关操作
+ * 处理数据库统计、重置和备份恢复相关操作
  */
 export class DataManagementService extends BaseService {
   /**
@@ -58,6 +63,86 @@ export class DataManagementService extends BaseService {
       return this.client.invoke<void>('delete_database_and_restart', {});
     }, setLoading);
   }
+
+  /**
+   * 立即创建手动备份（数据库和发音缓存）
+   */
+  async createBackup(
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<BackupInfo>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<BackupInfo>('create_backup', {});
+    }, setLoading);
+  }
+
+  /**
+   * 获取备份列表（最新的在前）
+   */
+  async getBackups(
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<BackupInfo[]>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<BackupInfo[]>('get_backups', {});
+    }, setLoading);
+  }
+
+  /**
+   * 获取自动备份设置
+   */
+  async getBackupSettings(
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<BackupSettings>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<BackupSettings>('get_backup_settings', {});
+    }, setLoading);
+  }
+
+  /**
+   * 更新自动备份设置
+   */
+  async updateBackupSettings(
+    request: UpdateBackupSettingsRequest,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<BackupSettings>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<BackupSettings>('update_backup_settings', { request });
+    }, setLoading);
+  }
+
+  /**
+   * 从备份恢复数据并重启应用程序
+   * 注意：恢复前会自动备份当前数据，成功后应用立即重启，无法获取返回结果
+   */
+  async restoreBackup(
+    filePath: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<void>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<void>('restore_backup', { filePath });
+    }, setLoading);
+  }
+
+  /**
+   * 获取破坏性操作快照历史（最新的在前）
+   */
+  async getOperationSnapshots(
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<OperationSnapshot[]>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<OperationSnapshot[]>('get_operation_snapshots', {});
+    }, setLoading);
+  }
+
+  /**
+   * 撤销最近一次破坏性操作（重置数据、删除单词本/学习计划、移除计划单词）
+   */
+  async undoLastOperation(
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<UndoOperationResult>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<UndoOperationResult>('undo_last_operation', {});
+    }, setLoading);
+  }
 }
 
 // 导出服务实例
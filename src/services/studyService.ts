@@ -10,6 +10,13 @@ import {
   StudyPlanWord,
   StudyPlanStatistics,
   StudyPlanStatusHistory,
+  ResumeStudyPlanResult,
+  StudyPlanRebalancePreview,
+  AddPlanWordsRequest,
+  AddPlanWordsResult,
+  StudyPlanTemplate,
+  CreateStudyPlanTemplateRequest,
+  CloneStudyPlanRequest,
   ApiResult,
   LoadingState,
   Id,
@@ -126,6 +133,8 @@ export class StudyService extends BaseService {
         start_date: request.startDate,
         wordbook_ids: request.wordbookIds,
         model_id: request.modelId || null,
+        use_local_planner: request.useLocalPlanner ?? false,
+        template_id: request.templateId ?? null,
       };
 
       return this.client.invoke<StudyPlanAIResult>('generate_study_plan_schedule', { request: backendRequest });
@@ -362,6 +371,152 @@ export class StudyService extends BaseService {
     }, setLoading);
   }
 
+  /**
+   * 暂停学习计划（暂停期间不计逾期）
+   */
+  async pauseStudyPlan(
+    planId: number,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<void>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<void>('pause_study_plan', { planId });
+    }, setLoading);
+  }
+
+  /**
+   * 恢复学习计划（未完成的日程按暂停天数后移）
+   */
+  async resumeStudyPlan(
+    planId: number,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<ResumeStudyPlanResult>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<ResumeStudyPlanResult>('resume_study_plan', { planId });
+    }, setLoading);
+  }
+
+  /**
+   * 预览逾期日程重新分配（不修改数据）
+   */
+  async previewRebalanceStudyPlan(
+    planId: number,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<StudyPlanRebalancePreview>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<StudyPlanRebalancePreview>('preview_rebalance_study_plan', { planId });
+    }, setLoading);
+  }
+
+  /**
+   * 重新分配逾期日程（未完成的单词分配到今天及之后，必要时延长计划）
+   * fingerprint 为预览返回的指纹，日程在预览后变化时拒绝应用
+   */
+  async rebalanceStudyPlan(
+    planId: number,
+    fingerprint: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<StudyPlanRebalancePreview>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<StudyPlanRebalancePreview>('rebalance_study_plan', { planId, fingerprint });
+    }, setLoading);
+  }
+
+  /**
+   * 向草稿或进行中的学习计划追加单词
+   */
+  async addWordsToPlan(
+    planId: number,
+    wordIds: number[],
+    options: Pick<AddPlanWordsRequest, 'model_id' | 'use_local_planner'> = {},
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<AddPlanWordsResult>> {
+    return this.addToPlan({ ...options, plan_id: planId, word_ids: wordIds }, setLoading);
+  }
+
+  /**
+   * 向草稿或进行中的学习计划追加整个单词本
+   */
+  async addWordbooksToPlan(
+    planId: number,
+    wordbookIds: number[],
+    options: Pick<AddPlanWordsRequest, 'model_id' | 'use_local_planner'> = {},
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<AddPlanWordsResult>> {
+    return this.addToPlan({ ...options, plan_id: planId, wordbook_ids: wordbookIds }, setLoading);
+  }
+
+  private async addToPlan(
+    request: AddPlanWordsRequest,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<AddPlanWordsResult>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<AddPlanWordsResult>('add_words_to_study_plan', { request });
+    }, setLoading);
+  }
+
+  /**
+   * 复制学习计划（沿用设置和日程形状，学习新的单词本）
+   */
+  async cloneStudyPlan(
+    request: CloneStudyPlanRequest,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<Id>> {
+    return this.executeWithLoading(async () => {
+      if (request.wordbook_ids.length === 0) {
+        throw new Error('必须选择至少一个单词本');
+      }
+      return this.client.invoke<Id>('clone_study_plan', { request });
+    }, setLoading);
+  }
+
+  /**
+   * 获取所有学习计划模板
+   */
+  async getStudyPlanTemplates(
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<StudyPlanTemplate[]>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<StudyPlanTemplate[]>('get_study_plan_templates');
+    }, setLoading);
+  }
+
+  /**
+   * 创建学习计划模板
+   */
+  async createStudyPlanTemplate(
+    request: CreateStudyPlanTemplateRequest,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<Id>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<Id>('create_study_plan_template', { request });
+    }, setLoading);
+  }
+
+  /**
+   * 把学习计划的设置和日程形状保存为模板
+   */
+  async saveStudyPlanAsTemplate(
+    planId: number,
+    name: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<Id>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<Id>('save_study_plan_as_template', { planId, name });
+    }, setLoading);
+  }
+
+  /**
+   * 删除学习计划模板
+   */
+  async deleteStudyPlanTemplate(
+    templateId: number,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<void>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<void>('delete_study_plan_template', { templateId });
+    }, setLoading);
+  }
+
   /**
    * 重新学习计划
    */
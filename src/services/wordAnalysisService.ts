@@ -8,9 +8,14 @@ import type {
   BatchAnalysisRequest,
   BatchAnalysisResult,
   BatchAnalysisProgress,
+  BatchAnalysisJob,
   BatchAnalysisOptions,
   WordExtractionResult,
+  ExtractionWordLists,
+  ExtractionWordListType,
+  PhonicsRuleCheck,
 } from '../types/word-analysis';
+import type { WordExtractionMode } from '../types/wordbook';
 
 /**
  * 批量单词分析服务
@@ -19,21 +24,25 @@ export class WordAnalysisService {
   /**
    * 从文本中提取单词（第一步）
    * @param text 输入文本
-   * @param modelId 可选的模型 ID
+   * @param extractionMode 提取模式（默认 focus）
+   * @param options 可选的模型 ID；useLocalExtractor 为 true 时在本地提取，不请求 AI 模型
    * @returns 提取结果
    */
   async extractWordsFromText(
     text: string,
-    modelId?: number
+    extractionMode: WordExtractionMode = 'focus',
+    options: { modelId?: number; useLocalExtractor?: boolean } = {}
   ): Promise<WordExtractionResult> {
-    console.log('WordAnalysisService: Starting word extraction', { textLength: text.length, modelId });
+    console.log('WordAnalysisService: Starting word extraction', { textLength: text.length, extractionMode, ...options });
 
     try {
       const result = await apiClient.invoke<WordExtractionResult>(
         'extract_words_from_text',
         {
           text,
-          modelId,
+          modelId: options.modelId,
+          extractionMode,
+          useLocalExtractor: options.useLocalExtractor ?? false,
         }
       );
 
@@ -154,6 +163,7 @@ export class WordAnalysisService {
           text: request.text,
           modelId: request.modelId,
           extractionMode: request.extractionMode || 'focus',
+          useLocalExtractor: request.useLocalExtractor ?? false,
           config: request.config || {
             batchSize: 10,
             maxConcurrentBatches: 3,
@@ -195,6 +205,136 @@ export class WordAnalysisService {
     }
   }
 
+  /**
+   * 获取本地单词提取的用户词表
+   * @returns 停用词和已掌握单词
+   */
+  async getExtractionWordLists(): Promise<ExtractionWordLists> {
+    const result = await apiClient.invoke<ExtractionWordLists>('get_extraction_word_lists');
+
+    if (!result.success || !result.data) {
+      throw new Error((result as any).error || '获取提取词表失败');
+    }
+
+    return result.data;
+  }
+
+  /**
+   * 替换本地单词提取的用户词表
+   * @param listType 词表类型
+   * @param words 单词列表
+   * @returns 保存后的单词数
+   */
+  async setExtractionWordList(listType: ExtractionWordListType, words: string[]): Promise<number> {
+    const result = await apiClient.invoke<number>('set_extraction_word_list', { listType, words });
+
+    if (!result.success) {
+      throw new Error((result as any).error || '保存提取词表失败');
+    }
+
+    return result.data ?? 0;
+  }
+
+  /**
+   * 向本地单词提取的用户词表追加单词（如标记为已掌握）
+   * @param listType 词表类型
+   * @param words 单词列表
+   * @returns 新增的单词数
+   */
+  async addExtractionWords(listType: ExtractionWordListType, words: string[]): Promise<number> {
+    const result = await apiClient.invoke<number>('add_extraction_words', { listType, words });
+
+    if (!result.success) {
+      throw new Error((result as any).error || '添加提取词表单词失败');
+    }
+
+    return result.data ?? 0;
+  }
+
+  /**
+   * 按拼读规则分析单词，并检查音节划分和拼读规则是否与规则一致
+   * @param word 单词
+   * @param syllables 待检查的音节划分
+   * @param phonicsRule 待检查的拼读规则
+   * @returns 规则分析结果和不一致的字段
+   */
+  async checkWordPhonics(
+    word: string,
+    syllables?: string,
+    phonicsRule?: string
+  ): Promise<PhonicsRuleCheck> {
+    const result = await apiClient.invoke<PhonicsRuleCheck>('check_word_phonics', {
+      word,
+      syllables,
+      phonicsRule,
+    });
+
+    if (!result.success || !result.data) {
+      throw new Error((result as any).error || '拼读规则检查失败');
+    }
+
+    return result.data;
+  }
+
+  /**
+   * 获取批量分析任务列表
+   * @param limit 返回数量
+   * @returns 任务列表（最近更新的在前）
+   */
+  async getBatchAnalysisJobs(limit?: number): Promise<BatchAnalysisJob[]> {
+    const result = await apiClient.invoke<BatchAnalysisJob[]>('get_batch_analysis_jobs', { limit });
+
+    if (!result.success) {
+      throw new Error((result as any).error || '获取分析任务失败');
+    }
+
+    return result.data || [];
+  }
+
+  /**
+   * 恢复批量分析任务（只分析尚未完成的单词）
+   * @param jobId 任务 ID
+   * @param options 可选配置（进度回调等）
+   * @returns 任务的完整分析结果
+   */
+  async resumeBatchAnalysis(
+    jobId: number,
+    options?: BatchAnalysisOptions
+  ): Promise<BatchAnalysisResult> {
+    console.log('WordAnalysisService: Resuming batch analysis job', { jobId });
+
+    if (options?.onProgress) {
+      this.startProgressPolling(options.onProgress, options.onError);
+    }
+
+    try {
+      const result = await apiClient.invoke<BatchAnalysisResult>('resume_batch_analysis', { jobId });
+
+      if (!result.success) {
+        throw new Error((result as any).error || '恢复批量分析失败');
+      }
+      if (!result.data) {
+        throw new Error('批量分析返回数据为空');
+      }
+
+      this.stopProgressPolling();
+
+      if (options?.onComplete) {
+        options.onComplete(result.data);
+      }
+
+      return result.data;
+    } catch (error) {
+      this.stopProgressPolling();
+
+      if (options?.onError) {
+        options.onError(error instanceof Error ? error : new Error(String(error)));
+      }
+
+      throw error;
+    }
+  }
+
   /**
    * 获取批量分析进度
    * @returns 当前进度
@@ -8,13 +8,23 @@ import {
   CreateWordRequest,
   UpdateWordRequest,
   WordQuery,
+  WordSearchQuery,
   AnalysisProgress,
   PaginationQuery,
   PaginatedResponse,
   WordBookStatistics,
   WordTypeDistribution,
   WordSaveResult,
+  WordImportRequest,
+  WordImportPreview,
+  WordBookExportRequest,
+  WordBookExportResult,
   ThemeTag,
+  IpaConvention,
+  IpaCheckItem,
+  IpaCheckReport,
+  PronunciationImportResult,
+  PronunciationLookup,
   Id,
   ApiResult,
   LoadingState,
@@ -179,6 +189,19 @@ export class WordBookService extends BaseService {
     }, setLoading);
   }
 
+  /**
+   * 全文搜索所有单词本中的单词（按相关度排序，带高亮）
+   */
+  async searchWords(
+    query: WordSearchQuery,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<PaginatedResponse<Word>>> {
+    return this.executeWithLoading(async () => {
+      this.validateRequired({ query: query.query }, ['query']);
+      return this.client.invoke<PaginatedResponse<Word>>('search_words', { query });
+    }, setLoading);
+  }
+
   /**
    * 添加单词到单词本
    */
@@ -315,15 +338,108 @@ export class WordBookService extends BaseService {
         throw new Error('单词本必须包含至少一个单词');
       }
 
-      return this.client.invoke<{
-        book_id: Id;
-        added_count: number;
-        updated_count: number;
-        skipped_count: number;
-      }>('create_word_book_from_analysis', { request });
+      return this.client.invoke<WordSaveResult>('create_word_book_from_analysis', { request });
+    }, setLoading);
+  }
+
+  /**
+   * 预览单词导入（CSV、TSV 或 Anki 纯文本）
+   */
+  async previewWordImport(
+    request: WordImportRequest,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<WordImportPreview>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<WordImportPreview>('preview_word_import', { request });
+    }, setLoading);
+  }
+
+  /**
+   * 从 CSV、TSV 或 Anki 纯文本导入单词
+   */
+  async importWords(
+    request: WordImportRequest,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<WordSaveResult>> {
+    return this.executeWithLoading(async () => {
+      if (!request.book_id && !request.new_book) {
+        throw new Error('请指定要导入的单词本');
+      }
+
+      return this.client.invoke<WordSaveResult>('import_words', { request });
+    }, setLoading);
+  }
+
+  /**
+   * 导入单词本 JSON 导出包（创建新单词本）
+   */
+  async importWordBookBundle(
+    content: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<WordSaveResult>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<WordSaveResult>('import_word_book_bundle', { content });
+    }, setLoading);
+  }
+
+  /**
+   * 导出单词本为 CSV、JSON 导出包或 Anki 牌组
+   */
+  async exportWordBook(
+    bookId: Id,
+    request: WordBookExportRequest,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<WordBookExportResult>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<WordBookExportResult>('export_word_book', { bookId, request });
     }, setLoading);
   }
 
+  /**
+   * 导入 CMUdict 格式的发音文件
+   * @param content 文件内容
+   */
+  async importPronunciationDictionary(
+    content: string,
+    setLoading?: (state: LoadingState) => void
+  ): Promise<ApiResult<PronunciationImportResult>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<PronunciationImportResult>('import_pronunciation_dictionary', { content });
+    }, setLoading);
+  }
+
+  /**
+   * 获取发音词典的条目总数
+   */
+  async getPronunciationDictionaryCount(): Promise<ApiResult<number>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<number>('get_pronunciation_dictionary_count');
+    });
+  }
+
+  /**
+   * 查询单词的词典读音
+   */
+  async lookupPronunciation(word: string): Promise<ApiResult<PronunciationLookup[]>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<PronunciationLookup[]>('lookup_pronunciation', { word });
+    });
+  }
+
+  /**
+   * 用发音词典校验单词音标
+   * @param words 待校验的单词和音标
+   * @param convention 补全音标使用的音标体系
+   */
+  async checkWordIpa(
+    words: IpaCheckItem[],
+    convention: IpaConvention = 'us'
+  ): Promise<ApiResult<IpaCheckReport>> {
+    return this.executeWithLoading(async () => {
+      return this.client.invoke<IpaCheckReport>('check_word_ipa', { words, convention });
+    });
+  }
+
   /**
    * 获取分析进度
    */
@@ -1,5 +1,8 @@
 import type { Id, Timestamp } from './common';
 
+/// AI提供商接口类型
+export type AIProviderType = 'openai' | 'anthropic' | 'ollama' | 'mock'; // mock 的 baseUrl 为脚本文件路径
+
 /// AI提供商
 export interface AIProvider {
   id: Id;
@@ -7,6 +10,7 @@ export interface AIProvider {
   displayName: string;
   baseUrl: string;
   apiKey: string;
+  providerType: AIProviderType;
   description?: string;
   isActive: boolean;
   createdAt: Timestamp;
@@ -40,6 +44,10 @@ export interface AIModelConfig {
   temperature?: number;
   isActive: boolean;
   isDefault: boolean;
+  /** 输入价格（美元 / 百万 token），未配置时不估算费用 */
+  inputPricePerMillion?: number;
+  /** 输出价格（美元 / 百万 token） */
+  outputPricePerMillion?: number;
   createdAt: Timestamp;
   updatedAt: Timestamp;
   provider: AIProvider;
@@ -51,6 +59,7 @@ export interface CreateAIProviderRequest {
   displayName: string;
   baseUrl: string;
   apiKey: string;
+  providerType?: AIProviderType;
   description?: string;
 }
 
@@ -59,6 +68,7 @@ export interface UpdateAIProviderRequest {
   displayName?: string;
   baseUrl?: string;
   apiKey?: string;
+  providerType?: AIProviderType;
   description?: string;
   isActive?: boolean;
 }
@@ -124,3 +134,53 @@ export interface AIModelTestRequest {
   modelId: Id;
   testText?: string; // 测试文本，如果不提供则使用默认文本
 }
+
+/// AI用量统计粒度
+export type AIUsageGranularity = 'daily' | 'monthly';
+
+/// AI用量统计项
+export interface AIUsageSummary {
+  key: string; // 日期 / 月份 / 提供商名称 / 用途
+  calls: number;
+  failedCalls: number;
+  promptTokens: number;
+  completionTokens: number;
+  totalTokens: number;
+  estimatedCost: number; // 估算费用（美元）
+}
+
+/// AI用量报告
+export interface AIUsageReport {
+  granularity: AIUsageGranularity;
+  startDate: string;
+  endDate: string;
+  total: AIUsageSummary;
+  byPeriod: AIUsageSummary[];
+  byProvider: AIUsageSummary[];
+  byFeature: AIUsageSummary[];
+}
+
+/// 提示词模板名称
+export type PromptName = 'word_extraction' | 'phonics' | 'batch_phonics' | 'study_plan';
+
+/// 提示词模板来源：内置 / 应用数据目录中的自定义模板
+export type PromptSource = 'default' | 'custom';
+
+/// 当前生效的提示词模板
+export interface PromptTemplate {
+  name: PromptName;
+  content: string;
+  version: string; // 内容 SHA-256 前 12 位
+  source: PromptSource;
+  requiredPlaceholders: string[];
+  customError?: string; // 自定义模板无效时的原因（此时使用内置模板）
+}
+
+/// 提示词历史版本
+export interface PromptVersion {
+  name: PromptName;
+  version: string;
+  content: string;
+  source: PromptSource;
+  createdAt: string;
+}
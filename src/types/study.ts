@@ -269,6 +269,8 @@ export interface StudyPlanScheduleRequest {
   startDate: string; // YYYY-MM-DD
   wordbookIds: Id[]; // 选择的单词本ID列表
   modelId?: number; // AI模型ID
+  useLocalPlanner?: boolean; // 使用本地规划器（无需AI模型）
+  templateId?: Id; // 计划模板ID，指定时使用模板的强度、周期、复习频率和日程形状（日程形状只用于本地规划器）
 }
 
 /// 学习计划规划参数（传递给AI的参数）
@@ -357,6 +359,13 @@ export interface StudyPlanWord {
 export interface StudyPlanAIResult {
   planMetadata: StudyPlanMetadata;
   dailyPlans: DailyStudyPlan[];
+  promptVersion?: string; // 生成该规划的提示词版本
+}
+
+/// AI规划流式接收到一天计划的事件（study-plan-daily-plan）
+export interface StudyPlanDailyPlanEvent {
+  index: number; // 第几个每日计划（从 0 开始）
+  dailyPlan: DailyStudyPlan;
 }
 
 /// 学习计划单词列表（扁平化）
@@ -532,6 +541,116 @@ export interface StatusTransitionResponse {
   message: string;
 }
 
+/// 恢复学习计划结果
+export interface ResumeStudyPlanResult {
+  paused_days: number;       // 暂停的天数（未完成日程后移的天数）
+  shifted_schedules: number; // 后移的日程数
+  end_date?: string;         // 新的结束日期
+}
+
+/**
+ * 逾期日程重新分配：某一天的单词数变化
+ */
+export interface RebalanceDayDiff {
+  date: string;
+  schedule_id?: number; // 延长计划新增的日期为空
+  before_new_words: number;
+  before_review_words: number;
+  after_new_words: number;
+  after_review_words: number;
+}
+
+/**
+ * 逾期日程重新分配：单词安排的移动
+ */
+export interface RebalanceWordMove {
+  word_id: number;
+  word: string;
+  is_review: boolean;
+  review_count?: number;
+  from_date: string;
+  to_date?: string; // 为空表示复习超出计划范围，不再安排
+}
+
+/**
+ * 逾期日程重新分配预览（应用后返回实际执行的方案）
+ */
+export interface StudyPlanRebalancePreview {
+  plan_id: number;
+  max_new_words_per_day: number; // 学习强度的每日新词上限
+  overdue_schedules: number;     // 被清空的逾期日程数
+  moved_words: number;
+  dropped_reviews: number;
+  added_days: number;            // 延长计划新增的天数
+  old_end_date?: string;
+  new_end_date?: string;
+  days: RebalanceDayDiff[];
+  moves: RebalanceWordMove[];
+  fingerprint: string;           // 预览时日程的指纹，应用时原样传回
+}
+
+/**
+ * 向学习计划追加单词的请求（单词和单词本可以同时指定）
+ */
+export interface AddPlanWordsRequest {
+  plan_id: number;
+  word_ids?: number[];
+  wordbook_ids?: number[];
+  model_id?: number;           // AI模型ID
+  use_local_planner?: boolean; // 使用本地规划器（无需AI模型）
+}
+
+/**
+ * 向学习计划追加单词的结果
+ */
+export interface AddPlanWordsResult {
+  added_words: number;
+  skipped_words: number;    // 已在计划中的单词
+  new_word_entries: number;
+  review_entries: number;
+  added_days: number;       // 新建的日程数
+  end_date?: string;
+}
+
+/**
+ * 学习计划模板：计划设置和日程形状
+ */
+export interface StudyPlanTemplate {
+  id: Id;
+  name: string;
+  description: string;
+  intensity_level: IntensityLevel;
+  study_period_days: number;
+  review_frequency: number;
+  day_weights: number[]; // 每天新词数的相对比例，为空表示平均分配
+  source_plan_id?: Id;
+  created_at: string;
+  updated_at: string;
+}
+
+/**
+ * 创建学习计划模板请求
+ */
+export interface CreateStudyPlanTemplateRequest {
+  name: string;
+  description?: string;
+  intensity_level: IntensityLevel;
+  study_period_days: number;
+  review_frequency: number;
+  day_weights?: number[];
+}
+
+/**
+ * 复制学习计划请求：沿用源计划的设置和日程形状，学习新的单词本
+ */
+export interface CloneStudyPlanRequest {
+  plan_id: Id;
+  name?: string; // 默认为「源计划名称 (副本)」
+  start_date: string; // YYYY-MM-DD
+  wordbook_ids: Id[];
+  status?: 'draft' | 'active';
+}
+
 // ==================== 统一状态管理工具函数 ====================
 
 /// 状态显示信息
@@ -585,7 +704,7 @@ export const getAvailableActions = (status: UnifiedStudyPlanStatus): StudyPlanAc
   }
 };
 
-/// 检查状态转换是否合法
+/// 检查状态转换是否合法（与后端 StudyPlanTransition::TABLE 保持一致）
 export const canTransitionTo = (from: UnifiedStudyPlanStatus, to: UnifiedStudyPlanStatus): boolean => {
   switch (from) {
     case 'Draft':
@@ -593,10 +712,12 @@ export const canTransitionTo = (from: UnifiedStudyPlanStatus, to: UnifiedStudyPl
     case 'Pending':
       return ['Active', 'Draft', 'Deleted'].includes(to);
     case 'Active':
-      return ['Completed', 'Terminated', 'Draft'].includes(to);
+      return ['Paused', 'Completed', 'Terminated', 'Draft', 'Deleted'].includes(to);
+    case 'Paused':
+      return ['Active', 'Terminated', 'Draft', 'Deleted'].includes(to);
     case 'Completed':
     case 'Terminated':
-      return ['Draft'].includes(to);
+      return ['Pending', 'Draft', 'Deleted'].includes(to);
     case 'Deleted':
       return false; // 删除状态不能转换到其他状态，需要恢复操作
     default:
@@ -693,3 +814,59 @@ export interface ResetResult {
 export interface SelectiveResetRequest {
   table_names: string[];
 }
+
+/// 备份类型
+export type BackupKind = 'manual' | 'scheduled' | 'pre_restore';
+
+/// 备份文件信息
+export interface BackupInfo {
+  fileName: string;
+  filePath: string;
+  fileSize: number;
+  kind: BackupKind;
+  createdAt: string;
+  migrationVersion: number;
+  appVersion: string;
+  ttsFileCount: number;
+}
+
+/// 自动备份设置
+export interface BackupSettings {
+  enabled: boolean;
+  intervalHours: number;
+  retentionCount: number;
+  lastBackupAt?: string;
+}
+
+/// 更新自动备份设置请求
+export interface UpdateBackupSettingsRequest {
+  enabled?: boolean;
+  intervalHours?: number;
+  retentionCount?: number;
+}
+
+/// 会先保存快照的破坏性操作
+export type SnapshotOperation =
+  | 'reset_user_data'
+  | 'reset_selected_tables'
+  | 'delete_word_book'
+  | 'delete_study_plan'
+  | 'remove_plan_words';
+
+/// 破坏性操作快照
+export interface OperationSnapshot {
+  id: number;
+  operation: SnapshotOperation;
+  targetId?: number;
+  description: string;
+  rowCount: number;
+  tables: string[];
+  createdAt: string;
+  undoneAt?: string;
+}
+
+/// 撤销结果
+export interface UndoOperationResult {
+  snapshot: OperationSnapshot;
+  restoredRows: number;
+}
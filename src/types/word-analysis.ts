@@ -22,6 +22,52 @@ export interface WordExtractionResult {
   uniqueCount: number;
 }
 
+/**
+ * 本地单词提取的用户词表类型
+ * - stop: 重点模式下额外排除的停用词
+ * - known: 已掌握的单词，任何模式都排除
+ */
+export type ExtractionWordListType = 'stop' | 'known';
+
+/**
+ * 本地单词提取的用户词表
+ */
+export interface ExtractionWordLists {
+  stopWords: string[];
+  knownWords: string[];
+}
+
+/**
+ * 基于规则的自然拼读分析结果
+ */
+export interface RulePhonicsAnalysis {
+  word: string;
+  syllables: string; // 以 - 连接
+  syllableCount: number;
+  phonicsSegments: string; // 以 ", " 连接，魔法 e 记为 a_e
+  phonicsRule: string; // 主要规则
+  detectedRules: string[]; // 适用的全部规则
+}
+
+/**
+ * AI 分析结果与拼读规则不一致的字段
+ */
+export interface PhonicsDisagreement {
+  word: string;
+  field: 'syllables' | 'phonics_rule';
+  aiValue: string;
+  ruleValue: string;
+  message: string;
+}
+
+/**
+ * 单词拼读规则检查结果
+ */
+export interface PhonicsRuleCheck {
+  analysis: RulePhonicsAnalysis | null;
+  disagreements: PhonicsDisagreement[];
+}
+
 /**
  * 批次信息
  */
@@ -63,6 +109,16 @@ export interface WordAnalysisStatus {
   result: PhonicsWord | null;
 }
 
+/**
+ * 单词状态更新事件（word-status-update），流式解析到单词时即发送
+ */
+export interface WordStatusUpdateEvent {
+  word: string;
+  status: string; // "pending", "analyzing", "completed", "failed"
+  error: string | null;
+  result: PhonicsWord | null;
+}
+
 /**
  * 批量分析进度
  */
@@ -94,6 +150,40 @@ export interface BatchAnalysisResult {
   completedWords: number;
   failedWords: number;
   elapsedSeconds: number;
+  jobId?: number;
+  cacheHits?: number;
+  cacheMisses?: number;
+  phonicsWarnings?: PhonicsDisagreement[];
+}
+
+/**
+ * 批量分析任务状态
+ */
+export type BatchAnalysisJobStatus =
+  | 'extracting'
+  | 'analyzing'
+  | 'completed'
+  | 'partial'
+  | 'cancelled'
+  | 'failed';
+
+/**
+ * 批量分析任务
+ */
+export interface BatchAnalysisJob {
+  id: number;
+  inputPreview?: string;
+  extractionMode: string;
+  localExtraction: boolean; // 是否在本地提取单词（否则由 AI 模型提取）
+  modelId?: number;
+  promptVersion?: string; // 批量分析提示词版本
+  status: BatchAnalysisJobStatus;
+  totalWords: number;
+  completedWords: number;
+  failedWords: number;
+  errorMessage?: string;
+  createdAt: string;
+  updatedAt: string;
 }
 
 /**
@@ -121,6 +211,7 @@ export interface BatchAnalysisRequest {
   text: string;
   modelId?: number;
   extractionMode?: string;
+  useLocalExtractor?: boolean; // 在本地提取单词，不请求 AI 模型
   config?: BatchAnalysisConfig;
 }
 
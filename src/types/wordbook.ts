@@ -73,6 +73,14 @@ export interface Word {
   analysis_explanation?: string;
   created_at: Timestamp;
   updated_at: Timestamp;
+  highlight?: WordHighlight; // 仅全文搜索结果包含
+}
+
+/// 全文搜索命中的高亮内容（匹配部分用 <mark></mark> 包裹）
+export interface WordHighlight {
+  word: string;
+  meaning: string;
+  snippet?: string;
 }
 
 /// 创建单词请求
@@ -119,6 +127,16 @@ export interface WordQuery {
   part_of_speech?: string;
 }
 
+/// 全局单词搜索请求
+export interface WordSearchQuery {
+  query: string;
+  book_id?: Id;
+  theme_tag_id?: Id;
+  part_of_speech?: string;
+  page?: number;
+  page_size?: number;
+}
+
 /// 单词分类
 export interface Category {
   id: Id;
@@ -195,6 +213,121 @@ export interface WordSaveResult {
   added_count: number;
   updated_count: number;
   skipped_count: number;
+  ipa_mismatches?: IpaMismatch[]; // 与发音词典不一致的音标（保留原音标）
+}
+
+/// 单词导入文件格式
+export type WordImportFormat = 'csv' | 'tsv' | 'anki_text';
+
+/// 导入单词与单词本已有单词重复时的处理方式
+export type ImportConflictStrategy = 'skip' | 'update';
+
+/// 导入列映射（列序号从 0 开始）
+export interface WordImportMapping {
+  word: number;
+  meaning?: number;
+  description?: number;
+  ipa?: number;
+  syllables?: number;
+  phonics_segments?: number;
+  part_of_speech?: number;
+  pos_abbreviation?: number;
+  pos_english?: number;
+  pos_chinese?: number;
+  phonics_rule?: number;
+  analysis_explanation?: number;
+}
+
+export interface WordImportRequest {
+  content: string;
+  format?: WordImportFormat; // 未指定时自动识别
+  has_header?: boolean; // 未指定时根据第一行自动判断
+  mapping?: WordImportMapping; // 未指定时根据表头推断
+  book_id?: Id; // 导入到现有单词本
+  new_book?: CreateWordBookRequest; // 或创建新单词本
+  conflict_strategy?: ImportConflictStrategy;
+}
+
+export interface WordImportConflict {
+  line: number;
+  word: string;
+  existing_word_id: Id;
+}
+
+export interface WordImportRowError {
+  line: number;
+  message: string;
+}
+
+export interface WordImportPreview {
+  format: WordImportFormat;
+  has_header: boolean;
+  headers: string[];
+  mapping: WordImportMapping;
+  total_rows: number;
+  new_count: number;
+  conflicts: WordImportConflict[];
+  duplicate_lines: number[]; // 文件内重复的单词（只导入第一次出现的）
+  errors: WordImportRowError[];
+  sample: CreateWordRequest[];
+}
+
+/// 单词本导出格式
+export type WordBookExportFormat = 'csv' | 'json' | 'apkg';
+
+export interface WordBookExportRequest {
+  format: WordBookExportFormat;
+  include_audio?: boolean; // 仅 Anki 牌组：打包已缓存的发音
+  output_path?: string; // 未指定时保存到下载目录
+}
+
+export interface WordBookExportResult {
+  file_path: string;
+  format: WordBookExportFormat;
+  word_count: number;
+  audio_count: number;
+  file_size: number;
+}
+
+/// 音标体系
+export type IpaConvention = 'us' | 'uk';
+
+/// 发音词典导入结果
+export interface PronunciationImportResult {
+  imported: number;
+  skippedLines: number;
+  totalEntries: number;
+}
+
+/// 单词的词典读音
+export interface PronunciationLookup {
+  word: string;
+  variant: number;
+  arpabet: string;
+  usIpa: string;
+  ukIpa: string;
+}
+
+/// 待校验音标的单词
+export interface IpaCheckItem {
+  word: string;
+  ipa?: string | null;
+}
+
+/// 与词典读音不一致的音标
+export interface IpaMismatch {
+  word: string;
+  aiIpa: string;
+  dictionaryIpa: string[];
+}
+
+/// 音标校验报告
+export interface IpaCheckReport {
+  checked: number;
+  matched: number;
+  filled: IpaCheckItem[]; // 没有音标、可由词典补全的单词
+  notFound: string[];
+  mismatches: IpaMismatch[];
 }
 
 /// 单词提取模式